@@ -0,0 +1,128 @@
+//! Резервный способ вызвать окно приложения без глобальной горячей клавиши.
+//!
+//! Не все композиторы Wayland позволяют плагину зарегистрировать глобальное сочетание клавиш,
+//! поэтому запущенное приложение слушает локальный сокет, а `prompt-tool-cli toggle`
+//! отправляет в него команду. Такую команду можно привязать к клавише в настройках композитора.
+
+use std::path::{Path, PathBuf};
+
+use crate::error::{PromptToolError, Result};
+use crate::paths::{current_user, CONFIG_DIR_ARG};
+
+/// Команда, отправляемая запущенному приложению
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivationCommand {
+    /// Показать окно, а если оно уже активно - скрыть
+    Toggle,
+    /// Показать окно и передать ему фокус
+    Show,
+}
+
+impl ActivationCommand {
+    /// Имя команды в протоколе сокета
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivationCommand::Toggle => "toggle",
+            ActivationCommand::Show => "show",
+        }
+    }
+
+    /// Разбирает имя команды; неизвестные команды игнорируются
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "toggle" => Some(ActivationCommand::Toggle),
+            "show" => Some(ActivationCommand::Show),
+            _ => None,
+        }
+    }
+}
+
+/// Ищет в аргументах повторного запуска путь к файлу, который нужно импортировать
+/// Берется первый аргумент, не являющийся флагом или значением `--config-dir`;
+/// относительный путь отсчитывается от рабочей директории повторно запущенного процесса
+pub fn import_path_arg<I, S>(args: I, cwd: &Path) -> Option<PathBuf>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let arg = arg.as_ref();
+        if arg == CONFIG_DIR_ARG {
+            args.next();
+            continue;
+        }
+        if arg.starts_with('-') {
+            continue;
+        }
+        return Some(cwd.join(arg));
+    }
+    None
+}
+
+/// Путь к сокету активации текущего пользователя
+/// Используется `$XDG_RUNTIME_DIR`, а если он не задан - временная директория
+pub fn socket_path() -> PathBuf {
+    let dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+
+    dir.join(format!("prompt-tool-{}.sock", current_user()))
+}
+
+/// Отправляет команду запущенному приложению
+#[cfg(unix)]
+pub fn send_command(path: &std::path::Path, command: ActivationCommand) -> Result<()> {
+    use std::io::Write;
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(path)
+        .map_err(|e| PromptToolError::Config(format!("Приложение не запущено ({}): {}", path.display(), e)))?;
+
+    writeln!(stream, "{}", command.as_str())
+        .map_err(PromptToolError::Io)
+}
+
+/// Начинает слушать сокет в фоновом потоке и вызывает обработчик на каждую команду
+/// Оставшийся от завершившегося процесса сокет удаляется; если сокет занят
+/// работающим экземпляром, возвращается ошибка
+#[cfg(unix)]
+pub fn listen<F>(path: &std::path::Path, handler: F) -> Result<()>
+where
+    F: Fn(ActivationCommand) + Send + 'static,
+{
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    if path.exists() {
+        if UnixStream::connect(path).is_ok() {
+            return Err(PromptToolError::Config(format!(
+                "Сокет активации {} уже используется другим экземпляром",
+                path.display()
+            )));
+        }
+        std::fs::remove_file(path)
+            .map_err(PromptToolError::Io)?;
+    }
+
+    let listener = UnixListener::bind(path)
+        .map_err(PromptToolError::Io)?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            for line in BufReader::new(stream).lines().map_while(std::result::Result::ok) {
+                if let Some(command) = ActivationCommand::parse(&line) {
+                    handler(command);
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// На системах без Unix-сокетов резервная активация недоступна
+#[cfg(not(unix))]
+pub fn send_command(_path: &std::path::Path, _command: ActivationCommand) -> Result<()> {
+    Err(PromptToolError::Config("Активация через сокет поддерживается только в Unix-системах".to_string()))
+}
@@ -0,0 +1,150 @@
+//! Резервные копии файла промптов.
+//!
+//! Перед каждым сохранением прежнее содержимое файла копируется в директорию резервных копий
+//! под именем `<имя>.<время>.<расширение>`; хранятся только последние копии каждого файла.
+//! Зашифрованный файл копируется как есть и остается зашифрованным.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+
+use crate::error::{PromptToolError, Result};
+
+/// Имя директории с резервными копиями внутри директории данных приложения
+pub const BACKUPS_DIR_NAME: &str = "backups";
+
+/// Сколько последних копий хранить по умолчанию
+pub const DEFAULT_BACKUP_COUNT: usize = 20;
+
+/// Формат времени в имени копии; лексикографический порядок имен совпадает с хронологическим
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%.3f";
+
+/// Резервная копия файла промптов
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Backup {
+    /// Имя файла копии; по нему копия восстанавливается
+    pub file_name: String,
+
+    /// Момент создания копии
+    pub created_at: DateTime<Utc>,
+
+    /// Размер копии в байтах
+    pub size: u64,
+}
+
+/// Резервные копии файлов промптов в одной директории
+#[derive(Debug, Clone)]
+pub struct BackupStore {
+    dir: PathBuf,
+}
+
+/// Имя и расширение файла промптов, из которых складываются имена его копий
+fn name_parts(source: &Path) -> (String, String) {
+    let stem = source.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let extension = source.extension().unwrap_or_default().to_string_lossy().to_string();
+    (stem, extension)
+}
+
+impl BackupStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Директория с копиями
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Время создания копии файла `source` по ее имени; `None`, если это копия другого файла
+    fn created_at(source: &Path, file_name: &str) -> Option<DateTime<Utc>> {
+        let (stem, extension) = name_parts(source);
+        let timestamp = file_name
+            .strip_prefix(&format!("{}.", stem))?
+            .strip_suffix(&format!(".{}", extension))?;
+
+        NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+            .ok()
+            .map(|time| time.and_utc())
+    }
+
+    /// Копии файла `source`, начиная с самой новой
+    pub fn list(&self, source: &Path) -> Result<Vec<Backup>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(PromptToolError::Io(e)),
+        };
+
+        let mut backups: Vec<Backup> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let created_at = Self::created_at(source, &file_name)?;
+                let size = entry.metadata().ok()?.len();
+                Some(Backup { file_name, created_at, size })
+            })
+            .collect();
+
+        backups.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+        Ok(backups)
+    }
+
+    /// Самая новая копия файла `source`
+    pub fn newest(&self, source: &Path) -> Result<Option<Backup>> {
+        Ok(self.list(source)?.into_iter().next())
+    }
+
+    /// Копирует текущее содержимое `source` и удаляет копии сверх `keep` последних
+    /// Возвращает путь копии; `None`, если файла еще нет, копии отключены (`keep == 0`)
+    /// или содержимое не изменилось с последней копии
+    pub fn create(&self, source: &Path, now: DateTime<Utc>, keep: usize) -> Result<Option<PathBuf>> {
+        if keep == 0 || !source.is_file() {
+            return Ok(None);
+        }
+
+        // Одинаковые копии вытеснили бы из ротации более старые, но разные
+        if let Some(newest) = self.newest(source)? {
+            let unchanged = fs::read(self.dir.join(&newest.file_name))
+                .ok()
+                .is_some_and(|backup| fs::read(source).is_ok_and(|current| current == backup));
+            if unchanged {
+                return Ok(None);
+            }
+        }
+
+        fs::create_dir_all(&self.dir)
+            .map_err(PromptToolError::Io)?;
+
+        let (stem, extension) = name_parts(source);
+        let backup = self.dir.join(format!("{}.{}.{}", stem, now.format(TIMESTAMP_FORMAT), extension));
+        fs::copy(source, &backup)
+            .map_err(PromptToolError::Io)?;
+
+        self.prune(source, keep)?;
+        Ok(Some(backup))
+    }
+
+    /// Удаляет копии файла `source` сверх `keep` последних; возвращает количество удаленных
+    pub fn prune(&self, source: &Path, keep: usize) -> Result<usize> {
+        let outdated: Vec<Backup> = self.list(source)?.into_iter().skip(keep).collect();
+        for backup in &outdated {
+            fs::remove_file(self.dir.join(&backup.file_name))
+                .map_err(PromptToolError::Io)?;
+        }
+        Ok(outdated.len())
+    }
+
+    /// Содержимое копии файла `source` с именем `file_name`
+    /// Принимаются только имена из списка копий, поэтому путь не может выйти за пределы директории
+    pub fn read(&self, source: &Path, file_name: &str) -> Result<String> {
+        let known = self.list(source)?.iter().any(|backup| backup.file_name == file_name);
+        if !known {
+            return Err(PromptToolError::Validation(format!("Резервная копия \"{}\" не найдена", file_name)));
+        }
+
+        fs::read_to_string(self.dir.join(file_name))
+            .map_err(PromptToolError::Io)
+    }
+}
@@ -0,0 +1,152 @@
+//! Консольная утилита для работы с библиотеками промптов без запуска приложения.
+//!
+//! Использование:
+//!     prompt-tool-cli validate <path>
+//!     prompt-tool-cli render <path> <name> [param=value ...]
+//!     prompt-tool-cli toggle
+//!     prompt-tool-cli show
+//!
+//! `toggle` и `show` управляют окном запущенного приложения; команду можно привязать
+//! к сочетанию клавиш в настройках композитора, если глобальная горячая клавиша не работает.
+//!
+//! `render` отправляет промпт в его цель по умолчанию (файл, вебхук); буфер обмена
+//! в консоли заменяется стандартным выводом.
+//!
+//! Коды выхода: 0 - ошибок нет, 1 - найдены ошибки, 2 - неверные аргументы или файл не читается.
+
+use std::collections::HashMap;
+use std::process::ExitCode;
+
+use prompt_tool_lib::{
+    activation::{self, ActivationCommand},
+    file_io::load_library,
+    lint::{has_errors, lint_library, Severity},
+    output::{self, OutputTargetConfig},
+    render,
+};
+
+const USAGE: &str = "Использование: prompt-tool-cli validate <path> | render <path> <name> [param=value ...] | toggle | show";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["validate", path] => validate(path),
+        ["render", path, name, values @ ..] => render(path, name, values),
+        ["toggle"] => activate(ActivationCommand::Toggle),
+        ["show"] => activate(ActivationCommand::Show),
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Передает команду окну запущенного приложения
+fn activate(command: ActivationCommand) -> ExitCode {
+    match activation::send_command(&activation::socket_path(), command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Подставляет значения параметров в промпт и отправляет результат в его цель по умолчанию
+fn render(path: &str, name: &str, args: &[&str]) -> ExitCode {
+    let mut values = HashMap::new();
+    for arg in args {
+        match arg.split_once('=') {
+            Some((key, value)) => values.insert(key.to_string(), value.to_string()),
+            None => {
+                eprintln!("{}", USAGE);
+                return ExitCode::from(2);
+            }
+        };
+    }
+
+    let list = match load_library(path) {
+        Ok(list) => list,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let Some(prompt) = list.prompts.iter().find(|prompt| prompt.name == name) else {
+        eprintln!("{}: промпт \"{}\" не найден", path, name);
+        return ExitCode::from(2);
+    };
+
+    let mut prompt = prompt.clone();
+    let output = match render::expand_includes(&prompt, &list) {
+        Ok(content) => {
+            prompt.content = content;
+            render::render_validated(&prompt, &values)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Some(error) = &output.template_error {
+        eprintln!("{}", error);
+        return ExitCode::FAILURE;
+    }
+    for error in &output.errors {
+        eprintln!("{}: {}", error.parameter, error.message);
+    }
+    for warning in &output.warnings {
+        eprintln!("warning: {}", warning.message);
+    }
+    let Some(text) = output.text else {
+        return ExitCode::FAILURE;
+    };
+
+    let target = match prompt.output.unwrap_or_default() {
+        OutputTargetConfig::Clipboard | OutputTargetConfig::Paste => OutputTargetConfig::Stdout,
+        target => target,
+    };
+
+    match output::target_for(&target, Default::default()).deliver(&prompt.name, &text) {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Проверяет файл библиотеки теми же правилами, что и приложение
+fn validate(path: &str) -> ExitCode {
+    let list = match load_library(path) {
+        Ok(list) => list,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let diagnostics = lint_library(&list);
+    for diagnostic in &diagnostics {
+        let level = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        println!(
+            "{}: {}[{}]: prompt #{} \"{}\": {}",
+            path, level, diagnostic.code, diagnostic.index + 1, diagnostic.prompt, diagnostic.message
+        );
+    }
+
+    let errors = diagnostics.iter().filter(|d| d.severity == Severity::Error).count();
+    println!("{}: {} prompts, {} errors, {} warnings", path, list.prompts.len(), errors, diagnostics.len() - errors);
+
+    if has_errors(&diagnostics) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
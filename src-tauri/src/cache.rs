@@ -0,0 +1,190 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+use serde::{Deserialize, Serialize};
+
+/// Приблизительный размер значения в памяти, используется для ограничения кэшей по байтам
+pub trait CacheWeight {
+    /// Размер значения в байтах (оценка)
+    fn weight(&self) -> usize;
+}
+
+impl CacheWeight for String {
+    fn weight(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T> CacheWeight for Vec<T> {
+    fn weight(&self) -> usize {
+        self.capacity() * std::mem::size_of::<T>()
+    }
+}
+
+macro_rules! fixed_weight {
+    ($($t:ty),*) => {
+        $(impl CacheWeight for $t {
+            fn weight(&self) -> usize {
+                std::mem::size_of::<$t>()
+            }
+        })*
+    };
+}
+
+fixed_weight!(i32, i64, u32, u64, usize);
+
+/// Ограничения размера кэша
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CacheLimits {
+    /// Максимальное количество записей
+    pub max_entries: usize,
+
+    /// Максимальный суммарный размер записей в байтах
+    pub max_bytes: usize,
+}
+
+impl Default for CacheLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: 256,
+            max_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// Статистика использования кэша
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct CacheStats {
+    /// Текущее количество записей
+    pub entries: usize,
+
+    /// Текущий суммарный размер записей в байтах
+    pub bytes: usize,
+
+    /// Количество попаданий
+    pub hits: u64,
+
+    /// Количество промахов
+    pub misses: u64,
+
+    /// Количество записей, вытесненных из-за ограничений
+    pub evictions: u64,
+
+    /// Действующие ограничения
+    pub max_entries: usize,
+
+    /// Действующие ограничения по размеру
+    pub max_bytes: usize,
+}
+
+/// Кэш с вытеснением давно не использованных записей (LRU)
+/// Ограничивается и количеством записей, и их суммарным размером; размер записи - ключ плюс значение
+#[derive(Debug)]
+pub struct LruCache<K, V> {
+    entries: HashMap<K, V>,
+    // Порядок использования: в начале - самые давние записи
+    order: VecDeque<K>,
+    limits: CacheLimits,
+    stats: CacheStats,
+}
+
+impl<K: Hash + Eq + Clone + CacheWeight, V: CacheWeight + Clone> LruCache<K, V> {
+    /// Создает пустой кэш с указанными ограничениями
+    pub fn new(limits: CacheLimits) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            limits,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Возвращает копию значения и отмечает запись как недавно использованную
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        match self.entries.get(key) {
+            Some(value) => {
+                let value = value.clone();
+                self.touch(key);
+                self.stats.hits += 1;
+                Some(value)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Добавляет запись, вытесняя самые давние записи при превышении ограничений
+    /// Запись больше `max_bytes` не кэшируется, а прежнее значение для этого ключа удаляется,
+    /// чтобы не отдавать устаревший результат
+    pub fn insert(&mut self, key: K, value: V) {
+        self.remove(&key);
+
+        let weight = key.weight() + value.weight();
+        if weight > self.limits.max_bytes || self.limits.max_entries == 0 {
+            return;
+        }
+
+        self.order.push_back(key.clone());
+        self.entries.insert(key, value);
+        self.stats.bytes += weight;
+
+        self.evict();
+    }
+
+    /// Удаляет запись; возвращает, была ли она в кэше
+    pub fn remove(&mut self, key: &K) -> bool {
+        let Some((key, value)) = self.entries.remove_entry(key) else {
+            return false;
+        };
+        self.stats.bytes -= key.weight() + value.weight();
+        if let Some(position) = self.order.iter().position(|k| *k == key) {
+            self.order.remove(position);
+        }
+        true
+    }
+
+    /// Удаляет все записи (статистика попаданий сохраняется)
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+        self.stats.bytes = 0;
+    }
+
+    /// Меняет ограничения и сразу вытесняет лишние записи
+    pub fn set_limits(&mut self, limits: CacheLimits) {
+        self.limits = limits;
+        self.evict();
+    }
+
+    /// Текущая статистика
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            max_entries: self.limits.max_entries,
+            max_bytes: self.limits.max_bytes,
+            ..self.stats
+        }
+    }
+
+    fn touch(&mut self, key: &K) {
+        if let Some(position) = self.order.iter().position(|k| k == key) {
+            if let Some(k) = self.order.remove(position) {
+                self.order.push_back(k);
+            }
+        }
+    }
+
+    fn evict(&mut self) {
+        while self.entries.len() > self.limits.max_entries || self.stats.bytes > self.limits.max_bytes {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            if let Some((key, value)) = self.entries.remove_entry(&oldest) {
+                self.stats.bytes -= key.weight() + value.weight();
+                self.stats.evictions += 1;
+            }
+        }
+    }
+}
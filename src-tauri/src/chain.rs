@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+use crate::llm::Usage;
+
+/// Шаг цепочки промптов
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainStep {
+    /// Название выполняемого промпта
+    pub prompt_name: String,
+
+    /// Параметр, в который подставляется ответ предыдущего шага
+    #[serde(default = "default_input_parameter")]
+    pub input_parameter: String,
+
+    /// Значения параметров, заданные для этого шага
+    #[serde(default)]
+    pub values: HashMap<String, String>,
+
+    /// Модель для шага; если не указана, используется модель из настроек
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+fn default_input_parameter() -> String {
+    "input".to_string()
+}
+
+/// Цепочка промптов: ответ каждого шага передается параметром в следующий,
+/// например "резюмировать -> перевести -> оформить"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptChain {
+    /// Название цепочки
+    pub name: String,
+
+    /// Шаги в порядке выполнения
+    pub steps: Vec<ChainStep>,
+}
+
+/// Результат одного шага цепочки
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainStepResult {
+    /// Название выполненного промпта
+    pub prompt_name: String,
+
+    /// Текст, отправленный модели
+    pub rendered_input: String,
+
+    /// Ответ модели
+    pub output: String,
+
+    /// Расход токенов, если сервер его сообщил
+    pub usage: Option<Usage>,
+}
+
+impl PromptChain {
+    /// Проверяет, что цепочку можно выполнить
+    pub fn validate(&self) -> Result<()> {
+        if self.steps.is_empty() {
+            return Err(PromptToolError::Validation(format!("Цепочка \"{}\" не содержит шагов", self.name)));
+        }
+        Ok(())
+    }
+
+    /// Значения параметров для шага: общие значения цепочки, затем значения шага,
+    /// затем ответ предыдущего шага в `input_parameter`
+    pub fn step_values(
+        &self,
+        index: usize,
+        initial: &HashMap<String, String>,
+        previous_output: Option<&str>,
+    ) -> HashMap<String, String> {
+        let step = &self.steps[index];
+
+        let mut values = initial.clone();
+        values.extend(step.values.iter().map(|(k, v)| (k.clone(), v.clone())));
+        if let Some(output) = previous_output {
+            values.insert(step.input_parameter.clone(), output.to_string());
+        }
+        values
+    }
+}
@@ -0,0 +1,168 @@
+//! Импорт промптов из выгрузки данных ChatGPT (`conversations.json`).
+//!
+//! Из каждой беседы берутся сообщения пользователя; они предлагаются как новые промпты
+//! с названием из первых слов сообщения и тегами `chatgpt` и названия беседы.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::{PromptToolError, Result};
+use crate::prompt::{Prompt, PromptList};
+use crate::render::slugify;
+
+/// Тег, которым помечаются импортированные промпты
+pub const CHATGPT_TAG: &str = "chatgpt";
+
+/// Наибольшая длина автоматически созданного названия в символах
+const MAX_NAME_CHARS: usize = 60;
+
+/// Отбор сообщений для импорта
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ChatGptImportOptions {
+    /// Регулярное выражение, которому должен соответствовать текст сообщения
+    pub pattern: Option<String>,
+
+    /// Минимальная длина сообщения в символах
+    pub min_length: usize,
+
+    /// Максимальная длина сообщения в символах
+    pub max_length: Option<usize>,
+}
+
+/// Сообщение пользователя из выгрузки
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserMessage {
+    /// Название беседы
+    pub conversation: String,
+    pub text: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+fn timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    let seconds = value.as_f64()?;
+    DateTime::from_timestamp(seconds.trunc() as i64, (seconds.fract() * 1e9) as u32)
+}
+
+/// Текст сообщения: строковые части содержимого через перевод строки (вложения пропускаются)
+fn message_text(message: &Value) -> String {
+    message["content"]["parts"]
+        .as_array()
+        .map(|parts| parts.iter().filter_map(Value::as_str).collect::<Vec<_>>().join("\n"))
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+/// Сообщения пользователя из `conversations.json` в порядке бесед и времени отправки
+pub fn extract_user_messages(export: &Value) -> Result<Vec<UserMessage>> {
+    let conversations = export.as_array()
+        .ok_or_else(|| PromptToolError::Config("conversations.json: ожидался массив бесед".to_string()))?;
+
+    let mut messages = Vec::new();
+    for conversation in conversations {
+        let title = conversation["title"].as_str().unwrap_or_default().trim().to_string();
+        let Some(mapping) = conversation["mapping"].as_object() else {
+            continue;
+        };
+
+        let mut found: Vec<UserMessage> = mapping
+            .values()
+            .map(|node| &node["message"])
+            .filter(|message| message["author"]["role"] == "user")
+            .map(|message| UserMessage {
+                conversation: title.clone(),
+                text: message_text(message),
+                created_at: timestamp(&message["create_time"]),
+            })
+            .filter(|message| !message.text.is_empty())
+            .collect();
+
+        // Узлы беседы хранятся в словаре без порядка, поэтому упорядочиваем по времени
+        found.sort_by_key(|message| message.created_at);
+        messages.extend(found);
+    }
+
+    Ok(messages)
+}
+
+/// Название промпта из первых слов сообщения
+fn prompt_name(text: &str) -> String {
+    let mut name = String::new();
+    for word in text.split_whitespace() {
+        if name.chars().count() + word.chars().count() + 1 > MAX_NAME_CHARS {
+            name.push('…');
+            break;
+        }
+        if !name.is_empty() {
+            name.push(' ');
+        }
+        name.push_str(word);
+    }
+
+    if name.is_empty() || name == "…" { "ChatGPT prompt".to_string() } else { name }
+}
+
+/// Предлагает промпты из сообщений, прошедших отбор
+/// Повторяющиеся сообщения предлагаются один раз; названия не совпадают с названиями библиотеки
+pub fn propose_prompts(messages: Vec<UserMessage>, options: &ChatGptImportOptions, library: &PromptList) -> Result<Vec<Prompt>> {
+    let pattern = options.pattern
+        .as_deref()
+        .filter(|pattern| !pattern.is_empty())
+        .map(Regex::new)
+        .transpose()
+        .map_err(|e| PromptToolError::Validation(format!("Некорректное регулярное выражение: {}", e)))?;
+
+    let mut names: HashSet<String> = library.prompts.iter().map(|prompt| prompt.name.trim().to_lowercase()).collect();
+    let mut texts = HashSet::new();
+    let mut prompts = Vec::new();
+
+    for message in messages {
+        let length = message.text.chars().count();
+        if length < options.min_length
+            || options.max_length.is_some_and(|max| length > max)
+            || pattern.as_ref().is_some_and(|pattern| !pattern.is_match(&message.text))
+            || !texts.insert(message.text.clone())
+        {
+            continue;
+        }
+
+        let base = prompt_name(&message.text);
+        let mut name = base.clone();
+        let mut counter = 2;
+        while !names.insert(name.to_lowercase()) {
+            name = format!("{} ({})", base, counter);
+            counter += 1;
+        }
+
+        let mut tags = HashSet::from([CHATGPT_TAG.to_string()]);
+        let topic = slugify(&message.conversation);
+        if !topic.is_empty() {
+            tags.insert(topic);
+        }
+
+        let mut prompt = Prompt::new(name, message.text, Vec::new(), HashSet::new(), tags);
+        if let Some(created_at) = message.created_at {
+            prompt.created_at = created_at;
+            prompt.updated_at = created_at;
+        }
+        prompts.push(prompt);
+    }
+
+    Ok(prompts)
+}
+
+/// Читает `conversations.json` и предлагает промпты для импорта
+pub fn import_conversations(path: &Path, options: &ChatGptImportOptions, library: &PromptList) -> Result<Vec<Prompt>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(PromptToolError::Io)?;
+    let export: Value = serde_json::from_str(&contents)
+        .map_err(|e| PromptToolError::Config(format!("{}: {}", path.display(), e)))?;
+
+    propose_prompts(extract_user_messages(&export)?, options, library)
+}
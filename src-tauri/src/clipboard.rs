@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+/// Метка порядка байтов, которую некоторые редакторы оставляют в начале текста
+const BOM: char = '\u{FEFF}';
+
+/// Как приводить переводы строк перед вставкой
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// Принятые в текущей ОС: CRLF в Windows, LF в остальных системах
+    #[default]
+    Native,
+    /// Только LF
+    Lf,
+    /// Только CRLF
+    Crlf,
+}
+
+/// Настройки подготовки текста к вставке в другие приложения
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PasteOptions {
+    /// Приводить ли переводы строк к единому виду
+    #[serde(default)]
+    pub line_endings: LineEnding,
+
+    /// Удалять ли BOM и нулевые символы, которые ломают вставку в часть приложений
+    #[serde(default = "default_strip_bom")]
+    pub strip_bom: bool,
+
+    /// Класть ли в буфер обмена дополнительно HTML-версию текста (только Windows).
+    /// Electron-приложения берут HTML-формат и не теряют в нем переводы строк
+    #[serde(default = "default_include_html")]
+    pub include_html: bool,
+
+    /// Запрещать ли копирование текста, в котором остались плейсхолдеры без значения
+    #[serde(default)]
+    pub block_unresolved: bool,
+
+    /// Возвращать ли после копирования фокус окну, из которого вызвали PromptTool,
+    /// и вставлять в него текст (Ctrl+V / Cmd+V)
+    #[serde(default)]
+    pub auto_paste: bool,
+}
+
+fn default_strip_bom() -> bool {
+    true
+}
+
+fn default_include_html() -> bool {
+    true
+}
+
+impl Default for PasteOptions {
+    fn default() -> Self {
+        Self {
+            line_endings: LineEnding::default(),
+            strip_bom: default_strip_bom(),
+            include_html: default_include_html(),
+            block_unresolved: false,
+            auto_paste: false,
+        }
+    }
+}
+
+/// Подготавливает текст к вставке: убирает BOM и приводит переводы строк
+pub fn prepare_text(text: &str, options: &PasteOptions) -> String {
+    let text = if options.strip_bom {
+        text.chars().filter(|c| *c != BOM && *c != '\0').collect()
+    } else {
+        text.to_string()
+    };
+
+    // Сначала сводим CRLF и одиночные CR к LF, затем расставляем нужный вариант
+    let unified = text.replace("\r\n", "\n").replace('\r', "\n");
+
+    let crlf = match options.line_endings {
+        LineEnding::Native => cfg!(windows),
+        LineEnding::Lf => false,
+        LineEnding::Crlf => true,
+    };
+
+    if crlf {
+        unified.replace('\n', "\r\n")
+    } else {
+        unified
+    }
+}
+
+/// Экранирует текст для HTML, сохраняя переводы строк через `<br>`
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.replace("\r\n", "\n").chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("<br>\r\n"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Заголовок CF_HTML; смещения записываются фиксированной ширины,
+/// поэтому длина заголовка не зависит от их значений
+fn html_header(start_html: usize, end_html: usize, start_fragment: usize, end_fragment: usize) -> String {
+    format!(
+        "Version:0.9\r\nStartHTML:{:010}\r\nEndHTML:{:010}\r\nStartFragment:{:010}\r\nEndFragment:{:010}\r\n",
+        start_html, end_html, start_fragment, end_fragment
+    )
+}
+
+/// Формирует содержимое формата Windows "HTML Format" (CF_HTML).
+/// Смещения в заголовке считаются в байтах UTF-8
+pub fn html_clipboard_format(text: &str) -> String {
+    let prefix = "<html><body>\r\n<!--StartFragment-->";
+    let fragment = format!("<div style=\"white-space: pre-wrap\">{}</div>", escape_html(text));
+    let suffix = "<!--EndFragment-->\r\n</body></html>";
+
+    let start_html = html_header(0, 0, 0, 0).len();
+    let start_fragment = start_html + prefix.len();
+    let end_fragment = start_fragment + fragment.len();
+    let end_html = end_fragment + suffix.len();
+
+    format!(
+        "{}{}{}{}",
+        html_header(start_html, end_html, start_fragment, end_fragment),
+        prefix,
+        fragment,
+        suffix
+    )
+}
+
+/// Записывает текст в буфер обмена средствами ОС.
+/// Возвращает `false`, если для текущей ОС нативная запись не реализована
+/// и текст должен положить в буфер фронтенд
+pub fn write_text(text: &str, options: &PasteOptions) -> Result<bool> {
+    #[cfg(windows)]
+    {
+        windows::write_text(text, options)?;
+        Ok(true)
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = (text, options);
+        Ok(false)
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::ptr;
+
+    use windows_sys::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, OpenClipboard, RegisterClipboardFormatW, SetClipboardData,
+    };
+    use windows_sys::Win32::System::Memory::{GlobalAlloc, GlobalFree, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+
+    use super::{html_clipboard_format, PasteOptions};
+    use crate::error::{PromptToolError, Result};
+
+    // Стандартный идентификатор формата текста в UTF-16
+    const CF_UNICODETEXT: u32 = 13;
+
+    /// Открытый буфер обмена; закрывается при выходе из области видимости
+    struct OpenedClipboard;
+
+    impl OpenedClipboard {
+        fn open() -> Result<Self> {
+            // SAFETY: нулевое окно-владелец допустимо, буфер закрывается в Drop
+            if unsafe { OpenClipboard(ptr::null_mut()) } == 0 {
+                return Err(clipboard_error("не удалось открыть буфер обмена"));
+            }
+            Ok(Self)
+        }
+    }
+
+    impl Drop for OpenedClipboard {
+        fn drop(&mut self) {
+            // SAFETY: буфер был открыт в OpenedClipboard::open
+            unsafe { CloseClipboard() };
+        }
+    }
+
+    fn clipboard_error(message: &str) -> PromptToolError {
+        PromptToolError::Config(format!("Ошибка буфера обмена: {}", message))
+    }
+
+    /// Копирует байты в глобальную память и передает ее буферу обмена
+    fn set_data(format: u32, bytes: &[u8]) -> Result<()> {
+        // SAFETY: память выделяется нужного размера, после успешного
+        // SetClipboardData ей владеет система, иначе освобождаем сами
+        unsafe {
+            let handle = GlobalAlloc(GMEM_MOVEABLE, bytes.len());
+            if handle.is_null() {
+                return Err(clipboard_error("не удалось выделить память"));
+            }
+
+            let target = GlobalLock(handle) as *mut u8;
+            if target.is_null() {
+                GlobalFree(handle);
+                return Err(clipboard_error("не удалось заблокировать память"));
+            }
+            ptr::copy_nonoverlapping(bytes.as_ptr(), target, bytes.len());
+            GlobalUnlock(handle);
+
+            if SetClipboardData(format, handle).is_null() {
+                GlobalFree(handle);
+                return Err(clipboard_error("не удалось записать данные"));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn write_text(text: &str, options: &PasteOptions) -> Result<()> {
+        let _clipboard = OpenedClipboard::open()?;
+
+        // SAFETY: буфер открыт текущим потоком
+        if unsafe { EmptyClipboard() } == 0 {
+            return Err(clipboard_error("не удалось очистить буфер обмена"));
+        }
+
+        let utf16: Vec<u8> = text
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        set_data(CF_UNICODETEXT, &utf16)?;
+
+        if options.include_html {
+            let name: Vec<u16> = "HTML Format".encode_utf16().chain(std::iter::once(0)).collect();
+            // SAFETY: имя формата - строка UTF-16 с завершающим нулем
+            let format = unsafe { RegisterClipboardFormatW(name.as_ptr()) };
+            if format != 0 {
+                let mut html = html_clipboard_format(text).into_bytes();
+                html.push(0);
+                set_data(format, &html)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+//! Версии формата config.json и последовательное обновление конфигурации старых версий.
+//!
+//! Версия хранится в ключе `config_version`; конфигурация без него имеет версию 0.
+//! Обновление выполняется над JSON до разбора, поэтому переименованные поля и измененные
+//! значения по умолчанию переносятся, а не теряются. Поле с неверным значением заменяется
+//! значением по умолчанию, остальные настройки пользователя сохраняются.
+
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::error::{PromptToolError, Result};
+use crate::paths::{migrate_legacy_prompt_file, LEGACY_PROMPT_FILE};
+
+/// Ключ версии формата в config.json
+pub const CONFIG_VERSION_KEY: &str = "config_version";
+
+/// Ключ версии приложения, последним записавшего config.json
+pub const APP_VERSION_KEY: &str = "app_version";
+
+/// Версия формата, которую пишет текущая версия приложения
+pub const CURRENT_CONFIG_VERSION: u32 = 1;
+
+/// Версия приложения
+pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Пути, которые нужны шагам обновления
+pub struct MigrationContext<'a> {
+    /// Файл промптов по умолчанию в директории данных
+    pub default_prompt_file: &'a Path,
+    /// Файл промптов по умолчанию старых версий, относительно рабочей директории
+    pub legacy_prompt_file: &'a Path,
+}
+
+/// Шаг обновления формата до версии `version`
+struct Migration {
+    version: u32,
+    description: &'static str,
+    apply: fn(&mut Map<String, Value>, &MigrationContext) -> Result<()>,
+}
+
+/// Шаги обновления по возрастанию версий
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Файл промптов по умолчанию перенесен из рабочей директории в директорию данных",
+        apply: move_default_prompt_file,
+    },
+];
+
+/// `"prompt_file_path": "prompts/default.toml"` -> абсолютный путь в директории данных
+fn move_default_prompt_file(config: &mut Map<String, Value>, context: &MigrationContext) -> Result<()> {
+    if config.get("prompt_file_path").and_then(Value::as_str) != Some(LEGACY_PROMPT_FILE) {
+        return Ok(());
+    }
+
+    migrate_legacy_prompt_file(context.legacy_prompt_file, context.default_prompt_file)?;
+    config.insert(
+        "prompt_file_path".to_string(),
+        Value::String(context.default_prompt_file.to_string_lossy().into_owned()),
+    );
+    Ok(())
+}
+
+/// Версия формата конфигурации; без ключа версии - 0
+pub fn config_version(config: &Map<String, Value>) -> Result<u32> {
+    match config.get(CONFIG_VERSION_KEY) {
+        None => Ok(0),
+        Some(value) => value.as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| PromptToolError::Config(format!("Недопустимая версия конфигурации: {}", value))),
+    }
+}
+
+/// Обновляет конфигурацию до текущей версии формата и записывает в нее версию приложения
+/// Возвращает описания выполненных шагов. Конфигурация более новой версии не обновляется
+pub fn migrate_config(config: &mut Map<String, Value>, context: &MigrationContext) -> Result<Vec<&'static str>> {
+    let version = config_version(config)?;
+    if version > CURRENT_CONFIG_VERSION {
+        return Err(PromptToolError::Config(format!(
+            "Конфигурация создана более новой версией приложения (формат {}, поддерживается до {})",
+            version, CURRENT_CONFIG_VERSION
+        )));
+    }
+
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|migration| migration.version > version) {
+        (migration.apply)(config, context)?;
+        applied.push(migration.description);
+    }
+
+    config.insert(CONFIG_VERSION_KEY.to_string(), Value::from(CURRENT_CONFIG_VERSION));
+    config.insert(APP_VERSION_KEY.to_string(), Value::from(APP_VERSION));
+    Ok(applied)
+}
+
+/// Разбирает конфигурацию, заменяя значениями по умолчанию только поля, которые не удалось разобрать
+/// Возвращает конфигурацию и имена замененных полей
+pub fn deserialize_lenient<T>(config: Map<String, Value>) -> Result<(T, Vec<String>)>
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    if let Ok(parsed) = serde_json::from_value(Value::Object(config.clone())) {
+        return Ok((parsed, Vec::new()));
+    }
+
+    let mut merged = match serde_json::to_value(T::default()) {
+        Ok(Value::Object(defaults)) => defaults,
+        _ => Map::new(),
+    };
+
+    // Поля переносятся по одному; поле, после которого конфигурация не разбирается, отбрасывается
+    let mut dropped = Vec::new();
+    for (key, value) in config {
+        let previous = merged.insert(key.clone(), value);
+        if serde_json::from_value::<T>(Value::Object(merged.clone())).is_err() {
+            match previous {
+                Some(previous) => merged.insert(key.clone(), previous),
+                None => merged.remove(&key),
+            };
+            dropped.push(key);
+        }
+    }
+
+    let parsed = serde_json::from_value(Value::Object(merged))
+        .map_err(|e| PromptToolError::Config(format!("Ошибка разбора конфигурации: {}", e)))?;
+    Ok((parsed, dropped))
+}
@@ -0,0 +1,143 @@
+//! Отчеты о падениях: обработчик паники записывает отчет (сообщение, место, стек вызовов,
+//! версию приложения и последние команды) в директорию данных, а при следующем запуске
+//! приложение предлагает его открыть.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{DateTime, Utc};
+
+use crate::config_migrations::APP_VERSION;
+use crate::error::{PromptToolError, Result};
+
+/// Поддиректория директории данных с отчетами о падениях
+pub const CRASH_DIR_NAME: &str = "crashes";
+
+/// Файл с именем отчета, который еще не был показан пользователю
+const PENDING_FILE_NAME: &str = "pending";
+
+/// Сколько последних команд попадает в отчет
+pub const RECENT_COMMANDS_LIMIT: usize = 20;
+
+/// Последние вызванные команды фронтенда
+fn recent_commands() -> &'static Mutex<VecDeque<String>> {
+    static COMMANDS: OnceLock<Mutex<VecDeque<String>>> = OnceLock::new();
+    COMMANDS.get_or_init(|| Mutex::new(VecDeque::with_capacity(RECENT_COMMANDS_LIMIT)))
+}
+
+/// Запоминает вызов команды; хранятся только имена, без аргументов с текстами промптов
+pub fn record_command(command: &str) {
+    if let Ok(mut commands) = recent_commands().lock() {
+        if commands.len() == RECENT_COMMANDS_LIMIT {
+            commands.pop_front();
+        }
+        commands.push_back(command.to_string());
+    }
+}
+
+/// Отчет о падении
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrashReport {
+    pub time: DateTime<Utc>,
+    pub app_version: String,
+    /// Сообщение паники
+    pub message: String,
+    /// Файл и строка, где произошла паника
+    pub location: Option<String>,
+    /// Имя потока
+    pub thread: Option<String>,
+    pub backtrace: String,
+    /// Последние команды, от старых к новым
+    pub recent_commands: Vec<String>,
+}
+
+impl CrashReport {
+    /// Отчет с последними записанными командами
+    pub fn new(message: String, location: Option<String>, backtrace: String, time: DateTime<Utc>) -> Self {
+        let recent_commands = recent_commands()
+            .lock()
+            .map(|commands| commands.iter().cloned().collect())
+            .unwrap_or_default();
+
+        Self {
+            time,
+            app_version: APP_VERSION.to_string(),
+            message,
+            location,
+            thread: std::thread::current().name().map(str::to_string),
+            backtrace,
+            recent_commands,
+        }
+    }
+
+    /// Текст отчета для приложения к сообщению об ошибке
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "PromptTool {}\nВремя: {}\nПоток: {}\nМесто: {}\nСообщение: {}\n\nПоследние команды:\n",
+            self.app_version,
+            self.time.to_rfc3339(),
+            self.thread.as_deref().unwrap_or("<без имени>"),
+            self.location.as_deref().unwrap_or("<неизвестно>"),
+            self.message,
+        );
+        for command in &self.recent_commands {
+            text.push_str("  ");
+            text.push_str(command);
+            text.push('\n');
+        }
+        text.push_str("\nСтек вызовов:\n");
+        text.push_str(&self.backtrace);
+        text.push('\n');
+        text
+    }
+
+    /// Записывает отчет в директорию отчетов и отмечает его как еще не показанный
+    /// Возвращает путь к файлу отчета
+    pub fn write(&self, crash_dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(crash_dir)
+            .map_err(PromptToolError::Io)?;
+
+        let file_name = format!("crash-{}.txt", self.time.format("%Y%m%d-%H%M%S"));
+        let path = crash_dir.join(&file_name);
+        fs::write(&path, self.to_text())
+            .map_err(PromptToolError::Io)?;
+        fs::write(crash_dir.join(PENDING_FILE_NAME), &file_name)
+            .map_err(PromptToolError::Io)?;
+        Ok(path)
+    }
+}
+
+/// Отчет о падении в прошлом запуске, который еще не был показан; отметка снимается
+pub fn take_pending_report(crash_dir: &Path) -> Option<PathBuf> {
+    let marker = crash_dir.join(PENDING_FILE_NAME);
+    let file_name = fs::read_to_string(&marker).ok()?;
+    let _ = fs::remove_file(&marker);
+
+    let path = crash_dir.join(file_name.trim());
+    path.is_file().then_some(path)
+}
+
+/// Устанавливает обработчик паники, записывающий отчет в `crash_dir`
+/// Стандартный обработчик вызывается после записи, поэтому сообщение по-прежнему попадает в stderr
+pub fn install_panic_hook(crash_dir: PathBuf) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info.payload()
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "<паника без сообщения>".to_string());
+        let location = info.location().map(|location| format!("{}:{}", location.file(), location.line()));
+        let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+
+        let report = CrashReport::new(message, location, backtrace, Utc::now());
+        match report.write(&crash_dir) {
+            Ok(path) => tracing::error!("Приложение аварийно завершилось, отчет: {}", path.display()),
+            Err(e) => tracing::error!("Не удалось записать отчет о падении: {}", e),
+        }
+
+        previous(info);
+    }));
+}
@@ -1,15 +1,151 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::ops::Bound;
 use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use tantivy::collector::TopDocs;
 use tantivy::{directory::MmapDirectory,
-              doc, query::{QueryParser, TermQuery},
-              schema::{IndexRecordOption, OwnedValue, Schema, STORED, TextFieldIndexing, TextOptions, INDEXED},
+              doc, query::{AllQuery, BooleanQuery, Occur, Query, QueryParser, RangeQuery, TermQuery},
+              schema::{IndexRecordOption, OwnedValue, Schema, STORED, TextFieldIndexing, TextOptions, FAST, INDEXED},
               Index,
+              IndexReader,
               IndexWriter,
-              tokenizer::{LowerCaser, RemoveLongFilter, SimpleTokenizer, Stemmer, TextAnalyzer, TokenizerManager}
+              ReloadPolicy,
+              Searcher,
+              TantivyDocument,
+              TantivyError,
+              tokenizer::{LowerCaser, NgramTokenizer, RawTokenizer, RemoveLongFilter, SimpleTokenizer, Stemmer, TextAnalyzer, TokenizerManager}
 };
 use tantivy::tokenizer::Language;
+use serde::{Deserialize, Serialize};
+
+use crate::cache::CacheStats;
+use crate::prompt::Prompt;
+use crate::synonyms::SynonymMap;
+
+/// Максимальная длина префикса, индексируемого для автодополнения.
+/// Более длинные префиксы ищутся по первым символам и дофильтровываются в памяти.
+const SUGGEST_MAX_GRAM: usize = 20;
+
+/// Наибольшее число подсказок автодополнения за один запрос.
+pub const MAX_SUGGESTIONS: usize = 100;
+
+/// Вес совпадения с псевдонимом: короткий код должен находить свой промпт первым.
+const ALIAS_BOOST: f32 = 100.0;
+
+/// Сколько распакованных блоков хранилища документов держит в памяти читатель индекса по умолчанию.
+pub const DEFAULT_READER_CACHE_BLOCKS: usize = 100;
+
+/// Размер блока хранилища документов Tantivy; по нему оценивается объём кэша читателя.
+const DOC_STORE_BLOCK_BYTES: usize = 16 * 1024;
+
+/// Языки стемминга по умолчанию.
+pub const DEFAULT_LANGUAGES: [Language; 2] = [Language::Russian, Language::English];
+
+/// Имя файла рядом с индексом, в котором хранится список языков стемминга.
+const LANGUAGES_FILE_NAME: &str = "stemming_languages";
+
+/// Создаёт читатель индекса с кэшем из `blocks` блоков хранилища документов.
+/// Читатель перечитывается вручную перед поиском, фоновый поток слежения за индексом не нужен.
+fn build_reader(index: &Index, blocks: usize) -> tantivy::Result<IndexReader> {
+    index.reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .doc_store_cache_num_blocks(blocks)
+        .try_into()
+}
+
+/// Разбирает название языка стемминга (английское название или код ISO 639-1).
+///
+/// # Аргументы
+/// * `name` - Название языка, например `"german"` или `"de"`.
+///
+/// # Возвращает
+/// `Some(Language)`, если язык поддерживается стеммером, иначе `None`.
+pub fn parse_language(name: &str) -> Option<Language> {
+    let language = match name.trim().to_lowercase().as_str() {
+        "arabic" | "ar" => Language::Arabic,
+        "danish" | "da" => Language::Danish,
+        "dutch" | "nl" => Language::Dutch,
+        "english" | "en" => Language::English,
+        "finnish" | "fi" => Language::Finnish,
+        "french" | "fr" => Language::French,
+        "german" | "de" => Language::German,
+        "greek" | "el" => Language::Greek,
+        "hungarian" | "hu" => Language::Hungarian,
+        "italian" | "it" => Language::Italian,
+        "norwegian" | "no" => Language::Norwegian,
+        "portuguese" | "pt" => Language::Portuguese,
+        "romanian" | "ro" => Language::Romanian,
+        "russian" | "ru" => Language::Russian,
+        "spanish" | "es" => Language::Spanish,
+        "swedish" | "sv" => Language::Swedish,
+        "tamil" | "ta" => Language::Tamil,
+        "turkish" | "tr" => Language::Turkish,
+        _ => return None,
+    };
+    Some(language)
+}
+
+/// Строковое представление списка языков для сравнения между запусками.
+fn languages_key(languages: &[Language]) -> String {
+    languages.iter()
+        .map(|language| format!("{:?}", language))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Строит схему поискового индекса.
+///
+/// # Описание
+/// Текстовые поля title и text используют токенизатор `multilang` (стемминг по языкам из настроек),
+/// теги индексируются целиком (`raw`), псевдонимы - целиком без учета регистра (`alias`), поле `suggest` хранит префиксы для автодополнения.
+pub fn build_schema() -> Schema {
+    let mut schema_builder = Schema::builder();
+
+    // Настраиваем индексацию для текстовых полей
+    let text_indexing = TextFieldIndexing::default()
+        .set_tokenizer("multilang")  // Используем мультиязычный токенизатор
+        .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+
+    let text_options = TextOptions::default()
+        .set_indexing_options(text_indexing)
+        .set_stored();
+
+    let tag_indexing = TextFieldIndexing::default()
+        .set_tokenizer("raw")  // Для тегов используем raw токенизатор
+        .set_index_option(IndexRecordOption::Basic);
+
+    let tag_options = TextOptions::default()
+        .set_indexing_options(tag_indexing)
+        .set_stored();
+
+    // Каждый псевдоним - отдельное значение поля, совпадает только целиком
+    let alias_options = TextOptions::default()
+        .set_indexing_options(TextFieldIndexing::default()
+            .set_tokenizer("alias")
+            .set_index_option(IndexRecordOption::Basic))
+        .set_stored();
+
+    // Поле автодополнения только индексируется, значения берутся из title и tags
+    let suggest_options = TextOptions::default()
+        .set_indexing_options(TextFieldIndexing::default()
+            .set_tokenizer("suggest")
+            .set_index_option(IndexRecordOption::Basic));
+
+    // Добавляем поля с оптимизированными настройками
+    schema_builder.add_u64_field("id", INDEXED | STORED);  // Уникальный идентификатор
+    schema_builder.add_text_field("title", text_options.clone());  // Полнотекстовый поиск по заголовку
+    schema_builder.add_text_field("tags", tag_options);  // Точный поиск по тегам
+    schema_builder.add_text_field("text", text_options.clone());  // Полнотекстовый поиск по содержимому
+    schema_builder.add_text_field("metadata", text_options);  // Полнотекстовый поиск по автору, источнику, лицензии и заметкам
+    schema_builder.add_text_field("aliases", alias_options);  // Точный поиск по коротким кодам
+    schema_builder.add_u64_field("created_at", INDEXED | STORED | FAST);  // Хранение и поиск по диапазону
+    schema_builder.add_u64_field("updated_at", INDEXED | STORED | FAST);  // Хранение и поиск по диапазону
+    schema_builder.add_text_field("suggest", suggest_options);  // Префиксы для автодополнения
+
+    schema_builder.build()
+}
 
 /// Структура для представления записи в базе данных.
 /// Содержит основные данные, которые хранятся в индексе: название, теги, текст, время создания и редактирования.
@@ -27,6 +163,12 @@ pub struct Record {
     /// Текст самого промпта.
     pub text: String,
 
+    /// Метаданные промпта: автор, источник, лицензия, рекомендуемые модели и заметки.
+    pub metadata: String,
+
+    /// Псевдонимы промпта - короткие коды для быстрого поиска.
+    pub aliases: Vec<String>,
+
     /// Время создания записи в формате UNIX (секунды с эпохи Unix).
     pub created_at: u64,
 
@@ -34,6 +176,134 @@ pub struct Record {
     pub updated_at: u64,
 }
 
+/// Ошибка синтаксиса поискового запроса, которую не удалось исправить.
+#[derive(Debug, Clone)]
+pub struct QuerySyntaxError {
+    /// Исходный запрос пользователя.
+    pub query: String,
+
+    /// Описание ошибки от парсера Tantivy.
+    pub message: String,
+}
+
+impl std::fmt::Display for QuerySyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Некорректный поисковый запрос \"{}\": {}", self.query, self.message)
+    }
+}
+
+impl std::error::Error for QuerySyntaxError {}
+
+/// Вариант автодополнения.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Suggestion {
+    /// Текст подсказки: название промпта или тег.
+    pub text: String,
+
+    /// Откуда взята подсказка.
+    pub kind: SuggestionKind,
+}
+
+/// Источник подсказки автодополнения.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuggestionKind {
+    /// Название промпта.
+    Title,
+
+    /// Тег промпта.
+    Tag,
+}
+
+/// Убирает повторные попадания одного и того же промпта, сохраняя порядок первых вхождений.
+/// Результаты поиска отсортированы по релевантности, поэтому у каждого промпта остается лучшее попадание.
+pub fn dedup_hits<T: Eq + Hash + Copy>(hits: impl IntoIterator<Item = T>) -> Vec<T> {
+    let mut seen = HashSet::new();
+    hits.into_iter().filter(|hit| seen.insert(*hit)).collect()
+}
+
+/// Веса полей при ранжировании результатов поиска.
+/// Совпадение в поле с большим весом поднимает документ выше в выдаче.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FieldBoosts {
+    /// Вес совпадений в названии.
+    pub title: f32,
+
+    /// Вес совпадений в тегах.
+    pub tags: f32,
+
+    /// Вес совпадений в тексте промпта.
+    pub text: f32,
+}
+
+impl Default for FieldBoosts {
+    fn default() -> Self {
+        Self {
+            title: 3.0,
+            tags: 2.0,
+            text: 1.0,
+        }
+    }
+}
+
+/// Поле с временной меткой, по которому выполняется фильтрация по диапазону.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateField {
+    /// Время создания записи.
+    CreatedAt,
+
+    /// Время последнего редактирования записи.
+    UpdatedAt,
+}
+
+impl DateField {
+    /// Имя поля в схеме индекса.
+    fn field_name(self) -> &'static str {
+        match self {
+            DateField::CreatedAt => "created_at",
+            DateField::UpdatedAt => "updated_at",
+        }
+    }
+}
+
+/// Диапазон дат для поиска, границы включительные.
+/// Значения в формате UNIX (секунды с эпохи Unix); `None` означает отсутствие границы.
+#[derive(Debug, Clone, Copy)]
+pub struct DateRange {
+    /// Поле, по которому выполняется фильтрация.
+    pub field: DateField,
+
+    /// Нижняя граница диапазона.
+    pub from: Option<u64>,
+
+    /// Верхняя граница диапазона.
+    pub to: Option<u64>,
+}
+
+impl Record {
+    /// Создаёт запись индекса из промпта.
+    ///
+    /// # Аргументы
+    /// * `id` - Идентификатор, под которым промпт будет храниться в индексе.
+    /// * `prompt` - Исходный промпт.
+    pub fn from_prompt(id: u64, prompt: &Prompt) -> Self {
+        // Сортируем теги, чтобы порядок в индексе не зависел от HashSet
+        let mut tags: Vec<String> = prompt.tags.iter().cloned().collect();
+        tags.sort();
+
+        Record {
+            id,
+            title: prompt.name.clone(),
+            tags,
+            text: prompt.content.clone(),
+            metadata: prompt.metadata_text(),
+            aliases: prompt.aliases.clone(),
+            created_at: prompt.created_at.timestamp().max(0) as u64,
+            updated_at: prompt.updated_at.timestamp().max(0) as u64,
+        }
+    }
+}
+
 /// Структура базы данных, управляющая индексом Tantivy.
 /// Эта структура обеспечивает добавление, редактирование, удаление и поиск записей в индексе.
 pub struct Database {
@@ -42,6 +312,24 @@ pub struct Database {
 
     /// Схема, определяющая поля для индекса.
     pub schema: Schema,
+
+    /// Синонимы, которыми расширяются поисковые запросы.
+    synonyms: SynonymMap,
+
+    /// Веса полей при ранжировании.
+    boosts: FieldBoosts,
+
+    /// Сколько опечаток допускается в слове запроса (0 - нечеткий поиск выключен).
+    fuzzy_distance: u8,
+
+    /// Требуется ли переиндексация из-за смены языков стемминга.
+    needs_reindex: bool,
+
+    /// Читатель индекса; его кэш блоков хранилища документов ограничен `reader_cache_blocks`.
+    reader: IndexReader,
+
+    /// Сколько блоков хранилища документов кэширует читатель.
+    reader_cache_blocks: usize,
 }
 
 impl Database {
@@ -51,58 +339,186 @@ impl Database {
     /// * `index_path` - Путь к директории, где будет храниться индекс.
     ///
     /// # Возвращает
-    /// Новый экземпляр `Database` с настроенным индексом и схемой.
-    pub fn new(index_path: &str) -> Self {
-        // Строим схему для индекса
-        let mut schema_builder = Schema::builder();
+    /// Новый экземпляр `Database` с настроенным индексом и схемой
+    /// или ошибку, если директорию индекса не удалось открыть.
+    pub fn new(index_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::with_languages(index_path, &DEFAULT_LANGUAGES)
+    }
 
+    /// Создаёт новый экземпляр базы данных со стеммингом для указанных языков.
+    ///
+    /// # Аргументы
+    /// * `index_path` - Путь к директории, где будет храниться индекс.
+    /// * `languages` - Языки, для которых применяется стемминг (в указанном порядке).
+    ///
+    /// # Описание
+    /// Список языков сохраняется рядом с индексом. Если он отличается от того, с которым
+    /// индекс был построен, `needs_reindex` вернёт `true`: старые термы построены другим
+    /// анализатором и должны быть переиндексированы.
+    pub fn with_languages(index_path: &str, languages: &[Language]) -> Result<Self, Box<dyn std::error::Error>> {
+        // Строим саму схему
+        let schema = build_schema();
+
+        let index = match Index::open_or_create(MmapDirectory::open(Path::new(index_path))?, schema.clone()) {
+            Ok(index) => index,
+            // Схема изменилась в новой версии приложения - пересоздаём индекс с нуля,
+            // содержимое восстанавливается переиндексацией промптов
+            Err(TantivyError::SchemaError(_)) => {
+                std::fs::remove_dir_all(index_path)?;
+                std::fs::create_dir_all(index_path)?;
+                Index::create_in_dir(index_path, schema.clone())?
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        // Сравниваем языки стемминга с теми, с которыми индекс был построен
+        let languages_path = Path::new(index_path).join(LANGUAGES_FILE_NAME);
+        let current_languages = languages_key(languages);
+        let previous_languages = std::fs::read_to_string(&languages_path)
+            .unwrap_or_else(|_| languages_key(&DEFAULT_LANGUAGES));
+        let needs_reindex = previous_languages.trim() != current_languages;
+        std::fs::write(&languages_path, &current_languages)?;
+
+        Self::from_index(index, schema, languages, needs_reindex)
+    }
+
+    /// Создаёт базу данных с индексом только в памяти.
+    ///
+    /// # Аргументы
+    /// * `languages` - Языки, для которых применяется стемминг (в указанном порядке).
+    ///
+    /// # Описание
+    /// Используется, пока файл промптов зашифрован: хранимые поля и термы индекса
+    /// не должны попадать на диск открытым текстом. Индекс пуст, его заполняет переиндексация.
+    pub fn in_memory(languages: &[Language]) -> Result<Self, Box<dyn std::error::Error>> {
+        let schema = build_schema();
+        let index = Index::create_in_ram(schema.clone());
+        Self::from_index(index, schema, languages, false)
+    }
+
+    /// Регистрирует в индексе токенизаторы для указанных языков стемминга.
+    fn from_index(index: Index, schema: Schema, languages: &[Language], needs_reindex: bool) -> Result<Self, Box<dyn std::error::Error>> {
         // Регистрируем токенизаторы
         let tokenizer_manager = TokenizerManager::default();
 
         // Создаем мультиязычный токенизатор
-        let multilang_tokenizer = TextAnalyzer::builder(SimpleTokenizer::default())
+        let mut multilang_builder = TextAnalyzer::builder(SimpleTokenizer::default())
             .filter(RemoveLongFilter::limit(40))  // Ограничиваем длину токенов
             .filter(LowerCaser)  // Приводим к нижнему регистру
-            .filter(Stemmer::new(Language::Russian))  // Стемминг для русского
-            .filter(Stemmer::new(Language::English))  // Стемминг для английского
-            .build();
+            .dynamic();
+        for language in languages {
+            multilang_builder = multilang_builder.filter_dynamic(Stemmer::new(*language));  // Стемминг для каждого языка
+        }
+        let multilang_tokenizer = multilang_builder.build();
 
         tokenizer_manager.register("multilang", multilang_tokenizer.clone());
 
-        // Настраиваем индексацию для текстовых полей
-        let text_indexing = TextFieldIndexing::default()
-            .set_tokenizer("multilang")  // Используем мультиязычный токенизатор
-            .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+        // Токенизатор для автодополнения: префиксы (edge n-grams) всей строки в нижнем регистре
+        let suggest_tokenizer = TextAnalyzer::builder(NgramTokenizer::prefix_only(1, SUGGEST_MAX_GRAM)?)
+            .filter(LowerCaser)
+            .build();
 
-        let text_options = TextOptions::default()
-            .set_indexing_options(text_indexing)
-            .set_stored();
+        // Токенизатор псевдонимов: значение целиком в нижнем регистре
+        let alias_tokenizer = TextAnalyzer::builder(RawTokenizer::default())
+            .filter(LowerCaser)
+            .build();
 
-        let tag_indexing = TextFieldIndexing::default()
-            .set_tokenizer("raw")  // Для тегов используем raw токенизатор
-            .set_index_option(IndexRecordOption::Basic);
+        // Применяем токенизатор к индексу
+        index.tokenizers().register("multilang", multilang_tokenizer);
+        index.tokenizers().register("suggest", suggest_tokenizer);
+        index.tokenizers().register("alias", alias_tokenizer);
 
-        let tag_options = TextOptions::default()
-            .set_indexing_options(tag_indexing)
-            .set_stored();
+        let reader = build_reader(&index, DEFAULT_READER_CACHE_BLOCKS)?;
 
-        // Добавляем поля с оптимизированными настройками
-        schema_builder.add_u64_field("id", INDEXED | STORED);  // Уникальный идентификатор
-        schema_builder.add_text_field("title", text_options.clone());  // Полнотекстовый поиск по заголовку
-        schema_builder.add_text_field("tags", tag_options);  // Точный поиск по тегам
-        schema_builder.add_text_field("text", text_options);  // Полнотекстовый поиск по содержимому
-        schema_builder.add_u64_field("created_at", STORED);  // Только хранение
-        schema_builder.add_u64_field("updated_at", STORED);  // Только хранение
+        // Возвращаем структуру базы данных с индексом и схемой
+        Ok(Database {
+            index,
+            schema,
+            synonyms: SynonymMap::default(),
+            boosts: FieldBoosts::default(),
+            fuzzy_distance: 0,
+            needs_reindex,
+            reader,
+            reader_cache_blocks: DEFAULT_READER_CACHE_BLOCKS,
+        })
+    }
 
-        // Строим саму схему
-        let schema = schema_builder.build();
+    /// Возвращает поисковик по последней зафиксированной версии индекса.
+    ///
+    /// # Описание
+    /// Читатель перечитывается перед каждым поиском, поэтому видит записи,
+    /// зафиксированные любым writer'ом, в том числе созданным вне `Database`.
+    fn searcher(&self) -> tantivy::Result<Searcher> {
+        self.reader.reload()?;
+        Ok(self.reader.searcher())
+    }
 
-        // Применяем токенизатор к индексу
-        let index = Index::open_or_create(MmapDirectory::open(Path::new(index_path)).unwrap(), schema.clone()).unwrap();
-        index.tokenizers().register("multilang", multilang_tokenizer);
+    /// Задаёт, сколько блоков хранилища документов кэширует читатель индекса.
+    ///
+    /// # Аргументы
+    /// * `blocks` - Количество блоков (0 - не кэшировать).
+    pub fn set_reader_cache_blocks(&mut self, blocks: usize) -> Result<(), Box<dyn std::error::Error>> {
+        self.reader = build_reader(&self.index, blocks)?;
+        self.reader_cache_blocks = blocks;
+        Ok(())
+    }
 
-        // Возвращаем структуру базы данных с индексом и схемой
-        Database { index, schema }
+    /// Возвращает заполненность кэша читателя индекса.
+    ///
+    /// # Описание
+    /// Объём оценивается по размеру блока хранилища документов; вытеснения Tantivy не считает.
+    pub fn reader_cache_stats(&self) -> CacheStats {
+        let stats = self.reader.searcher().doc_store_cache_stats();
+        CacheStats {
+            entries: stats.num_entries,
+            bytes: stats.num_entries * DOC_STORE_BLOCK_BYTES,
+            hits: stats.cache_hits as u64,
+            misses: stats.cache_misses as u64,
+            evictions: 0,
+            max_entries: self.reader_cache_blocks,
+            max_bytes: self.reader_cache_blocks * DOC_STORE_BLOCK_BYTES,
+        }
+    }
+
+    /// Возвращает `true`, если индекс построен с другими языками стемминга
+    /// и его содержимое нужно переиндексировать.
+    pub fn needs_reindex(&self) -> bool {
+        self.needs_reindex
+    }
+
+    /// Возвращает количество документов в индексе.
+    pub fn num_docs(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        Ok(self.searcher()?.num_docs())
+    }
+
+    /// Возвращает объём, занимаемый индексом (сегменты, отображённые в память), в байтах.
+    pub fn space_usage(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        let searcher = self.searcher()?;
+        Ok(searcher.space_usage()?.total().get_bytes())
+    }
+
+    /// Задаёт синонимы, которыми будут расширяться поисковые запросы.
+    ///
+    /// # Аргументы
+    /// * `synonyms` - Словарь синонимов, например построенный из настроек пользователя.
+    pub fn set_synonyms(&mut self, synonyms: SynonymMap) {
+        self.synonyms = synonyms;
+    }
+
+    /// Задаёт веса полей при ранжировании результатов поиска.
+    ///
+    /// # Аргументы
+    /// * `boosts` - Веса названия, тегов и текста.
+    pub fn set_field_boosts(&mut self, boosts: FieldBoosts) {
+        self.boosts = boosts;
+    }
+
+    /// Задаёт допустимое число опечаток в словах запроса.
+    ///
+    /// # Аргументы
+    /// * `distance` - Расстояние Левенштейна (0 - только точные совпадения, не больше 2).
+    pub fn set_fuzzy_distance(&mut self, distance: u8) {
+        self.fuzzy_distance = distance;
     }
 
     /// Добавляет новую запись в индекс базы данных.
@@ -116,25 +532,86 @@ impl Database {
     /// Эта функция добавляет новый документ в индекс с указанием времени создания и редактирования.
     pub fn add_record(&self, record: Record) -> Result<(), Box<dyn std::error::Error>> {
         // Создаём writer для записи данных в индекс
-        let mut index_writer = self.index.writer(50_000_000).expect("Failed to create writer");
+        let mut index_writer: IndexWriter = self.index.writer(50_000_000)?;
+
+        // Добавляем документ в индекс
+        index_writer.add_document(self.record_to_document(record))?;
+
+        // Сохраняем изменения в индексе
+        index_writer.commit()?;
+        
+        Ok(())
+    }
+
+    /// Добавляет в индекс сразу несколько записей.
+    ///
+    /// # Аргументы
+    /// * `records` - Список записей для добавления.
+    ///
+    /// # Описание
+    /// В отличие от `add_record`, все документы добавляются одним writer'ом и фиксируются
+    /// одним коммитом, что на порядки быстрее при индексации тысяч промптов.
+    pub fn add_records(&self, records: Vec<Record>) -> Result<(), Box<dyn std::error::Error>> {
+        let mut index_writer: IndexWriter = self.index.writer(50_000_000)?;
+
+        for record in records {
+            index_writer.add_document(self.record_to_document(record))?;
+        }
+
+        index_writer.commit()?;
+
+        Ok(())
+    }
+
+    /// Полностью заменяет содержимое индекса переданными записями.
+    ///
+    /// # Аргументы
+    /// * `records` - Новый набор записей.
+    ///
+    /// # Описание
+    /// Удаление старых документов и добавление новых фиксируются одним коммитом,
+    /// поэтому читатели никогда не увидят индекс в промежуточном состоянии.
+    pub fn replace_all_records(&self, records: Vec<Record>) -> Result<(), Box<dyn std::error::Error>> {
+        let mut index_writer: IndexWriter = self.index.writer(50_000_000)?;
 
-        // Создаём документ для записи в индекс
-        let doc = doc!(
+        index_writer.delete_all_documents()?;
+        for record in records {
+            index_writer.add_document(self.record_to_document(record))?;
+        }
+
+        index_writer.commit()?;
+
+        Ok(())
+    }
+
+    /// Преобразует запись в документ Tantivy согласно схеме индекса.
+    fn record_to_document(&self, record: Record) -> TantivyDocument {
+        let suggest_field = self.schema.get_field("suggest").unwrap();
+        let aliases_field = self.schema.get_field("aliases").unwrap();
+
+        // Для автодополнения индексируем название с каждого слова и каждый тег отдельно,
+        // чтобы префиксы совпадали не только с началом строки, но и с началом любого слова
+        let mut suggest_values = title_word_suffixes(&record.title);
+        suggest_values.extend(record.tags.iter().cloned());
+
+        let mut doc = doc!(
             self.schema.get_field("id").unwrap() => record.id,               // Добавляем идентификатор
             self.schema.get_field("title").unwrap() => record.title,         // Добавляем название
             self.schema.get_field("tags").unwrap() => record.tags.join(","), // Добавляем теги как строку
             self.schema.get_field("text").unwrap() => record.text,           // Добавляем текст
+            self.schema.get_field("metadata").unwrap() => record.metadata,   // Добавляем метаданные
             self.schema.get_field("created_at").unwrap() => record.created_at,      // Добавляем время создания
             self.schema.get_field("updated_at").unwrap() => record.updated_at,      // Добавляем время редактирования
         );
 
-        // Добавляем документ в индекс
-        index_writer.add_document(doc).expect("Failed to add document");
+        for value in suggest_values {
+            doc.add_text(suggest_field, value);
+        }
+        for alias in record.aliases {
+            doc.add_text(aliases_field, alias);
+        }
 
-        // Сохраняем изменения в индексе
-        index_writer.commit().expect("Failed to commit changes");
-        
-        Ok(())
+        doc
     }
 
     /// Обновляет существующую запись в индексе.
@@ -148,13 +625,12 @@ impl Database {
     /// Эта функция обновляет текст и теги для записи с заданным идентификатором, а также обновляет время редактирования.
     pub fn update_record(&self, id: u64, new_text: Option<&str>, new_tags: Option<Vec<String>>) -> Result<(), Box<dyn std::error::Error>> {
         // Создаём writer для записи данных в индекс
-        let mut index_writer = self.index.writer(50_000_000).expect("Failed to create writer");
+        let mut index_writer = self.index.writer(50_000_000)?;
 
         // Получаем текущее время для обновления записи
-        let updated_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let updated_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
-        let reader = self.index.reader().expect("Failed to create searcher");
-        let searcher = reader.searcher();
+        let searcher = self.searcher()?;
 
         let id_field = self.schema.get_field("id").unwrap();
         let query = TermQuery::new(
@@ -162,10 +638,10 @@ impl Database {
             tantivy::schema::IndexRecordOption::Basic
         );
 
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(1)).expect("Search failed");
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(1))?;
         
         if let Some((_, doc_addr)) = top_docs.first() {
-            let retrieved_doc: tantivy::TantivyDocument = searcher.doc(*doc_addr).unwrap();
+            let retrieved_doc: tantivy::TantivyDocument = searcher.doc(*doc_addr)?;
             
             // Извлекаем существующие значения
             let current_title = retrieved_doc
@@ -192,6 +668,16 @@ impl Database {
                 })
                 .unwrap_or_default();
 
+            let metadata = retrieved_doc
+                .get_first(self.schema.get_field("metadata").unwrap())
+                .and_then(|val| match val {
+                    OwnedValue::Str(s) => Some(s.to_string()),
+                    _ => None
+                })
+                .unwrap_or_default();
+
+            let aliases = stored_strings(&retrieved_doc, self.schema.get_field("aliases").unwrap());
+
             let created_at = retrieved_doc
                 .get_first(self.schema.get_field("created_at").unwrap())
                 .and_then(|val| match val {
@@ -203,23 +689,26 @@ impl Database {
             let tags = new_tags.unwrap_or_else(|| {
                 current_tags.split(',')
                     .map(|s| s.to_string())
+                    .filter(|s| !s.is_empty())
                     .collect()
             });
 
             let text = new_text.unwrap_or(&current_text);
 
-            let doc = doc!(
-                self.schema.get_field("id").unwrap() => id,
-                self.schema.get_field("title").unwrap() => current_title,
-                self.schema.get_field("tags").unwrap() => tags.join(","),
-                self.schema.get_field("text").unwrap() => text,
-                self.schema.get_field("created_at").unwrap() => *created_at,
-                self.schema.get_field("updated_at").unwrap() => updated_at
-            );
+            let doc = self.record_to_document(Record {
+                id,
+                title: current_title,
+                tags,
+                text: text.to_string(),
+                metadata,
+                aliases,
+                created_at: *created_at,
+                updated_at,
+            });
 
             index_writer.delete_term(tantivy::Term::from_field_u64(id_field, id));
-            index_writer.add_document(doc).expect("Failed to add updated document");
-            index_writer.commit().expect("Failed to commit changes");
+            index_writer.add_document(doc)?;
+            index_writer.commit()?;
             
             Ok(())
         } else {
@@ -236,13 +725,13 @@ impl Database {
     /// Эта функция удаляет документ из индекса по заданному идентификатору.
     pub fn delete_record(&self, id: u64) -> Result<(), Box<dyn std::error::Error>> {
         // Создаём writer для записи данных в индекс
-        let mut index_writer: IndexWriter = self.index.writer(50_000_000).expect("Failed to create writer");
+        let mut index_writer: IndexWriter = self.index.writer(50_000_000)?;
 
         let id_field = self.schema.get_field("id").unwrap();
 
         // Удаление по точному совпадению идентификатора
         index_writer.delete_term(tantivy::Term::from_field_u64(id_field, id));
-        index_writer.commit().expect("Failed to commit changes");
+        index_writer.commit()?;
 
         Ok(())
     }
@@ -258,35 +747,211 @@ impl Database {
     /// # Описание
     /// Эта функция выполняет поиск по полям `title`, `text` и `tags` и возвращает 5 первых совпадений.
     pub fn search(&self, query: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-        // Создаём парсер для запроса по полям title, text и tags
-        let query_parser = QueryParser::for_index(&self.index, vec![
-            self.schema.get_field("title").unwrap(),  // Поле для поиска в заголовках
-            self.schema.get_field("text").unwrap(),   // Поле для поиска в тексте
-            self.schema.get_field("tags").unwrap(),   // Поле для поиска по тегам
-        ]);
+        self.search_with_range(query, None)
+    }
 
-        // Парсим запрос
-        let query = query_parser.parse_query(query).expect("Failed to parse query");
+    /// Выполняет поиск с ограничением по диапазону дат и возвращает 5 первых совпадений.
+    ///
+    /// # Аргументы
+    /// * `query` - Строка поиска; пустая строка означает "все записи".
+    /// * `range` - Необязательный диапазон по `created_at` или `updated_at`.
+    ///
+    /// # Возвращает
+    /// Вектор строк, содержащих совпавшие фрагменты текста.
+    ///
+    /// # Описание
+    /// Диапазон проверяется самим индексом по FAST-полям, поэтому запросы вида
+    /// "обновлённые за последние 30 дней" не требуют фильтрации результатов после поиска.
+    pub fn search_with_range(&self, query: &str, range: Option<DateRange>) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let query = self.build_query(query, range)?;
 
         // Создаём объект для поиска
-        let searcher = self.index.reader().expect("Failed to create searcher").searcher();
+        let searcher = self.searcher()?;
 
         // Выполняем поиск и получаем 5 лучших совпадений
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(5)).expect("Search failed");
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(5))?;
 
-        let results = top_docs.into_iter().map(|(_, doc_addr)| {
-            let doc: tantivy::TantivyDocument = searcher.doc(doc_addr).unwrap();
-            doc.get_first(self.schema.get_field("text").unwrap())
-                .and_then(|val| match val {
-                    OwnedValue::Str(s) => Some(s.to_string()),
-                    _ => None
-                })
-                .unwrap_or_default()
-        }).collect();
+        let mut results = Vec::with_capacity(top_docs.len());
+        for (_, doc_addr) in top_docs {
+            let doc: tantivy::TantivyDocument = searcher.doc(doc_addr)?;
+            results.push(self.get_str(&doc, "text"));
+        }
 
         Ok(results)
     }
 
+    /// Выполняет поиск и возвращает идентификаторы найденных записей в порядке релевантности.
+    ///
+    /// # Аргументы
+    /// * `query` - Строка поиска; пустая строка означает "все записи".
+    /// * `range` - Необязательный диапазон дат.
+    /// * `limit` - Максимальное количество результатов.
+    pub fn search_ids(&self, query: &str, range: Option<DateRange>, limit: usize) -> Result<Vec<u64>, Box<dyn std::error::Error>> {
+        let query = self.build_query(query, range)?;
+        let searcher = self.searcher()?;
+        let id_field = self.schema.get_field("id").unwrap();
+
+        // TopDocs не принимает нулевой лимит
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit.max(1)))?;
+
+        let mut ids = Vec::with_capacity(top_docs.len());
+        for (_, doc_addr) in top_docs {
+            let doc: TantivyDocument = searcher.doc(doc_addr)?;
+            if let Some(OwnedValue::U64(id)) = doc.get_first(id_field) {
+                ids.push(*id);
+            }
+        }
+
+        // Один промпт может быть представлен несколькими документами (например, после add_record
+        // без удаления старой версии); документы отсортированы по релевантности, оставляем лучший
+        Ok(dedup_hits(ids))
+    }
+
+    /// Возвращает названия промптов и теги, начинающиеся с введённого префикса.
+    ///
+    /// # Аргументы
+    /// * `prefix` - Введённая пользователем часть слова.
+    /// * `limit` - Максимальное количество подсказок.
+    ///
+    /// # Описание
+    /// Поиск идёт по полю `suggest` с префиксами (edge n-grams) названий и тегов,
+    /// поэтому выполняется точным совпадением терма без перебора словаря.
+    /// Название подходит, если префикс совпадает с началом любого его слова.
+    pub fn suggest(&self, prefix: &str, limit: usize) -> Result<Vec<Suggestion>, Box<dyn std::error::Error>> {
+        let prefix = prefix.trim().to_lowercase();
+        if prefix.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        // В индексе хранятся префиксы не длиннее SUGGEST_MAX_GRAM символов
+        let indexed_prefix: String = prefix.chars().take(SUGGEST_MAX_GRAM).collect();
+        let query = TermQuery::new(
+            tantivy::Term::from_field_text(self.schema.get_field("suggest").unwrap(), &indexed_prefix),
+            IndexRecordOption::Basic,
+        );
+
+        let searcher = self.searcher()?;
+        // Берём документы с запасом: один документ может дать несколько подсказок или ни одной
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit.saturating_mul(4)))?;
+
+        let mut seen = HashSet::new();
+        let mut suggestions = Vec::new();
+
+        for (_, doc_addr) in top_docs {
+            let doc: TantivyDocument = searcher.doc(doc_addr)?;
+            let title = self.get_str(&doc, "title");
+            let tags = self.get_str(&doc, "tags");
+
+            let title_matches = title_word_suffixes(&title).iter()
+                .any(|value| value.to_lowercase().starts_with(&prefix));
+            if title_matches && seen.insert((SuggestionKind::Title, title.clone())) {
+                suggestions.push(Suggestion { text: title, kind: SuggestionKind::Title });
+            }
+
+            for tag in tags.split(',').filter(|tag| tag.to_lowercase().starts_with(&prefix)) {
+                if seen.insert((SuggestionKind::Tag, tag.to_string())) {
+                    suggestions.push(Suggestion { text: tag.to_string(), kind: SuggestionKind::Tag });
+                }
+            }
+
+            if suggestions.len() >= limit {
+                break;
+            }
+        }
+
+        suggestions.truncate(limit);
+        Ok(suggestions)
+    }
+
+    /// Извлекает строковое значение поля из документа.
+    fn get_str(&self, doc: &TantivyDocument, field: &str) -> String {
+        doc.get_first(self.schema.get_field(field).unwrap())
+            .and_then(|val| match val {
+                OwnedValue::Str(s) => Some(s.to_string()),
+                _ => None
+            })
+            .unwrap_or_default()
+    }
+
+    /// Разбирает пользовательский запрос, не падая на синтаксических ошибках.
+    ///
+    /// # Описание
+    /// Сначала запрос разбирается как есть (с расширением синонимами). Если в нём есть
+    /// ошибка синтаксиса, например `title:(`, служебные символы убираются и запрос
+    /// разбирается как набор обычных слов, тоже с синонимами. Если и это не удалось,
+    /// возвращается `QuerySyntaxError`.
+    fn parse_lenient(&self, query_parser: &QueryParser, query: &str) -> Result<Box<dyn Query>, Box<dyn std::error::Error>> {
+        let parse_error = match query_parser.parse_query(&self.synonyms.expand_query(query)) {
+            Ok(parsed) => return Ok(parsed),
+            Err(e) => e,
+        };
+
+        // Оставляем только слова, без синтаксиса запросов
+        let plain_query = strip_query_syntax(query);
+        if !plain_query.is_empty() {
+            if let Ok(parsed) = query_parser.parse_query(&self.synonyms.expand_query(&plain_query)) {
+                return Ok(parsed);
+            }
+        }
+
+        Err(Box::new(QuerySyntaxError {
+            query: query.to_string(),
+            message: parse_error.to_string(),
+        }))
+    }
+
+    /// Строит запрос Tantivy из строки поиска и диапазона дат.
+    fn build_query(&self, query: &str, range: Option<DateRange>) -> Result<Box<dyn Query>, Box<dyn std::error::Error>> {
+        let text_query: Box<dyn Query> = if query.trim().is_empty() {
+            Box::new(AllQuery)
+        } else {
+            let title_field = self.schema.get_field("title").unwrap();
+            let text_field = self.schema.get_field("text").unwrap();
+            let tags_field = self.schema.get_field("tags").unwrap();
+            let metadata_field = self.schema.get_field("metadata").unwrap();
+            let aliases_field = self.schema.get_field("aliases").unwrap();
+
+            // Создаём парсер для запроса по полям title, text, tags, metadata и aliases
+            let mut query_parser = QueryParser::for_index(&self.index, vec![
+                title_field,     // Поле для поиска в заголовках
+                text_field,      // Поле для поиска в тексте
+                tags_field,      // Поле для поиска по тегам
+                metadata_field,  // Поле для поиска по автору, источнику и заметкам
+                aliases_field,   // Поле для поиска по коротким кодам
+            ]);
+
+            // Название и теги важнее длинного текста, метаданные весят как текст
+            query_parser.set_field_boost(title_field, self.boosts.title);
+            query_parser.set_field_boost(tags_field, self.boosts.tags);
+            query_parser.set_field_boost(text_field, self.boosts.text);
+            query_parser.set_field_boost(metadata_field, self.boosts.text);
+            // Совпадение с псевдонимом важнее любых других полей
+            query_parser.set_field_boost(aliases_field, ALIAS_BOOST);
+
+            // Псевдонимы - короткие коды, опечатки в них не прощаются
+            if self.fuzzy_distance > 0 {
+                for field in [title_field, text_field, tags_field, metadata_field] {
+                    query_parser.set_field_fuzzy(field, false, self.fuzzy_distance, true);
+                }
+            }
+
+            self.parse_lenient(&query_parser, query)?
+        };
+
+        let Some(range) = range else {
+            return Ok(text_query);
+        };
+
+        let lower = range.from.map_or(Bound::Unbounded, Bound::Included);
+        let upper = range.to.map_or(Bound::Unbounded, Bound::Included);
+        let range_query = RangeQuery::new_u64_bounds(range.field.field_name().to_string(), lower, upper);
+
+        Ok(Box::new(BooleanQuery::new(vec![
+            (Occur::Must, text_query),
+            (Occur::Must, Box::new(range_query)),
+        ])))
+    }
+
     /// Получает конкретную запись по её идентификатору.
     ///
     /// # Аргументы
@@ -298,8 +963,7 @@ impl Database {
     /// # Описание
     /// Эта функция выполняет поиск записи по её идентификатору и возвращает соответствующие данные.
     pub fn get_record_by_id(&self, id: u64) -> Result<Option<Record>, Box<dyn std::error::Error>> {
-        let reader = self.index.reader().expect("Failed to create searcher");
-        let searcher = reader.searcher();
+        let searcher = self.searcher()?;
 
         let id_field = self.schema.get_field("id").unwrap();
         let query = TermQuery::new(
@@ -308,7 +972,7 @@ impl Database {
         );
 
         // Получаем топ-1 результат
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(1)).expect("Search failed");
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(1))?;
         
         // Если запись найдена, извлекаем её данные
         if let Some((_, doc_addr)) = top_docs.first() {
@@ -338,6 +1002,13 @@ impl Database {
                         _ => None
                     })
                     .unwrap_or_default(),
+                metadata: doc.get_first(self.schema.get_field("metadata").unwrap())
+                    .and_then(|val| match val {
+                        OwnedValue::Str(s) => Some(s.to_string()),
+                        _ => None
+                    })
+                    .unwrap_or_default(),
+                aliases: stored_strings(&doc, self.schema.get_field("aliases").unwrap()),
                 created_at: *doc.get_first(self.schema.get_field("created_at").unwrap())
                     .and_then(|val| match val {
                         OwnedValue::U64(t) => Some(t),
@@ -356,3 +1027,33 @@ impl Database {
         }
     }
 }
+
+/// Возвращает все строковые значения поля сохраненного документа.
+fn stored_strings(doc: &TantivyDocument, field: tantivy::schema::Field) -> Vec<String> {
+    doc.get_all(field)
+        .filter_map(|val| match val {
+            OwnedValue::Str(s) => Some(s.to_string()),
+            _ => None
+        })
+        .collect()
+}
+
+/// Возвращает название, начиная с каждого слова: "Commit message" -> ["Commit message", "message"].
+/// Используется для автодополнения по началу любого слова в названии.
+fn title_word_suffixes(title: &str) -> Vec<String> {
+    title.char_indices()
+        .filter(|(i, c)| !c.is_whitespace() && (*i == 0 || title[..*i].ends_with(char::is_whitespace)))
+        .map(|(i, _)| title[i..].to_string())
+        .collect()
+}
+
+/// Убирает из запроса служебные символы синтаксиса Tantivy, оставляя только слова.
+/// Например, `title:(foo` превращается в `title foo`.
+fn strip_query_syntax(query: &str) -> String {
+    query.chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
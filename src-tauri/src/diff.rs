@@ -0,0 +1,257 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::parameters::ParameterSpec;
+use crate::prompt::Prompt;
+
+/// Сколько неизмененных строк показывать вокруг изменений
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Equal,
+    Removed,
+    Added,
+}
+
+/// Строка сравнения вместе с видом изменения
+struct Op<'a> {
+    change: Change,
+    text: &'a str,
+}
+
+/// Построчное сравнение через наибольшую общую подпоследовательность
+/// Общие начало и конец отбрасываются заранее, поэтому таблица строится только для измененной середины
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Op<'a>> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    // table[i][j] - длина общей подпоследовательности a[i..] и b[j..]
+    let width = b.len() + 1;
+    let mut table = vec![0u32; (a.len() + 1) * width];
+    for (i, line_a) in a.iter().enumerate().rev() {
+        for (j, line_b) in b.iter().enumerate().rev() {
+            table[i * width + j] = if line_a == line_b {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+
+    let equal = |text: &&'a str| Op { change: Change::Equal, text: *text };
+    let mut ops: Vec<Op<'a>> = old[..prefix].iter().map(equal).collect();
+
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            ops.push(Op { change: Change::Equal, text: a[i] });
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+            ops.push(Op { change: Change::Removed, text: a[i] });
+            i += 1;
+        } else {
+            ops.push(Op { change: Change::Added, text: b[j] });
+            j += 1;
+        }
+    }
+    ops.extend(a[i..].iter().map(|text| Op { change: Change::Removed, text: *text }));
+    ops.extend(b[j..].iter().map(|text| Op { change: Change::Added, text: *text }));
+    ops.extend(old[old.len() - suffix..].iter().map(equal));
+
+    ops
+}
+
+/// Для каждой строки `old` - номер совпавшей с ней строки `new`, если она не изменилась
+/// Номера возрастают, поэтому по ним можно выравнивать несколько версий одного текста
+pub(crate) fn matched_lines(old: &[&str], new: &[&str]) -> Vec<Option<usize>> {
+    let mut matches = Vec::with_capacity(old.len());
+    let mut new_index = 0;
+
+    for op in diff_lines(old, new) {
+        match op.change {
+            Change::Equal => {
+                matches.push(Some(new_index));
+                new_index += 1;
+            }
+            Change::Removed => matches.push(None),
+            Change::Added => new_index += 1,
+        }
+    }
+
+    matches
+}
+
+/// Диапазон строк в заголовке фрагмента: "начало,количество"
+fn hunk_range(before: usize, count: usize) -> String {
+    let start = if count == 0 { before } else { before + 1 };
+    if count == 1 {
+        start.to_string()
+    } else {
+        format!("{},{}", start, count)
+    }
+}
+
+/// Построчная разница текстов в формате unified diff
+/// Возвращает пустую строку, если тексты совпадают
+pub fn unified_diff(old: &str, new: &str, from_label: &str, to_label: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = diff_lines(&old_lines, &new_lines);
+
+    // Фрагменты - диапазоны операций с изменениями и контекстом; близкие фрагменты сливаются
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (index, op) in ops.iter().enumerate() {
+        if op.change == Change::Equal {
+            continue;
+        }
+
+        let start = index.saturating_sub(CONTEXT_LINES);
+        let end = (index + CONTEXT_LINES + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    if hunks.is_empty() {
+        return String::new();
+    }
+
+    let count = |ops: &[Op], skip: Change| ops.iter().filter(|op| op.change != skip).count();
+
+    let mut output = format!("--- {}\n+++ {}\n", from_label, to_label);
+    for (start, end) in hunks {
+        let hunk = &ops[start..end];
+        output.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(count(&ops[..start], Change::Added), count(hunk, Change::Added)),
+            hunk_range(count(&ops[..start], Change::Removed), count(hunk, Change::Removed)),
+        ));
+
+        for op in hunk {
+            let marker = match op.change {
+                Change::Equal => ' ',
+                Change::Removed => '-',
+                Change::Added => '+',
+            };
+            output.push(marker);
+            output.push_str(op.text);
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+/// Добавленные и удаленные элементы множества (тегов или категорий)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SetDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl SetDiff {
+    fn new(old: &HashSet<String>, new: &HashSet<String>) -> Self {
+        let mut added: Vec<String> = new.difference(old).cloned().collect();
+        let mut removed: Vec<String> = old.difference(new).cloned().collect();
+        added.sort();
+        removed.sort();
+        Self { added, removed }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Параметр, объявленный в обеих версиях, но с разными типом, значением по умолчанию или описанием
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParameterChange {
+    pub before: ParameterSpec,
+    pub after: ParameterSpec,
+}
+
+/// Различия в объявлениях параметров
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ParameterDiff {
+    pub added: Vec<ParameterSpec>,
+    pub removed: Vec<ParameterSpec>,
+    pub changed: Vec<ParameterChange>,
+}
+
+impl ParameterDiff {
+    fn new(old: &[ParameterSpec], new: &[ParameterSpec]) -> Self {
+        let old_by_name: HashMap<&str, &ParameterSpec> = old.iter().map(|spec| (spec.name.as_str(), spec)).collect();
+        let new_by_name: HashMap<&str, &ParameterSpec> = new.iter().map(|spec| (spec.name.as_str(), spec)).collect();
+
+        let mut diff = Self::default();
+        for spec in new {
+            match old_by_name.get(spec.name.as_str()) {
+                None => diff.added.push(spec.clone()),
+                Some(before) if *before != spec => diff.changed.push(ParameterChange {
+                    before: (*before).clone(),
+                    after: spec.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        diff.removed = old
+            .iter()
+            .filter(|spec| !new_by_name.contains_key(spec.name.as_str()))
+            .cloned()
+            .collect();
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Различия двух промптов или двух ревизий одного промпта
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PromptDiff {
+    /// Подпись старой стороны сравнения
+    pub from: String,
+
+    /// Подпись новой стороны сравнения
+    pub to: String,
+
+    /// Разница текстов в формате unified diff; пустая, если тексты совпадают
+    pub content: String,
+
+    pub tags: SetDiff,
+    pub categories: SetDiff,
+    pub parameters: ParameterDiff,
+}
+
+impl PromptDiff {
+    /// Нет ли различий ни в тексте, ни в метаданных
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty() && self.tags.is_empty() && self.categories.is_empty() && self.parameters.is_empty()
+    }
+}
+
+/// Сравнивает два промпта: текст построчно, теги, категории и параметры поэлементно
+pub fn diff_prompts(old: &Prompt, new: &Prompt, from_label: &str, to_label: &str) -> PromptDiff {
+    PromptDiff {
+        from: from_label.to_string(),
+        to: to_label.to_string(),
+        content: unified_diff(&old.content, &new.content, from_label, to_label),
+        tags: SetDiff::new(&old.tags, &new.tags),
+        categories: SetDiff::new(&old.categories, &new.categories),
+        parameters: ParameterDiff::new(&old.parameters, &new.parameters),
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::prompt::PromptList;
+
+/// Сколько слов подряд образуют один шингл
+const SHINGLE_WORDS: usize = 3;
+
+/// Пара похожих промптов
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicatePair {
+    /// Название первого промпта (по порядку в библиотеке)
+    pub first: String,
+
+    /// Название второго промпта
+    pub second: String,
+
+    /// Сходство текстов от 0 до 1; 1 - тексты совпадают с точностью до регистра и пробелов
+    pub similarity: f64,
+}
+
+/// Слова текста в нижнем регистре без знаков препинания
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '{' && c != '}')
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Множество шинглов - последовательностей из SHINGLE_WORDS слов
+/// Короткие тексты дают один шингл из всех слов
+pub fn shingles(text: &str) -> HashSet<String> {
+    let words = words(text);
+    if words.len() <= SHINGLE_WORDS {
+        return if words.is_empty() { HashSet::new() } else { HashSet::from([words.join(" ")]) };
+    }
+
+    words.windows(SHINGLE_WORDS).map(|window| window.join(" ")).collect()
+}
+
+/// Коэффициент Жаккара двух множеств шинглов
+pub fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 0.0;
+    }
+
+    let intersection = a.intersection(b).count();
+    let union = a.len() + b.len() - intersection;
+    intersection as f64 / union as f64
+}
+
+/// Находит пары промптов, тексты которых похожи не меньше чем на `threshold` (от 0 до 1)
+/// Пары отсортированы от самых похожих; промпты с пустым текстом не сравниваются
+pub fn find_duplicates(list: &PromptList, threshold: f64) -> Vec<DuplicatePair> {
+    let sets: Vec<HashSet<String>> = list.prompts.iter().map(|prompt| shingles(&prompt.content)).collect();
+
+    let mut pairs = Vec::new();
+    for (i, a) in sets.iter().enumerate() {
+        for (j, b) in sets.iter().enumerate().skip(i + 1) {
+            // Размеры множеств ограничивают сходство сверху, поэтому заведомо разные тексты пропускаем
+            let (small, large) = (a.len().min(b.len()), a.len().max(b.len()));
+            if small == 0 || (small as f64 / large as f64) < threshold {
+                continue;
+            }
+
+            let similarity = similarity(a, b);
+            if similarity >= threshold {
+                pairs.push(DuplicatePair {
+                    first: list.prompts[i].name.clone(),
+                    second: list.prompts[j].name.clone(),
+                    similarity,
+                });
+            }
+        }
+    }
+
+    pairs.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    pairs
+}
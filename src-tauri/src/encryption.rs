@@ -0,0 +1,165 @@
+//! Шифрование файла промптов: AES-256-GCM с ключом из парольной фразы (Argon2id).
+//!
+//! Зашифрованный файл - одна текстовая строка `PROMPTTOOL-ENCRYPTED:v1:<соль>:<nonce>:<шифротекст>`
+//! (части в base64), поэтому его можно хранить в git и синхронизировать как обычный текст.
+//! Ключи разблокированных файлов хранятся только в памяти процесса; `load_prompts` и
+//! `save_prompts` расшифровывают и шифруют такие файлы прозрачно.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+
+use crate::error::{PromptToolError, Result};
+
+/// Начало зашифрованного файла
+pub const ENCRYPTED_PREFIX: &str = "PROMPTTOOL-ENCRYPTED:v1:";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+/// Ключи разблокированных файлов: путь -> ключ
+static UNLOCKED: Mutex<BTreeMap<PathBuf, FileKey>> = Mutex::new(BTreeMap::new());
+
+/// Ключ файла вместе с солью, из которой он получен
+#[derive(Clone)]
+pub struct FileKey {
+    salt: [u8; SALT_LEN],
+    key: [u8; KEY_LEN],
+}
+
+impl FileKey {
+    /// Получает ключ из парольной фразы и соли
+    pub fn derive(passphrase: &str, salt: [u8; SALT_LEN]) -> Result<Self> {
+        if passphrase.is_empty() {
+            return Err(PromptToolError::Validation("Парольная фраза не может быть пустой".to_string()));
+        }
+
+        let mut key = [0u8; KEY_LEN];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| PromptToolError::Config(format!("Не удалось получить ключ: {}", e)))?;
+        Ok(Self { salt, key })
+    }
+
+    /// Новый ключ со случайной солью, для первого шифрования файла
+    pub fn generate(passphrase: &str) -> Result<Self> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self::derive(passphrase, salt)
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.key))
+    }
+}
+
+impl Drop for FileKey {
+    fn drop(&mut self) {
+        self.key.fill(0);
+    }
+}
+
+/// Зашифрован ли файл с таким содержимым
+pub fn is_encrypted(contents: &str) -> bool {
+    contents.starts_with(ENCRYPTED_PREFIX)
+}
+
+fn decode(part: &str) -> Result<Vec<u8>> {
+    STANDARD.decode(part.trim())
+        .map_err(|_| PromptToolError::Config("Поврежденный зашифрованный файл".to_string()))
+}
+
+/// Соль, nonce и шифротекст зашифрованного файла
+fn parse(contents: &str) -> Result<([u8; SALT_LEN], Vec<u8>, Vec<u8>)> {
+    let body = contents.trim().strip_prefix(ENCRYPTED_PREFIX)
+        .ok_or_else(|| PromptToolError::Config("Файл не зашифрован".to_string()))?;
+
+    let parts: Vec<&str> = body.split(':').collect();
+    let [salt, nonce, ciphertext] = parts[..] else {
+        return Err(PromptToolError::Config("Поврежденный зашифрованный файл".to_string()));
+    };
+
+    let salt: [u8; SALT_LEN] = decode(salt)?
+        .try_into()
+        .map_err(|_| PromptToolError::Config("Поврежденный зашифрованный файл".to_string()))?;
+    let nonce = decode(nonce)?;
+    if nonce.len() != NONCE_LEN {
+        return Err(PromptToolError::Config("Поврежденный зашифрованный файл".to_string()));
+    }
+
+    Ok((salt, nonce, decode(ciphertext)?))
+}
+
+/// Шифрует содержимое файла; каждый вызов использует новый nonce
+pub fn encrypt(plaintext: &str, key: &FileKey) -> Result<String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = key.cipher()
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| PromptToolError::Config("Не удалось зашифровать файл".to_string()))?;
+
+    Ok(format!(
+        "{}{}:{}:{}\n",
+        ENCRYPTED_PREFIX,
+        STANDARD.encode(key.salt),
+        STANDARD.encode(nonce),
+        STANDARD.encode(ciphertext)
+    ))
+}
+
+/// Расшифровывает содержимое файла
+pub fn decrypt(contents: &str, key: &FileKey) -> Result<String> {
+    let (salt, nonce, ciphertext) = parse(contents)?;
+    if salt != key.salt {
+        return Err(PromptToolError::Validation("Файл зашифрован другим ключом".to_string()));
+    }
+
+    let plaintext = key.cipher()
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| PromptToolError::Validation("Неверная парольная фраза или файл поврежден".to_string()))?;
+
+    String::from_utf8(plaintext)
+        .map_err(|_| PromptToolError::Config("Расшифрованный файл не является текстом".to_string()))
+}
+
+/// Получает ключ зашифрованного файла из парольной фразы и проверяет его расшифровкой
+pub fn key_for(contents: &str, passphrase: &str) -> Result<FileKey> {
+    let (salt, _, _) = parse(contents)?;
+    let key = FileKey::derive(passphrase, salt)?;
+    decrypt(contents, &key)?;
+    Ok(key)
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Доступ к ключам разблокированных файлов; если другой поток упал, держа их, возвращается ошибка состояния
+fn unlocked_keys() -> Result<MutexGuard<'static, BTreeMap<PathBuf, FileKey>>> {
+    UNLOCKED.lock().map_err(|_| {
+        PromptToolError::State("ключи шифрования: данные недоступны после сбоя в другой операции".to_string())
+    })
+}
+
+/// Запоминает ключ файла до блокировки или завершения процесса
+pub fn unlock(path: &Path, key: FileKey) -> Result<()> {
+    unlocked_keys()?.insert(normalize(path), key);
+    Ok(())
+}
+
+/// Забывает ключ файла; возвращает, был ли файл разблокирован
+pub fn lock(path: &Path) -> Result<bool> {
+    Ok(unlocked_keys()?.remove(&normalize(path)).is_some())
+}
+
+/// Ключ разблокированного файла
+pub fn unlocked_key(path: &Path) -> Result<Option<FileKey>> {
+    Ok(unlocked_keys()?.get(&normalize(path)).cloned())
+}
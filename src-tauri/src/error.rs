@@ -1,6 +1,8 @@
 use std::io;
 use thiserror::Error;
 
+use crate::messages;
+
 #[derive(Error, Debug)]
 pub enum PromptToolError {
     #[error("IO error: {0}")]
@@ -9,6 +11,9 @@ pub enum PromptToolError {
     #[error("TOML parsing error: {0}")]
     TomlParse(#[from] toml::de::Error),
 
+    #[error("Prompt file parsing error: {0}")]
+    Parse(String),
+
     #[error("Configuration error: {0}")]
     Config(String),
 
@@ -17,15 +22,79 @@ pub enum PromptToolError {
 
     #[error("Search error: {0}")]
     Search(String),
+
+    #[error("Execution error: {0}")]
+    Execution(String),
+
+    #[error("Git error: {0}")]
+    Git(#[from] git2::Error),
+
+    #[error("Resource is locked: {0}")]
+    Locked(String),
+
+    #[error("Prompt file is read-only: {0}")]
+    ReadOnly(String),
+
+    #[error("Application state is unavailable: {0}")]
+    State(String),
+
+    #[error("Prompt file was changed by another program: {0}")]
+    Conflict(String),
 }
 
 pub type Result<T> = std::result::Result<T, PromptToolError>;
 
+impl PromptToolError {
+    /// Стабильный код ошибки, по которому фронтенд выбирает текст
+    pub fn code(&self) -> &'static str {
+        match self {
+            PromptToolError::Io(_) => "io",
+            PromptToolError::TomlParse(_) => "toml_parse",
+            PromptToolError::Parse(_) => "parse",
+            PromptToolError::Config(_) => "config",
+            PromptToolError::Validation(_) => "validation",
+            PromptToolError::Search(_) => "search",
+            PromptToolError::Execution(_) => "execution",
+            PromptToolError::Git(_) => "git",
+            PromptToolError::Locked(_) => "locked",
+            PromptToolError::ReadOnly(_) => "read_only",
+            PromptToolError::State(_) => "state",
+            PromptToolError::Conflict(_) => "conflict",
+        }
+    }
+
+    /// Подробности ошибки без общего описания
+    pub fn details(&self) -> String {
+        match self {
+            PromptToolError::Io(e) => e.to_string(),
+            PromptToolError::TomlParse(e) => e.to_string(),
+            PromptToolError::Git(e) => e.message().to_string(),
+            PromptToolError::Parse(details)
+            | PromptToolError::Config(details)
+            | PromptToolError::Validation(details)
+            | PromptToolError::Search(details)
+            | PromptToolError::Execution(details)
+            | PromptToolError::Locked(details)
+            | PromptToolError::ReadOnly(details)
+            | PromptToolError::State(details)
+            | PromptToolError::Conflict(details) => details.clone(),
+        }
+    }
+}
+
+/// Фронтенду ошибка передается объектом `{ code, message, details }`;
+/// `message` - текст на языке интерфейса из настроек
 impl serde::Serialize for PromptToolError {
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        use serde::ser::SerializeStruct;
+
+        let mut error = serializer.serialize_struct("PromptToolError", 3)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", messages::error_message(self.code(), messages::language()))?;
+        error.serialize_field("details", &self.details())?;
+        error.end()
     }
 }
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::error::{PromptToolError, Result};
+use crate::launchers::generate_keywords;
+use crate::parameters::ParameterKind;
+use crate::prompt::Prompt;
+use crate::render::{find_placeholders, rewrite_placeholders};
+
+/// Префикс триггеров по умолчанию, принятый в Espanso
+pub const DEFAULT_TRIGGER_PREFIX: &str = ":";
+
+/// Файл правил Espanso (`match/*.yml`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EspansoFile {
+    pub matches: Vec<EspansoMatch>,
+}
+
+/// Правило Espanso: промпт без параметров раскрывается сразу (`replace`),
+/// с параметрами - через форму (`form`), в которой параметры стали полями `[[имя]]`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EspansoMatch {
+    pub trigger: String,
+
+    /// Название правила в меню поиска Espanso
+    pub label: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replace: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub form: Option<String>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub form_fields: BTreeMap<String, FormField>,
+}
+
+/// Настройки поля формы; текстовому полю без значения по умолчанию настройки не нужны
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FormField {
+    /// `choice` для параметров со списком значений
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+}
+
+impl FormField {
+    fn is_plain(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Поле формы для параметра: перечисления, логические значения и ограничения `allowed`
+/// становятся выпадающим списком, значение по умолчанию берется из описания параметра
+fn form_field(prompt: &Prompt, name: &str, placeholder_default: Option<&str>) -> FormField {
+    let spec = prompt.parameters.iter().find(|spec| spec.name == name);
+    let allowed = prompt.constraints.get(name).map(|constraint| constraint.allowed.clone()).unwrap_or_default();
+
+    let values = match spec.map(|spec| &spec.kind) {
+        Some(ParameterKind::Enum(values)) => values.clone(),
+        Some(ParameterKind::Bool) => vec!["true".to_string(), "false".to_string()],
+        _ => allowed,
+    };
+
+    FormField {
+        kind: (!values.is_empty()).then(|| "choice".to_string()),
+        default: placeholder_default
+            .map(str::to_string)
+            .or_else(|| spec.and_then(|spec| spec.default.clone())),
+        values,
+    }
+}
+
+/// Правило Espanso для промпта с указанным триггером
+pub fn espanso_match(prompt: &Prompt, trigger: String) -> EspansoMatch {
+    let mut rule = EspansoMatch {
+        trigger,
+        label: prompt.name.clone(),
+        replace: None,
+        form: None,
+        form_fields: BTreeMap::new(),
+    };
+
+    if find_placeholders(&prompt.content).is_empty() {
+        rule.replace = Some(prompt.content.clone());
+        return rule;
+    }
+
+    let mut fields = BTreeMap::new();
+    let layout = rewrite_placeholders(&prompt.content, str::to_string, |placeholder| {
+        fields
+            .entry(placeholder.name.to_string())
+            .or_insert_with(|| form_field(prompt, placeholder.name, placeholder.default));
+        format!("[[{}]]", placeholder.name)
+    });
+
+    fields.retain(|_, field: &mut FormField| !field.is_plain());
+    rule.form = Some(layout);
+    rule.form_fields = fields;
+    rule
+}
+
+/// Правила Espanso для промптов; триггеры собираются из тегов и названий, как ключевые слова лаунчеров
+pub fn build_matches(prompts: &[&Prompt], prefix: &str) -> EspansoFile {
+    let matches = prompts
+        .iter()
+        .zip(generate_keywords(prompts, prefix))
+        .map(|(prompt, trigger)| espanso_match(prompt, trigger))
+        .collect();
+
+    EspansoFile { matches }
+}
+
+/// Записывает правила Espanso в YAML-файл; возвращает количество правил
+pub fn export_matches(prompts: &[&Prompt], prefix: &str, path: &Path) -> Result<usize> {
+    let file = build_matches(prompts, prefix);
+    let contents = serde_yaml::to_string(&file)
+        .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации правил Espanso: {}", e)))?;
+
+    std::fs::write(path, contents)
+        .map_err(PromptToolError::Io)?;
+    Ok(file.matches.len())
+}
@@ -0,0 +1,184 @@
+//! Режим расширителя текста: `;;псевдоним`, набранный в любом приложении, заменяется текстом промпта.
+//!
+//! Нажатия клавиш читаются глобальным слушателем (rdev), замена набирается эмуляцией клавиатуры (enigo).
+//! Срабатывание проверяется по завершающей клавише - пробелу, поэтому псевдоним `cm`
+//! не мешает набрать `;;cmt`. Tab и Enter не подходят: слушатель не может их перехватить,
+//! и до замены приложение успело бы отправить сообщение или перевести фокус.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+
+/// Префикс срабатывания по умолчанию
+pub const DEFAULT_TRIGGER_PREFIX: &str = ";;";
+
+/// Сколько последних набранных символов хранится для поиска срабатывания
+const MAX_BUFFER_CHARS: usize = 128;
+
+/// Настройки расширителя текста
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpanderSettings {
+    /// Включен ли расширитель; выключенный не хранит набранные символы
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Префикс перед псевдонимом, например `;;`
+    #[serde(default = "default_trigger_prefix")]
+    pub trigger_prefix: String,
+}
+
+fn default_trigger_prefix() -> String {
+    DEFAULT_TRIGGER_PREFIX.to_string()
+}
+
+impl Default for ExpanderSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            trigger_prefix: default_trigger_prefix(),
+        }
+    }
+}
+
+impl ExpanderSettings {
+    /// Проверяет префикс: непустой и без пробельных символов, иначе он не отделяется от слова
+    pub fn validate(&self) -> Result<()> {
+        if self.trigger_prefix.is_empty() || self.trigger_prefix.chars().any(char::is_whitespace) {
+            return Err(PromptToolError::Validation(
+                "Префикс расширителя должен быть непустым и без пробелов".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Нажатие клавиши с точки зрения расширителя
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyInput {
+    /// Набранный символ
+    Char(char),
+    /// Удаление предыдущего символа
+    Backspace,
+    /// Клавиша, завершающая слово: пробел. Ее символ стирается вместе со срабатыванием
+    Terminator,
+    /// Курсор переместился (стрелки, Tab, Enter, щелчок мыши и т.п.), набранное ранее больше не перед курсором
+    Reset,
+}
+
+/// Найденное срабатывание
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trigger {
+    /// Псевдоним промпта без префикса
+    pub alias: String,
+    /// Сколько символов стереть перед вставкой: префикс, псевдоним и завершающая клавиша
+    pub erase: usize,
+}
+
+/// Последние набранные символы, в которых ищется `префикс + псевдоним`
+#[derive(Debug, Default)]
+pub struct TriggerBuffer {
+    typed: String,
+}
+
+impl TriggerBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Учитывает нажатие; на завершающей клавише возвращает срабатывание, если слово перед ней
+    /// начинается с префикса
+    pub fn push(&mut self, input: KeyInput, prefix: &str) -> Option<Trigger> {
+        match input {
+            KeyInput::Char(c) => {
+                self.typed.push(c);
+                let excess = self.typed.chars().count().saturating_sub(MAX_BUFFER_CHARS);
+                if excess > 0 {
+                    self.typed = self.typed.chars().skip(excess).collect();
+                }
+                None
+            }
+            KeyInput::Backspace => {
+                self.typed.pop();
+                None
+            }
+            KeyInput::Reset => {
+                self.typed.clear();
+                None
+            }
+            KeyInput::Terminator => {
+                let typed = std::mem::take(&mut self.typed);
+                let alias = typed.rsplit_once(prefix)?.1;
+                if alias.is_empty() {
+                    return None;
+                }
+
+                Some(Trigger {
+                    alias: alias.to_string(),
+                    erase: prefix.chars().count() + alias.chars().count() + 1,
+                })
+            }
+        }
+    }
+}
+
+/// Запускает глобальный слушатель клавиатуры; блокирует вызывающий поток до ошибки
+/// Пока `typing` установлен, события игнорируются: это нажатия, которые набирает сам расширитель
+pub fn listen<F>(typing: Arc<AtomicBool>, mut handler: F) -> Result<()>
+where
+    F: FnMut(KeyInput) + 'static,
+{
+    use rdev::{EventType, Key};
+
+    rdev::listen(move |event| {
+        if typing.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let input = match event.event_type {
+            EventType::KeyPress(Key::Backspace) => Some(KeyInput::Backspace),
+            EventType::KeyPress(Key::Space) => Some(KeyInput::Terminator),
+            EventType::KeyPress(
+                Key::Tab | Key::Return | Key::KpReturn
+                | Key::LeftArrow | Key::RightArrow | Key::UpArrow | Key::DownArrow
+                | Key::Home | Key::End | Key::PageUp | Key::PageDown | Key::Escape,
+            )
+            | EventType::ButtonPress(_) => Some(KeyInput::Reset),
+            EventType::KeyPress(_) => None,
+            _ => return,
+        };
+
+        match input {
+            Some(input) => handler(input),
+            // Для остальных клавиш берем набранный текст с учетом раскладки и Shift
+            None => {
+                for c in event.name.unwrap_or_default().chars().filter(|c| !c.is_control()) {
+                    handler(KeyInput::Char(c));
+                }
+            }
+        }
+    })
+    .map_err(|e| PromptToolError::Execution(format!("Не удалось запустить слушатель клавиатуры: {:?}", e)))
+}
+
+/// Стирает срабатывание и набирает вместо него текст
+/// На время набора устанавливает `typing`, чтобы слушатель не принял вставку за ввод пользователя
+pub fn type_replacement(typing: &AtomicBool, erase: usize, text: &str) -> Result<()> {
+    use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+
+    let error = |e: &dyn std::fmt::Display| PromptToolError::Execution(format!("Ошибка эмуляции клавиатуры: {}", e));
+
+    typing.store(true, Ordering::SeqCst);
+    let result = (|| {
+        let mut enigo = Enigo::new(&Settings::default()).map_err(|e| error(&e))?;
+        for _ in 0..erase {
+            enigo.key(Key::Backspace, Direction::Click).map_err(|e| error(&e))?;
+        }
+        enigo.text(text).map_err(|e| error(&e))
+    })();
+    typing.store(false, Ordering::SeqCst);
+
+    result
+}
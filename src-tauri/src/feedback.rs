@@ -0,0 +1,130 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+
+/// Сколько разных запросов хранить; при переполнении забываются запросы с наименьшим числом выборов
+pub const MAX_QUERIES: usize = 500;
+
+/// Минимальное сходство запросов (доля общих слов), при котором выборы учитываются
+pub const MIN_QUERY_SIMILARITY: f64 = 0.5;
+
+/// Вес выборов относительно исходного порядка выдачи: при этом весе второй результат
+/// обгоняет первый примерно после двух выборов
+const FEEDBACK_WEIGHT: f64 = 0.5;
+
+/// Слова запроса в нижнем регистре
+fn query_words(query: &str) -> HashSet<String> {
+    query.split_whitespace().map(str::to_lowercase).collect()
+}
+
+/// Запрос в виде ключа хранилища: слова в нижнем регистре через один пробел
+fn normalize(query: &str) -> String {
+    query.split_whitespace().map(str::to_lowercase).collect::<Vec<_>>().join(" ")
+}
+
+/// Какие промпты пользователь выбирал в результатах поиска по запросам
+/// Хранится в директории данных, поэтому у каждого профиля (--config-dir) свое хранилище
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClickFeedback {
+    /// Запрос -> название промпта -> сколько раз его выбирали
+    queries: HashMap<String, HashMap<String, u32>>,
+}
+
+impl ClickFeedback {
+    /// Загружает хранилище из файла; отсутствующий или поврежденный файл дает пустое хранилище
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Сохраняет хранилище в файл
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(PromptToolError::Io)?;
+        }
+
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации выборов: {}", e)))?;
+
+        fs::write(path, contents)
+            .map_err(PromptToolError::Io)
+    }
+
+    /// Отмечает, что по запросу был выбран промпт
+    pub fn record(&mut self, query: &str, prompt_name: &str) {
+        let key = normalize(query);
+        if key.is_empty() {
+            return;
+        }
+
+        *self.queries
+            .entry(key.clone())
+            .or_default()
+            .entry(prompt_name.to_string())
+            .or_default() += 1;
+
+        if self.queries.len() > MAX_QUERIES {
+            let least_used = self.queries
+                .iter()
+                .filter(|(query, _)| **query != key)
+                .min_by_key(|(_, clicks)| clicks.values().sum::<u32>())
+                .map(|(query, _)| query.clone());
+            if let Some(query) = least_used {
+                self.queries.remove(&query);
+            }
+        }
+    }
+
+    /// Забывает все выборы
+    pub fn clear(&mut self) {
+        self.queries.clear();
+    }
+
+    /// Выборы промпта по похожим запросам, взвешенные сходством запросов
+    pub fn clicks(&self, query: &str, prompt_name: &str) -> f64 {
+        let words = query_words(query);
+        if words.is_empty() {
+            return 0.0;
+        }
+
+        self.queries
+            .iter()
+            .filter_map(|(stored, clicks)| {
+                let count = *clicks.get(prompt_name)?;
+                let stored_words = query_words(stored);
+                let common = words.intersection(&stored_words).count();
+                let similarity = common as f64 / words.union(&stored_words).count() as f64;
+                (similarity >= MIN_QUERY_SIMILARITY).then_some(similarity * count as f64)
+            })
+            .sum()
+    }
+
+    /// Переупорядочивает результаты поиска с учетом выборов
+    /// Исходное место дает оценку 1/(место+1), выборы добавляют логарифмическую прибавку,
+    /// поэтому частые выборы постепенно поднимаются, но не перекрывают релевантность полностью
+    pub fn rerank<T>(&self, query: &str, results: Vec<T>, name: impl Fn(&T) -> &str) -> Vec<T> {
+        if self.queries.is_empty() || query.trim().is_empty() {
+            return results;
+        }
+
+        let mut scored: Vec<(f64, T)> = results
+            .into_iter()
+            .enumerate()
+            .map(|(rank, result)| {
+                let boost = FEEDBACK_WEIGHT * (1.0 + self.clicks(query, name(&result))).ln();
+                (1.0 / (rank as f64 + 1.0) + boost, result)
+            })
+            .collect();
+
+        // Сортировка устойчивая: при равных оценках сохраняется исходный порядок
+        scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        scored.into_iter().map(|(_, result)| result).collect()
+    }
+}
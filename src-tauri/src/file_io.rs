@@ -1,10 +1,16 @@
 use std::fs;
 use std::path::Path;
 use std::io::Write;
-use crate::prompt::PromptList;
+use std::fmt;
+use crate::encryption;
+use crate::file_lock;
+use crate::migrations::{self, CURRENT_SCHEMA_VERSION, SCHEMA_VERSION_KEY};
+use crate::prompt::{Prompt, PromptList};
 use crate::error::{Result, PromptToolError};
+use serde::{Deserialize, Serialize};
 use toml;
-use std::fs::File;
+use toml_edit::{ImDocument, Item};
+use tempfile::NamedTempFile;
 
 /// Функция для загрузки промптов из файла.
 pub fn load_prompts(file_path: &str) -> Result<PromptList> {
@@ -19,33 +25,342 @@ pub fn load_prompts(file_path: &str) -> Result<PromptList> {
     }
 
     // Читаем содержимое файла
-    let contents = fs::read_to_string(path)
-        .map_err(PromptToolError::Io)?;
+    let contents = read_prompt_file(file_path)?;
+
+    parse_prompts(file_path, &contents)
+}
+
+/// Функция для чтения содержимого файла промптов без разбора.
+/// Пока файл читается, другой процесс не может его записать.
+pub fn read_prompt_file(file_path: &str) -> Result<String> {
+    let _lock = file_lock::lock_shared(Path::new(file_path))?;
+    fs::read_to_string(file_path)
+        .map_err(PromptToolError::Io)
+}
+
+/// Функция для разбора содержимого файла промптов.
+/// Зашифрованное содержимое расшифровывается ключом, с которым файл `file_path` был разблокирован.
+/// Ошибка сообщает строку, столбец и промпт, запись которого не удалось прочитать.
+pub fn parse_prompts(file_path: &str, contents: &str) -> Result<PromptList> {
+    let parsed = parse_prompt_entries(file_path, contents, false)?;
+    if !parsed.broken.is_empty() {
+        let details: Vec<String> = parsed.broken.iter().map(ToString::to_string).collect();
+        return Err(PromptToolError::Parse(details.join("; ")));
+    }
+
+    Ok(parsed.prompts)
+}
+
+/// Функция для разбора содержимого файла промптов в режиме восстановления.
+/// Загружает все корректные промпты и перечисляет записи, которые прочитать не удалось.
+/// При синтаксической ошибке файл разбирается по отдельным записям `[[prompts]]`.
+pub fn parse_prompts_recovering(file_path: &str, contents: &str) -> Result<RecoveredPrompts> {
+    parse_prompt_entries(file_path, contents, true)
+}
+
+/// Запись файла промптов, которую не удалось прочитать
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptDiagnostic {
+    /// Номер записи промпта в файле, начиная с 1; `None` для ошибок вне записей промптов
+    pub entry: Option<usize>,
+
+    /// Название промпта, если его удалось определить
+    pub name: Option<String>,
+
+    /// Строка, начиная с 1
+    pub line: usize,
+
+    /// Столбец, начиная с 1
+    pub column: usize,
+
+    /// Описание ошибки
+    pub message: String,
+}
+
+impl fmt::Display for PromptDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "строка {}, столбец {}", self.line, self.column)?;
+        if let Some(entry) = self.entry {
+            write!(f, ", промпт #{}", entry)?;
+        }
+        if let Some(name) = &self.name {
+            write!(f, " «{}»", name)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Результат разбора файла промптов в режиме восстановления
+#[derive(Debug)]
+pub struct RecoveredPrompts {
+    /// Промпты, которые удалось прочитать
+    pub prompts: PromptList,
+
+    /// Записи, которые прочитать не удалось
+    pub broken: Vec<PromptDiagnostic>,
+}
+
+/// Номер строки и столбца (с 1) для смещения в байтах
+pub fn line_column(contents: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(contents.len());
+    while !contents.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    let before = &contents[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+    (line, column)
+}
+
+/// Запись промпта: ее номер в файле и смещение начала
+type EntryPosition = (usize, Option<usize>);
+
+fn parse_prompt_entries(file_path: &str, contents: &str, recover: bool) -> Result<RecoveredPrompts> {
+    let contents = decrypt_contents(file_path, contents)?;
 
     // Проверяем, не пустой ли файл
     if contents.trim().is_empty() {
-        return Ok(PromptList { prompts: Vec::new() });
+        return Ok(RecoveredPrompts { prompts: PromptList::new(), broken: Vec::new() });
     }
 
-    // Преобразуем строку в структуру PromptList
-    let prompt_list: PromptList = toml::from_str(&contents)
-        .map_err(PromptToolError::TomlParse)?;
+    let mut broken = Vec::new();
+    let (mut library, positions): (toml::Table, Vec<EntryPosition>) = match parse_fragment(&contents, 0) {
+        Ok((library, starts)) => (library, starts.into_iter().enumerate().map(|(index, start)| (index + 1, start)).collect()),
+        Err((offset, message)) if !recover => {
+            return Err(PromptToolError::Parse(diagnostic(&contents, offset, None, None, message).to_string()));
+        }
+        Err(_) => recover_fragments(&contents, &mut broken),
+    };
+
+    // Файл старого формата обновляется в памяти, на диск новый формат попадет при сохранении
+    migrations::migrate_table(&mut library, chrono::Utc::now())?;
 
-    Ok(prompt_list)
+    let entries = match library.remove("prompts") {
+        Some(toml::Value::Array(entries)) => entries,
+        Some(_) => return Err(PromptToolError::Parse("`prompts` должен быть списком промптов".to_string())),
+        None => return Err(PromptToolError::Parse("В файле нет списка `prompts`".to_string())),
+    };
+
+    let mut prompts = Vec::with_capacity(entries.len());
+    for (index, value) in entries.into_iter().enumerate() {
+        let (entry, start) = positions.get(index).copied().unwrap_or((index + 1, None));
+        let name = value.get("name").and_then(toml::Value::as_str).map(str::to_string);
+        match value.try_into::<Prompt>() {
+            Ok(prompt) => prompts.push(prompt),
+            Err(error) => broken.push(diagnostic(&contents, start.unwrap_or(0), Some(entry), name, error.message().to_string())),
+        }
+    }
+
+    broken.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+    Ok(RecoveredPrompts { prompts: PromptList { prompts }, broken })
+}
+
+fn diagnostic(contents: &str, offset: usize, entry: Option<usize>, name: Option<String>, message: String) -> PromptDiagnostic {
+    let (line, column) = line_column(contents, offset);
+    PromptDiagnostic { entry, name, line, column, message }
+}
+
+/// Разбирает фрагмент файла, начинающийся со смещения `offset`
+/// Возвращает таблицу и смещения начала записей промптов либо смещение и текст синтаксической ошибки
+fn parse_fragment(fragment: &str, offset: usize) -> std::result::Result<(toml::Table, Vec<Option<usize>>), (usize, String)> {
+    let document = ImDocument::parse(fragment)
+        .map_err(|error| (offset + error.span().map_or(0, |span| span.start), error.message().to_string()))?;
+
+    let starts = match document.get("prompts") {
+        Some(Item::ArrayOfTables(tables)) => tables.iter()
+            .map(|table| table.span().map(|span| offset + span.start))
+            .collect(),
+        Some(Item::Value(toml_edit::Value::Array(array))) => array.iter()
+            .map(|value| value.span().map(|span| offset + span.start))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let library = toml::from_str(fragment)
+        .map_err(|error: toml::de::Error| (offset + error.span().map_or(0, |span| span.start), error.message().to_string()))?;
+
+    Ok((library, starts))
+}
+
+/// Разбирает файл с синтаксической ошибкой по отдельным записям `[[prompts]]`
+/// Записи с ошибками попадают в `broken`, остальные - в таблицу библиотеки
+fn recover_fragments(contents: &str, broken: &mut Vec<PromptDiagnostic>) -> (toml::Table, Vec<EntryPosition>) {
+    let mut boundaries: Vec<usize> = Vec::new();
+    let mut offset = 0;
+    for line in contents.split_inclusive('\n') {
+        if line.trim() == "[[prompts]]" {
+            boundaries.push(offset);
+        }
+        offset += line.len();
+    }
+
+    // Все до первой записи - общие ключи файла, например версия формата
+    let preamble_end = boundaries.first().copied().unwrap_or(contents.len());
+    let mut library = match parse_fragment(&contents[..preamble_end], 0) {
+        Ok((library, _)) => library,
+        Err((offset, message)) => {
+            broken.push(diagnostic(contents, offset, None, None, message));
+            toml::Table::new()
+        }
+    };
+
+    let mut entries = Vec::new();
+    let mut positions = Vec::new();
+    for (index, &start) in boundaries.iter().enumerate() {
+        let end = boundaries.get(index + 1).copied().unwrap_or(contents.len());
+        let fragment = &contents[start..end];
+
+        match parse_fragment(fragment, start) {
+            Ok((mut parsed, starts)) => {
+                if let Some(toml::Value::Array(parsed_entries)) = parsed.remove("prompts") {
+                    entries.extend(parsed_entries);
+                    positions.extend(starts.into_iter().map(|entry_start| (index + 1, entry_start.or(Some(start)))));
+                }
+            }
+            Err((offset, message)) => broken.push(diagnostic(contents, offset, Some(index + 1), fragment_name(fragment), message)),
+        }
+    }
+
+    library.insert("prompts".to_string(), toml::Value::Array(entries));
+    (library, positions)
+}
+
+/// Название промпта из записи, которую не удалось разобрать целиком
+fn fragment_name(fragment: &str) -> Option<String> {
+    fragment.lines()
+        .filter_map(|line| line.trim().strip_prefix("name"))
+        .filter_map(|rest| rest.trim_start().strip_prefix('='))
+        .map(|value| value.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+        .find(|name| !name.is_empty())
+}
+
+/// Функция для получения открытого текста файла промптов.
+/// Зашифрованное содержимое расшифровывается ключом разблокированного файла, открытое возвращается как есть.
+pub fn decrypt_contents(file_path: &str, contents: &str) -> Result<String> {
+    if !encryption::is_encrypted(contents) {
+        return Ok(contents.to_string());
+    }
+
+    let key = encryption::unlocked_key(Path::new(file_path))?
+        .ok_or_else(|| PromptToolError::Validation("Файл промптов зашифрован; сначала разблокируйте его".to_string()))?;
+    encryption::decrypt(contents, &key)
+}
+
+/// Функция для загрузки библиотеки промптов в формате TOML или JSON.
+/// Формат определяется по расширению файла, все остальные расширения читаются как TOML.
+pub fn load_library(file_path: &str) -> Result<PromptList> {
+    let is_json = Path::new(file_path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+    if !is_json {
+        return load_prompts(file_path);
+    }
+
+    let contents = fs::read_to_string(file_path)
+        .map_err(PromptToolError::Io)?;
+
+    serde_json::from_str(&contents)
+        .map_err(|e| PromptToolError::Config(format!("Ошибка разбора JSON: {}", e)))
 }
 
 /// Функция для сохранения промптов в файл.
+/// Разблокированный зашифрованный файл остается зашифрованным; заблокированный не перезаписывается.
 pub fn save_prompts(file_path: &str, prompt_list: &PromptList) -> Result<()> {
-    // Сериализуем промпты в TOML
-    let toml_string = toml::to_string_pretty(prompt_list)
+    save_prompts_with(file_path, prompt_list, |_, _| Ok(()))
+}
+
+/// Функция для сохранения промптов в файл с действием перед записью.
+/// `before_write` вызывается под исключительной блокировкой с прежним содержимым файла
+/// (`None`, если файла нет) и новым, например для проверки внешних изменений или резервной копии.
+/// Ошибка из `before_write` отменяет запись.
+pub fn save_prompts_with(
+    file_path: &str,
+    prompt_list: &PromptList,
+    before_write: impl FnOnce(Option<&str>, &str) -> Result<()>,
+) -> Result<()> {
+    // Сериализуем промпты в TOML вместе с версией формата
+    let mut library = toml::Table::try_from(prompt_list)
         .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации: {}", e)))?;
+    library.insert(SCHEMA_VERSION_KEY.to_string(), toml::Value::Integer(CURRENT_SCHEMA_VERSION as i64));
 
-    // Записываем в файл
-    let mut file = File::create(file_path)
+    let mut toml_string = toml::to_string_pretty(&library)
+        .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации: {}", e)))?;
+
+    let _lock = file_lock::lock_exclusive(Path::new(file_path))?;
+    let current = fs::read_to_string(file_path).ok();
+
+    match encryption::unlocked_key(Path::new(file_path))? {
+        Some(key) => toml_string = encryption::encrypt(&toml_string, &key)?,
+        None => {
+            // Не заменяем зашифрованный файл открытым текстом
+            if current.as_deref().is_some_and(encryption::is_encrypted) {
+                return Err(PromptToolError::Validation("Файл промптов зашифрован; сначала разблокируйте его".to_string()));
+            }
+        }
+    }
+
+    before_write(current.as_deref(), &toml_string)?;
+    write_atomic(Path::new(file_path), toml_string.as_bytes())
+}
+
+/// Функция для замены файла промптов готовым содержимым (например, полученным с сервера).
+/// Файл записывается атомарно под исключительной блокировкой.
+pub fn write_prompt_file(file_path: &str, contents: &str) -> Result<()> {
+    let _lock = file_lock::lock_exclusive(Path::new(file_path))?;
+    write_atomic(Path::new(file_path), contents.as_bytes())
+}
+
+/// Функция для проверки, можно ли записать файл промптов.
+/// Нужны права на запись самого файла (если он есть) и его директории: файл заменяется
+/// через временный файл рядом с ним.
+pub fn is_writable(path: &Path) -> bool {
+    let file_writable = match fs::OpenOptions::new().write(true).open(path) {
+        Ok(_) => true,
+        Err(e) => e.kind() == std::io::ErrorKind::NotFound,
+    };
+
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    file_writable && NamedTempFile::new_in(dir).is_ok()
+}
+
+/// Функция для атомарной записи файла.
+/// Содержимое пишется во временный файл в той же директории, сбрасывается на диск и переименовывается
+/// поверх исходного, поэтому при сбое посреди записи на диске остается либо старый, либо новый файл.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut file = NamedTempFile::new_in(dir)
+        .map_err(PromptToolError::Io)?;
+
+    // Новый файл получает права исходного, а не временного (0600)
+    if let Ok(metadata) = fs::metadata(path) {
+        file.as_file()
+            .set_permissions(metadata.permissions())
+            .map_err(PromptToolError::Io)?;
+    }
+
+    file.write_all(contents)
         .map_err(PromptToolError::Io)?;
-    
-    file.write_all(toml_string.as_bytes())
+    file.as_file()
+        .sync_all()
         .map_err(PromptToolError::Io)?;
 
+    file.persist(path)
+        .map_err(|e| PromptToolError::Io(e.error))?;
+
+    // Переименование фиксируется на диске только после сброса записи директории
+    #[cfg(unix)]
+    if let Ok(dir) = fs::File::open(dir) {
+        let _ = dir.sync_all();
+    }
+
     Ok(())
 }
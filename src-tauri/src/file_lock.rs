@@ -0,0 +1,107 @@
+//! Рекомендательные блокировки файла промптов между процессами.
+//!
+//! Файл промптов записывается через переименование временного файла, поэтому блокируется не он сам,
+//! а соседний файл `.<имя>.lock`: чтение берет разделяемую блокировку, запись - исключительную.
+//! Блокировки рекомендательные: их соблюдают другие экземпляры приложения и скрипты, которые
+//! берут блокировку того же файла (например, `flock .prompts.toml.lock`).
+
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use fs2::FileExt;
+
+use crate::error::{PromptToolError, Result};
+
+/// Сколько ждать освобождения блокировки, прежде чем вернуть ошибку
+pub const LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Пауза между попытками взять блокировку
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Вид блокировки
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    /// Чтение: несколько читателей не мешают друг другу
+    Shared,
+    /// Запись: никто другой не читает и не пишет
+    Exclusive,
+}
+
+/// Взятая блокировка; снимается при удалении
+#[derive(Debug)]
+pub struct FileLock {
+    file: Option<File>,
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if let Some(file) = &self.file {
+            let _ = file.unlock();
+        }
+    }
+}
+
+/// Путь файла блокировки: `.<имя>.lock` рядом с файлом
+pub fn lock_path(path: &Path) -> PathBuf {
+    let mut file_name = std::ffi::OsString::from(".");
+    file_name.push(path.file_name().unwrap_or_default());
+    file_name.push(".lock");
+    path.with_file_name(file_name)
+}
+
+/// Является ли `path` файлом блокировки, созданным `lock_path`
+pub fn is_lock_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.len() > ".lock".len() + 1 && name.starts_with('.') && name.ends_with(".lock"))
+}
+
+fn is_contended(error: &io::Error) -> bool {
+    error.kind() == fs2::lock_contended_error().kind()
+}
+
+/// Берет блокировку файла `path`, ожидая ее освобождения не дольше `timeout`
+/// Если блокировку держит другой процесс, возвращает `PromptToolError::Locked`.
+/// Файл в директории только для чтения читается без блокировки: записать его все равно никто не может
+pub fn lock(path: &Path, mode: LockMode, timeout: Duration) -> Result<FileLock> {
+    let file = match OpenOptions::new().read(true).write(true).create(true).truncate(false).open(lock_path(path)) {
+        Ok(file) => file,
+        Err(e) if mode == LockMode::Shared && e.kind() == io::ErrorKind::PermissionDenied => {
+            return Ok(FileLock { file: None });
+        }
+        Err(e) => return Err(PromptToolError::Io(e)),
+    };
+
+    let started = Instant::now();
+    loop {
+        let attempt = match mode {
+            LockMode::Shared => file.try_lock_shared(),
+            LockMode::Exclusive => file.try_lock_exclusive(),
+        };
+
+        match attempt {
+            Ok(()) => return Ok(FileLock { file: Some(file) }),
+            Err(e) if is_contended(&e) && started.elapsed() < timeout => thread::sleep(RETRY_INTERVAL),
+            Err(e) if is_contended(&e) => {
+                return Err(PromptToolError::Locked(format!(
+                    "Файл {} занят другим экземпляром приложения или скриптом",
+                    path.display()
+                )));
+            }
+            Err(e) => return Err(PromptToolError::Io(e)),
+        }
+    }
+}
+
+/// Блокировка для чтения с ожиданием по умолчанию
+pub fn lock_shared(path: &Path) -> Result<FileLock> {
+    lock(path, LockMode::Shared, LOCK_TIMEOUT)
+}
+
+/// Блокировка для записи с ожиданием по умолчанию
+pub fn lock_exclusive(path: &Path) -> Result<FileLock> {
+    lock(path, LockMode::Exclusive, LOCK_TIMEOUT)
+}
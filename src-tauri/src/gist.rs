@@ -0,0 +1,206 @@
+//! Публикация промптов в GitHub Gist и импорт из него.
+//!
+//! Промпты публикуются одним файлом `prompts.toml` в формате библиотеки, поэтому опубликованный
+//! Gist можно импортировать обратно без потерь. При импорте чужих Gist'ов прочие текстовые файлы
+//! превращаются в промпты с названием по имени файла.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::error::{PromptToolError, Result};
+use crate::prompt::{Prompt, PromptList};
+
+/// Имя файла с промптами внутри Gist
+pub const GIST_FILE_NAME: &str = "prompts.toml";
+
+/// GitHub отклоняет запросы без User-Agent
+const USER_AGENT: &str = "PromptTool";
+
+/// Настройки доступа к GitHub Gist
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GistSettings {
+    /// Персональный токен доступа с правом `gist`; для импорта публичных Gist'ов не обязателен
+    #[serde(default)]
+    pub token: String,
+
+    /// Публиковать ли Gist'ы публичными (по умолчанию - секретные, доступные по ссылке)
+    #[serde(default)]
+    pub public: bool,
+
+    /// Адрес API; меняется для GitHub Enterprise
+    #[serde(default = "default_api_url")]
+    pub api_url: String,
+}
+
+fn default_api_url() -> String {
+    "https://api.github.com".to_string()
+}
+
+impl Default for GistSettings {
+    fn default() -> Self {
+        Self {
+            token: String::new(),
+            public: false,
+            api_url: default_api_url(),
+        }
+    }
+}
+
+impl GistSettings {
+    fn request(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        let request = request
+            .header(reqwest::header::USER_AGENT, USER_AGENT)
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json");
+
+        if self.token.is_empty() {
+            request
+        } else {
+            request.bearer_auth(&self.token)
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.api_url.trim().trim_end_matches('/'), path)
+    }
+}
+
+/// Опубликованный Gist
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublishedGist {
+    pub id: String,
+
+    /// Ссылка на страницу Gist'а
+    #[serde(rename = "html_url")]
+    pub url: String,
+}
+
+/// Идентификатор Gist'а из ссылки вида `https://gist.github.com/user/<id>` или из самого идентификатора
+pub fn gist_id(url: &str) -> Option<String> {
+    let url = url.trim();
+    let path = url.split(['#', '?']).next()?;
+    let id = path.trim_end_matches('/').rsplit('/').next()?.trim_end_matches(".git");
+
+    (!id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric())).then(|| id.to_string())
+}
+
+/// Тело запроса на создание Gist'а с промптами
+pub fn gist_payload(prompts: &[Prompt], description: &str, public: bool) -> Result<Value> {
+    let library = PromptList { prompts: prompts.to_vec() };
+    let content = toml::to_string_pretty(&library)
+        .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации: {}", e)))?;
+
+    let mut files = Map::new();
+    files.insert(GIST_FILE_NAME.to_string(), json!({ "content": content }));
+
+    Ok(json!({
+        "description": description,
+        "public": public,
+        "files": files,
+    }))
+}
+
+/// Промпты из файлов Gist'а: файлы `.toml` читаются как библиотека,
+/// остальные превращаются в промпт с названием по имени файла без расширения
+pub fn prompts_from_files(files: &Map<String, Value>) -> Result<PromptList> {
+    let mut prompts = Vec::new();
+
+    let mut names: Vec<&String> = files.keys().collect();
+    names.sort();
+    for name in names {
+        let content = files[name]["content"].as_str().unwrap_or_default();
+
+        if name.ends_with(".toml") {
+            let library: PromptList = toml::from_str(content)?;
+            prompts.extend(library.prompts);
+        } else if !content.trim().is_empty() {
+            let title = name.rsplit_once('.').map_or(name.as_str(), |(stem, _)| stem);
+            prompts.push(Prompt::new(title.to_string(), content.to_string(), Vec::new(), HashSet::new(), HashSet::new()));
+        }
+    }
+
+    Ok(PromptList { prompts })
+}
+
+/// Проверяет ответ GitHub и читает его как JSON
+async fn read_response(response: reqwest::Response) -> Result<Value> {
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| PromptToolError::Execution(format!("Некорректный ответ GitHub ({}): {}", status, e)))?;
+
+    if !status.is_success() {
+        let message = body["message"].as_str().unwrap_or("неизвестная ошибка");
+        return Err(PromptToolError::Execution(format!("GitHub вернул ошибку {}: {}", status, message)));
+    }
+    Ok(body)
+}
+
+/// Публикует промпты новым Gist'ом
+pub async fn publish(settings: &GistSettings, prompts: &[Prompt], description: &str) -> Result<PublishedGist> {
+    if settings.token.is_empty() {
+        return Err(PromptToolError::Config("Для публикации нужен токен GitHub с правом gist".to_string()));
+    }
+
+    let url = settings.url("/gists");
+    let request = reqwest::Client::new()
+        .post(&url)
+        .json(&gist_payload(prompts, description, settings.public)?);
+
+    let response = settings.request(request)
+        .send()
+        .await
+        .map_err(|e| PromptToolError::Execution(format!("Ошибка запроса к {}: {}", url, e)))?;
+
+    serde_json::from_value(read_response(response).await?)
+        .map_err(|e| PromptToolError::Execution(format!("Некорректный ответ GitHub: {}", e)))
+}
+
+/// Загружает промпты из Gist'а по ссылке или идентификатору
+/// Содержимое больших файлов, обрезанное в ответе API, догружается по raw-ссылке
+pub async fn fetch(settings: &GistSettings, url: &str) -> Result<PromptList> {
+    let id = gist_id(url)
+        .ok_or_else(|| PromptToolError::Validation(format!("Не удалось распознать ссылку на Gist: {}", url)))?;
+
+    let client = reqwest::Client::new();
+    let api_url = settings.url(&format!("/gists/{}", id));
+    let response = settings.request(client.get(&api_url))
+        .send()
+        .await
+        .map_err(|e| PromptToolError::Execution(format!("Ошибка запроса к {}: {}", api_url, e)))?;
+
+    let body = read_response(response).await?;
+    let mut files = body["files"].as_object().cloned().unwrap_or_default();
+
+    for file in files.values_mut() {
+        if !file["truncated"].as_bool().unwrap_or(false) {
+            continue;
+        }
+        let Some(raw_url) = file["raw_url"].as_str().map(str::to_string) else {
+            continue;
+        };
+
+        let content = client.get(&raw_url)
+            .header(reqwest::header::USER_AGENT, USER_AGENT)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| PromptToolError::Execution(format!("Ошибка запроса к {}: {}", raw_url, e)))?
+            .text()
+            .await
+            .map_err(|e| PromptToolError::Execution(format!("Ошибка чтения {}: {}", raw_url, e)))?;
+        file["content"] = Value::String(content);
+    }
+
+    // Импортированные промпты помнят, откуда и от кого они получены
+    let mut library = prompts_from_files(&files)?;
+    let author = body["owner"]["login"].as_str().unwrap_or_default();
+    let source_url = body["html_url"].as_str().unwrap_or_default();
+    for prompt in &mut library.prompts {
+        prompt.fill_provenance(author, source_url);
+    }
+
+    Ok(library)
+}
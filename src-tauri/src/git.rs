@@ -0,0 +1,457 @@
+//! Синхронизация файла промптов через git.
+//!
+//! Папка с файлом промптов становится git-репозиторием: каждое сохранение фиксируется коммитом
+//! с автоматически составленным сообщением, а история, отправка и получение изменений доступны
+//! через команды приложения. Для доступа к удаленному репозиторию используются ssh-agent
+//! и credential helper из настроек git пользователя.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, TimeZone, Utc};
+use git2::build::CheckoutBuilder;
+use git2::{
+    BranchType, Commit, Cred, CredentialType, FetchOptions, FileMode, PushOptions, RemoteCallbacks, Repository,
+    RepositoryOpenFlags, Signature, Sort, Status, StatusOptions,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+use crate::file_io;
+use crate::file_lock;
+use crate::prompt::{Prompt, PromptList};
+
+/// Сколько раз запрашивать учетные данные, прежде чем сдаться
+const MAX_CREDENTIAL_ATTEMPTS: usize = 3;
+
+/// Настройки интеграции с git
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitSettings {
+    /// Фиксировать ли каждое сохранение промптов коммитом
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Удаленный репозиторий для отправки и получения изменений
+    #[serde(default = "default_remote")]
+    pub remote: String,
+
+    /// Автор коммитов, если в настройках git пользователя он не задан
+    #[serde(default = "default_author_name")]
+    pub author_name: String,
+
+    #[serde(default = "default_author_email")]
+    pub author_email: String,
+}
+
+fn default_remote() -> String {
+    "origin".to_string()
+}
+
+fn default_author_name() -> String {
+    "PromptTool".to_string()
+}
+
+fn default_author_email() -> String {
+    "prompt-tool@localhost".to_string()
+}
+
+impl Default for GitSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            remote: default_remote(),
+            author_name: default_author_name(),
+            author_email: default_author_email(),
+        }
+    }
+}
+
+/// Состояние файла в рабочей копии
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GitFileStatus {
+    /// Путь относительно корня репозитория
+    pub path: String,
+
+    /// `new`, `modified`, `deleted`, `renamed` или `conflicted`
+    pub status: &'static str,
+}
+
+/// Состояние репозитория с промптами
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GitStatus {
+    /// Текущая ветка; `None`, если HEAD не указывает на ветку
+    pub branch: Option<String>,
+
+    /// Отслеживаемая удаленная ветка
+    pub upstream: Option<String>,
+
+    /// Сколько локальных коммитов еще не отправлено
+    pub ahead: usize,
+
+    /// Сколько удаленных коммитов еще не получено
+    pub behind: usize,
+
+    /// Незафиксированные изменения
+    pub files: Vec<GitFileStatus>,
+}
+
+/// Коммит, изменивший промпт
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GitCommit {
+    pub id: String,
+    pub summary: String,
+    pub author: String,
+    pub time: DateTime<Utc>,
+}
+
+/// Результат получения изменений
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PullOutcome {
+    /// Новых коммитов нет
+    UpToDate,
+    /// Локальная ветка перемотана на удаленную
+    FastForward,
+    /// Локальная и удаленная истории разошлись; локальные коммиты остались нетронутыми
+    Diverged,
+}
+
+/// Папка, которая становится репозиторием: папка файла промптов
+fn repository_dir(prompt_file: &Path) -> PathBuf {
+    match prompt_file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Открывает репозиторий в папке файла промптов
+/// Родительские папки не просматриваются: репозиторий выше по дереву (например, с настройками
+/// в домашней папке) принадлежит пользователю, и коммиты и получение изменений его не трогают
+pub fn open_repository(prompt_file: &Path) -> Result<Repository> {
+    Repository::open_ext(repository_dir(prompt_file), RepositoryOpenFlags::NO_SEARCH, &[] as &[&std::ffi::OsStr]).map_err(|_| {
+        PromptToolError::Config(format!(
+            "Папка {} не является git-репозиторием",
+            repository_dir(prompt_file).display()
+        ))
+    })
+}
+
+/// Открывает репозиторий с файлом промптов, создавая его в папке файла при необходимости
+pub fn open_or_init(prompt_file: &Path) -> Result<Repository> {
+    match open_repository(prompt_file) {
+        Ok(repo) => Ok(repo),
+        Err(_) => Ok(Repository::init(repository_dir(prompt_file))?),
+    }
+}
+
+/// Путь файла относительно корня рабочей копии
+fn relative_path(repo: &Repository, file: &Path) -> Result<PathBuf> {
+    let workdir = repo.workdir()
+        .ok_or_else(|| PromptToolError::Config("Репозиторий без рабочей копии не поддерживается".to_string()))?
+        .canonicalize()?;
+    let file = file.canonicalize()?;
+
+    file.strip_prefix(&workdir)
+        .map(Path::to_path_buf)
+        .map_err(|_| PromptToolError::Config(format!("Файл {} вне репозитория", file.display())))
+}
+
+/// Автор коммита: из настроек git пользователя, иначе из настроек приложения
+fn signature(repo: &Repository, settings: &GitSettings) -> Result<Signature<'static>> {
+    match repo.signature() {
+        Ok(signature) => Ok(signature),
+        Err(_) => Ok(Signature::now(&settings.author_name, &settings.author_email)?),
+    }
+}
+
+/// Совпадают ли промпты по содержимому (без учета времени создания и изменения)
+fn same_prompt(a: &Prompt, b: &Prompt) -> bool {
+    a.content == b.content
+        && a.parameters == b.parameters
+        && a.constraints == b.constraints
+        && a.output == b.output
+        && a.hooks == b.hooks
+        && a.categories == b.categories
+        && a.tags == b.tags
+}
+
+/// Сообщение коммита с перечнем добавленных, измененных и удаленных промптов
+pub fn summarize_changes(previous: &PromptList, current: &PromptList) -> String {
+    let find = |list: &PromptList, name: &str| list.prompts.iter().find(|prompt| prompt.name == name).cloned();
+
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for prompt in &current.prompts {
+        match find(previous, &prompt.name) {
+            None => added.push(prompt.name.as_str()),
+            Some(before) if !same_prompt(&before, prompt) => changed.push(prompt.name.as_str()),
+            Some(_) => {}
+        }
+    }
+
+    let current_names: HashSet<&str> = current.prompts.iter().map(|prompt| prompt.name.as_str()).collect();
+    let removed: Vec<&str> = previous.prompts
+        .iter()
+        .map(|prompt| prompt.name.as_str())
+        .filter(|name| !current_names.contains(name))
+        .collect();
+
+    let parts: Vec<String> = [("Добавлено", added), ("Изменено", changed), ("Удалено", removed)]
+        .into_iter()
+        .filter(|(_, names)| !names.is_empty())
+        .map(|(label, names)| format!("{}: {}", label, names.join(", ")))
+        .collect();
+
+    if parts.is_empty() {
+        "Обновлен файл промптов".to_string()
+    } else {
+        parts.join("; ")
+    }
+}
+
+/// Библиотека промптов в том виде, в каком она была зафиксирована в коммите
+/// Зашифрованное содержимое расшифровывается ключом разблокированного файла `prompt_file`.
+fn library_at(repo: &Repository, commit: &Commit, prompt_file: &Path, relative: &Path) -> Option<PromptList> {
+    let entry = commit.tree().ok()?.get_path(relative).ok()?;
+    let blob = entry.to_object(repo).ok()?.peel_to_blob().ok()?;
+    let contents = std::str::from_utf8(blob.content()).ok()?;
+    file_io::parse_prompts(&prompt_file.to_string_lossy(), contents).ok()
+}
+
+/// Фиксирует текущее состояние файла промптов коммитом
+/// В коммит попадает только файл промптов: остальные файлы, в том числе добавленные
+/// пользователем в индекс, остаются такими, как в прошлом коммите.
+/// Возвращает идентификатор коммита или `None`, если фиксировать нечего
+pub fn commit_prompts(prompt_file: &Path, settings: &GitSettings) -> Result<Option<String>> {
+    let repo = open_or_init(prompt_file)?;
+    let relative = relative_path(&repo, prompt_file)?;
+    let file_name = match (relative.parent(), relative.file_name()) {
+        (Some(parent), Some(file_name)) if parent.as_os_str().is_empty() => file_name.to_owned(),
+        _ => return Err(PromptToolError::Config(format!("Файл {} не в корне репозитория", relative.display()))),
+    };
+
+    // Индекс обновляется только для файла промптов, чтобы git status не показывал его измененным
+    let mut index = repo.index()?;
+    index.add_path(&relative)?;
+    index.write()?;
+
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let parent_tree = parent.as_ref().map(|parent| parent.tree()).transpose()?;
+    let blob = repo.blob_path(prompt_file)?;
+    let mut builder = repo.treebuilder(parent_tree.as_ref())?;
+    builder.insert(&file_name, blob, FileMode::Blob.into())?;
+    let tree_id = builder.write()?;
+
+    if parent.as_ref().is_some_and(|parent| parent.tree_id() == tree_id) {
+        return Ok(None);
+    }
+
+    let current = file_io::parse_prompts(&prompt_file.to_string_lossy(), &fs::read_to_string(prompt_file)?)?;
+    let previous = parent.as_ref()
+        .and_then(|parent| library_at(&repo, parent, prompt_file, &relative))
+        .unwrap_or_else(PromptList::new);
+    let message = summarize_changes(&previous, &current);
+
+    let tree = repo.find_tree(tree_id)?;
+    let signature = signature(&repo, settings)?;
+    let parents: Vec<&Commit> = parent.iter().collect();
+    let id = repo.commit(Some("HEAD"), &signature, &signature, &message, &tree, &parents)?;
+
+    Ok(Some(id.to_string()))
+}
+
+/// Название состояния файла для интерфейса
+fn status_name(status: Status) -> &'static str {
+    if status.is_conflicted() {
+        "conflicted"
+    } else if status.intersects(Status::INDEX_NEW | Status::WT_NEW) {
+        "new"
+    } else if status.intersects(Status::INDEX_DELETED | Status::WT_DELETED) {
+        "deleted"
+    } else if status.intersects(Status::INDEX_RENAMED | Status::WT_RENAMED) {
+        "renamed"
+    } else {
+        "modified"
+    }
+}
+
+/// Состояние репозитория: ветка, расхождение с удаленной веткой и незафиксированные файлы
+pub fn status(prompt_file: &Path) -> Result<GitStatus> {
+    let repo = open_repository(prompt_file)?;
+
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).include_ignored(false);
+    let files = repo.statuses(Some(&mut options))?
+        .iter()
+        .filter(|entry| entry.status() != Status::CURRENT)
+        // Файлы блокировок служебные и в репозиторий не попадают
+        .filter(|entry| !entry.path().is_some_and(|path| file_lock::is_lock_file(Path::new(path))))
+        .map(|entry| GitFileStatus {
+            path: entry.path().unwrap_or_default().to_string(),
+            status: status_name(entry.status()),
+        })
+        .collect();
+
+    let branch = repo.head().ok().and_then(|head| head.shorthand().map(str::to_string));
+
+    let mut upstream = None;
+    let (mut ahead, mut behind) = (0, 0);
+    if let Some(name) = &branch {
+        if let Ok(remote_branch) = repo.find_branch(name, BranchType::Local).and_then(|local| local.upstream()) {
+            upstream = remote_branch.name().ok().flatten().map(str::to_string);
+
+            let local_id = repo.head().ok().and_then(|head| head.target());
+            if let (Some(local_id), Some(remote_id)) = (local_id, remote_branch.get().target()) {
+                (ahead, behind) = repo.graph_ahead_behind(local_id, remote_id)?;
+            }
+        }
+    }
+
+    Ok(GitStatus { branch, upstream, ahead, behind, files })
+}
+
+/// Коммиты, изменившие промпт, от новых к старым
+pub fn history(prompt_file: &Path, prompt_name: &str, limit: usize) -> Result<Vec<GitCommit>> {
+    let repo = open_repository(prompt_file)?;
+    let relative = relative_path(&repo, prompt_file)?;
+
+    let mut walk = repo.revwalk()?;
+    if walk.push_head().is_err() {
+        // В репозитории еще нет коммитов
+        return Ok(Vec::new());
+    }
+    walk.set_sorting(Sort::TIME)?;
+
+    let snapshot = |commit: &Commit| {
+        library_at(&repo, commit, prompt_file, &relative)
+            .and_then(|library| library.prompts.into_iter().find(|prompt| prompt.name == prompt_name))
+    };
+
+    let mut commits = Vec::new();
+    for id in walk {
+        if commits.len() >= limit {
+            break;
+        }
+
+        let commit = repo.find_commit(id?)?;
+        let after = snapshot(&commit);
+        let before = commit.parent(0).ok().and_then(|parent| snapshot(&parent));
+
+        let changed = match (&before, &after) {
+            (Some(before), Some(after)) => !same_prompt(before, after),
+            (None, None) => false,
+            _ => true,
+        };
+        if changed {
+            commits.push(GitCommit {
+                id: commit.id().to_string(),
+                summary: commit.summary().unwrap_or_default().to_string(),
+                author: commit.author().name().unwrap_or_default().to_string(),
+                time: Utc.timestamp_opt(commit.time().seconds(), 0).single().unwrap_or_default(),
+            });
+        }
+    }
+
+    Ok(commits)
+}
+
+/// Обработчики удаленного репозитория: учетные данные из ssh-agent или credential helper
+fn remote_callbacks<'a>() -> RemoteCallbacks<'a> {
+    let mut attempts = 0;
+    let mut callbacks = RemoteCallbacks::new();
+
+    callbacks.credentials(move |url, username, allowed| {
+        attempts += 1;
+        if attempts > MAX_CREDENTIAL_ATTEMPTS {
+            return Err(git2::Error::from_str("Не удалось авторизоваться в удаленном репозитории"));
+        }
+
+        if allowed.contains(CredentialType::SSH_KEY) {
+            return Cred::ssh_key_from_agent(username.unwrap_or("git"));
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            let config = git2::Config::open_default()?;
+            return Cred::credential_helper(&config, url, username);
+        }
+        Cred::default()
+    });
+
+    callbacks
+}
+
+/// Текущая ветка или ошибка, если HEAD не указывает на ветку
+fn current_branch(repo: &Repository) -> Result<String> {
+    repo.head()
+        .ok()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand().map(str::to_string))
+        .ok_or_else(|| PromptToolError::Config("В репозитории нет текущей ветки".to_string()))
+}
+
+/// Отправляет текущую ветку в удаленный репозиторий
+pub fn push(prompt_file: &Path, settings: &GitSettings) -> Result<()> {
+    let repo = open_repository(prompt_file)?;
+    let branch = current_branch(&repo)?;
+    let mut remote = repo.find_remote(&settings.remote)?;
+
+    let mut rejection = None;
+    {
+        let mut callbacks = remote_callbacks();
+        callbacks.push_update_reference(|_, status| {
+            rejection = status.map(str::to_string);
+            Ok(())
+        });
+
+        let mut options = PushOptions::new();
+        options.remote_callbacks(callbacks);
+
+        let refspec = format!("refs/heads/{0}:refs/heads/{0}", branch);
+        remote.push(&[refspec.as_str()], Some(&mut options))?;
+    }
+
+    match rejection {
+        Some(reason) => Err(PromptToolError::Config(format!("Удаленный репозиторий отклонил изменения: {}", reason))),
+        None => Ok(()),
+    }
+}
+
+/// Получает изменения текущей ветки из удаленного репозитория
+/// Поддерживается только перемотка вперед: если истории разошлись, ничего не меняется,
+/// и истории нужно объединить вручную
+pub fn pull(prompt_file: &Path, settings: &GitSettings) -> Result<PullOutcome> {
+    let repo = open_repository(prompt_file)?;
+    let branch = current_branch(&repo)?;
+    let mut remote = repo.find_remote(&settings.remote)?;
+
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(remote_callbacks());
+    remote.fetch(&[branch.as_str()], Some(&mut options), None)?;
+
+    let fetch_head = repo.find_reference("FETCH_HEAD")?;
+    let incoming = repo.reference_to_annotated_commit(&fetch_head)?;
+    let (analysis, _) = repo.merge_analysis(&[&incoming])?;
+
+    if analysis.is_up_to_date() {
+        return Ok(PullOutcome::UpToDate);
+    }
+    if !analysis.is_fast_forward() {
+        return Ok(PullOutcome::Diverged);
+    }
+
+    // Рабочая копия обновляется до перемотки ветки и без перезаписи: если какой-либо файл
+    // изменен и не зафиксирован, получение отменяется и ничего не меняется
+    let target = repo.find_commit(incoming.id())?;
+    repo.checkout_tree(target.as_object(), Some(CheckoutBuilder::default().safe()))
+        .map_err(|e| PromptToolError::Config(format!(
+            "В папке репозитория есть незафиксированные изменения, получение отменено: {}",
+            e.message()
+        )))?;
+
+    let refname = format!("refs/heads/{}", branch);
+    let mut reference = repo.find_reference(&refname)?;
+    reference.set_target(incoming.id(), "pull: fast-forward")?;
+    repo.set_head(&refname)?;
+
+    Ok(PullOutcome::FastForward)
+}
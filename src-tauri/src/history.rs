@@ -0,0 +1,138 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::cache::CacheStats;
+use crate::error::{PromptToolError, Result};
+
+/// Один выполненный поисковый запрос
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Текст запроса
+    pub query: String,
+
+    /// Сколько раз запрос выполнялся
+    pub count: u32,
+
+    /// Когда запрос выполнялся последний раз
+    pub last_used: DateTime<Utc>,
+}
+
+/// История поисковых запросов, сохраняемая между запусками
+/// Новые запросы добавляются в начало, при превышении лимита вытесняются самые старые
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHistory {
+    /// Записи от самой свежей к самой старой
+    entries: VecDeque<HistoryEntry>,
+
+    /// Максимальное количество хранимых запросов
+    #[serde(skip)]
+    max_entries: usize,
+}
+
+impl SearchHistory {
+    /// Создает пустую историю с указанным лимитом
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            max_entries,
+        }
+    }
+
+    /// Загружает историю из файла; отсутствующий или поврежденный файл дает пустую историю
+    pub fn load(path: &Path, max_entries: usize) -> Self {
+        let mut history: Self = fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_else(|| Self::new(max_entries));
+
+        history.set_max_entries(max_entries);
+        history
+    }
+
+    /// Сохраняет историю в файл
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(PromptToolError::Io)?;
+        }
+
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации истории: {}", e)))?;
+
+        fs::write(path, contents)
+            .map_err(PromptToolError::Io)
+    }
+
+    /// Добавляет выполненный запрос; повторный запрос поднимается в начало
+    pub fn record(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() || self.max_entries == 0 {
+            return;
+        }
+
+        let count = match self.entries.iter().position(|entry| entry.query == query) {
+            Some(position) => self.entries.remove(position).map_or(0, |entry| entry.count),
+            None => 0,
+        };
+
+        self.entries.push_front(HistoryEntry {
+            query: query.to_string(),
+            count: count + 1,
+            last_used: Utc::now(),
+        });
+        self.entries.truncate(self.max_entries);
+    }
+
+    /// Меняет лимит, сразу отбрасывая лишние записи
+    pub fn set_max_entries(&mut self, max_entries: usize) {
+        self.max_entries = max_entries;
+        self.entries.truncate(max_entries);
+    }
+
+    /// Все записи от самой свежей к самой старой
+    pub fn entries(&self) -> Vec<HistoryEntry> {
+        self.entries.iter().cloned().collect()
+    }
+
+    /// Очищает историю
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Количество записей и их приблизительный размер в памяти
+    /// История ограничена только количеством записей, поэтому `max_bytes` равен 0
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            bytes: self.entries
+                .iter()
+                .map(|entry| std::mem::size_of::<HistoryEntry>() + entry.query.capacity())
+                .sum(),
+            max_entries: self.max_entries,
+            ..CacheStats::default()
+        }
+    }
+
+    /// Прошлые запросы, начинающиеся с префикса (без учета регистра)
+    /// Сначала самые частые, при равенстве - самые свежие
+    pub fn suggest(&self, prefix: &str, limit: usize) -> Vec<String> {
+        let prefix = prefix.trim().to_lowercase();
+
+        let mut matches: Vec<(usize, &HistoryEntry)> = self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.query.to_lowercase().starts_with(&prefix))
+            .collect();
+
+        matches.sort_by(|(a_pos, a), (b_pos, b)| b.count.cmp(&a.count).then(a_pos.cmp(b_pos)));
+
+        matches.into_iter()
+            .take(limit)
+            .map(|(_, entry)| entry.query.clone())
+            .collect()
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+
+/// Имя директории со скриптами хуков внутри директории конфигурации
+pub const HOOKS_DIR_NAME: &str = "hooks";
+
+/// Сколько ждать завершения скрипта хука; зависший скрипт завершается принудительно
+pub const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Как часто проверять, завершился ли скрипт
+const HOOK_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Хуки, подключенные к промпту, по именам скриптов в директории хуков
+///
+/// Хук до подстановки получает на stdin JSON со значениями параметров и должен
+/// вывести в stdout JSON с новыми значениями. Хук после подстановки получает
+/// готовый текст и выводит измененный. Хуки выполняются в порядке перечисления
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PromptHooks {
+    /// Хуки, меняющие значения параметров перед подстановкой
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_render: Vec<String>,
+
+    /// Хуки, меняющие готовый текст
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_render: Vec<String>,
+}
+
+impl PromptHooks {
+    /// Нет ни одного хука
+    pub fn is_empty(&self) -> bool {
+        self.pre_render.is_empty() && self.post_render.is_empty()
+    }
+}
+
+/// Этап, на котором выполняется хук; передается скрипту в PROMPT_TOOL_HOOK_STAGE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    PreRender,
+    PostRender,
+}
+
+impl HookStage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookStage::PreRender => "pre-render",
+            HookStage::PostRender => "post-render",
+        }
+    }
+}
+
+/// Путь к скрипту хука; имя не может выходить за пределы директории хуков
+pub fn hook_path(dir: &Path, name: &str) -> Result<PathBuf> {
+    let valid = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\']);
+    if !valid {
+        return Err(PromptToolError::Validation(format!("Некорректное имя хука \"{}\"", name)));
+    }
+
+    let path = dir.join(name);
+    if !path.is_file() {
+        return Err(PromptToolError::Validation(format!("Хук \"{}\" не найден в {}", name, dir.display())));
+    }
+    Ok(path)
+}
+
+/// Имена скриптов в директории хуков, по алфавиту
+pub fn list_hooks(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_file())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| !name.starts_with('.'))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    names.sort();
+    names
+}
+
+/// Читает поток целиком в отдельном потоке, чтобы заполненный канал не остановил скрипт
+fn read_in_background(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<std::io::Result<Vec<u8>>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        pipe.read_to_end(&mut buffer).map(|_| buffer)
+    })
+}
+
+/// Ждет завершения потока не дольше срока; None - поток не успел завершиться и остается в фоне
+fn join_until<T>(handle: thread::JoinHandle<T>, deadline: Instant) -> Option<thread::Result<T>> {
+    while !handle.is_finished() {
+        if Instant::now() >= deadline {
+            return None;
+        }
+        thread::sleep(HOOK_POLL_INTERVAL);
+    }
+    Some(handle.join())
+}
+
+/// Запускает скрипт хука, передает ему данные на stdin и возвращает его stdout
+/// Скрипт, не завершившийся за HOOK_TIMEOUT, завершается принудительно. Тот же срок ограничивает
+/// ожидание его вывода: запущенный скриптом фоновый процесс может держать каналы открытыми
+fn run_hook(path: &Path, stage: HookStage, prompt_name: &str, input: Vec<u8>) -> Result<Vec<u8>> {
+    let hook_error = |message: String| {
+        PromptToolError::Execution(format!("Хук {} ({}): {}", path.display(), stage.as_str(), message))
+    };
+
+    let mut child = Command::new(path)
+        .env("PROMPT_TOOL_HOOK_STAGE", stage.as_str())
+        .env("PROMPT_TOOL_PROMPT", prompt_name)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| hook_error(e.to_string()))?;
+
+    // Пишем в отдельном потоке, чтобы скрипт, выводящий данные до чтения stdin, не заблокировал нас
+    let mut stdin = child.stdin.take().ok_or_else(|| hook_error("нет stdin".to_string()))?;
+    let writer = thread::spawn(move || stdin.write_all(&input));
+    let stdout = read_in_background(child.stdout.take().ok_or_else(|| hook_error("нет stdout".to_string()))?);
+    let stderr = read_in_background(child.stderr.take().ok_or_else(|| hook_error("нет stderr".to_string()))?);
+
+    let deadline = Instant::now() + HOOK_TIMEOUT;
+    let status = loop {
+        match child.try_wait().map_err(|e| hook_error(e.to_string()))? {
+            Some(status) => break status,
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(hook_error(format!("не завершился за {} с", HOOK_TIMEOUT.as_secs())));
+            }
+            None => thread::sleep(HOOK_POLL_INTERVAL),
+        }
+    };
+    // Скрипт может не читать stdin целиком; ошибка записи важна, только если он завершился неудачно
+    let _ = join_until(writer, deadline);
+
+    let read = |reader: thread::JoinHandle<std::io::Result<Vec<u8>>>| {
+        join_until(reader, deadline)
+            .ok_or_else(|| hook_error(format!(
+                "вывод не закрыт за {} с: его удерживает запущенный скриптом процесс",
+                HOOK_TIMEOUT.as_secs()
+            )))?
+            .map_err(|_| hook_error("поток чтения вывода завершился аварийно".to_string()))?
+            .map_err(|e| hook_error(e.to_string()))
+    };
+    let stdout = read(stdout)?;
+    let stderr = read(stderr)?;
+
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&stderr);
+        return Err(hook_error(format!("завершился с кодом {}: {}", status, stderr.trim())));
+    }
+    Ok(stdout)
+}
+
+/// Выполняет хуки до подстановки: каждый получает и возвращает значения параметров в JSON
+pub fn run_pre_render(
+    dir: &Path,
+    hooks: &PromptHooks,
+    prompt_name: &str,
+    mut values: HashMap<String, String>,
+) -> Result<HashMap<String, String>> {
+    for name in &hooks.pre_render {
+        let path = hook_path(dir, name)?;
+        let input = serde_json::to_vec(&values)
+            .map_err(|e| PromptToolError::Execution(format!("Ошибка сериализации параметров: {}", e)))?;
+
+        let output = run_hook(&path, HookStage::PreRender, prompt_name, input)?;
+        values = serde_json::from_slice(&output).map_err(|e| {
+            PromptToolError::Execution(format!("Хук \"{}\" вернул некорректный JSON параметров: {}", name, e))
+        })?;
+    }
+    Ok(values)
+}
+
+/// Выполняет хуки после подстановки: каждый получает и возвращает готовый текст
+pub fn run_post_render(dir: &Path, hooks: &PromptHooks, prompt_name: &str, mut text: String) -> Result<String> {
+    for name in &hooks.post_render {
+        let path = hook_path(dir, name)?;
+        let output = run_hook(&path, HookStage::PostRender, prompt_name, text.into_bytes())?;
+
+        text = String::from_utf8(output)
+            .map_err(|_| PromptToolError::Execution(format!("Хук \"{}\" вернул текст не в UTF-8", name)))?;
+    }
+    Ok(text)
+}
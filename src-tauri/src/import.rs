@@ -0,0 +1,420 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{PromptToolError, Result};
+use crate::file_io::load_prompts;
+use crate::parameters::ParameterSpec;
+use crate::prompt::{Prompt, PromptList};
+
+// Возможные имена колонок/ключей для каждого поля промпта.
+// Имена сравниваются после нормализации (нижний регистр, без `_` и `-`).
+const NAME_KEYS: &[&str] = &["name", "title", "act"];
+const CONTENT_KEYS: &[&str] = &["content", "prompt", "text", "body"];
+const PARAMETERS_KEYS: &[&str] = &["parameters", "params"];
+const CATEGORIES_KEYS: &[&str] = &["categories", "category"];
+const TAGS_KEYS: &[&str] = &["tags", "tag", "keywords"];
+const CREATED_KEYS: &[&str] = &["createdat", "created", "creationdate", "datecreated"];
+const UPDATED_KEYS: &[&str] = &["updatedat", "updated", "modified", "modifiedat", "lastmodified", "datemodified"];
+
+/// Категория по умолчанию для промптов из списка awesome-chatgpt-prompts (prompts.chat)
+pub const AWESOME_PROMPTS_CATEGORY: &str = "awesome-chatgpt-prompts";
+
+/// Тег промптов, отмеченных в списке awesome-chatgpt-prompts как полезные разработчикам
+pub const AWESOME_DEV_TAG: &str = "dev";
+
+/// Источник списка awesome-chatgpt-prompts, записывается в импортированные промпты
+pub const AWESOME_PROMPTS_URL: &str = "https://github.com/f/awesome-chatgpt-prompts";
+
+/// Лицензия промптов из списка awesome-chatgpt-prompts
+pub const AWESOME_PROMPTS_LICENSE: &str = "CC0-1.0";
+
+// Значения больше этого порога считаются миллисекундами, а не секундами
+// (1e11 секунд - это уже 5138 год)
+const EPOCH_MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// Импортирует промпты из файла, выбирая формат по расширению (`.json` или `.csv`).
+pub fn import_file(file_path: &str) -> Result<PromptList> {
+    let path = Path::new(file_path);
+    let extension = path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default();
+
+    let contents = fs::read_to_string(path)
+        .map_err(PromptToolError::Io)?;
+
+    match extension.as_str() {
+        "json" => import_json(&contents),
+        "csv" => import_csv(&contents),
+        _ => Err(PromptToolError::Config(format!("Неподдерживаемый формат импорта: {}", file_path))),
+    }
+}
+
+/// Куда записываются имена папок при импорте директории
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FolderTarget {
+    /// Имена папок становятся тегами
+    #[default]
+    Tags,
+    /// Имена папок становятся категориями
+    Categories,
+    /// Имена папок добавляются и в теги, и в категории
+    Both,
+}
+
+/// Настройки вывода метаданных из структуры папок при импорте директории
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FolderMapping {
+    /// Куда записывать имена папок
+    #[serde(default)]
+    pub target: FolderTarget,
+
+    /// Переименование папок, например `"dev" -> "development"`
+    /// Пустое значение означает, что папка не превращается в метаданные
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+}
+
+impl FolderMapping {
+    /// Возвращает имя метки для папки с учетом переименований
+    /// Поиск в `aliases` выполняется сначала по точному имени, затем без учета регистра
+    fn label_for(&self, folder: &str) -> Option<String> {
+        let label = self.aliases.get(folder)
+            .or_else(|| self.aliases.get(&folder.to_lowercase()))
+            .cloned()
+            .unwrap_or_else(|| folder.to_string());
+
+        let label = label.trim().to_string();
+        if label.is_empty() { None } else { Some(label) }
+    }
+
+    /// Добавляет метки папок к промпту
+    fn apply(&self, prompt: &mut Prompt, folders: &[String]) {
+        for label in folders.iter().filter_map(|folder| self.label_for(folder)) {
+            if matches!(self.target, FolderTarget::Tags | FolderTarget::Both) {
+                prompt.tags.insert(label.clone());
+            }
+            if matches!(self.target, FolderTarget::Categories | FolderTarget::Both) {
+                prompt.categories.insert(label);
+            }
+        }
+    }
+}
+
+/// Рекурсивно импортирует все файлы промптов из директории.
+///
+/// Поддерживаются `.toml` (формат приложения), `.json`, `.csv`, а также `.md`/`.txt`,
+/// где файл целиком становится одним промптом с именем по имени файла.
+/// Имена папок между `root` и файлом превращаются в теги/категории согласно `mapping`.
+/// Скрытые файлы и папки (начинающиеся с `.`) пропускаются.
+pub fn import_directory(root: &str, mapping: &FolderMapping) -> Result<PromptList> {
+    let root = Path::new(root);
+    if !root.is_dir() {
+        return Err(PromptToolError::Config(format!("Директория не найдена: {}", root.display())));
+    }
+
+    let mut prompts = Vec::new();
+    collect_directory(root, &mut Vec::new(), mapping, &mut prompts)?;
+
+    Ok(PromptList { prompts })
+}
+
+fn collect_directory(
+    dir: &Path,
+    folders: &mut Vec<String>,
+    mapping: &FolderMapping,
+    prompts: &mut Vec<Prompt>,
+) -> Result<()> {
+    // Сортируем записи, чтобы порядок импорта не зависел от файловой системы
+    let mut entries = fs::read_dir(dir)
+        .map_err(PromptToolError::Io)?
+        .collect::<std::io::Result<Vec<_>>>()
+        .map_err(PromptToolError::Io)?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if file_name.starts_with('.') {
+            continue;
+        }
+
+        if path.is_dir() {
+            folders.push(file_name);
+            collect_directory(&path, folders, mapping, prompts)?;
+            folders.pop();
+            continue;
+        }
+
+        let mut imported = match import_path(&path)? {
+            Some(list) => list.prompts,
+            None => continue,
+        };
+
+        for prompt in imported.iter_mut() {
+            mapping.apply(prompt, folders);
+        }
+        prompts.extend(imported);
+    }
+
+    Ok(())
+}
+
+/// Импортирует один файл из директории; для неподдерживаемых расширений возвращает `None`
+fn import_path(path: &Path) -> Result<Option<PromptList>> {
+    let extension = path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default();
+    let path_str = path.to_string_lossy();
+
+    let list = match extension.as_str() {
+        "toml" => load_prompts(&path_str)?,
+        "json" | "csv" => import_file(&path_str)?,
+        "md" | "txt" => {
+            let content = fs::read_to_string(path).map_err(PromptToolError::Io)?;
+            let name = path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            PromptList {
+                prompts: vec![Prompt::new(name, content, Vec::new(), HashSet::new(), HashSet::new())],
+            }
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(list))
+}
+
+/// Импортирует промпты из JSON.
+/// Поддерживается как массив объектов, так и объект с ключом `prompts`.
+pub fn import_json(contents: &str) -> Result<PromptList> {
+    let value: Value = serde_json::from_str(contents)
+        .map_err(|e| PromptToolError::Config(format!("Ошибка разбора JSON: {}", e)))?;
+
+    let items = match value {
+        Value::Array(items) => items,
+        Value::Object(mut object) => match object.remove("prompts") {
+            Some(Value::Array(items)) => items,
+            _ => return Err(PromptToolError::Config("В JSON не найден массив prompts".to_string())),
+        },
+        _ => return Err(PromptToolError::Config("Ожидался массив промптов".to_string())),
+    };
+
+    let prompts = items
+        .into_iter()
+        .filter_map(|item| match item {
+            Value::Object(object) => Some(normalize_keys(object.into_iter())),
+            _ => None,
+        })
+        .map(|fields| prompt_from_fields(&fields))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(PromptList { prompts })
+}
+
+/// Импортирует промпты из CSV с заголовком.
+/// Списки (теги, категории, параметры) внутри ячейки разделяются `,` или `;`.
+pub fn import_csv(contents: &str) -> Result<PromptList> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+
+    let headers = reader.headers()
+        .map_err(|e| PromptToolError::Config(format!("Ошибка разбора CSV: {}", e)))?
+        .clone();
+
+    let mut prompts = Vec::new();
+    for row in reader.records() {
+        let row = row.map_err(|e| PromptToolError::Config(format!("Ошибка разбора CSV: {}", e)))?;
+
+        let fields = normalize_keys(
+            headers.iter()
+                .zip(row.iter())
+                .map(|(header, cell)| (header.to_string(), Value::String(cell.to_string())))
+        );
+
+        prompts.push(prompt_from_fields(&fields)?);
+    }
+
+    Ok(PromptList { prompts })
+}
+
+/// Импортирует CSV списка awesome-chatgpt-prompts (prompts.chat) с колонками `act,prompt`.
+/// `act` становится названием, каждому промпту назначается категория `category`, если она задана;
+/// строки с `for_devs = TRUE` (в новых версиях списка) получают тег `dev`; источник и лицензия списка
+/// записываются в метаданные промптов.
+/// Строки, чье название уже есть в `existing` или встречалось выше, пропускаются,
+/// поэтому повторный импорт обновленного списка добавляет только новые промпты
+pub fn import_awesome_csv(contents: &str, category: Option<&str>, existing: &PromptList) -> Result<PromptList> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+
+    let headers = reader.headers()
+        .map_err(|e| PromptToolError::Config(format!("Ошибка разбора CSV: {}", e)))?
+        .clone();
+    let column = |name: &str| headers.iter().position(|header| header.trim().eq_ignore_ascii_case(name));
+    let (Some(act), Some(text)) = (column("act"), column("prompt")) else {
+        return Err(PromptToolError::Config("Ожидался CSV с колонками act и prompt".to_string()));
+    };
+    let for_devs = column("for_devs");
+
+    let category = category.map(str::trim).filter(|category| !category.is_empty());
+    let mut names: HashSet<String> = existing.prompts.iter().map(|prompt| prompt.name.trim().to_lowercase()).collect();
+
+    let mut prompts = Vec::new();
+    for row in reader.records() {
+        let row = row.map_err(|e| PromptToolError::Config(format!("Ошибка разбора CSV: {}", e)))?;
+
+        let name = row.get(act).unwrap_or_default().trim();
+        if name.is_empty() || !names.insert(name.to_lowercase()) {
+            continue;
+        }
+
+        let mut tags = HashSet::new();
+        if for_devs.and_then(|index| row.get(index)).is_some_and(|value| value.trim().eq_ignore_ascii_case("true")) {
+            tags.insert(AWESOME_DEV_TAG.to_string());
+        }
+
+        let mut prompt = Prompt::new(
+            name.to_string(),
+            row.get(text).unwrap_or_default().trim().to_string(),
+            Vec::new(),
+            category.into_iter().map(str::to_string).collect(),
+            tags,
+        );
+        prompt.source_url = AWESOME_PROMPTS_URL.to_string();
+        prompt.license = AWESOME_PROMPTS_LICENSE.to_string();
+        prompts.push(prompt);
+    }
+
+    Ok(PromptList { prompts })
+}
+
+/// Разбирает временную метку из внешнего формата.
+///
+/// Поддерживаются:
+/// * RFC 3339 / ISO 8601 (`2024-01-31T12:00:00Z`, `2024-01-31T12:00:00+03:00`)
+/// * дата и время без часового пояса (`2024-01-31 12:00:00`, `2024-01-31T12:00:00`) - считается UTC
+/// * только дата (`2024-01-31`)
+/// * UNIX-время в секундах или миллисекундах, числом или строкой
+pub fn parse_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::Number(number) => number.as_i64()
+            .or_else(|| number.as_f64().map(|f| f as i64))
+            .and_then(from_epoch),
+        Value::String(text) => parse_timestamp_str(text.trim()),
+        _ => None,
+    }
+}
+
+fn parse_timestamp_str(text: &str) -> Option<DateTime<Utc>> {
+    if text.is_empty() {
+        return None;
+    }
+
+    if let Ok(epoch) = text.parse::<i64>() {
+        return from_epoch(epoch);
+    }
+
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(text) {
+        return Some(date_time.with_timezone(&Utc));
+    }
+
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(text, format) {
+            return Some(Utc.from_utc_datetime(&naive));
+        }
+    }
+
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|naive| Utc.from_utc_datetime(&naive))
+}
+
+fn from_epoch(epoch: i64) -> Option<DateTime<Utc>> {
+    if epoch.abs() >= EPOCH_MILLIS_THRESHOLD {
+        DateTime::from_timestamp_millis(epoch)
+    } else {
+        DateTime::from_timestamp(epoch, 0)
+    }
+}
+
+/// Приводит ключи к единому виду, чтобы `createdAt`, `created_at` и `Created-At` совпадали
+fn normalize_keys(fields: impl Iterator<Item = (String, Value)>) -> HashMap<String, Value> {
+    fields
+        .map(|(key, value)| {
+            let key = key.chars()
+                .filter(|c| *c != '_' && *c != '-' && !c.is_whitespace())
+                .collect::<String>()
+                .to_lowercase();
+            (key, value)
+        })
+        .collect()
+}
+
+fn find_field<'a>(fields: &'a HashMap<String, Value>, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter().find_map(|key| fields.get(*key))
+}
+
+fn string_field(fields: &HashMap<String, Value>, keys: &[&str]) -> Option<String> {
+    match find_field(fields, keys)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+fn list_field(fields: &HashMap<String, Value>, keys: &[&str]) -> Vec<String> {
+    match find_field(fields, keys) {
+        Some(Value::Array(items)) => items.iter()
+            .filter_map(|item| match item {
+                Value::String(s) => Some(s.trim().to_string()),
+                _ => None,
+            })
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Some(Value::String(s)) => s.split([',', ';'])
+            .map(|part| part.trim().to_string())
+            .filter(|part| !part.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn prompt_from_fields(fields: &HashMap<String, Value>) -> Result<Prompt> {
+    let name = string_field(fields, NAME_KEYS)
+        .filter(|name| !name.trim().is_empty())
+        .ok_or_else(|| PromptToolError::Validation("У импортируемого промпта нет названия".to_string()))?;
+    let content = string_field(fields, CONTENT_KEYS).unwrap_or_default();
+
+    let mut prompt = Prompt::new(
+        name,
+        content,
+        list_field(fields, PARAMETERS_KEYS).into_iter().map(ParameterSpec::from).collect(),
+        list_field(fields, CATEGORIES_KEYS).into_iter().collect::<HashSet<_>>(),
+        list_field(fields, TAGS_KEYS).into_iter().collect::<HashSet<_>>(),
+    );
+
+    // Сохраняем историю: если во внешнем файле есть временные метки, используем их
+    let created_at = find_field(fields, CREATED_KEYS).and_then(parse_timestamp);
+    let updated_at = find_field(fields, UPDATED_KEYS).and_then(parse_timestamp);
+
+    if let Some(created_at) = created_at {
+        prompt.created_at = created_at;
+        prompt.updated_at = created_at;
+    }
+    if let Some(updated_at) = updated_at {
+        prompt.updated_at = updated_at;
+    }
+
+    Ok(prompt)
+}
@@ -0,0 +1,153 @@
+//! Экспорт промптов в сниппеты лаунчеров macOS: JSON-файл для импорта в Raycast
+//! и пакет `.alfredsnippets` (zip-архив с JSON-файлом на каждый сниппет) для Alfred.
+//! Форматы совпадают с теми, что читает `snippet_import::import_source`
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::error::{PromptToolError, Result};
+use crate::prompt::Prompt;
+use crate::render::{rewrite_placeholders, slugify};
+use crate::webdav::content_hash;
+
+/// Префикс ключевых слов по умолчанию; не встречается в обычном тексте
+pub const DEFAULT_KEYWORD_PREFIX: &str = ";";
+
+/// Лаунчер, для которого экспортируются сниппеты
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LauncherFormat {
+    /// JSON-массив `{name, text, keyword}` для команды Import Snippets
+    Raycast,
+    /// Пакет `.alfredsnippets`
+    Alfred,
+}
+
+/// Сниппет лаунчера
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LauncherSnippet {
+    pub name: String,
+    pub text: String,
+    pub keyword: String,
+}
+
+/// Ключевые слова для промптов: префикс, первый по алфавиту тег и название,
+/// например `;git-commit`. Совпадающие ключевые слова получают числовой суффикс
+pub fn generate_keywords(prompts: &[&Prompt], prefix: &str) -> Vec<String> {
+    let mut used = HashSet::new();
+
+    prompts
+        .iter()
+        .map(|prompt| {
+            let name = slugify(&prompt.name);
+            let tag = prompt.tags.iter().map(|tag| slugify(tag)).filter(|tag| !tag.is_empty()).min();
+
+            let base = match tag {
+                Some(tag) if name.is_empty() => tag,
+                // Тег уже в начале названия: `git` + `Git Log` дает `git-log`
+                Some(tag) if name == tag || name.starts_with(&format!("{}-", tag)) => name,
+                Some(tag) => format!("{}-{}", tag, name),
+                None if name.is_empty() => "prompt".to_string(),
+                None => name,
+            };
+
+            let mut keyword = format!("{}{}", prefix, base);
+            let mut counter = 2;
+            while !used.insert(keyword.clone()) {
+                keyword = format!("{}{}{}", prefix, base, counter);
+                counter += 1;
+            }
+            keyword
+        })
+        .collect()
+}
+
+/// Сниппеты для выбранных промптов
+/// Для Raycast параметры становятся аргументами `{argument name="..."}`, которые Raycast
+/// запрашивает при вставке; Alfred аргументов не поддерживает, поэтому плейсхолдеры остаются в тексте
+pub fn build_snippets(prompts: &[&Prompt], format: LauncherFormat, prefix: &str) -> Vec<LauncherSnippet> {
+    prompts
+        .iter()
+        .zip(generate_keywords(prompts, prefix))
+        .map(|(prompt, keyword)| {
+            let text = match format {
+                LauncherFormat::Raycast => rewrite_placeholders(&prompt.content, str::to_string, |placeholder| {
+                    match placeholder.default {
+                        Some(default) => format!("{{argument name=\"{}\" default=\"{}\"}}", placeholder.name, default.replace('"', "'")),
+                        None => format!("{{argument name=\"{}\"}}", placeholder.name),
+                    }
+                }),
+                LauncherFormat::Alfred => prompt.content.clone(),
+            };
+            LauncherSnippet { name: prompt.name.clone(), text, keyword }
+        })
+        .collect()
+}
+
+/// Идентификатор сниппета Alfred в виде UUID; зависит только от названия,
+/// поэтому повторный импорт пакета обновляет сниппеты, а не дублирует их
+fn alfred_uid(name: &str) -> String {
+    let hex = format!("{}{}", content_hash(name), content_hash(&format!("alfred:{}", name))).to_uppercase();
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+/// Имя файла сниппета внутри пакета Alfred: `<название> [<uid>].json`
+fn alfred_file_name(name: &str, uid: &str) -> String {
+    let name: String = name.chars().map(|c| if "/\\:".contains(c) { '_' } else { c }).collect();
+    format!("{} [{}].json", name, uid)
+}
+
+fn write_raycast(snippets: &[LauncherSnippet], path: &Path) -> Result<()> {
+    let contents = serde_json::to_string_pretty(snippets)
+        .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации сниппетов: {}", e)))?;
+
+    std::fs::write(path, contents)
+        .map_err(PromptToolError::Io)
+}
+
+fn write_alfred(snippets: &[LauncherSnippet], path: &Path) -> Result<()> {
+    let zip_error = |e: zip::result::ZipError| PromptToolError::Config(format!("Ошибка записи пакета Alfred: {}", e));
+
+    let file = File::create(path)
+        .map_err(PromptToolError::Io)?;
+    let mut archive = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for snippet in snippets {
+        let uid = alfred_uid(&snippet.name);
+        let item = json!({
+            "alfredsnippet": {
+                "snippet": snippet.text,
+                "uid": uid,
+                "name": snippet.name,
+                "keyword": snippet.keyword,
+            }
+        });
+        let contents = serde_json::to_vec_pretty(&item)
+            .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации сниппетов: {}", e)))?;
+
+        archive.start_file(alfred_file_name(&snippet.name, &uid), options).map_err(zip_error)?;
+        archive.write_all(&contents).map_err(PromptToolError::Io)?;
+    }
+
+    archive.finish().map_err(zip_error)?;
+    Ok(())
+}
+
+/// Записывает сниппеты выбранных промптов в файл формата лаунчера; возвращает количество сниппетов
+pub fn export_snippets(prompts: &[&Prompt], format: LauncherFormat, prefix: &str, path: &Path) -> Result<usize> {
+    let snippets = build_snippets(prompts, format, prefix);
+
+    match format {
+        LauncherFormat::Raycast => write_raycast(&snippets, path)?,
+        LauncherFormat::Alfred => write_alfred(&snippets, path)?,
+    }
+    Ok(snippets.len())
+}
@@ -0,0 +1,122 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use toml::{Table, Value};
+
+use crate::error::{PromptToolError, Result};
+use crate::file_io::save_prompts;
+use crate::prompt::{new_prompt_id, PromptList};
+
+/// Расширение резервной копии, создаваемой перед обновлением файла
+pub const BACKUP_EXTENSION: &str = "bak";
+
+/// Итог обновления файла библиотеки старого формата
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MigrationReport {
+    /// Обновленный файл
+    pub path: PathBuf,
+
+    /// Резервная копия исходного файла; `None`, если файл уже был в актуальном формате
+    pub backup: Option<PathBuf>,
+
+    /// Сколько промптов в библиотеке
+    pub prompts: usize,
+
+    /// Скольким промптам назначен идентификатор
+    pub filled_ids: usize,
+
+    /// Скольким промптам проставлены время создания и обновления
+    pub filled_timestamps: usize,
+
+    /// Скольким промптам добавлен пустой список категорий
+    pub filled_categories: usize,
+
+    /// Скольким промптам добавлен пустой список тегов
+    pub filled_tags: usize,
+
+    /// Скольким промптам добавлен пустой список параметров
+    pub filled_parameters: usize,
+}
+
+impl MigrationReport {
+    /// Изменился ли файл
+    pub fn changed(&self) -> bool {
+        self.filled_ids + self.filled_timestamps + self.filled_categories + self.filled_tags + self.filled_parameters > 0
+    }
+}
+
+/// Добавляет поле, если его нет; возвращает, было ли оно добавлено
+fn fill(table: &mut Table, key: &str, value: impl FnOnce() -> Value) -> bool {
+    if table.contains_key(key) {
+        return false;
+    }
+    table.insert(key.to_string(), value());
+    true
+}
+
+/// Дополняет промпты старого формата недостающими полями
+/// Время создания без времени обновления копируется в него; если нет обоих, используется `now`
+pub fn upgrade_table(library: &mut Table, now: DateTime<Utc>) -> Result<MigrationReport> {
+    let prompts = library
+        .entry("prompts")
+        .or_insert_with(|| Value::Array(Vec::new()))
+        .as_array_mut()
+        .ok_or_else(|| PromptToolError::Validation("Поле prompts должно быть массивом".to_string()))?;
+
+    let mut report = MigrationReport { prompts: prompts.len(), ..MigrationReport::default() };
+    for (index, prompt) in prompts.iter_mut().enumerate() {
+        let table = prompt
+            .as_table_mut()
+            .ok_or_else(|| PromptToolError::Validation(format!("Промпт №{} должен быть таблицей", index + 1)))?;
+
+        let empty = || Value::Array(Vec::new());
+        report.filled_ids += fill(table, "id", || Value::String(new_prompt_id())) as usize;
+        report.filled_categories += fill(table, "categories", empty) as usize;
+        report.filled_tags += fill(table, "tags", empty) as usize;
+        report.filled_parameters += fill(table, "parameters", empty) as usize;
+
+        let created = fill(table, "created_at", || Value::String(now.to_rfc3339()));
+        let created_at = table["created_at"].clone();
+        let updated = fill(table, "updated_at", || created_at);
+        report.filled_timestamps += (created || updated) as usize;
+    }
+
+    Ok(report)
+}
+
+/// Путь резервной копии: `<файл>.<время>.bak` рядом с исходным файлом
+pub fn backup_path(path: &Path, now: DateTime<Utc>) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".{}.{}", now.format("%Y%m%d-%H%M%S"), BACKUP_EXTENSION));
+    path.with_file_name(file_name)
+}
+
+/// Обновляет файл библиотеки старого формата на месте
+/// Перед записью исходный файл копируется в резервную копию; файл, которому нечего
+/// добавлять, не перезаписывается. Если после дополнения библиотека все равно не читается,
+/// файл остается нетронутым
+pub fn migrate_library(path: &Path, now: DateTime<Utc>) -> Result<MigrationReport> {
+    let contents = fs::read_to_string(path)
+        .map_err(PromptToolError::Io)?;
+    let mut library: Table = toml::from_str(&contents)?;
+
+    let mut report = upgrade_table(&mut library, now)?;
+    report.path = path.to_path_buf();
+    if !report.changed() {
+        return Ok(report);
+    }
+
+    let upgraded: PromptList = Value::Table(library)
+        .try_into()
+        .map_err(|e| PromptToolError::Validation(format!("Библиотеку не удалось обновить: {}", e)))?;
+
+    let backup = backup_path(path, now);
+    fs::copy(path, &backup)
+        .map_err(PromptToolError::Io)?;
+    save_prompts(&path.to_string_lossy(), &upgraded)?;
+
+    report.backup = Some(backup);
+    Ok(report)
+}
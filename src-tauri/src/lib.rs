@@ -1,4 +1,64 @@
 pub mod prompt;    // Подключаем модели
 pub mod file_io;   // Подключаем функции работы с файлами
 pub mod error;     // Подключаем обработку ошибок
-pub mod database;  // Подключаем БД
\ No newline at end of file
+pub mod database;  // Подключаем БД
+pub mod import;    // Подключаем импорт из внешних форматов
+pub mod pack;      // Подключаем манифест пакетов .ptpack
+pub mod schema;    // Подключаем генерацию JSON Schema
+pub mod lint;      // Подключаем проверку библиотек промптов
+pub mod synonyms;  // Подключаем синонимы для поиска
+pub mod cache;     // Подключаем кэши с ограничением размера
+pub mod paths;     // Подключаем пути к данным пользователя
+pub mod history;   // Подключаем историю поисковых запросов
+pub mod clipboard; // Подключаем подготовку текста к вставке и буфер обмена
+pub mod activation; // Подключаем вызов окна через локальный сокет
+pub mod render;    // Подключаем подстановку параметров в шаблоны
+pub mod llm;       // Подключаем выполнение промптов через LLM
+pub mod platform;  // Подключаем интеграцию с возможностями ОС
+pub mod notifications; // Подключаем настройки системных уведомлений
+pub mod tokens;    // Подключаем подсчет токенов
+pub mod pricing;   // Подключаем оценку стоимости выполнения
+pub mod usage;     // Подключаем статистику использования промптов
+pub mod report;    // Подключаем Markdown-отчеты по библиотеке
+pub mod snippet_import; // Подключаем перенос сниппетов из других программ
+pub mod runs;      // Подключаем журнал выполнений промптов
+pub mod chain;     // Подключаем цепочки промптов
+pub mod parameters; // Подключаем описание и проверку параметров промптов
+pub mod output;    // Подключаем цели отправки готового текста промптов
+pub mod session;   // Подключаем переменные текущей сессии
+pub mod hooks;     // Подключаем скрипты-хуки до и после подстановки
+pub mod validation; // Подключаем проверку промптов перед сохранением
+pub mod duplicates; // Подключаем поиск похожих промптов
+pub mod search_config; // Подключаем перенос настроек поиска между установками
+pub mod revisions; // Подключаем историю изменений промптов
+pub mod diff; // Подключаем сравнение промптов
+pub mod merge; // Подключаем трехстороннее слияние правок промпта
+pub mod git; // Подключаем синхронизацию промптов через git
+pub mod query_macros; // Подключаем макросы поисковых запросов
+pub mod gist; // Подключаем публикацию и импорт промптов через GitHub Gist
+pub mod feedback; // Подключаем учет выбранных результатов поиска
+pub mod split; // Подключаем разбиение библиотеки на файлы по категориям
+pub mod webdav; // Подключаем синхронизацию файла промптов через WebDAV
+pub mod obsidian; // Подключаем экспорт библиотеки в хранилище Obsidian
+pub mod legacy; // Подключаем обновление библиотек старого формата
+pub mod vscode; // Подключаем экспорт промптов в сниппеты VS Code
+pub mod launchers; // Подключаем экспорт сниппетов для Raycast и Alfred
+pub mod espanso; // Подключаем экспорт правил Espanso
+pub mod chatgpt; // Подключаем импорт промптов из выгрузки ChatGPT
+pub mod encryption; // Подключаем шифрование файла промптов
+pub mod secrets; // Подключаем хранилище секретов в системной связке ключей
+pub mod backups; // Подключаем резервные копии файла промптов
+pub mod file_lock; // Подключаем блокировки файла промптов между процессами
+pub mod migrations; // Подключаем версии формата файла промптов и их обновление
+pub mod profiles; // Подключаем профили с отдельными настройками и библиотеками
+pub mod shortcuts; // Подключаем глобальные горячие клавиши промптов
+pub mod expander; // Подключаем расширитель текста по псевдонимам промптов
+pub mod palette; // Подключаем окно быстрого запуска
+pub mod window_state; // Подключаем сохранение положения окна
+pub mod settings; // Подключаем общие настройки приложения и их проверку
+pub mod config_migrations; // Подключаем версии формата конфигурации и их обновление
+pub mod logging; // Подключаем журнал приложения с ротацией файлов
+pub mod crash; // Подключаем отчеты о падениях
+pub mod messages; // Подключаем каталог сообщений об ошибках
+pub mod state; // Подключаем доступ к общему состоянию приложения
+pub mod paging; // Подключаем постраничную выдачу промптов
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::parameters::detect_parameters;
+use crate::platform::SELECTION_VARIABLE;
+use crate::prompt::{Prompt, PromptList};
+
+/// Серьезность найденной проблемы
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Библиотека некорректна, например, в CI такая проблема должна ронять проверку
+    Error,
+    /// Подозрительное место, которое стоит исправить
+    Warning,
+}
+
+/// Одна проблема, найденная при проверке библиотеки
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// Серьезность проблемы
+    pub severity: Severity,
+
+    /// Машиночитаемый код проблемы, например `duplicate-name`
+    pub code: String,
+
+    /// Позиция промпта в файле (с нуля)
+    pub index: usize,
+
+    /// Название промпта, к которому относится проблема
+    pub prompt: String,
+
+    /// Описание проблемы для человека
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, code: &str, index: usize, prompt: &str, message: String) -> Self {
+        Self {
+            severity,
+            code: code.to_string(),
+            index,
+            prompt: prompt.to_string(),
+            message,
+        }
+    }
+}
+
+/// Проверяет библиотеку промптов и возвращает список найденных проблем
+/// Используется и приложением, и консольной утилитой, чтобы правила проверки совпадали
+pub fn lint_library(list: &PromptList) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut seen_names: HashMap<String, usize> = HashMap::new();
+
+    for (index, prompt) in list.prompts.iter().enumerate() {
+        let name = prompt.name.trim();
+
+        if name.is_empty() {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error, "empty-name", index, &prompt.name,
+                "Название промпта не может быть пустым".to_string(),
+            ));
+        } else if let Some(first) = seen_names.get(&name.to_lowercase()) {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error, "duplicate-name", index, &prompt.name,
+                format!("Промпт с таким названием уже объявлен под номером {}", first + 1),
+            ));
+        } else {
+            seen_names.insert(name.to_lowercase(), index);
+        }
+
+        if prompt.content.trim().is_empty() {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning, "empty-content", index, &prompt.name,
+                "Содержимое промпта пустое".to_string(),
+            ));
+        }
+
+        lint_placeholders(index, prompt, &mut diagnostics);
+    }
+
+    diagnostics
+}
+
+/// Сверяет объявленные параметры с плейсхолдерами в тексте промпта
+fn lint_placeholders(index: usize, prompt: &Prompt, diagnostics: &mut Vec<Diagnostic>) {
+    let placeholders = detect_parameters(&prompt.content);
+
+    for spec in &prompt.parameters {
+        if !placeholders.contains(&spec.name) && !used_in_tera(&prompt.content, &spec.name) {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning, "unused-parameter", index, &prompt.name,
+                format!("Параметр \"{}\" объявлен, но не используется в тексте", spec.name),
+            ));
+        }
+    }
+
+    for placeholder in placeholders {
+        // {selection} заполняется текстом из системного сервиса, объявлять его не нужно
+        if placeholder == SELECTION_VARIABLE || prompt.parameters.iter().any(|spec| spec.name == placeholder) {
+            continue;
+        }
+        diagnostics.push(Diagnostic::new(
+            Severity::Warning, "undeclared-placeholder", index, &prompt.name,
+            format!("Плейсхолдер {{{}}} не объявлен в списке параметров", placeholder),
+        ));
+    }
+}
+
+/// Упоминается ли переменная внутри выражения или тега Tera (`{{ name }}`, `{% if name %}`)
+fn used_in_tera(content: &str, name: &str) -> bool {
+    Regex::new(&format!(r"\{{[{{%][^}}]*\b{}\b", regex::escape(name)))
+        .map(|regex| regex.is_match(content))
+        .unwrap_or(false)
+}
+
+/// Есть ли среди проблем хотя бы одна ошибка
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(|d| d.severity == Severity::Error)
+}
@@ -0,0 +1,452 @@
+//! Выполнение промптов через LLM.
+//!
+//! Поддерживаются OpenAI-совместимые API (`POST {base_url}/chat/completions`)
+//! и локальный сервер Ollama (`POST {base_url}/api/chat`).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::{PromptToolError, Result};
+
+/// Поставщик модели
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    /// OpenAI и совместимые с ним API
+    #[default]
+    OpenAi,
+    /// Локальные модели через Ollama
+    Ollama,
+}
+
+impl ProviderKind {
+    /// Адрес API, если в настройках он не указан
+    pub fn default_base_url(&self) -> &'static str {
+        match self {
+            ProviderKind::OpenAi => "https://api.openai.com/v1",
+            ProviderKind::Ollama => "http://localhost:11434",
+        }
+    }
+
+    fn chat_path(&self) -> &'static str {
+        match self {
+            ProviderKind::OpenAi => "/chat/completions",
+            ProviderKind::Ollama => "/api/chat",
+        }
+    }
+
+    fn models_path(&self) -> &'static str {
+        match self {
+            ProviderKind::OpenAi => "/models",
+            ProviderKind::Ollama => "/api/tags",
+        }
+    }
+
+    /// Тело запроса к чату с единственным сообщением пользователя
+    pub fn build_request_body(&self, config: &LlmConfig, model: &str, prompt: &str) -> Value {
+        let mut body = json!({
+            "model": model,
+            "messages": [{ "role": "user", "content": prompt }],
+        });
+
+        match self {
+            ProviderKind::OpenAi => {
+                if let Some(temperature) = config.temperature {
+                    body["temperature"] = json!(temperature);
+                }
+                if let Some(max_tokens) = config.max_tokens {
+                    body["max_tokens"] = json!(max_tokens);
+                }
+            }
+            ProviderKind::Ollama => {
+                body["stream"] = json!(false);
+                let mut options = json!({});
+                if let Some(temperature) = config.temperature {
+                    options["temperature"] = json!(temperature);
+                }
+                if let Some(max_tokens) = config.max_tokens {
+                    options["num_predict"] = json!(max_tokens);
+                }
+                body["options"] = options;
+            }
+        }
+
+        body
+    }
+
+    /// Тело запроса с потоковой выдачей ответа
+    pub fn build_stream_request_body(&self, config: &LlmConfig, model: &str, prompt: &str) -> Value {
+        let mut body = self.build_request_body(config, model, prompt);
+        body["stream"] = json!(true);
+        if *self == ProviderKind::OpenAi {
+            // Без этой опции OpenAI не сообщает расход токенов в потоке
+            body["stream_options"] = json!({ "include_usage": true });
+        }
+        body
+    }
+
+    /// Разбирает одну строку потокового ответа
+    /// OpenAI присылает события SSE (`data: {...}`, `data: [DONE]`), Ollama - JSON по строке.
+    /// Пустые строки и служебные события дают `None`
+    pub fn parse_stream_line(&self, line: &str) -> Result<Option<StreamChunk>> {
+        let line = line.trim();
+
+        let payload = match self {
+            ProviderKind::OpenAi => match line.strip_prefix("data:") {
+                Some(payload) => payload.trim(),
+                None => return Ok(None),
+            },
+            ProviderKind::Ollama => line,
+        };
+
+        if payload.is_empty() {
+            return Ok(None);
+        }
+        if payload == "[DONE]" {
+            return Ok(Some(StreamChunk { done: true, ..StreamChunk::default() }));
+        }
+
+        let value: Value = serde_json::from_str(payload)
+            .map_err(|e| PromptToolError::Execution(format!("Некорректный фрагмент ответа: {}", e)))?;
+        check_error(&value)?;
+
+        let model = value["model"].as_str().map(str::to_string);
+        let chunk = match self {
+            ProviderKind::OpenAi => StreamChunk {
+                token: value["choices"][0]["delta"]["content"].as_str().unwrap_or_default().to_string(),
+                usage: serde_json::from_value(value["usage"].clone()).ok(),
+                model,
+                done: false,
+            },
+            ProviderKind::Ollama => {
+                let done = value["done"].as_bool().unwrap_or(false);
+                StreamChunk {
+                    token: value["message"]["content"].as_str().unwrap_or_default().to_string(),
+                    usage: if done { ollama_usage(&value) } else { None },
+                    model,
+                    done,
+                }
+            }
+        };
+
+        Ok(Some(chunk))
+    }
+
+    /// Разбирает ответ чата
+    pub fn parse_completion(&self, response: &Value) -> Result<Completion> {
+        check_error(response)?;
+
+        let (text, usage) = match self {
+            ProviderKind::OpenAi => (
+                response["choices"][0]["message"]["content"].as_str(),
+                serde_json::from_value(response["usage"].clone()).ok(),
+            ),
+            ProviderKind::Ollama => (response["message"]["content"].as_str(), ollama_usage(response)),
+        };
+
+        let text = text
+            .ok_or_else(|| PromptToolError::Execution("Ответ не содержит текста".to_string()))?
+            .to_string();
+
+        Ok(Completion {
+            text,
+            model: response["model"].as_str().unwrap_or_default().to_string(),
+            usage,
+        })
+    }
+
+    /// Разбирает список доступных моделей
+    pub fn parse_models(&self, response: &Value) -> Result<Vec<String>> {
+        check_error(response)?;
+
+        let (items, key) = match self {
+            ProviderKind::OpenAi => (&response["data"], "id"),
+            ProviderKind::Ollama => (&response["models"], "name"),
+        };
+
+        let mut models: Vec<String> = items
+            .as_array()
+            .ok_or_else(|| PromptToolError::Execution("Ответ не содержит списка моделей".to_string()))?
+            .iter()
+            .filter_map(|item| item[key].as_str().map(str::to_string))
+            .collect();
+
+        models.sort();
+        Ok(models)
+    }
+}
+
+/// Настройки подключения к LLM
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmConfig {
+    /// Поставщик модели
+    #[serde(default)]
+    pub provider: ProviderKind,
+
+    /// Базовый адрес API; если пустой, используется адрес поставщика по умолчанию
+    #[serde(default)]
+    pub base_url: String,
+
+    /// Ключ API; пустой ключ не передается (Ollama и локальные совместимые серверы)
+    #[serde(default)]
+    pub api_key: String,
+
+    /// Модель по умолчанию
+    #[serde(default = "default_model")]
+    pub model: String,
+
+    /// Температура генерации; если не задана, используется значение сервера
+    #[serde(default)]
+    pub temperature: Option<f32>,
+
+    /// Ограничение длины ответа в токенах
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
+fn default_model() -> String {
+    "gpt-4o-mini".to_string()
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            provider: ProviderKind::default(),
+            base_url: String::new(),
+            api_key: String::new(),
+            model: default_model(),
+            temperature: None,
+            max_tokens: None,
+        }
+    }
+}
+
+impl LlmConfig {
+    /// Полный адрес метода API
+    fn url(&self, path: &str) -> String {
+        let base_url = if self.base_url.trim().is_empty() {
+            self.provider.default_base_url()
+        } else {
+            self.base_url.trim()
+        };
+        format!("{}{}", base_url.trim_end_matches('/'), path)
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if self.api_key.is_empty() {
+            request
+        } else {
+            request.bearer_auth(&self.api_key)
+        }
+    }
+}
+
+/// Расход токенов на один запрос
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Usage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+/// Ответ модели
+#[derive(Debug, Clone, Serialize)]
+pub struct Completion {
+    /// Текст ответа
+    pub text: String,
+
+    /// Модель, которая фактически ответила
+    pub model: String,
+
+    /// Расход токенов, если сервер его сообщил
+    pub usage: Option<Usage>,
+}
+
+/// Фрагмент потокового ответа
+#[derive(Debug, Clone, Default)]
+pub struct StreamChunk {
+    /// Очередной кусок текста (может быть пустым)
+    pub token: String,
+
+    /// Расход токенов; приходит в последних фрагментах
+    pub usage: Option<Usage>,
+
+    /// Модель, которая отвечает
+    pub model: Option<String>,
+
+    /// Признак конца ответа
+    pub done: bool,
+}
+
+/// Собирает строки из произвольно нарезанных кусков байтов потока
+/// Незавершенная строка хранится до прихода следующего куска
+#[derive(Debug, Default)]
+pub struct LineBuffer {
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    /// Добавляет кусок и возвращает все завершенные строки
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(bytes);
+
+        let mut lines = Vec::new();
+        while let Some(position) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=position).collect();
+            lines.push(String::from_utf8_lossy(&line).trim_end().to_string());
+        }
+        lines
+    }
+
+    /// Возвращает остаток после конца потока
+    pub fn finish(&mut self) -> Option<String> {
+        let rest = String::from_utf8_lossy(&std::mem::take(&mut self.pending)).trim().to_string();
+        (!rest.is_empty()).then_some(rest)
+    }
+}
+
+/// Расход токенов из ответа Ollama
+fn ollama_usage(response: &Value) -> Option<Usage> {
+    let prompt_tokens = response["prompt_eval_count"].as_u64()?;
+    let completion_tokens = response["eval_count"].as_u64()?;
+    Some(Usage {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens: prompt_tokens + completion_tokens,
+    })
+}
+
+/// Ошибка, которую сервер вернул в теле ответа
+/// OpenAI присылает `{"error": {"message": ...}}`, Ollama - `{"error": "..."}`
+fn check_error(response: &Value) -> Result<()> {
+    let message = response["error"]["message"]
+        .as_str()
+        .or_else(|| response["error"].as_str());
+
+    match message {
+        Some(message) => Err(PromptToolError::Execution(message.to_string())),
+        None => Ok(()),
+    }
+}
+
+/// Отправляет запрос и читает ответ как JSON
+async fn send_json(request: reqwest::RequestBuilder, url: &str) -> Result<Value> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| PromptToolError::Execution(format!("Ошибка запроса к {}: {}", url, e)))?;
+
+    let status = response.status();
+    response
+        .json()
+        .await
+        .map_err(|e| PromptToolError::Execution(format!("Некорректный ответ сервера ({}): {}", status, e)))
+}
+
+/// Отправляет текст промпта модели и возвращает ответ
+/// Если `model` не указана, используется модель из настроек
+pub async fn complete(config: &LlmConfig, model: Option<&str>, prompt: &str) -> Result<Completion> {
+    let model = model.unwrap_or(&config.model);
+    let url = config.url(config.provider.chat_path());
+
+    let request = reqwest::Client::new()
+        .post(&url)
+        .json(&config.provider.build_request_body(config, model, prompt));
+
+    let body = send_json(config.authorize(request), &url).await?;
+    config.provider.parse_completion(&body)
+}
+
+/// Список моделей, доступных у настроенного поставщика
+pub async fn list_models(config: &LlmConfig) -> Result<Vec<String>> {
+    let url = config.url(config.provider.models_path());
+    let request = reqwest::Client::new().get(&url);
+
+    let body = send_json(config.authorize(request), &url).await?;
+    config.provider.parse_models(&body)
+}
+
+/// Отправляет текст промпта модели и получает ответ по частям
+/// `on_token` вызывается для каждого непустого куска текста. Если `cancel` выставлен,
+/// чтение прерывается и возвращается ошибка; итоговый ответ собирается из всех кусков
+pub async fn complete_streaming<F>(
+    config: &LlmConfig,
+    model: Option<&str>,
+    prompt: &str,
+    cancel: &AtomicBool,
+    mut on_token: F,
+) -> Result<Completion>
+where
+    F: FnMut(&str),
+{
+    let model = model.unwrap_or(&config.model);
+    let url = config.url(config.provider.chat_path());
+
+    let request = reqwest::Client::new()
+        .post(&url)
+        .json(&config.provider.build_stream_request_body(config, model, prompt));
+
+    let mut response = config.authorize(request)
+        .send()
+        .await
+        .map_err(|e| PromptToolError::Execution(format!("Ошибка запроса к {}: {}", url, e)))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or_default();
+        check_error(&body)?;
+        return Err(PromptToolError::Execution(format!("Сервер вернул {}", status)));
+    }
+
+    let mut completion = Completion {
+        text: String::new(),
+        model: model.to_string(),
+        usage: None,
+    };
+    let mut buffer = LineBuffer::default();
+
+    let mut apply = |line: &str, completion: &mut Completion| -> Result<bool> {
+        let Some(chunk) = config.provider.parse_stream_line(line)? else {
+            return Ok(false);
+        };
+        if !chunk.token.is_empty() {
+            on_token(&chunk.token);
+            completion.text.push_str(&chunk.token);
+        }
+        if let Some(model) = chunk.model {
+            completion.model = model;
+        }
+        if chunk.usage.is_some() {
+            completion.usage = chunk.usage;
+        }
+        Ok(chunk.done)
+    };
+
+    loop {
+        if cancel.load(Ordering::SeqCst) {
+            return Err(PromptToolError::Execution("Выполнение отменено".to_string()));
+        }
+
+        let bytes = response
+            .chunk()
+            .await
+            .map_err(|e| PromptToolError::Execution(format!("Ошибка чтения ответа: {}", e)))?;
+
+        let Some(bytes) = bytes else {
+            if let Some(line) = buffer.finish() {
+                apply(&line, &mut completion)?;
+            }
+            break;
+        };
+
+        for line in buffer.push(&bytes) {
+            if apply(&line, &mut completion)? {
+                return Ok(completion);
+            }
+        }
+    }
+
+    Ok(completion)
+}
@@ -0,0 +1,181 @@
+//! Журнал приложения: события `tracing` пишутся в stderr и в файлы в директории журналов
+//! с ежедневной ротацией. Уровни задаются для приложения в целом и для отдельных модулей
+//! и меняются без перезапуска.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use tracing::level_filters::LevelFilter;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+use crate::error::{PromptToolError, Result};
+
+/// Префикс имени файлов журнала; к нему добавляется дата
+pub const LOG_FILE_PREFIX: &str = "prompttool.log";
+
+/// Сколько файлов журнала (дней) хранить
+pub const MAX_LOG_FILES: usize = 7;
+
+/// Сколько последних строк журнала возвращать по умолчанию
+pub const DEFAULT_RECENT_LINES: usize = 500;
+
+/// Уровни журнала
+/// Модули задаются целями `tracing`, например `prompt_tool_lib::git` или `prompt_tool`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogLevels {
+    /// Уровень для всех модулей, для которых не задан отдельный
+    #[serde(default = "default_level")]
+    pub default: String,
+
+    /// Уровни отдельных модулей
+    #[serde(default)]
+    pub modules: BTreeMap<String, String>,
+}
+
+fn default_level() -> String {
+    "info".to_string()
+}
+
+impl Default for LogLevels {
+    fn default() -> Self {
+        Self {
+            default: default_level(),
+            modules: BTreeMap::new(),
+        }
+    }
+}
+
+impl LogLevels {
+    /// Проверяет уровни и имена модулей; в ошибке перечисляются все неверные значения
+    pub fn validate(&self) -> Result<()> {
+        let mut errors = Vec::new();
+
+        if LevelFilter::from_str(&self.default).is_err() {
+            errors.push(format!("default: неизвестный уровень \"{}\"", self.default));
+        }
+        for (module, level) in &self.modules {
+            let valid_name = !module.is_empty()
+                && module.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':');
+            if !valid_name {
+                errors.push(format!("{}: недопустимое имя модуля", module));
+            }
+            if LevelFilter::from_str(level).is_err() {
+                errors.push(format!("{}: неизвестный уровень \"{}\"", module, level));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(PromptToolError::Validation(errors.join("; ")))
+        }
+    }
+
+    /// Уровни в синтаксисе `RUST_LOG`: `info,prompt_tool_lib::git=debug`
+    pub fn directives(&self) -> String {
+        std::iter::once(self.default.clone())
+            .chain(self.modules.iter().map(|(module, level)| format!("{}={}", module, level)))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn filter(&self) -> Result<EnvFilter> {
+        self.validate()?;
+        EnvFilter::try_new(self.directives())
+            .map_err(|e| PromptToolError::Validation(format!("Неверные уровни журнала: {}", e)))
+    }
+}
+
+/// Подключенный журнал: позволяет менять уровни и дописывает буферизованные записи при завершении
+pub struct Logging {
+    filter: reload::Handle<EnvFilter, Registry>,
+    log_dir: PathBuf,
+    _guard: WorkerGuard,
+}
+
+impl Logging {
+    /// Подключает журнал процесса; повторно вызывать нельзя
+    pub fn init(log_dir: &Path, levels: &LogLevels) -> Result<Self> {
+        fs::create_dir_all(log_dir)
+            .map_err(PromptToolError::Io)?;
+
+        let appender = RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .max_log_files(MAX_LOG_FILES)
+            .build(log_dir)
+            .map_err(|e| PromptToolError::Config(format!("Не удалось открыть файл журнала: {}", e)))?;
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+
+        let (filter, handle) = reload::Layer::new(levels.filter()?);
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt::layer().with_writer(std::io::stderr))
+            .with(fmt::layer().with_ansi(false).with_writer(writer))
+            .try_init()
+            .map_err(|e| PromptToolError::Config(format!("Журнал уже подключен: {}", e)))?;
+
+        Ok(Self {
+            filter: handle,
+            log_dir: log_dir.to_path_buf(),
+            _guard: guard,
+        })
+    }
+
+    /// Меняет уровни журнала без перезапуска
+    pub fn set_levels(&self, levels: &LogLevels) -> Result<()> {
+        self.filter
+            .reload(levels.filter()?)
+            .map_err(|e| PromptToolError::Config(format!("Не удалось изменить уровни журнала: {}", e)))
+    }
+
+    /// Директория с файлами журнала
+    pub fn log_dir(&self) -> &Path {
+        &self.log_dir
+    }
+}
+
+/// Последние `limit` строк журнала, от старых к новым, по всем сохраненным файлам
+pub fn recent_logs(log_dir: &Path, limit: usize) -> Result<Vec<String>> {
+    if !log_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    // Дата в имени файла идет после префикса, поэтому порядок имен совпадает с порядком записи
+    let mut files: Vec<PathBuf> = fs::read_dir(log_dir)
+        .map_err(PromptToolError::Io)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+        })
+        .collect();
+    files.sort();
+
+    let mut lines = Vec::new();
+    for file in files.iter().rev() {
+        let contents = fs::read(file)
+            .map_err(PromptToolError::Io)?;
+        let mut file_lines: Vec<String> = String::from_utf8_lossy(&contents)
+            .lines()
+            .map(str::to_string)
+            .collect();
+        let take = limit.saturating_sub(lines.len()).min(file_lines.len());
+        let mut tail = file_lines.split_off(file_lines.len() - take);
+        tail.append(&mut lines);
+        lines = tail;
+        if lines.len() >= limit {
+            break;
+        }
+    }
+    Ok(lines)
+}
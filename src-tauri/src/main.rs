@@ -1,230 +1,4732 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use serde::{Deserialize, Serialize};
-use tauri_plugin_dialog::DialogExt;
-use std::sync::Mutex;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_opener::OpenerExt;
+use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_global_shortcut::{GlobalShortcut, GlobalShortcutExt, Shortcut, ShortcutState};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLockReadGuard};
+use std::time::{Duration, Instant};
 use tauri::State;
+use tracing::{error, info, warn};
 use std::path::PathBuf;
-use tauri::Manager;
+use tauri::{Emitter, Manager, Runtime};
 use prompt_tool_lib::{
-    file_io::load_prompts,
-    prompt::{Prompt, PromptList, SearchFilter},
+    activation::{self, ActivationCommand},
+    backups::{Backup, BackupStore, BACKUPS_DIR_NAME},
+    chain::{ChainStepResult, PromptChain},
+    chatgpt::{self, ChatGptImportOptions},
+    config_migrations::{self, MigrationContext, APP_VERSION, CURRENT_CONFIG_VERSION},
+    cache::{CacheLimits, CacheStats, LruCache},
+    clipboard::{self, PasteOptions},
+    duplicates::{self, DuplicatePair},
+    diff::{self, PromptDiff},
+    feedback::ClickFeedback,
+    database::{dedup_hits, parse_language, Database, DateField, DateRange, FieldBoosts, Record, Suggestion, DEFAULT_READER_CACHE_BLOCKS, MAX_SUGGESTIONS},
+    encryption::{self, FileKey},
+    expander::{self, ExpanderSettings, KeyInput, Trigger, TriggerBuffer},
+    file_io::{self, load_prompts, parse_prompts, parse_prompts_recovering, read_prompt_file, save_prompts_with, write_prompt_file, PromptDiagnostic},
+    history::{HistoryEntry, SearchHistory},
+    gist::{self, GistSettings, PublishedGist},
+    git::{self, GitCommit, GitSettings, GitStatus, PullOutcome},
+    hooks::{self, PromptHooks, HOOKS_DIR_NAME},
+    import::{import_awesome_csv, import_directory, import_file, FolderMapping, AWESOME_PROMPTS_CATEGORY},
+    espanso::{self, DEFAULT_TRIGGER_PREFIX},
+    launchers::{self, LauncherFormat, DEFAULT_KEYWORD_PREFIX},
+    legacy::{self, MigrationReport},
+    lint::{lint_library, Diagnostic},
+    llm::{self, Completion, LlmConfig},
+    output::{self, Delivery, OutputTargetConfig},
+    parameters::{self, ParameterSync},
+    merge::{self, PromptMerge},
+    snippet_import::{detect_sources, import_source, ImportableSource, SourceKind},
+    migrations,
+    notifications::{DndSettings, NotificationKind, NotificationSettings},
+    crash::{self, CRASH_DIR_NAME},
+    messages,
+    logging::{self, LogLevels, Logging, DEFAULT_RECENT_LINES},
+    palette::{LauncherItem, LauncherSelection, LAUNCHER_RESULT_LIMIT, LAUNCHER_WINDOW},
+    paging::{page_of_prompts, Page, PageRequest, PromptSummary},
+    paths::{claim_directory, parse_config_dir_arg, AppPaths, LEGACY_PROMPT_FILE},
+    platform::{self, ForeignWindow, SelectionAction, SELECTION_VARIABLE},
+    pricing::{self, CostEstimate, ModelPrice},
+    profiles::{self, ProfileRegistry, DEFAULT_PROFILE, PROFILES_FILE_NAME},
+    query_macros,
+    schema::{self, export_schemas, SchemaError},
+    search_config::SearchConfig,
+    synonyms::SynonymMap,
+    tokens,
+    report::build_stats_report,
+    revisions::{PromptRevision, RevisionStore},
+    runs::{Run, RunStore},
+    secrets,
+    obsidian::{self, VaultExport},
+    usage::UsageStats,
+    vscode,
+    webdav::{self, content_hash, SyncAction, SyncState, WebDavSettings},
+    window_state::{MonitorArea, Rect, WindowState, WINDOW_STATE_FILE_NAME},
+    session::{SessionVariable, SessionVariables},
+    settings::{Settings, SettingsChange, SettingsUpdate},
+    shortcuts::{self, PromptHotkey},
+    state::StateCell,
+    split::{self, SharedPromptStrategy, SplitFile},
+    validation,
+    prompt::{aliases_first, new_prompt_id, IdRepair, Prompt, PromptList, SearchFilter},
+    render::{self, RenderDebug, RenderOutput, SecretBindings, TemplateFunction, TEMPLATE_FUNCTIONS},
     error::{Result, PromptToolError},
 };
 
 // Путь к файлу с промптами по умолчанию
-const DEFAULT_PROMPT_FILE: &str = "prompts/default.toml";
+
+// Имя директории с поисковым индексом внутри директории данных приложения
+const INDEX_DIR_NAME: &str = "index";
+
+// Имя файла с историей поиска внутри директории данных приложения
+const SEARCH_HISTORY_FILE_NAME: &str = "search_history.json";
+
+// Имя файла со статистикой использования промптов внутри директории данных приложения
+const USAGE_FILE_NAME: &str = "usage.json";
+
+// Имя файла журнала выполнений промптов внутри директории данных приложения
+const RUNS_FILE_NAME: &str = "runs.jsonl";
+
+// Имя файла истории ревизий промптов внутри директории данных приложения
+const REVISIONS_FILE_NAME: &str = "revisions.jsonl";
+
+// Имя файла с закрепленными переменными сессии внутри директории данных приложения
+const SESSION_FILE_NAME: &str = "session.json";
+
+// Имя файла с выборами результатов поиска внутри директории данных приложения
+const FEEDBACK_FILE_NAME: &str = "feedback.json";
+
+// Имя файла с состоянием последней синхронизации через WebDAV внутри директории данных приложения
+const WEBDAV_STATE_FILE_NAME: &str = "webdav_sync.json";
+
+// Размер пачки при перестроении индекса (после каждой пачки отправляется прогресс)
+const REBUILD_BATCH_SIZE: usize = 500;
+
+// Как часто фоновый поток проверяет, не пора ли выгрузить ресурсы после простоя
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+// Как часто фоновый поток автосохранения проверяет несохраненные изменения
+const AUTOSAVE_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+// Наибольшая пауза автосохранения после неудачных попыток (файл занят, недоступен или заблокирован)
+const AUTOSAVE_MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+// Как часто сохранение правок из памяти (в том числе автосохранение) создает резервную копию;
+// иначе ротация копий покрывала бы только последние минуты правок
+const EDIT_BACKUP_INTERVAL: Duration = Duration::from_secs(600);
+
+// Сколько ждать после возврата фокуса, прежде чем нажимать сочетание вставки
+const FOCUS_RESTORE_DELAY: Duration = Duration::from_millis(150);
 
 /// Структура конфигурации приложения
 /// Содержит настройки, которые сохраняются между запусками
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct AppConfig {
+    // Версия формата конфигурации; конфигурация старой версии обновляется при загрузке
+    #[serde(default)]
+    config_version: u32,
+    // Версия приложения, последним записавшего конфигурацию
+    #[serde(default)]
+    app_version: String,
     // Путь к файлу с промптами
     prompt_file_path: String,
     // Горячая клавиша для быстрого доступа
     hotkey: String,
+    // Правила превращения папок в теги/категории при импорте директории
+    #[serde(default)]
+    folder_mapping: FolderMapping,
+    // Использовать ли поисковый индекс Tantivy; без него весь поиск идет по промптам в памяти
+    #[serde(default = "default_index_enabled")]
+    index_enabled: bool,
+    // Группы синонимов для поиска, например "js = javascript"
+    #[serde(default)]
+    synonyms: Vec<String>,
+    // Языки стемминга поискового индекса, например ["russian", "english"]
+    #[serde(default = "default_stemming_languages")]
+    stemming_languages: Vec<String>,
+    // Ограничения кэша результатов поиска
+    #[serde(default)]
+    search_cache_limits: CacheLimits,
+    // Сколько блоков хранилища документов индекса держать в кэше читателя
+    #[serde(default = "default_reader_cache_blocks")]
+    reader_cache_blocks: usize,
+    // Веса полей при ранжировании результатов поиска
+    #[serde(default)]
+    field_boosts: FieldBoosts,
+    // Через сколько минут бездействия выгружать индекс и кэши (0 - не выгружать)
+    #[serde(default = "default_idle_release_minutes")]
+    idle_release_minutes: u64,
+    // Сколько последних поисковых запросов хранить в истории
+    #[serde(default = "default_search_history_size")]
+    search_history_size: usize,
+    // Подготовка текста к вставке: переводы строк, BOM, HTML-формат в Windows
+    #[serde(default)]
+    paste: PasteOptions,
+    // Подключение к LLM (OpenAI-совместимый API или Ollama) для выполнения промптов
+    #[serde(default)]
+    llm: LlmConfig,
+    // Что делать с текстом, присланным через системный сервис (macOS Services)
+    #[serde(default)]
+    selection_action: SelectionAction,
+    // Какие фоновые события показывать системными уведомлениями
+    #[serde(default)]
+    notifications: NotificationSettings,
+    // Режим "Не беспокоить": ручное включение и учет системного режима
+    #[serde(default)]
+    do_not_disturb: DndSettings,
+    // Цены моделей в долларах за миллион токенов
+    #[serde(default = "pricing::default_price_table")]
+    model_prices: HashMap<String, ModelPrice>,
+    // Фиксация сохранений промптов в git и синхронизация с удаленным репозиторием
+    #[serde(default)]
+    git: GitSettings,
+    // Макросы поисковых запросов: "new" -> "updated:7d" раскрывает @new перед поиском.
+    // У каждой директории конфигурации (--config-dir) свой набор макросов
+    #[serde(default = "query_macros::default_macros")]
+    query_macros: BTreeMap<String, String>,
+    // Доступ к GitHub Gist для публикации и импорта промптов
+    #[serde(default)]
+    gist: GistSettings,
+    // Синхронизация файла промптов с WebDAV-сервером (Nextcloud и т.п.)
+    #[serde(default)]
+    webdav: WebDavSettings,
+    // Зашифрован ли файл промптов; такой файл загружается только после разблокировки
+    #[serde(default)]
+    prompt_file_encrypted: bool,
+    // Общие настройки: число результатов, порядок, язык, автосохранение, резервные копии, нечеткий поиск.
+    // Хранятся на верхнем уровне config.json, поэтому прежние поля backup_count и
+    // autosave_delay_seconds читаются без изменений
+    #[serde(flatten)]
+    settings: Settings,
+    // Файлы промптов, открываемые только для чтения (например, общий файл команды).
+    // Файлы без прав на запись считаются такими и без отметки
+    #[serde(default)]
+    read_only_files: BTreeSet<String>,
+    // Глобальные горячие клавиши отдельных промптов: сочетание -> привязанный промпт
+    #[serde(default)]
+    prompt_hotkeys: BTreeMap<String, PromptHotkey>,
+    // Расширитель текста: `;;псевдоним`, набранный в любом приложении, заменяется промптом
+    #[serde(default)]
+    text_expander: ExpanderSettings,
+    // Главная горячая клавиша открывает окно быстрого запуска вместо окна управления
+    #[serde(default)]
+    launcher_mode: bool,
+    // Окно управления закреплено поверх остальных окон
+    #[serde(default)]
+    always_on_top: bool,
+    // Уровни журнала для приложения и отдельных модулей
+    #[serde(default)]
+    log_levels: LogLevels,
+}
+
+fn default_reader_cache_blocks() -> usize {
+    DEFAULT_READER_CACHE_BLOCKS
+}
+
+fn default_search_history_size() -> usize {
+    50
+}
+
+fn default_idle_release_minutes() -> u64 {
+    10
+}
+
+fn default_stemming_languages() -> Vec<String> {
+    vec!["russian".to_string(), "english".to_string()]
+}
+
+fn default_index_enabled() -> bool {
+    true
+}
+
+// Реализация значений по умолчанию для конфигурации
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            config_version: CURRENT_CONFIG_VERSION,
+            app_version: APP_VERSION.to_string(),
+            // Путь по умолчанию зависит от директории данных и подставляется при загрузке конфигурации
+            prompt_file_path: String::new(),
+            hotkey: String::new(),
+            folder_mapping: FolderMapping::default(),
+            index_enabled: default_index_enabled(),
+            synonyms: Vec::new(),
+            stemming_languages: default_stemming_languages(),
+            search_cache_limits: CacheLimits::default(),
+            reader_cache_blocks: default_reader_cache_blocks(),
+            field_boosts: FieldBoosts::default(),
+            idle_release_minutes: default_idle_release_minutes(),
+            search_history_size: default_search_history_size(),
+            paste: PasteOptions::default(),
+            llm: LlmConfig::default(),
+            selection_action: SelectionAction::default(),
+            notifications: NotificationSettings::default(),
+            do_not_disturb: DndSettings::default(),
+            model_prices: pricing::default_price_table(),
+            git: GitSettings::default(),
+            query_macros: query_macros::default_macros(),
+            gist: GistSettings::default(),
+            webdav: WebDavSettings::default(),
+            prompt_file_encrypted: false,
+            settings: Settings::default(),
+            read_only_files: BTreeSet::new(),
+            prompt_hotkeys: BTreeMap::new(),
+            text_expander: ExpanderSettings::default(),
+            launcher_mode: false,
+            always_on_top: false,
+            log_levels: LogLevels::default(),
+        }
+    }
+}
+
+/// Состояние приложения, которое хранится в памяти
+/// Поля доступны через StateCell: команды, которые только читают, выполняются одновременно
+struct AppState {
+    config: StateCell<AppConfig>,
+    prompts: StateCell<PromptList>,
+    // Файл, из которого загружены промпты в памяти; None - библиотека не загружена
+    // (файл не разобран или зашифрован и заблокирован), изменять ее нельзя
+    loaded_file: StateCell<Option<String>>,
+    // Файл, открытый в режиме восстановления без поврежденных записей; записывать его можно
+    // только после подтверждения пользователя, иначе эти записи молча пропадут из файла
+    recovered_file: StateCell<Option<String>>,
+    // Хеш содержимого файла промптов на момент загрузки или последнего сохранения;
+    // если файл с тех пор изменила другая программа, сохранение отказывается его перезаписать
+    loaded_hash: StateCell<Option<String>>,
+    // Поисковый индекс; None, пока приложение не инициализировано
+    database: StateCell<Option<Database>>,
+    // Кэш результатов поиска: фильтр в JSON -> позиции найденных промптов
+    search_cache: StateCell<LruCache<String, Vec<usize>>>,
+    // Время последней команды, обращавшейся к индексу
+    last_activity: StateCell<Instant>,
+    // Индекс выгружен из-за простоя и будет открыт заново при следующем обращении
+    idle_released: AtomicBool,
+    // Момент запуска процесса, от него отсчитываются этапы инициализации
+    started_at: Instant,
+    // Замеры этапов запуска
+    startup_report: StateCell<StartupReport>,
+    // История выполненных поисковых запросов
+    search_history: StateCell<SearchHistory>,
+    // Последний текст, присланный через системный сервис, для переменной {selection}
+    selection: StateCell<Option<String>>,
+    // Флаги отмены выполняющихся потоковых запросов к LLM по их идентификаторам
+    executions: StateCell<HashMap<String, Arc<AtomicBool>>>,
+    // Сколько раз и когда использовался каждый промпт
+    usage: StateCell<UsageStats>,
+    // Переменные текущей сессии, доступные шаблонам
+    session: StateCell<SessionVariables>,
+    // Какие промпты выбирали в результатах поиска по запросам
+    feedback: StateCell<ClickFeedback>,
+    // Время последней правки промптов в памяти, еще не сохраненной на диск; None - все сохранено
+    unsaved_edit: StateCell<Option<Instant>>,
+    // Активный профиль; от него зависят директории конфигурации и данных
+    profile: StateCell<String>,
+    // Включен ли расширитель текста; слушатель клавиатуры без него отбрасывает нажатия
+    expander_enabled: AtomicBool,
+    // Запущен ли слушатель клавиатуры; остановить его нельзя, только выключить
+    expander_started: AtomicBool,
+    // Расширитель сейчас сам набирает текст, эти нажатия не учитываются
+    expander_typing: Arc<AtomicBool>,
+    // Окно другого приложения, активное до показа окна PromptTool; в него вставляется текст
+    previous_window: StateCell<Option<ForeignWindow>>,
+}
+
+impl AppState {
+    /// Состояние до инициализации: конфигурация по умолчанию, пустая библиотека, индекс не открыт
+    fn new() -> Self {
+        Self {
+            config: StateCell::new("Конфигурация", AppConfig::default()),
+            prompts: StateCell::new("Промпты", PromptList::new()),
+            loaded_file: StateCell::new("Загруженный файл промптов", None),
+            recovered_file: StateCell::new("Восстановленный файл промптов", None),
+            loaded_hash: StateCell::new("Хеш загруженного файла промптов", None),
+            database: StateCell::new("Поисковый индекс", None),
+            search_cache: StateCell::new("Кэш поиска", LruCache::new(CacheLimits::default())),
+            last_activity: StateCell::new("Время последней активности", Instant::now()),
+            idle_released: AtomicBool::new(false),
+            started_at: Instant::now(),
+            startup_report: StateCell::new("Отчет о запуске", StartupReport::default()),
+            search_history: StateCell::new("История поиска", SearchHistory::new(default_search_history_size())),
+            selection: StateCell::new("Выделенный текст", None),
+            executions: StateCell::new("Выполняющиеся запросы", HashMap::new()),
+            usage: StateCell::new("Статистика использования", UsageStats::default()),
+            session: StateCell::new("Переменные сессии", SessionVariables::default()),
+            feedback: StateCell::new("Статистика выбора результатов", ClickFeedback::default()),
+            unsaved_edit: StateCell::new("Несохраненные правки", None),
+            profile: StateCell::new("Активный профиль", DEFAULT_PROFILE.to_string()),
+            expander_enabled: AtomicBool::new(false),
+            expander_started: AtomicBool::new(false),
+            expander_typing: Arc::new(AtomicBool::new(false)),
+            previous_window: StateCell::new("Предыдущее окно", None),
+        }
+    }
+}
+
+/// Статистика использования памяти приложением
+#[derive(Debug, Serialize)]
+struct MemoryStats {
+    // Кэш результатов поиска
+    search_cache: CacheStats,
+    // История поисковых запросов
+    search_history: CacheStats,
+    // Кэш блоков документов читателя индекса (пустой, если индекс отключен)
+    reader_cache: CacheStats,
+    // Приблизительный размер загруженных промптов в байтах
+    prompts_bytes: usize,
+    // Объем сегментов поискового индекса в байтах (0, если индекс отключен)
+    index_bytes: u64,
+}
+
+/// Результат копирования текста в буфер обмена
+#[derive(Debug, Serialize)]
+struct ClipboardWrite {
+    // Текст после подготовки к вставке
+    text: String,
+    // Записан ли текст средствами ОС; если нет, его кладет в буфер фронтенд
+    native: bool,
+    // Нужно ли фронтенду после записи в буфер вызвать paste_into_previous_window
+    paste: bool,
+}
+
+/// Промпт в ответах команд: все поля промпта плюс вычисляемые данные
+#[derive(Debug, Serialize)]
+struct PromptDto {
+    #[serde(flatten)]
+    prompt: Prompt,
+    // Оценка количества токенов в тексте промпта для модели из настроек
+    token_count: Option<usize>,
+}
+
+/// Превращает промпты в DTO, подсчитывая токены для модели из настроек
+fn to_dtos(state: &AppState, prompts: Vec<Prompt>) -> Vec<PromptDto> {
+    let model = state.config
+        .read()
+        .map(|config| config.llm.model.clone())
+        .unwrap_or_default();
+
+    prompts.into_iter()
+        .map(|prompt| PromptDto {
+            token_count: tokens::count_tokens(&prompt.content, &model).ok(),
+            prompt,
+        })
+        .collect()
+}
+
+/// Выполняет работу с файлами в пуле блокирующих потоков, чтобы большой файл или медленный
+/// сетевой диск не занимал потоки асинхронных команд и не подвешивал интерфейс.
+/// Состояние приложения задача получает через `app_handle`: `State` команды в поток не передается
+async fn run_blocking<R: Runtime, T: Send + 'static>(
+    app_handle: &tauri::AppHandle<R>,
+    task: impl FnOnce(&AppState, &tauri::AppHandle<R>) -> Result<T> + Send + 'static,
+) -> Result<T> {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || task(&app_handle.state::<AppState>(), &app_handle))
+        .await
+        .map_err(|e| PromptToolError::Execution(format!("Фоновая операция с файлами прервана: {}", e)))?
+}
+
+/// Сохраняет данные на диск в пуле блокирующих потоков, не дожидаясь записи
+/// Нужна командам, которые вызываются часто (поиск при наборе, выбор результата): запись файла
+/// не должна их задерживать. Ошибки записи не мешают работе и попадают в журнал
+fn save_in_background<R: Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    what: &'static str,
+    save: impl FnOnce(&AppState, &tauri::AppHandle<R>) -> Result<()> + Send + 'static,
+) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(e) = save(&app_handle.state::<AppState>(), &app_handle) {
+            warn!("Не удалось сохранить {}: {}", what, e);
+        }
+    });
+}
+
+/// Директории конфигурации и данных активного профиля
+fn app_paths<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> AppPaths {
+    let profile = app_handle.state::<AppState>().profile
+        .read()
+        .map(|profile| profile.clone())
+        .unwrap_or_else(|_| DEFAULT_PROFILE.to_string());
+
+    profiles::profile_paths(&app_handle.state::<AppPaths>(), &profile)
+}
+
+/// Путь к списку профилей; он общий для всех профилей и лежит в директории конфигурации приложения
+fn profiles_path<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> PathBuf {
+    app_handle.state::<AppPaths>().config_dir.join(PROFILES_FILE_NAME)
+}
+
+/// Сохраняет конфигурацию в config.json в директории конфигурации активного профиля
+fn save_config<R: Runtime>(app_handle: &tauri::AppHandle<R>, config: &AppConfig) -> Result<()> {
+    let app_dir = app_paths(app_handle).config_dir.clone();
+
+    std::fs::create_dir_all(&app_dir)
+        .map_err(PromptToolError::Io)?;
+
+    let config_path = app_dir.join("config.json");
+    let config_str = serde_json::to_string_pretty(config)
+        .map_err(|_| PromptToolError::Config("Ошибка сериализации конфигурации".to_string()))?;
+
+    std::fs::write(config_path, config_str)
+        .map_err(PromptToolError::Io)?;
+
+    Ok(())
+}
+
+/// Команда для поиска промптов с фильтрацией
+/// Текстовый запрос и диапазон дат обрабатываются поисковым индексом,
+/// категории и теги проверяются в памяти. Макросы `@имя` раскрываются до поиска
+#[tauri::command]
+async fn search_prompts<R: Runtime>(
+    mut filter: SearchFilter,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<Vec<PromptDto>> {
+    if let Some(query) = &filter.query {
+        record_search(&state, &app_handle, query)?;
+    }
+    let user_query = filter.query.clone().unwrap_or_default();
+
+    // В историю попадает запрос в том виде, в каком его ввел пользователь, а ищется раскрытый
+    let (macros, settings) = state.config
+        .read()
+        .map(|config| (config.query_macros.clone(), config.settings.clone()))?;
+    query_macros::apply_macros(&mut filter, &macros, chrono::Utc::now())?;
+
+    let cache_key = serde_json::to_string(&filter).ok();
+    let cached = match &cache_key {
+        Some(key) => state.search_cache.write()?.get(key),
+        None => None,
+    };
+
+    // Индекс блокируется раньше промптов, как и во всех остальных командах
+    let database = database_guard(&state, &app_handle)?;
+    let prompts = state.prompts.read()?;
+
+    let positions = match cached {
+        Some(positions) => positions,
+        None => {
+            let positions = find_prompt_positions(&filter, &prompts, database.as_ref())?;
+            if let Some(key) = cache_key {
+                state.search_cache.write()?.insert(key, positions.clone());
+            }
+            positions
+        }
+    };
+
+    // Каждый промпт попадает в ответ один раз, даже если совпал в нескольких документах индекса
+    let found: Vec<Prompt> = dedup_hits(positions).into_iter()
+        .filter_map(|position| prompts.prompts.get(position))
+        .cloned()
+        .collect();
+
+    // Подсчет токенов не должен держать блокировки индекса и промптов
+    drop(prompts);
+    drop(database);
+
+    // Промпты, которые часто выбирали по похожим запросам, поднимаются выше
+    let mut found = state.feedback.write()?.rerank(&user_query, found, |prompt| prompt.name.as_str());
+    // Совпадение с псевдонимом важнее накопленных выборов
+    aliases_first(&mut found, &user_query);
+    filter.sort.unwrap_or(settings.default_sort).apply(&mut found);
+    found.truncate(settings.result_limit);
+
+    Ok(to_dtos(&state, found))
+}
+
+/// Зашифрован ли текущий файл промптов
+/// Пока зашифрован, данные с текстами промптов и запросами (индекс, история поиска, выборы
+/// результатов, выполнения, ревизии) хранятся только в памяти
+fn library_encrypted(state: &AppState) -> bool {
+    state.config
+        .read()
+        .map(|config| config.prompt_file_encrypted)
+        .unwrap_or(true)
+}
+
+/// Удаляет файл или директорию с данными открытым текстом; отсутствие файла - не ошибка
+fn remove_plaintext_file(path: &std::path::Path) {
+    let removed = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    if let Err(e) = removed {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!("Не удалось удалить {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Удаляет с диска данные открытым текстом, раскрывающие содержимое библиотеки
+/// Вызывается при включении шифрования; в памяти данные остаются до выхода из приложения
+fn remove_plaintext_data<R: Runtime>(app_handle: &tauri::AppHandle<R>) {
+    let data_dir = app_paths(app_handle).data_dir;
+    for name in [INDEX_DIR_NAME, SEARCH_HISTORY_FILE_NAME, FEEDBACK_FILE_NAME, RUNS_FILE_NAME, REVISIONS_FILE_NAME] {
+        remove_plaintext_file(&data_dir.join(name));
+    }
+}
+
+/// Путь к файлу с выборами результатов поиска
+fn feedback_path<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> PathBuf {
+    app_paths(app_handle).data_dir.join(FEEDBACK_FILE_NAME)
+}
+
+/// Команда для учета выбора результата поиска
+/// `query` - запрос в том виде, в каком его ввел пользователь
+#[tauri::command]
+async fn record_result_click(
+    query: String,
+    prompt_name: String,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    state.feedback.write()?.record(&query, &prompt_name);
+    save_feedback(&app_handle);
+    Ok(())
+}
+
+/// Сохраняет выборы результатов в фоне; пока библиотека зашифрована, они остаются только в памяти
+/// Признак шифрования проверяется при записи: его могли включить, пока запись ждала очереди
+fn save_feedback<R: Runtime>(app_handle: &tauri::AppHandle<R>) {
+    save_in_background(app_handle, "выборы результатов поиска", |state, app_handle| {
+        if library_encrypted(state) {
+            return Ok(());
+        }
+        state.feedback.write()?.save(&feedback_path(app_handle))
+    });
+}
+
+/// Команда для сброса накопленных выборов результатов поиска
+#[tauri::command]
+async fn clear_click_feedback(state: State<'_, AppState>, app_handle: tauri::AppHandle) -> Result<()> {
+    let mut feedback = state.feedback.write()?;
+
+    feedback.clear();
+    feedback.save(&feedback_path(&app_handle))
+}
+
+/// Путь к файлу истории поиска
+fn search_history_path<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> PathBuf {
+    app_paths(app_handle).data_dir.join(SEARCH_HISTORY_FILE_NAME)
+}
+
+/// Путь к файлу статистики использования промптов
+fn usage_path<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> PathBuf {
+    app_paths(app_handle).data_dir.join(USAGE_FILE_NAME)
+}
+
+/// Отмечает использование промпта и сохраняет статистику; ошибки записи не мешают работе
+fn record_usage<R: Runtime>(state: &AppState, app_handle: &tauri::AppHandle<R>, prompt_name: &str) -> Result<()> {
+    let mut usage = state.usage.write()?;
+    usage.record(prompt_name);
+    if let Err(e) = usage.save(&usage_path(app_handle)) {
+        warn!("Не удалось сохранить статистику использования: {}", e);
+    }
+    Ok(())
+}
+
+/// Команда для экспорта статистики библиотеки в Markdown-файл
+#[tauri::command]
+async fn export_stats_report(path: String, app_handle: tauri::AppHandle) -> Result<()> {
+    run_blocking(&app_handle, move |state, _| {
+        let report = {
+            let prompts = state.prompts.read()?;
+            let usage = state.usage.read()?;
+            build_stats_report(&prompts, &usage, chrono::Utc::now())
+        };
+
+        std::fs::write(&path, report)
+            .map_err(PromptToolError::Io)
+    }).await
+}
+
+/// Добавляет запрос в историю и сохраняет ее в фоне; ошибки записи не мешают поиску
+fn record_search<R: Runtime>(state: &AppState, app_handle: &tauri::AppHandle<R>, query: &str) -> Result<()> {
+    state.search_history.write()?.record(query);
+    save_in_background(app_handle, "историю поиска", |state, app_handle| {
+        if library_encrypted(state) {
+            return Ok(());
+        }
+        state.search_history.write()?.save(&search_history_path(app_handle))
+    });
+    Ok(())
+}
+
+/// Команда для получения истории поиска (от самых свежих запросов к старым)
+#[tauri::command]
+async fn get_search_history(state: State<'_, AppState>) -> Result<Vec<HistoryEntry>> {
+    state.search_history
+        .read()
+        .map(|history| history.entries())
+}
+
+/// Команда для очистки истории поиска
+#[tauri::command]
+async fn clear_search_history(
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let mut history = state.search_history.write()?;
+
+    history.clear();
+    history.save(&search_history_path(&app_handle))
+}
+
+/// Команда для подсказки прошлых запросов по введенному префиксу
+#[tauri::command]
+async fn suggest_search_history(
+    prefix: String,
+    limit: usize,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>> {
+    state.search_history
+        .read()
+        .map(|history| history.suggest(&prefix, limit))
+}
+
+/// Находит позиции промптов, подходящих под фильтр
+fn find_prompt_positions(filter: &SearchFilter, prompts: &PromptList, database: Option<&Database>) -> Result<Vec<usize>> {
+    let uses_index = filter.query.is_some() || filter.date_from.is_some() || filter.date_to.is_some();
+
+    let Some(database) = database.filter(|_| uses_index) else {
+        let mut positions: Vec<usize> = prompts.prompts
+            .iter()
+            .enumerate()
+            .filter(|(_, prompt)| prompt.matches_filter(filter))
+            .map(|(position, _)| position)
+            .collect();
+        if let Some(query) = &filter.query {
+            positions.sort_by_key(|position| !prompts.prompts[*position].matches_alias(query));
+        }
+        return Ok(positions);
+    };
+
+    let range = (filter.date_from.is_some() || filter.date_to.is_some()).then(|| DateRange {
+        field: DateField::UpdatedAt,
+        from: filter.date_from.map(|date| date.timestamp().max(0) as u64),
+        to: filter.date_to.map(|date| date.timestamp().max(0) as u64),
+    });
+
+    let query = filter.index_query().unwrap_or_default();
+    let ids = database
+        .search_ids(&query, range, prompts.prompts.len())
+        .map_err(|e| PromptToolError::Search(e.to_string()))?;
+
+    // Остальные критерии проверяем в памяти
+    let rest = SearchFilter {
+        query: None,
+        categories: filter.categories.clone(),
+        tags: filter.tags.clone(),
+        date_from: None,
+        date_to: None,
+        exact: false,
+        min_rating: filter.min_rating,
+        sort: filter.sort,
+        include_archived: filter.include_archived,
+    };
+
+    // Псевдоним из нескольких слов индекс целиком не сопоставляет, поэтому совпадения
+    // по псевдонимам берутся из памяти и идут первыми
+    let aliased: Vec<usize> = prompts.prompts
+        .iter()
+        .enumerate()
+        .filter(|(_, prompt)| filter.query.as_deref().is_some_and(|query| prompt.matches_alias(query)) && prompt.matches_filter(filter))
+        .map(|(position, _)| position)
+        .collect();
+
+    let found = ids.into_iter()
+        .map(|id| id as usize)
+        .filter(|position| !aliased.contains(position))
+        .filter(|position| prompts.prompts.get(*position).is_some_and(|prompt| prompt.matches_filter(&rest)));
+    Ok(aliased.iter().copied().chain(found).collect())
+}
+
+/// Команда для получения статистики использования памяти
+#[tauri::command]
+async fn get_memory_stats(state: State<'_, AppState>) -> Result<MemoryStats> {
+    let search_cache = state.search_cache.read()
+        .map(|cache| cache.stats())?;
+    let search_history = state.search_history.read()
+        .map(|history| history.stats())?;
+
+    let prompts_bytes = state.prompts.read()
+        .map(|prompts| prompts.approximate_size())?;
+
+    let (index_bytes, reader_cache) = match state.database.read() {
+        Ok(database) => match database.as_ref() {
+            Some(database) => (
+                database.space_usage()
+                    .map_err(|e| PromptToolError::Search(e.to_string()))?,
+                database.reader_cache_stats(),
+            ),
+            None => (0, CacheStats::default()),
+        },
+        Err(_) => (0, CacheStats::default()),
+    };
+
+    Ok(MemoryStats { search_cache, search_history, reader_cache, prompts_bytes, index_bytes })
+}
+
+/// Команда для изменения ограничений кэша результатов поиска
+#[tauri::command]
+async fn set_search_cache_limits(
+    limits: CacheLimits,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    state.search_cache.write()?.set_limits(limits);
+
+    {
+        let mut config = state.config.write()?;
+        config.search_cache_limits = limits;
+        save_config(&app_handle, &config)?;
+    }
+
+    Ok(())
+}
+
+/// Команда для изменения количества блоков документов в кэше читателя индекса (0 - не кэшировать)
+#[tauri::command]
+async fn set_reader_cache_blocks(
+    blocks: usize,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    if let Some(database) = state.database.write()?.as_mut() {
+        database.set_reader_cache_blocks(blocks)
+            .map_err(|e| PromptToolError::Search(e.to_string()))?;
+    }
+
+    {
+        let mut config = state.config.write()?;
+        config.reader_cache_blocks = blocks;
+        save_config(&app_handle, &config)?;
+    }
+
+    Ok(())
+}
+
+/// Команда автодополнения для быстрого поиска
+/// Возвращает названия промптов и теги, начинающиеся с введённого префикса
+#[tauri::command]
+async fn suggest(
+    prefix: String,
+    limit: usize,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<Suggestion>> {
+    if limit > MAX_SUGGESTIONS {
+        return Err(PromptToolError::Validation(format!(
+            "limit: должно быть не больше {}",
+            MAX_SUGGESTIONS
+        )));
+    }
+
+    let database = database_guard(&state, &app_handle)?;
+
+    if let Some(database) = database.as_ref() {
+        return database.suggest(&prefix, limit)
+            .map_err(|e| PromptToolError::Search(e.to_string()));
+    }
+
+    // Индекс отключен - подсказки строятся по промптам в памяти
+    let prompts = state.prompts.read()?;
+
+    Ok(prompts.suggest(&prefix, limit))
+}
+
+/// Команда для получения списка всех категорий
+#[tauri::command]
+async fn get_categories(
+    state: State<'_, AppState>
+) -> Result<Vec<String>> {
+    let prompts = state.prompts.read()?;
+    
+    Ok(prompts.get_categories()
+        .into_iter()
+        .cloned()
+        .collect())
+}
+
+/// Команда для получения списка всех тегов
+#[tauri::command]
+async fn get_tags(
+    state: State<'_, AppState>
+) -> Result<Vec<String>> {
+    let prompts = state.prompts.read()?;
+    
+    Ok(prompts.get_tags()
+        .into_iter()
+        .cloned()
+        .collect())
+}
+
+/// Команда для получения списка промптов
+/// Возвращает страницу кратких описаний; полный текст промпта - get_prompt_detail
+#[tauri::command]
+async fn get_prompts<R: Runtime>(
+    file_path: Option<String>,
+    page: Option<PageRequest>,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<Page<PromptSummary>> {
+    let page = page.unwrap_or_default();
+
+    // Без пути отдаем открытую библиотеку из памяти, не перечитывая файл
+    let Some(path) = file_path else {
+        let state = app_handle.state::<AppState>();
+        let prompts = state.prompts.read()?;
+        return page_of_prompts(&prompts.prompts, &page);
+    };
+
+    run_blocking(&app_handle, move |_, _| {
+        let prompt_list = load_prompts(&path)?;
+        page_of_prompts(&prompt_list.prompts, &page)
+    }).await
+}
+
+/// Команда для получения промпта целиком по идентификатору
+/// Промпты без идентификатора ищутся по имени
+#[tauri::command]
+async fn get_prompt_detail<R: Runtime>(
+    id: String,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<PromptDto> {
+    let state = app_handle.state::<AppState>();
+    let prompt = {
+        let prompts = state.prompts.read()?;
+        prompts.prompts.iter()
+            .find(|prompt| prompt.id == id)
+            .or_else(|| prompts.prompts.iter().find(|prompt| prompt.id.is_empty() && prompt.name == id))
+            .cloned()
+            .ok_or_else(|| PromptToolError::Validation(format!("Промпт не найден: {}", id)))?
+    };
+
+    Ok(to_dtos(&state, vec![prompt]).remove(0))
+}
+
+/// Команда для установки нового пути к файлу промптов
+#[tauri::command]
+async fn set_prompt_file_path<R: Runtime>(
+    path: String,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        // Проверяем существование файла
+        let path_buf = PathBuf::from(&path);
+        if !path_buf.exists() {
+            return Err(PromptToolError::Config("Файл не существует".to_string()));
+        }
+
+        // Несохраненные правки относятся к прежнему файлу
+        flush_prompts(state, app_handle)?;
+
+        // Загружаем промпты из нового файла
+        migrate_prompt_file(state, &path)?;
+        let new_prompts = load_prompts(&path)?;
+        open_prompt_file(path, new_prompts, state, app_handle)
+    }).await
+}
+
+/// Команда для загрузки поврежденного файла промптов в режиме восстановления
+/// Загружает все корректные промпты и возвращает записи, которые прочитать не удалось.
+/// Если такие записи есть, библиотека открывается только для чтения: сохранение удалит их из файла,
+/// поэтому изменять ее можно после подтверждения командой confirm_prompt_file_recovery
+#[tauri::command]
+async fn recover_prompt_file<R: Runtime>(
+    path: String,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<Vec<PromptDiagnostic>> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        if !PathBuf::from(&path).exists() {
+            return Err(PromptToolError::Config("Файл не существует".to_string()));
+        }
+
+        flush_prompts(state, app_handle)?;
+
+        // Формат на диске не обновляется: файл с ошибками перезапишется только при сохранении
+        let contents = read_prompt_file(&path)?;
+        let recovered = parse_prompts_recovering(&path, &contents)?;
+        open_prompt_file(path.clone(), recovered.prompts, state, app_handle)?;
+
+        if !recovered.broken.is_empty() {
+            *state.loaded_file.write()? = None;
+            *state.recovered_file.write()? = Some(path);
+        }
+        Ok(recovered.broken)
+    }).await
+}
+
+/// Команда для подтверждения восстановления: библиотеку, открытую recover_prompt_file,
+/// разрешается изменять, и при сохранении поврежденные записи пропадут из файла.
+/// Прежнее содержимое файла останется в резервных копиях
+#[tauri::command]
+async fn confirm_prompt_file_recovery(state: State<'_, AppState>) -> Result<()> {
+    let recovered = state.recovered_file.write()?.take();
+    let file_path = state.config
+        .read()
+        .map(|config| config.prompt_file_path.clone())?;
+
+    match recovered {
+        Some(path) if path == file_path => {
+            *state.loaded_file.write()? = Some(path);
+            Ok(())
+        }
+        _ => Err(PromptToolError::Validation("Нет восстановленного файла, ожидающего подтверждения".to_string())),
+    }
+}
+
+/// Делает загруженную библиотеку текущей и запоминает путь к ее файлу в конфигурации
+fn open_prompt_file<R: Runtime>(
+    path: String,
+    new_prompts: PromptList,
+    state: &AppState,
+    app_handle: &tauri::AppHandle<R>,
+) -> Result<()> {
+    let encrypted = std::fs::read_to_string(&path)
+        .is_ok_and(|contents| encryption::is_encrypted(&contents));
+
+    // Обновляем состояние
+    set_library(state, new_prompts, Some(&path))?;
+    sync_index(state, app_handle)?;
+
+    // Обновляем конфигурацию
+    let mut config = state.config.write()?;
+    config.prompt_file_path = path;
+    config.prompt_file_encrypted = encrypted;
+
+    // Сохраняем обновленную конфигурацию
+    save_config(app_handle, &config)?;
+
+    Ok(())
+}
+
+/// Команда для экспорта промптов в файл сниппетов VS Code (`.code-snippets`)
+/// `scope` - языки, в которых доступны сниппеты (например, `rust,python`); `category` ограничивает набор промптов
+#[tauri::command]
+async fn export_vscode_snippets(
+    path: String,
+    scope: Option<String>,
+    category: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<usize> {
+    run_blocking(&app_handle, move |state, _| {
+        let prompts = state.prompts.read()?;
+
+        let selected: Vec<&Prompt> = prompts.prompts
+            .iter()
+            .filter(|prompt| category.as_ref().is_none_or(|category| prompt.categories.contains(category)))
+            .collect();
+
+        vscode::export_snippets(&selected, scope.as_deref(), std::path::Path::new(&path))
+    }).await
+}
+
+/// Команда для экспорта выбранных промптов в сниппеты Raycast (JSON) или Alfred (`.alfredsnippets`)
+/// Ключевые слова собираются из тегов и названий; `keyword_prefix` по умолчанию `;`
+#[tauri::command]
+async fn export_launcher_snippets(
+    format: LauncherFormat,
+    path: String,
+    prompt_names: Vec<String>,
+    keyword_prefix: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<usize> {
+    run_blocking(&app_handle, move |state, _| {
+        let prompts = state.prompts.read()?;
+
+        let selected = select_prompts(&prompts, &prompt_names)?;
+        let prefix = keyword_prefix.as_deref().unwrap_or(DEFAULT_KEYWORD_PREFIX);
+        launchers::export_snippets(&selected, format, prefix, std::path::Path::new(&path))
+    }).await
+}
+
+/// Команда для экспорта выбранных промптов в файл правил Espanso
+/// Параметры промпта становятся полями формы; `trigger_prefix` по умолчанию `:`
+#[tauri::command]
+async fn export_espanso(
+    path: String,
+    prompt_names: Vec<String>,
+    trigger_prefix: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<usize> {
+    run_blocking(&app_handle, move |state, _| {
+        let prompts = state.prompts.read()?;
+
+        let selected = select_prompts(&prompts, &prompt_names)?;
+        let prefix = trigger_prefix.as_deref().unwrap_or(DEFAULT_TRIGGER_PREFIX);
+        espanso::export_matches(&selected, prefix, std::path::Path::new(&path))
+    }).await
+}
+
+/// Находит промпты по названиям в порядке перечисления
+fn select_prompts<'a>(prompts: &'a PromptList, names: &[String]) -> Result<Vec<&'a Prompt>> {
+    names
+        .iter()
+        .map(|name| {
+            prompts.prompts
+                .iter()
+                .find(|prompt| &prompt.name == name)
+                .ok_or_else(|| PromptToolError::Validation(format!("Промпт \"{}\" не найден", name)))
+        })
+        .collect()
+}
+
+/// Команда для обновления файла библиотеки старого формата на месте
+/// Недостающие идентификаторы, время создания и пустые списки дописываются в файл,
+/// исходный файл сохраняется в резервную копию. Если обновлен текущий файл промптов, он перечитывается
+#[tauri::command]
+async fn migrate_library(
+    path: String,
+    app_handle: tauri::AppHandle,
+) -> Result<MigrationReport> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        ensure_not_read_only(state, &path)?;
+        // Обновленный файл может оказаться текущим и будет перечитан
+        flush_prompts(state, app_handle)?;
+        let report = legacy::migrate_library(std::path::Path::new(&path), chrono::Utc::now())?;
+
+        let current = state.config
+            .read()
+            .map(|config| config.prompt_file_path.clone())?;
+
+        if report.changed() && PathBuf::from(&current) == report.path {
+            let upgraded = load_prompts(&current)?;
+            set_library(state, upgraded, Some(&current))?;
+            sync_index(state, app_handle)?;
+        }
+
+        Ok(report)
+    }).await
+}
+
+/// Команда для разбора выгрузки ChatGPT (`conversations.json`)
+/// Возвращает предложенные промпты, не добавляя их; выбранные сохраняются командой `add_prompts`
+#[tauri::command]
+async fn preview_chatgpt_import(
+    path: String,
+    options: Option<ChatGptImportOptions>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<Prompt>> {
+    run_blocking(&app_handle, move |state, _| {
+        let prompts = state.prompts.read()?;
+
+        chatgpt::import_conversations(std::path::Path::new(&path), &options.unwrap_or_default(), &prompts)
+    }).await
+}
+
+/// Команда для добавления промптов в библиотеку (например, выбранных из предложенных при импорте)
+#[tauri::command]
+async fn add_prompts(
+    mut prompts: Vec<Prompt>,
+    app_handle: tauri::AppHandle,
+) -> Result<usize> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let count = prompts.len();
+        for prompt in prompts.iter_mut().filter(|prompt| prompt.id.is_empty()) {
+            prompt.id = new_prompt_id();
+        }
+
+        let (file_path, git) = state.config
+            .read()
+            .map(|config| (config.prompt_file_path.clone(), config.git.clone()))?;
+        ensure_writable(state, &file_path)?;
+
+        {
+            let mut library = state.prompts.write()?;
+            validation::validate_new_prompts(&prompts, &library)?;
+            library.prompts.extend(prompts);
+            persist_prompts(app_handle, &file_path, &library, &git)?;
+        }
+        sync_index(state, app_handle)?;
+
+        Ok(count)
+    }).await
+}
+
+/// Команда для импорта промптов из JSON или CSV
+/// Импортированные промпты добавляются к текущему списку и сохраняются в файл из конфигурации
+#[tauri::command]
+async fn import_prompts(
+    path: String,
+    app_handle: tauri::AppHandle,
+) -> Result<usize> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let imported = import_file(&path)?;
+        let count = imported.prompts.len();
+
+        let (file_path, git) = state.config
+            .read()
+            .map(|config| (config.prompt_file_path.clone(), config.git.clone()))?;
+        ensure_writable(state, &file_path)?;
+
+        {
+            let mut prompts = state.prompts.write()?;
+            validation::validate_new_prompts(&imported.prompts, &prompts)?;
+            prompts.prompts.extend(imported.prompts);
+            persist_prompts(app_handle, &file_path, &prompts, &git)?;
+        }
+        sync_index(state, app_handle)?;
+
+        notify(app_handle, NotificationKind::ImportCompleted, "Импорт завершен", &format!("Импортировано промптов: {}", count));
+        Ok(count)
+    }).await
+}
+
+/// Команда для импорта списка awesome-chatgpt-prompts (CSV `act,prompt` с prompts.chat)
+/// Промпты получают категорию `category` (по умолчанию `awesome-chatgpt-prompts`, пустая строка - без категории);
+/// промпты с уже существующими названиями пропускаются. Возвращает количество добавленных промптов
+#[tauri::command]
+async fn import_awesome_prompts(
+    path: String,
+    category: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<usize> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let contents = std::fs::read_to_string(&path)
+            .map_err(PromptToolError::Io)?;
+
+        let (file_path, git) = state.config
+            .read()
+            .map(|config| (config.prompt_file_path.clone(), config.git.clone()))?;
+        ensure_writable(state, &file_path)?;
+
+        let count = {
+            let mut prompts = state.prompts.write()?;
+            let category = category.as_deref().unwrap_or(AWESOME_PROMPTS_CATEGORY);
+            let imported = import_awesome_csv(&contents, Some(category), &prompts)?;
+            validation::validate_new_prompts(&imported.prompts, &prompts)?;
+
+            let count = imported.prompts.len();
+            prompts.prompts.extend(imported.prompts);
+            persist_prompts(app_handle, &file_path, &prompts, &git)?;
+            count
+        };
+        sync_index(state, app_handle)?;
+
+        notify(app_handle, NotificationKind::ImportCompleted, "Импорт завершен", &format!("Импортировано промптов: {}", count));
+        Ok(count)
+    }).await
+}
+
+/// Команда для импорта дерева папок с промптами
+/// Имена папок превращаются в теги/категории согласно настройке folder_mapping
+#[tauri::command]
+async fn import_prompt_directory(
+    path: String,
+    app_handle: tauri::AppHandle,
+) -> Result<usize> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let (file_path, mapping, git) = state.config
+            .read()
+            .map(|config| (config.prompt_file_path.clone(), config.folder_mapping.clone(), config.git.clone()))?;
+        ensure_writable(state, &file_path)?;
+
+        let imported = import_directory(&path, &mapping)?;
+        let count = imported.prompts.len();
+
+        {
+            let mut prompts = state.prompts.write()?;
+            validation::validate_new_prompts(&imported.prompts, &prompts)?;
+            prompts.prompts.extend(imported.prompts);
+            persist_prompts(app_handle, &file_path, &prompts, &git)?;
+        }
+        sync_index(state, app_handle)?;
+
+        notify(app_handle, NotificationKind::ImportCompleted, "Импорт завершен", &format!("Импортировано промптов: {}", count));
+        Ok(count)
+    }).await
+}
+
+/// Команда для разбиения библиотеки на TOML-файлы по категориям, обратная импорту директории
+/// `strategy` определяет, дублировать ли промпты с несколькими категориями или ссылаться на них.
+/// В непустую директорию файлы записываются только после подтверждения пользователя (`overwrite`)
+#[tauri::command]
+async fn split_library_by_category(
+    output_dir: String,
+    strategy: Option<SharedPromptStrategy>,
+    overwrite: Option<bool>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<SplitFile>> {
+    run_blocking(&app_handle, move |state, _| {
+        let file_path = state.config
+            .read()
+            .map(|config| config.prompt_file_path.clone())?;
+        let prompts = state.prompts.read()?;
+
+        split::split_by_category(
+            &prompts,
+            std::path::Path::new(&output_dir),
+            strategy.unwrap_or_default(),
+            overwrite.unwrap_or(false),
+            std::path::Path::new(&file_path),
+        )
+    }).await
+}
+
+/// Команда для экспорта библиотеки в хранилище Obsidian: заметка на каждый промпт и категорию
+#[tauri::command]
+async fn export_obsidian_vault(output_dir: String, app_handle: tauri::AppHandle) -> Result<VaultExport> {
+    run_blocking(&app_handle, move |state, _| {
+        let prompts = state.prompts.read()?;
+
+        obsidian::export_vault(&prompts, std::path::Path::new(&output_dir))
+    }).await
+}
+
+/// Команда для поиска данных других менеджеров сниппетов на диске
+#[tauri::command]
+async fn detect_importable_sources(app_handle: tauri::AppHandle) -> Result<Vec<ImportableSource>> {
+    let home = app_handle.path().home_dir()
+        .map_err(|e| PromptToolError::Config(format!("Не удалось определить домашнюю директорию: {}", e)))?;
+
+    Ok(detect_sources(&home))
+}
+
+/// Команда для импорта найденного источника сниппетов в текущую библиотеку
+#[tauri::command]
+async fn import_from_source(
+    kind: SourceKind,
+    path: String,
+    app_handle: tauri::AppHandle,
+) -> Result<usize> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let imported = import_source(kind, std::path::Path::new(&path))?;
+        let count = imported.prompts.len();
+
+        let (file_path, git) = state.config
+            .read()
+            .map(|config| (config.prompt_file_path.clone(), config.git.clone()))?;
+        ensure_writable(state, &file_path)?;
+
+        {
+            let mut prompts = state.prompts.write()?;
+            validation::validate_new_prompts(&imported.prompts, &prompts)?;
+            prompts.prompts.extend(imported.prompts);
+            persist_prompts(app_handle, &file_path, &prompts, &git)?;
+        }
+        sync_index(state, app_handle)?;
+
+        notify(app_handle, NotificationKind::ImportCompleted, "Импорт завершен", &format!("Импортировано промптов: {}", count));
+        Ok(count)
+    }).await
+}
+
+/// Резервные копии файла промптов в директории данных приложения
+fn backup_store<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> BackupStore {
+    BackupStore::new(app_paths(app_handle).data_dir.join(BACKUPS_DIR_NAME))
+}
+
+/// Обновляет формат файла промптов старой версии на диске перед загрузкой
+/// Исходный файл сохраняется рядом как резервная копия `<файл>.<время>.bak`
+fn migrate_prompt_file(state: &AppState, file_path: &str) -> Result<()> {
+    // Файл только для чтения обновляется лишь в памяти при загрузке
+    let read_only = state.config
+        .read()
+        .map(|config| is_read_only(&config, file_path))
+        .unwrap_or(true);
+    if read_only {
+        return Ok(());
+    }
+
+    if let Some(migration) = migrations::migrate_file(std::path::Path::new(file_path), chrono::Utc::now())? {
+        info!(
+            "Формат файла {} обновлен с версии {} до {}, резервная копия: {}",
+            file_path,
+            migration.from_version,
+            migration.to_version,
+            migration.backup.display()
+        );
+    }
+    Ok(())
+}
+
+/// Сохраняет библиотеку в файл и, если включена интеграция с git, фиксирует сохранение коммитом
+/// Перед записью прежнее содержимое файла копируется в резервные копии.
+/// Файл к этому моменту уже записан, поэтому сбой git не отменяет сохранение
+fn persist_prompts<R: Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    file_path: &str,
+    prompts: &PromptList,
+    git: &GitSettings,
+) -> Result<()> {
+    persist_prompts_with_backup(app_handle, file_path, prompts, git, Duration::ZERO)
+}
+
+/// Сохраняет библиотеку как `persist_prompts`, но не создает резервную копию,
+/// если последняя копия файла моложе `backup_interval`
+/// Копия создается, когда исключительная блокировка файла уже взята: при неудачной
+/// попытке записи (файл занят или заблокирован) копий не прибавляется
+fn persist_prompts_with_backup<R: Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    file_path: &str,
+    prompts: &PromptList,
+    git: &GitSettings,
+    backup_interval: Duration,
+) -> Result<()> {
+    let state = app_handle.state::<AppState>();
+    ensure_not_read_only(&state, file_path)?;
+
+    let keep = state.config
+        .read()
+        .map(|config| config.settings.backup_count)
+        .unwrap_or_else(|_| Settings::default().backup_count);
+
+    let expected = state.loaded_hash.read()?.clone();
+    let mut written = None;
+    save_prompts_with(file_path, prompts, |current, new| {
+        // Файл изменили после загрузки: запись затерла бы чужие правки
+        if let (Some(expected), Some(current)) = (&expected, current) {
+            if content_hash(current) != *expected {
+                return Err(PromptToolError::Conflict(format!(
+                    "{}: перечитайте файл или объедините правки, прежде чем сохранять",
+                    file_path
+                )));
+            }
+        }
+        written = Some(content_hash(new));
+
+        let store = backup_store(app_handle);
+        let source = std::path::Path::new(file_path);
+        let now = chrono::Utc::now();
+
+        let recent = store.newest(source)
+            .ok()
+            .flatten()
+            .is_some_and(|newest| (now - newest.created_at).to_std().is_ok_and(|age| age < backup_interval));
+        if recent {
+            return Ok(());
+        }
+
+        match store.create(source, now, keep) {
+            Ok(Some(_)) => notify(
+                app_handle,
+                NotificationKind::BackupCreated,
+                "Резервная копия создана",
+                "Перед сохранением создана резервная копия файла промптов",
+            ),
+            Ok(None) => {}
+            Err(e) => {
+                warn!("Не удалось создать резервную копию файла промптов: {}", e);
+                notify(
+                    app_handle,
+                    NotificationKind::BackupFailed,
+                    "Резервная копия не создана",
+                    &format!("Не удалось создать резервную копию файла промптов: {}", e),
+                );
+            }
+        }
+        Ok(())
+    })?;
+    *state.loaded_hash.write()? = written;
+
+    if git.enabled {
+        if let Err(e) = git::commit_prompts(std::path::Path::new(file_path), git) {
+            warn!("Не удалось зафиксировать изменения промптов в git: {}", e);
+            notify_sync_failed(app_handle, &e);
+        }
+    }
+    Ok(())
+}
+
+/// Открыт ли файл промптов только для чтения: отмечен так в настройках
+/// или недоступен для записи в файловой системе (например, общий файл на сетевом диске)
+fn is_read_only(config: &AppConfig, file_path: &str) -> bool {
+    config.read_only_files.contains(file_path) || !file_io::is_writable(std::path::Path::new(file_path))
+}
+
+/// Возвращает ошибку, если файл промптов `file_path` открыт только для чтения
+/// Вызывается командами, которые записывают файл целиком, не опираясь на промпты в памяти
+fn ensure_not_read_only(state: &AppState, file_path: &str) -> Result<()> {
+    let read_only = state.config
+        .read()
+        .map(|config| is_read_only(&config, file_path))?;
+
+    if read_only {
+        return Err(PromptToolError::ReadOnly(file_path.to_string()));
+    }
+    Ok(())
+}
+
+/// Возвращает ошибку, если библиотеку в памяти нельзя менять и записывать в `file_path`:
+/// файл открыт только для чтения или не был загружен. Вызывается изменяющими командами до любых правок.
+/// Без проверки загрузки одна правка после ошибки разбора записала бы поверх файла пустую библиотеку
+fn ensure_writable(state: &AppState, file_path: &str) -> Result<()> {
+    ensure_not_read_only(state, file_path)?;
+
+    let loaded = state.loaded_file
+        .read()
+        .map(|loaded_file| loaded_file.as_deref() == Some(file_path))?;
+    if !loaded {
+        let recovering = state.recovered_file
+            .read()
+            .map(|recovered_file| recovered_file.as_deref() == Some(file_path))?;
+        if recovering {
+            return Err(PromptToolError::Config(format!(
+                "Файл {} открыт в режиме восстановления: подтвердите удаление поврежденных записей, чтобы изменять его",
+                file_path
+            )));
+        }
+        return Err(PromptToolError::Config(format!(
+            "Библиотека не загружена из {}: исправьте ошибки в файле и откройте его снова",
+            file_path
+        )));
+    }
+    Ok(())
+}
+
+/// Делает библиотеку текущей; `loaded_from` - файл, из которого она прочитана целиком,
+/// None - библиотеку нельзя записывать (например, файл заблокирован)
+/// Отказывает, пока в памяти есть несохраненные правки: вызывающий сначала сохраняет их `flush_prompts`
+fn set_library(state: &AppState, library: PromptList, loaded_from: Option<&str>) -> Result<()> {
+    {
+        let mut prompts = state.prompts.write()?;
+        ensure_no_unsaved_edits(state)?;
+        *prompts = library;
+    }
+    *state.loaded_file.write()? = loaded_from.map(str::to_string);
+    *state.recovered_file.write()? = None;
+
+    let contents = loaded_from.and_then(|path| std::fs::read_to_string(path).ok());
+    remember_contents(state, contents.as_deref())
+}
+
+/// Возвращает ошибку, если правки промптов в памяти еще не записаны на диск
+/// Вызывается под блокировкой промптов на запись, перед заменой библиотеки:
+/// иначе правка из `edit_prompt` потерялась бы или автосохранилась поверх другой библиотеки
+fn ensure_no_unsaved_edits(state: &AppState) -> Result<()> {
+    if state.unsaved_edit.read()?.is_some() {
+        return Err(PromptToolError::Conflict(
+            "В памяти есть несохраненные правки промптов: сохраните их, прежде чем заменять библиотеку".to_string()
+        ));
+    }
+    Ok(())
+}
+
+/// Запоминает содержимое файла промптов, на котором основана библиотека в памяти:
+/// сохранение перезапишет файл, только пока его содержимое не изменилось
+fn remember_contents(state: &AppState, contents: Option<&str>) -> Result<()> {
+    *state.loaded_hash.write()? = contents.map(content_hash);
+    Ok(())
+}
+
+/// Ошибка записи в поисковый индекс
+/// Индекс, который держит другой экземпляр приложения, дает ошибку блокировки, а не общую ошибку поиска
+fn index_write_error(error: Box<dyn std::error::Error>) -> PromptToolError {
+    match error.downcast_ref::<tantivy::TantivyError>() {
+        Some(tantivy::TantivyError::LockFailure(..)) => {
+            PromptToolError::Locked("Поисковый индекс занят другим экземпляром приложения".to_string())
+        }
+        _ => PromptToolError::Search(error.to_string()),
+    }
+}
+
+/// Собирает записи индекса из загруженных промптов
+/// Идентификатор записи - позиция промпта в списке
+fn collect_records(state: &AppState) -> Result<Vec<Record>> {
+    let prompts = state.prompts.read()?;
+
+    Ok(prompts.prompts
+        .iter()
+        .enumerate()
+        .map(|(id, prompt)| Record::from_prompt(id as u64, prompt))
+        .collect())
+}
+
+/// Синхронизирует поисковый индекс с загруженными промптами
+/// Вызывается после любого изменения списка промптов
+fn sync_index<R: Runtime>(state: &AppState, app_handle: &tauri::AppHandle<R>) -> Result<()> {
+    // Результаты поиска в кэше относятся к старому списку промптов
+    state.search_cache.write()?.clear();
+
+    let records = collect_records(state)?;
+
+    let database = database_guard(state, app_handle)?;
+
+    if let Some(database) = database.as_ref() {
+        database.replace_all_records(records)
+            .map_err(index_write_error)?;
+    }
+
+    Ok(())
+}
+
+/// Возвращает доступ к поисковому индексу на чтение и отмечает активность пользователя
+/// Если индекс был выгружен после простоя, он открывается заново и синхронизируется с промптами.
+/// Поиски из разных команд выполняются одновременно, блокировка на запись нужна только для открытия.
+/// Порядок блокировок: сначала индекс, затем промпты.
+fn database_guard<'a, R: Runtime>(state: &'a AppState, app_handle: &tauri::AppHandle<R>) -> Result<RwLockReadGuard<'a, Option<Database>>> {
+    *state.last_activity.write()? = Instant::now();
+
+    if state.idle_released.load(Ordering::SeqCst) {
+        let mut database = state.database.write()?;
+
+        // Пока этот поток ждал блокировку, индекс мог открыть другой
+        if database.is_none() && state.idle_released.swap(false, Ordering::SeqCst) {
+            let reopened = open_database(app_handle)?;
+            // Пока индекс был выгружен, промпты могли измениться
+            reopened.replace_all_records(collect_records(state)?)
+                .map_err(index_write_error)?;
+            *database = Some(reopened);
+        }
+    }
+
+    state.database.read()
+}
+
+/// Выгружает индекс и очищает кэши, если приложение простаивает дольше настроенного времени
+fn release_if_idle(state: &AppState) -> Result<()> {
+    let idle_minutes = state.config.read()?.idle_release_minutes;
+    if idle_minutes == 0 {
+        return Ok(());
+    }
+
+    let is_idle = state.last_activity.read()?.elapsed() >= Duration::from_secs(idle_minutes * 60);
+    if !is_idle {
+        return Ok(());
+    }
+
+    if state.database.write()?.take().is_some() {
+        state.idle_released.store(true, Ordering::SeqCst);
+    }
+
+    state.search_cache.write()?.clear();
+    Ok(())
+}
+
+/// Запускает фоновый поток, выгружающий тяжелые ресурсы после простоя
+fn spawn_idle_monitor(app_handle: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(IDLE_CHECK_INTERVAL);
+        if let Err(e) = release_if_idle(&app_handle.state::<AppState>()) {
+            error!("Не удалось выгрузить индекс после простоя: {}", e);
+        }
+    });
+}
+
+/// Сохраняет правки промптов, сделанные только в памяти, и обновляет индекс
+/// Возвращает, было ли что сохранять. При ошибке правки остаются несохраненными
+/// и будут записаны следующей попыткой
+fn flush_prompts<R: Runtime>(state: &AppState, app_handle: &tauri::AppHandle<R>) -> Result<bool> {
+    let (file_path, git) = state.config
+        .read()
+        .map(|config| (config.prompt_file_path.clone(), config.git.clone()))?;
+
+    {
+        let prompts = state.prompts.read()?;
+        let mut unsaved_edit = state.unsaved_edit.write()?;
+        if unsaved_edit.is_none() {
+            return Ok(false);
+        }
+
+        persist_prompts_with_backup(app_handle, &file_path, &prompts, &git, EDIT_BACKUP_INTERVAL)?;
+        *unsaved_edit = None;
+    }
+
+    sync_index(state, app_handle)?;
+    Ok(true)
+}
+
+/// Сохраняет правки в памяти, если после последней прошло больше настроенной задержки
+fn autosave_if_idle(app_handle: &tauri::AppHandle) -> Result<()> {
+    let state = app_handle.state::<AppState>();
+    let delay = state.config
+        .read()
+        .map(|config| config.settings.autosave_delay_seconds)
+        .unwrap_or_else(|_| Settings::default().autosave_delay_seconds);
+    if delay == 0 {
+        return Ok(());
+    }
+
+    let is_idle = state.unsaved_edit
+        .read()
+        .map(|unsaved_edit| unsaved_edit.is_some_and(|edited| edited.elapsed() >= Duration::from_secs(delay)))?;
+    if is_idle {
+        flush_prompts(&state, app_handle)?;
+    }
+    Ok(())
+}
+
+/// Пауза перед следующей попыткой автосохранения после `failures` неудач подряд:
+/// удваивается с каждой неудачей, но не превышает `AUTOSAVE_MAX_BACKOFF`
+fn autosave_backoff(failures: u32) -> Duration {
+    AUTOSAVE_CHECK_INTERVAL
+        .saturating_mul(2u32.saturating_pow(failures))
+        .min(AUTOSAVE_MAX_BACKOFF)
+}
+
+/// Запускает фоновый поток автосохранения правок, сделанных в памяти
+fn spawn_autosave_worker(app_handle: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut failures = 0;
+        loop {
+            std::thread::sleep(AUTOSAVE_CHECK_INTERVAL);
+            match autosave_if_idle(&app_handle) {
+                Ok(()) => failures = 0,
+                Err(e) => {
+                    failures += 1;
+                    let backoff = autosave_backoff(failures);
+                    warn!("Не удалось автоматически сохранить промпты, следующая попытка через {} с: {}", backoff.as_secs(), e);
+                    std::thread::sleep(backoff);
+                }
+            }
+        }
+    });
+}
+
+/// Возвращает путь к директории поискового индекса
+fn index_dir<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> Result<PathBuf> {
+    Ok(app_paths(app_handle).data_dir.join(INDEX_DIR_NAME))
+}
+
+/// Открывает (или создает) поисковый индекс в директории данных приложения
+/// и применяет к нему поисковые настройки из конфигурации.
+/// Пока файл промптов зашифрован, индекс хранится только в памяти, а индекс на диске удаляется
+fn open_database<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> Result<Database> {
+    open_database_in(app_handle, &index_dir(app_handle)?)
+}
+
+/// Открывает индекс, как `open_database`, но в директории `index_dir`
+fn open_database_in<R: Runtime>(app_handle: &tauri::AppHandle<R>, index_dir: &std::path::Path) -> Result<Database> {
+    let (synonyms, languages, boosts, fuzzy_distance, reader_cache_blocks, encrypted) = app_handle.state::<AppState>().config
+        .read()
+        .map(|config| (
+            config.synonyms.clone(),
+            config.stemming_languages.clone(),
+            config.field_boosts,
+            config.settings.fuzzy_threshold,
+            config.reader_cache_blocks,
+            config.prompt_file_encrypted,
+        ))?;
+
+    // Неизвестные языки пропускаются, они отсеиваются еще при сохранении настроек
+    let languages: Vec<_> = languages.iter()
+        .filter_map(|name| parse_language(name))
+        .collect();
+
+    let opened = if encrypted {
+        remove_plaintext_file(index_dir);
+        Database::in_memory(&languages)
+    } else {
+        // Индекс закрепляется за текущим пользователем ОС
+        claim_directory(index_dir)?;
+        Database::with_languages(&index_dir.to_string_lossy(), &languages)
+    };
+    let mut database = opened
+        .map_err(|e| PromptToolError::Search(format!("Не удалось открыть индекс: {}", e)))?;
+    database.set_synonyms(SynonymMap::from_rules(&synonyms));
+    database.set_field_boosts(boosts);
+    database.set_fuzzy_distance(fuzzy_distance);
+    database.set_reader_cache_blocks(reader_cache_blocks)
+        .map_err(|e| PromptToolError::Search(format!("Не удалось открыть индекс: {}", e)))?;
+
+    Ok(database)
+}
+
+/// Команда для установки весов полей при ранжировании
+#[tauri::command]
+async fn set_field_boosts(
+    boosts: FieldBoosts,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let is_valid = [boosts.title, boosts.tags, boosts.text]
+        .iter()
+        .all(|boost| boost.is_finite() && *boost >= 0.0);
+    if !is_valid {
+        return Err(PromptToolError::Validation("Вес поля должен быть неотрицательным числом".to_string()));
+    }
+
+    if let Some(database) = state.database.write()?.as_mut() {
+        database.set_field_boosts(boosts);
+    }
+
+    // Порядок результатов изменился
+    state.search_cache.write()?.clear();
+
+    {
+        let mut config = state.config.write()?;
+        config.field_boosts = boosts;
+        save_config(&app_handle, &config)?;
+    }
+
+    Ok(())
+}
+
+/// Команда для установки языков стемминга
+/// Анализатор индекса пересобирается, а все промпты переиндексируются
+#[tauri::command]
+async fn set_stemming_languages(
+    languages: Vec<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        if let Some(unknown) = languages.iter().find(|name| parse_language(name).is_none()) {
+            return Err(PromptToolError::Validation(format!("Неподдерживаемый язык стемминга: {}", unknown)));
+        }
+
+        // Индекс с новым анализатором открывается по настройкам в памяти; если он не открылся,
+        // прежние языки возвращаются, а текущий индекс продолжает работать
+        let previous = std::mem::replace(&mut state.config.write()?.stemming_languages, languages);
+
+        let index_open = state.database.read()?.is_some();
+        let opened = if index_open {
+            open_database(app_handle).map(Some)
+        } else {
+            Ok(None)
+        };
+        let saved = opened.and_then(|database| {
+            save_config(app_handle, &state.config.read()?)?;
+            Ok(database)
+        });
+        let new_database = match saved {
+            Ok(database) => database,
+            Err(e) => {
+                state.config.write()?.stemming_languages = previous;
+                return Err(e);
+            }
+        };
+
+        if let Some(new_database) = new_database {
+            *state.database.write()? = Some(new_database);
+        }
+
+        sync_index(state, app_handle)
+    }).await
+}
+
+/// Команда для установки групп синонимов
+/// Каждая строка - группа равноценных слов, разделенных `=` или `,`
+#[tauri::command]
+async fn set_synonyms<R: Runtime>(
+    rules: Vec<String>,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<()> {
+    if let Some(database) = state.database.write()?.as_mut() {
+        database.set_synonyms(SynonymMap::from_rules(&rules));
+    }
+
+    // Найденные промпты зависят от групп синонимов
+    state.search_cache.write()?.clear();
+
+    {
+        let mut config = state.config.write()?;
+        config.synonyms = rules;
+        save_config(&app_handle, &config)?;
+    }
+
+    Ok(())
+}
+
+/// Команда для экспорта настроек поиска (языки стемминга, синонимы, веса полей, схема индекса)
+/// в JSON-файл, который можно импортировать на другой установке
+#[tauri::command]
+async fn export_search_config(path: String, app_handle: tauri::AppHandle) -> Result<()> {
+    run_blocking(&app_handle, move |state, _| {
+        let search_config = state.config
+            .read()
+            .map(|config| SearchConfig::new(
+                config.index_enabled,
+                config.stemming_languages.clone(),
+                config.synonyms.clone(),
+                config.field_boosts,
+            ))?;
+
+        search_config.save(std::path::Path::new(&path))
+    }).await
+}
+
+/// Команда для импорта настроек поиска из JSON-файла
+/// Анализатор индекса пересобирается с новыми языками, а промпты переиндексируются
+#[tauri::command]
+async fn import_search_config(
+    path: String,
+    app_handle: tauri::AppHandle,
+) -> Result<SearchConfig> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let search_config = SearchConfig::load(std::path::Path::new(&path))?;
+
+        // Индекс с новым анализатором открывается по настройкам в памяти; если он не открылся,
+        // прежние настройки возвращаются, а текущий индекс продолжает работать
+        let previous = {
+            let mut config = state.config.write()?;
+            let previous = config.clone();
+
+            config.stemming_languages = search_config.stemming_languages.clone();
+            config.synonyms = search_config.synonyms.clone();
+            config.field_boosts = search_config.field_boosts;
+            config.index_enabled = search_config.index_enabled;
+            previous
+        };
+
+        let opened = if search_config.index_enabled {
+            open_database(app_handle).map(Some)
+        } else {
+            Ok(None)
+        };
+        let saved = opened.and_then(|database| {
+            save_config(app_handle, &state.config.read()?)?;
+            Ok(database)
+        });
+        let new_database = match saved {
+            Ok(database) => database,
+            Err(e) => {
+                *state.config.write()? = previous;
+                return Err(e);
+            }
+        };
+
+        {
+            let mut database = state.database.write()?;
+
+            *database = new_database;
+            state.idle_released.store(false, Ordering::SeqCst);
+            if !search_config.index_enabled {
+                let index_dir = index_dir(app_handle)?;
+                if index_dir.exists() {
+                    std::fs::remove_dir_all(&index_dir)
+                        .map_err(PromptToolError::Io)?;
+                }
+            }
+        }
+
+        if search_config.index_enabled {
+            sync_index(state, app_handle)?;
+        } else {
+            state.search_cache.write()?.clear();
+        }
+
+        Ok(search_config)
+    }).await
+}
+
+/// Команда для включения или отключения поискового индекса
+/// При отключении индекс закрывается и удаляется с диска, поиск переходит на промпты в памяти.
+/// При включении индекс создается заново и заполняется загруженными промптами.
+#[tauri::command]
+async fn set_index_enabled(
+    enabled: bool,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        {
+            let mut database = state.database.write()?;
+
+            state.idle_released.store(false, Ordering::SeqCst);
+
+            if enabled {
+                if database.is_none() {
+                    *database = Some(open_database(app_handle)?);
+                }
+            } else {
+                *database = None;
+
+                let index_dir = index_dir(app_handle)?;
+                if index_dir.exists() {
+                    std::fs::remove_dir_all(&index_dir)
+                        .map_err(PromptToolError::Io)?;
+                }
+            }
+        }
+
+        if enabled {
+            sync_index(state, app_handle)?;
+        }
+
+        {
+            let mut config = state.config.write()?;
+            config.index_enabled = enabled;
+            save_config(app_handle, &config)?;
+        }
+
+        Ok(())
+    }).await
+}
+
+/// Команда для переиндексации всех загруженных промптов
+/// Все документы добавляются одним коммитом, поэтому даже тысячи промптов индексируются быстро
+#[tauri::command]
+async fn reindex_prompts(
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<usize> {
+    let records = collect_records(&state)?;
+    let count = records.len();
+
+    let database = database_guard(&state, &app_handle)?;
+    let database = database.as_ref()
+        .ok_or_else(|| PromptToolError::Search("Индекс не инициализирован".to_string()))?;
+
+    database.replace_all_records(records)
+        .map_err(index_write_error)?;
+
+    Ok(count)
+}
+
+/// Прогресс перестроения индекса, отправляемый во frontend событием
+#[derive(Debug, Serialize, Clone)]
+struct RebuildProgress {
+    // Сколько промптов уже проиндексировано
+    indexed: usize,
+    // Сколько промптов нужно проиндексировать всего
+    total: usize,
+}
+
+/// Команда для полного перестроения индекса
+/// Удаляет директорию индекса, заново создает схему и индексирует все промпты,
+/// отправляя прогресс событием `rebuild-index-progress`.
+/// Нужна при повреждении индекса или изменении схемы.
+#[tauri::command]
+async fn rebuild_index(
+    app_handle: tauri::AppHandle,
+) -> Result<usize> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let records = collect_records(state)?;
+        let total = records.len();
+        let index_dir = index_dir(app_handle)?;
+
+        // Новый индекс строится рядом с текущим, который работает, пока новый не готов
+        let staging = index_dir.with_extension("rebuild");
+        remove_index_dir(&staging)?;
+        let built = open_database_in(app_handle, &staging).and_then(|new_database| {
+            let mut indexed = 0;
+            let _ = app_handle.emit("rebuild-index-progress", RebuildProgress { indexed, total });
+
+            let mut records = records.into_iter().peekable();
+            while records.peek().is_some() {
+                let batch: Vec<Record> = records.by_ref().take(REBUILD_BATCH_SIZE).collect();
+                indexed += batch.len();
+
+                new_database.add_records(batch)
+                    .map_err(index_write_error)?;
+
+                let _ = app_handle.emit("rebuild-index-progress", RebuildProgress { indexed, total });
+            }
+            Ok(new_database)
+        });
+        let new_database = match built {
+            Ok(new_database) => new_database,
+            Err(e) => {
+                let _ = remove_index_dir(&staging);
+                return Err(e);
+            }
+        };
+
+        let encrypted = library_encrypted(state);
+        let mut database = state.database.write()?;
+        state.idle_released.store(false, Ordering::SeqCst);
+
+        // Индекс зашифрованной библиотеки хранится только в памяти, переносить на диске нечего
+        if encrypted {
+            *database = Some(new_database);
+            return Ok(total);
+        }
+
+        // Открытые файлы индекса нельзя переместить, поэтому оба индекса закрываются до замены
+        drop(new_database);
+        *database = None;
+
+        let previous = index_dir.with_extension("old");
+        remove_index_dir(&previous)?;
+        match swap_index_dir(&index_dir, &staging, &previous).and_then(|()| open_database(app_handle)) {
+            Ok(reopened) => {
+                *database = Some(reopened);
+                if let Err(e) = remove_index_dir(&previous) {
+                    warn!("Не удалось удалить прежний индекс: {}", e);
+                }
+                Ok(total)
+            }
+            Err(e) => {
+                // Возвращаем на место прежний индекс и открываем его снова
+                if previous.exists() {
+                    let _ = remove_index_dir(&index_dir);
+                    let _ = std::fs::rename(&previous, &index_dir);
+                }
+                *database = open_database(app_handle)
+                    .inspect_err(|e| error!("Не удалось открыть прежний индекс: {}", e))
+                    .ok();
+                Err(e)
+            }
+        }
+    }).await
+}
+
+/// Удаляет директорию индекса; отсутствие директории - не ошибка
+fn remove_index_dir(dir: &std::path::Path) -> Result<()> {
+    match std::fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(PromptToolError::Io(e)),
+        _ => Ok(()),
+    }
+}
+
+/// Ставит перестроенный индекс из `staging` на место `index_dir`, перенося прежний в `previous`
+/// Если перенос не удался, прежний индекс возвращается на место
+fn swap_index_dir(index_dir: &std::path::Path, staging: &std::path::Path, previous: &std::path::Path) -> Result<()> {
+    if index_dir.exists() {
+        std::fs::rename(index_dir, previous)
+            .map_err(PromptToolError::Io)?;
+    }
+
+    if let Err(e) = std::fs::rename(staging, index_dir) {
+        if previous.exists() {
+            let _ = std::fs::rename(previous, index_dir);
+        }
+        return Err(PromptToolError::Io(e));
+    }
+    Ok(())
+}
+
+/// Команда для выгрузки JSON Schema форматов библиотеки и манифеста .ptpack
+/// Возвращает пути к созданным файлам
+#[tauri::command]
+async fn export_json_schemas(output_dir: String) -> Result<Vec<String>> {
+    Ok(export_schemas(&output_dir)?
+        .into_iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect())
+}
+
+/// Команда для получения JSON Schema формата библиотеки промптов
+#[tauri::command]
+async fn get_library_schema() -> serde_json::Value {
+    schema::library_schema()
+}
+
+/// Команда для проверки файла библиотеки (TOML или JSON) по JSON Schema перед импортом
+/// Возвращает найденные ошибки с путями к неверным значениям; пустой список означает, что файл корректен
+#[tauri::command]
+async fn validate_file(path: String) -> Result<Vec<SchemaError>> {
+    schema::validate_file(std::path::Path::new(&path))
+}
+
+/// Команда для установки новой горячей клавиши
+#[tauri::command]
+async fn set_hotkey(
+    new_hotkey: String,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    {
+        let mut config = state.config.write()?;
+        config.hotkey = new_hotkey;
+        save_config(&app_handle, &config)?;
+    }
+
+    Ok(())
+}
+
+/// Команда для получения списка профилей и активного профиля
+#[tauri::command]
+async fn list_profiles<R: Runtime>(app_handle: tauri::AppHandle<R>) -> ProfileRegistry {
+    ProfileRegistry::load(&profiles_path(&app_handle))
+}
+
+/// Команда для переключения профиля (например, work, personal, client-x)
+/// У каждого профиля своя конфигурация с файлом промптов и горячей клавишей, свой поисковый индекс,
+/// история и статистика; профиль с новым именем создается с настройками по умолчанию.
+/// Ошибка чтения файла промптов не отменяет переключение: библиотека остается пустой, пока файл не выбран заново.
+/// Возвращает горячую клавишу профиля, чтобы фронтенд перерегистрировал ее
+#[tauri::command]
+async fn switch_profile<R: Runtime>(
+    name: String,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<String> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let mut registry = ProfileRegistry::load(&profiles_path(app_handle));
+        registry.activate(&name)?;
+
+        // Несохраненные правки относятся к файлу прежнего профиля
+        flush_prompts(state, app_handle)?;
+
+        // Индекс прежнего профиля закрывается до смены директорий
+        {
+            let mut database = state.database.write()?;
+            *database = None;
+            state.idle_released.store(false, Ordering::SeqCst);
+        }
+
+        let previous_hotkeys = state.config
+            .read()
+            .map(|config| config.prompt_hotkeys.clone())?;
+
+        registry.save(&profiles_path(app_handle))?;
+        *state.profile.write()? = name.clone();
+
+        load_config(app_handle)?;
+        load_user_data(app_handle)?;
+        if let Err(e) = ensure_starter_content(app_handle) {
+            warn!("Не удалось создать файл промптов профиля: {}", e);
+        }
+
+        let (index_enabled, hotkey, prompt_hotkeys) = state.config
+            .read()
+            .map(|config| (config.index_enabled, config.hotkey.clone(), config.prompt_hotkeys.clone()))?;
+
+        // Сочетание, занятое другой программой, не мешает работать с профилем
+        if let Err(e) = apply_prompt_hotkeys(app_handle, &previous_hotkeys, &prompt_hotkeys) {
+            error!("Ошибка регистрации горячих клавиш промптов: {}", e);
+        }
+        apply_text_expander(app_handle);
+        apply_always_on_top(app_handle);
+
+        let load_error = load_active_library(state).err();
+
+        if index_enabled {
+            let database = open_database(app_handle)?;
+            *state.database.write()? = Some(database);
+        }
+        sync_index(state, app_handle)?;
+
+        let _ = app_handle.emit("profile-switched", &name);
+        match load_error {
+            Some(e) => Err(e),
+            None => Ok(hotkey),
+        }
+    }).await
+}
+
+/// Команда для привязки глобальной горячей клавиши к промпту
+/// По нажатию промпт подставляется со значениями по умолчанию и копируется в буфер обмена,
+/// при `auto_paste` - еще и вставляется в активное окно. `None` снимает привязку
+#[tauri::command]
+async fn set_prompt_hotkey<R: Runtime>(
+    prompt_name: String,
+    shortcut: Option<String>,
+    auto_paste: bool,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<()> {
+    let prompt_id = find_prompt(&state, &prompt_name)?.id;
+    if prompt_id.is_empty() {
+        return Err(PromptToolError::Validation(format!(
+            "У промпта \"{}\" нет идентификатора, сначала обновите формат библиотеки",
+            prompt_name
+        )));
+    }
+    let (bindings, main_hotkey) = state.config
+        .read()
+        .map(|config| (config.prompt_hotkeys.clone(), config.hotkey.clone()))?;
+
+    if let Some(shortcut) = &shortcut {
+        shortcuts::check_conflicts(&bindings, &main_hotkey, shortcut, &prompt_id)?;
+    }
+
+    let mut updated = bindings.clone();
+    updated.retain(|_, binding| binding.prompt_id != prompt_id);
+    if let Some(shortcut) = shortcut {
+        updated.insert(shortcut, PromptHotkey { prompt_id, auto_paste });
+    }
+
+    // Регистрация выполняется в главном потоке, поэтому конфигурация на это время не блокируется
+    apply_prompt_hotkeys(&app_handle, &bindings, &updated)?;
+
+    let mut config = state.config.write()?;
+    config.prompt_hotkeys = updated;
+    save_config(&app_handle, &config)
+}
+
+/// Снимает сочетания, которых нет в новых привязках, и регистрирует добавленные
+/// Без плагина глобальных клавиш (тестовый рантайм) ничего не делает
+fn apply_prompt_hotkeys<R: Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    previous: &BTreeMap<String, PromptHotkey>,
+    current: &BTreeMap<String, PromptHotkey>,
+) -> Result<()> {
+    if app_handle.try_state::<GlobalShortcut<R>>().is_none() {
+        return Ok(());
+    }
+    let global_shortcut = app_handle.global_shortcut();
+
+    for shortcut in previous.keys().filter(|shortcut| !current.contains_key(*shortcut)) {
+        if let Ok(parsed) = shortcuts::parse_shortcut(shortcut) {
+            let _ = global_shortcut.unregister(parsed);
+        }
+    }
+
+    for shortcut in current.keys().filter(|shortcut| !previous.contains_key(*shortcut)) {
+        global_shortcut.register(shortcuts::parse_shortcut(shortcut)?)
+            .map_err(|e| PromptToolError::Config(format!("Не удалось зарегистрировать сочетание \"{}\": {}", shortcut, e)))?;
+    }
+
+    Ok(())
+}
+
+/// Регистрирует горячие клавиши промптов из конфигурации при запуске
+fn register_prompt_hotkeys(app_handle: &tauri::AppHandle) {
+    let bindings = app_handle.state::<AppState>().config
+        .read()
+        .map(|config| config.prompt_hotkeys.clone())
+        .unwrap_or_default();
+
+    if let Err(e) = apply_prompt_hotkeys(app_handle, &BTreeMap::new(), &bindings) {
+        error!("Ошибка регистрации горячих клавиш промптов: {}", e);
+    }
+}
+
+/// Подставляет промпт, привязанный к нажатому сочетанию, и отправляет его в буфер обмена
+/// Если в ОС нет нативной записи в буфер, текст дописывает и вставляет фронтенд по событию `prompt-hotkey`
+fn run_prompt_hotkey(app_handle: &tauri::AppHandle, pressed: &Shortcut) -> Result<()> {
+    let state = app_handle.state::<AppState>();
+    let (binding, options) = state.config
+        .read()
+        .map(|config| (shortcuts::find_binding(&config.prompt_hotkeys, pressed).cloned(), config.paste))?;
+    let Some(binding) = binding else {
+        return Ok(());
+    };
+
+    let prompt_name = state.prompts
+        .read()?
+        .prompts
+        .iter()
+        .find(|prompt| prompt.id == binding.prompt_id)
+        .map(|prompt| prompt.name.clone())
+        .ok_or_else(|| PromptToolError::Validation("Промпт, привязанный к сочетанию, не найден".to_string()))?;
+
+    let prompt = find_prompt(&state, &prompt_name)?;
+    let values = with_context(&state, HashMap::new())?;
+    let text = render_checked(app_handle, &prompt, &values)?;
+
+    let target = if binding.auto_paste { OutputTargetConfig::Paste } else { OutputTargetConfig::Clipboard };
+    let delivery = paste_delivered(app_handle, output::target_for(&target, options).deliver(&prompt_name, &text)?)?;
+
+    record_usage(&state, app_handle, &prompt_name)?;
+    if !binding.auto_paste {
+        notify(
+            app_handle,
+            NotificationKind::PromptCopied,
+            "Промпт скопирован",
+            &format!("Промпт \"{}\" скопирован в буфер обмена", prompt_name),
+        );
+    }
+    let _ = app_handle.emit("prompt-hotkey", delivery);
+    Ok(())
+}
+
+/// Команда для настройки расширителя текста
+/// Во включенном режиме `префикс + псевдоним` и пробел, Tab или Enter в любом приложении
+/// заменяются текстом промпта со значениями параметров по умолчанию
+#[tauri::command]
+async fn set_text_expander<R: Runtime>(
+    settings: ExpanderSettings,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<()> {
+    settings.validate()?;
+
+    {
+        let mut config = state.config.write()?;
+        config.text_expander = settings;
+        save_config(&app_handle, &config)?;
+    }
+
+    apply_text_expander(&app_handle);
+    Ok(())
+}
+
+/// Включает или выключает расширитель текста по конфигурации
+/// Слушатель клавиатуры запускается при первом включении и дальше только отбрасывает нажатия, пока режим выключен
+fn apply_text_expander<R: Runtime>(app_handle: &tauri::AppHandle<R>) {
+    let state = app_handle.state::<AppState>();
+    let enabled = state.config
+        .read()
+        .map(|config| config.text_expander.enabled)
+        .unwrap_or(false);
+
+    state.expander_enabled.store(enabled, Ordering::SeqCst);
+    if !enabled || state.expander_started.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let handle = app_handle.clone();
+    let typing = state.expander_typing.clone();
+    std::thread::spawn(move || {
+        let listener = handle.clone();
+        let mut buffer = TriggerBuffer::new();
+
+        let result = expander::listen(typing, move |input| {
+            let state = listener.state::<AppState>();
+            if !state.expander_enabled.load(Ordering::SeqCst) {
+                // Выключенный расширитель не хранит набранный текст
+                buffer.push(KeyInput::Reset, "");
+                return;
+            }
+
+            let prefix = state.config
+                .read()
+                .map(|config| config.text_expander.trigger_prefix.clone())
+                .unwrap_or_default();
+            if let Some(trigger) = buffer.push(input, &prefix) {
+                // Набор замены генерирует новые события клавиатуры, поэтому выполняется вне потока слушателя
+                let handle = listener.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = expand_trigger(&handle, &trigger) {
+                        error!("Ошибка расширителя текста: {}", e);
+                    }
+                });
+            }
+        });
+
+        if let Err(e) = result {
+            error!("Ошибка запуска расширителя текста: {}", e);
+            handle.state::<AppState>().expander_started.store(false, Ordering::SeqCst);
+        }
+    });
+}
+
+/// Заменяет набранное срабатывание текстом промпта с этим псевдонимом
+/// Слово с префиксом, которое не является псевдонимом, остается как есть
+fn expand_trigger<R: Runtime>(app_handle: &tauri::AppHandle<R>, trigger: &Trigger) -> Result<()> {
+    let state = app_handle.state::<AppState>();
+    let prompt_name = state.prompts
+        .read()?
+        .prompts
+        .iter()
+        .find(|prompt| !prompt.archived && prompt.matches_alias(&trigger.alias))
+        .map(|prompt| prompt.name.clone());
+    let Some(prompt_name) = prompt_name else {
+        return Ok(());
+    };
+
+    let options = state.config
+        .read()
+        .map(|config| config.paste)?;
+
+    let prompt = find_prompt(&state, &prompt_name)?;
+    let values = with_context(&state, HashMap::new())?;
+    let text = clipboard::prepare_text(&render_checked(app_handle, &prompt, &values)?, &options);
+
+    expander::type_replacement(&state.expander_typing, trigger.erase, &text)?;
+    record_usage(&state, app_handle, &prompt_name)?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn open_prompt_file_dialog(app_handle: tauri::AppHandle) -> Result<String> {
+    let file_path = app_handle.dialog()
+        .file()
+        .set_title("Выберите файл с промптами")
+        .add_filter("TOML", &["toml"])
+        .blocking_pick_file()
+        .ok_or_else(|| PromptToolError::Config("Файл не выбран".to_string()))?;
+
+    Ok(file_path.into_path().unwrap().to_string_lossy().into_owned())
+}
+
+/// Команда для выбора файла, в который будут дописываться промпты
+#[tauri::command]
+async fn choose_output_file_dialog(app_handle: tauri::AppHandle) -> Result<String> {
+    let file_path = app_handle.dialog()
+        .file()
+        .set_title("Выберите файл для записи промптов")
+        .add_filter("Markdown", &["md"])
+        .add_filter("Текст", &["txt"])
+        .blocking_save_file()
+        .ok_or_else(|| PromptToolError::Config("Файл не выбран".to_string()))?;
+
+    let path = file_path.into_path()
+        .map_err(|_| PromptToolError::Config("Недопустимый путь к файлу".to_string()))?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Команда для получения текущей конфигурации
+#[tauri::command]
+async fn get_config(state: State<'_, AppState>) -> Result<AppConfig> {
+    state.config
+        .read()
+        .map(|config| config.clone())
+}
+
+/// Состояние режима "Не беспокоить"
+#[derive(Debug, Serialize)]
+struct DndStatus {
+    // Режим включен вручную в приложении
+    manual: bool,
+    // Состояние системного режима; None, если его не удалось определить
+    system: Option<bool>,
+    // Подавляются ли сейчас уведомления и всплывающие окна
+    active: bool,
+}
+
+/// Определяет, действует ли сейчас режим "Не беспокоить"
+/// Все всплывающие уведомления и окна должны проверять его перед показом
+fn do_not_disturb_status(state: &AppState) -> DndStatus {
+    let settings = state.config
+        .read()
+        .map(|config| config.do_not_disturb)
+        .unwrap_or_default();
+
+    let system = if settings.follow_system { platform::system_do_not_disturb() } else { None };
+
+    DndStatus {
+        manual: settings.manual,
+        system,
+        active: settings.is_active(system),
+    }
+}
+
+/// Команда для получения состояния режима "Не беспокоить"
+#[tauri::command]
+async fn get_do_not_disturb(state: State<'_, AppState>) -> Result<DndStatus> {
+    Ok(do_not_disturb_status(&state))
+}
+
+/// Команда для изменения настроек режима "Не беспокоить"
+#[tauri::command]
+async fn set_do_not_disturb(
+    settings: DndSettings,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let mut config = state.config.write()?;
+
+    config.do_not_disturb = settings;
+    save_config(&app_handle, &config)
+}
+
+/// Показывает системное уведомление, если уведомления этого типа включены
+/// и не действует режим "Не беспокоить"
+fn notify<R: Runtime>(app_handle: &tauri::AppHandle<R>, kind: NotificationKind, title: &str, body: &str) {
+    let state = app_handle.state::<AppState>();
+    let enabled = state.config
+        .read()
+        .map(|config| config.notifications.is_enabled(kind))
+        .unwrap_or(false);
+
+    if !enabled || do_not_disturb_status(&state).active {
+        return;
+    }
+
+    if let Err(e) = app_handle.notification().builder().title(title).body(body).show() {
+        warn!("Не удалось показать уведомление: {}", e);
+    }
+}
+
+/// Показывает уведомление об ошибке синхронизации
+/// Синхронизация часто идет при скрытом окне, и без уведомления ошибка осталась бы незамеченной
+fn notify_sync_failed<R: Runtime>(app_handle: &tauri::AppHandle<R>, error: &PromptToolError) {
+    notify(app_handle, NotificationKind::SyncFailed, "Ошибка синхронизации", &error.to_string());
+}
+
+/// Показывает уведомление о том, что файл промптов перечитан после изменений с другой стороны синхронизации
+fn notify_file_reloaded<R: Runtime>(app_handle: &tauri::AppHandle<R>) {
+    notify(
+        app_handle,
+        NotificationKind::FileReloaded,
+        "Файл промптов обновлен",
+        "Библиотека перечитана после изменений, полученных при синхронизации",
+    );
+}
+
+/// Команда для включения или выключения уведомлений определенного типа
+#[tauri::command]
+async fn set_notification_enabled(
+    kind: NotificationKind,
+    enabled: bool,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let mut config = state.config.write()?;
+
+    config.notifications.set_enabled(kind, enabled);
+    save_config(&app_handle, &config)
+}
+
+/// Команда для подсчета токенов в промпте
+/// Если модель не указана, используется модель из настроек
+#[tauri::command]
+async fn count_tokens(
+    prompt_name: String,
+    model: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<usize> {
+    let prompt = find_prompt(&state, &prompt_name)?;
+    let model = match model {
+        Some(model) => model,
+        None => state.config
+            .read()
+            .map(|config| config.llm.model.clone())?,
+    };
+
+    tokens::count_tokens(&prompt.content, &model)
+}
+
+/// Команда для оценки стоимости выполнения промпта
+/// Входные токены считаются по тексту промпта, выходные задаются ожидаемым количеством
+#[tauri::command]
+async fn estimate_cost(
+    prompt_name: String,
+    model: String,
+    expected_output_tokens: usize,
+    state: State<'_, AppState>,
+) -> Result<CostEstimate> {
+    let prompt = find_prompt(&state, &prompt_name)?;
+    let prices = state.config
+        .read()
+        .map(|config| config.model_prices.clone())?;
+
+    let input_tokens = tokens::count_tokens(&prompt.content, &model)?;
+    pricing::estimate_cost(&prices, &model, input_tokens, expected_output_tokens)
+}
+
+/// Команда для получения макросов поисковых запросов
+#[tauri::command]
+async fn get_query_macros(state: State<'_, AppState>) -> Result<BTreeMap<String, String>> {
+    state.config
+        .read()
+        .map(|config| config.query_macros.clone())
+}
+
+/// Команда для добавления или изменения макроса поискового запроса
+/// `None` удаляет макрос
+#[tauri::command]
+async fn set_query_macro(
+    name: String,
+    expansion: Option<String>,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let mut config = state.config.write()?;
+
+    let name = name.trim().trim_start_matches('@').to_string();
+    match expansion {
+        Some(expansion) => {
+            query_macros::validate_macro(&name, &expansion, &config.query_macros)?;
+            config.query_macros.insert(name, expansion);
+        }
+        None => {
+            config.query_macros.remove(&name);
+        }
+    }
+    save_config(&app_handle, &config)
+}
+
+/// Команда для изменения цены модели; `None` удаляет модель из таблицы цен
+#[tauri::command]
+async fn set_model_price(
+    model: String,
+    price: Option<ModelPrice>,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let mut config = state.config.write()?;
+
+    match price {
+        Some(price) => config.model_prices.insert(model, price),
+        None => config.model_prices.remove(&model),
+    };
+    save_config(&app_handle, &config)
+}
+
+/// Журнал ревизий промптов в директории данных
+fn revision_store<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> RevisionStore {
+    RevisionStore::new(app_paths(app_handle).data_dir.join(REVISIONS_FILE_NAME))
+}
+
+/// Записывает ревизию сохраненного промпта
+/// Для зашифрованного файла история не ведется, чтобы текст промптов не попадал на диск открытым.
+/// Библиотека уже сохранена, поэтому сбой журнала ревизий не отменяет изменение
+fn record_revision<R: Runtime>(app_handle: &tauri::AppHandle<R>, file_path: &str, previous: &Prompt, current: &Prompt) {
+    // Если ключи недоступны, неизвестно, зашифрован ли файл, поэтому ревизия тоже не пишется
+    if !matches!(encryption::unlocked_key(std::path::Path::new(file_path)), Ok(None)) {
+        return;
+    }
+
+    if let Err(e) = revision_store(app_handle).record_change(previous, current) {
+        warn!("Не удалось записать ревизию промпта \"{}\": {}", previous.name, e);
+    }
+}
+
+/// Изменяет промпт по названию, проверяет результат и сохраняет библиотеку
+/// Если изменение не проходит проверку, библиотека остается прежней.
+/// Сохраненное изменение записывается в историю ревизий
+fn update_prompt<T, R: Runtime>(
+    state: &AppState,
+    app_handle: &tauri::AppHandle<R>,
+    prompt_name: &str,
+    change: impl FnOnce(&mut Prompt) -> T,
+) -> Result<T> {
+    let (file_path, git) = state.config
+        .read()
+        .map(|config| (config.prompt_file_path.clone(), config.git.clone()))?;
+    ensure_writable(state, &file_path)?;
+
+    let mut prompts = state.prompts.write()?;
+    let index = prompts.prompts
+        .iter()
+        .position(|prompt| prompt.name == prompt_name)
+        .ok_or_else(|| PromptToolError::Validation(format!("Промпт \"{}\" не найден", prompt_name)))?;
+
+    let mut prompt = prompts.prompts[index].clone();
+    let result = change(&mut prompt);
+    validation::validate_prompt(&prompt, &prompts, Some(index))?;
+
+    let previous = std::mem::replace(&mut prompts.prompts[index], prompt);
+    persist_prompts(app_handle, &file_path, &prompts, &git)?;
+
+    record_revision(app_handle, &file_path, &previous, &prompts.prompts[index]);
+    Ok(result)
+}
+
+/// Находит промпт по названию и возвращает его копию
+/// Директивы `{{include:...}}` в тексте копии уже раскрыты
+fn find_prompt(state: &AppState, prompt_name: &str) -> Result<Prompt> {
+    let prompts = state.prompts.read()?;
+
+    let mut prompt = prompts.prompts
+        .iter()
+        .find(|prompt| prompt.name == prompt_name)
+        .cloned()
+        .ok_or_else(|| PromptToolError::Validation(format!("Промпт \"{}\" не найден", prompt_name)))?;
+
+    prompt.content = render::expand_includes(&prompt, &prompts)?;
+    Ok(prompt)
+}
+
+/// Добавляет к значениям параметров присланный через системный сервис текст
+/// и переменные сессии, если такие значения не переданы явно
+fn with_context(state: &AppState, mut values: HashMap<String, String>) -> Result<HashMap<String, String>> {
+    if !values.contains_key(SELECTION_VARIABLE) {
+        if let Some(selection) = state.selection.read()?.clone() {
+            values.insert(SELECTION_VARIABLE.to_string(), selection);
+        }
+    }
+    state.session.read()?.apply(&mut values);
+    Ok(values)
+}
+
+/// Путь к файлу с закрепленными переменными сессии
+fn session_path<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> PathBuf {
+    app_paths(app_handle).data_dir.join(SESSION_FILE_NAME)
+}
+
+/// Команда для получения переменных текущей сессии
+#[tauri::command]
+async fn get_session_vars(state: State<'_, AppState>) -> Result<BTreeMap<String, SessionVariable>> {
+    state.session
+        .read()
+        .map(|session| session.variables().clone())
+}
+
+/// Команда для задания переменной сессии, доступной шаблонам как обычный параметр
+/// Незакрепленные переменные сбрасываются при выходе из приложения
+#[tauri::command]
+async fn set_session_var(
+    name: String,
+    value: String,
+    pinned: Option<bool>,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let mut session = state.session.write()?;
+
+    session.set(&name, value, pinned.unwrap_or(false))?;
+    session.save(&session_path(&app_handle))
+}
+
+/// Команда для удаления переменной сессии
+#[tauri::command]
+async fn remove_session_var(
+    name: String,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<bool> {
+    let mut session = state.session.write()?;
+
+    let removed = session.remove(&name);
+    session.save(&session_path(&app_handle))?;
+    Ok(removed)
+}
+
+/// Команда для удаления всех незакрепленных переменных сессии
+#[tauri::command]
+async fn clear_session_vars(state: State<'_, AppState>) -> Result<()> {
+    state.session
+        .write()
+        .map(|mut session| session.clear_unpinned())
+}
+
+/// Директория со скриптами хуков
+fn hooks_dir<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> PathBuf {
+    app_paths(app_handle).config_dir.join(HOOKS_DIR_NAME)
+}
+
+/// Значение, которое показывается вместо секрета при отладке подстановки и в журнале выполнений
+const SECRET_MASK: &str = "******";
+
+/// Копия промпта, в которой директивы `{{secret:ИМЯ}}` заменены переменными,
+/// и значения этих переменных из `lookup`
+fn with_secrets(
+    prompt: &Prompt,
+    lookup: impl FnMut(&str) -> Result<String>,
+) -> Result<(Prompt, HashMap<String, String>)> {
+    let bindings = render::bind_secrets(&prompt.content, lookup)?;
+    let mut prompt = prompt.clone();
+    prompt.content = bindings.template;
+    Ok((prompt, bindings.values))
+}
+
+/// Проверяет значения и формирует текст промпта, выполняя его хуки:
+/// хуки до подстановки меняют значения параметров, хуки после - готовый текст.
+/// Секреты добавляются к значениям после хуков до подстановки, чтобы не передавать их скриптам
+fn render_with_hooks<R: Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    prompt: &Prompt,
+    values: &HashMap<String, String>,
+) -> Result<RenderOutput> {
+    let secret_bindings = render::bind_secrets(&prompt.content, secrets::get_secret)?;
+    render_with_secrets(app_handle, prompt, &secret_bindings, values)
+}
+
+/// Формирует текст промпта как `render_with_hooks`, но с уже прочитанными из связки ключей секретами
+fn render_with_secrets<R: Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    prompt: &Prompt,
+    secret_bindings: &SecretBindings,
+    values: &HashMap<String, String>,
+) -> Result<RenderOutput> {
+    let mut prompt = prompt.clone();
+    prompt.content = secret_bindings.template.clone();
+    let secret_values = secret_bindings.values.clone();
+    let prompt = &prompt;
+
+    if prompt.hooks.is_empty() {
+        let mut values = values.clone();
+        values.extend(secret_values);
+        return Ok(render::render_validated(prompt, &values));
+    }
+
+    let dir = hooks_dir(app_handle);
+    let mut values = hooks::run_pre_render(&dir, &prompt.hooks, &prompt.name, values.clone())?;
+    values.extend(secret_values);
+    let mut output = render::render_validated(prompt, &values);
+
+    if let Some(text) = output.text.take() {
+        output.text = Some(hooks::run_post_render(&dir, &prompt.hooks, &prompt.name, text)?);
+    }
+    Ok(output)
+}
+
+/// Команда для пошаговой отладки подстановки: возвращает текст после каждого включения,
+/// плейсхолдера и функции, чтобы найти, откуда взялся неожиданный фрагмент.
+/// Хуки промпта выполняются так же, как при обычной подстановке, и тоже попадают в шаги
+#[tauri::command]
+async fn debug_render(
+    prompt_name: String,
+    values: HashMap<String, String>,
+    app_handle: tauri::AppHandle,
+) -> Result<RenderDebug> {
+    // Хуки - внешние скрипты, они выполняются вне асинхронного потока
+    run_blocking(&app_handle, move |state, app_handle| debug_render_steps(state, app_handle, &prompt_name, values)).await
+}
+
+/// Шаги подстановки для `debug_render`
+fn debug_render_steps(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    prompt_name: &str,
+    values: HashMap<String, String>,
+) -> Result<RenderDebug> {
+    let (prompt, library) = {
+        let prompts = state.prompts.read()?;
+        let prompt = prompts.prompts
+            .iter()
+            .find(|prompt| prompt.name == prompt_name)
+            .cloned()
+            .ok_or_else(|| PromptToolError::Validation(format!("Промпт \"{}\" не найден", prompt_name)))?;
+        (prompt, PromptList { prompts: prompts.prompts.clone() })
+    };
+
+    // Вместо секретов в шагах отладки показывается маска
+    let mask = |_: &str| -> Result<String> { Ok(SECRET_MASK.to_string()) };
+    let (prompt, mut secret_values) = with_secrets(&prompt, mask)?;
+    let mut library = library;
+    for included in &mut library.prompts {
+        let (masked, included_values) = with_secrets(included, mask)?;
+        *included = masked;
+        secret_values.extend(included_values);
+    }
+
+    let dir = hooks_dir(app_handle);
+    let values = with_context(state, values)?;
+    let mut values = hooks::run_pre_render(&dir, &prompt.hooks, &prompt.name, values)?;
+    values.extend(secret_values);
+
+    let mut debug = render::debug_render(&prompt, &library, &values)?;
+    if !prompt.hooks.pre_render.is_empty() {
+        debug.steps.insert(1, render::DebugStep {
+            stage: "hook",
+            description: format!("Значения параметров изменены хуками: {}", prompt.hooks.pre_render.join(", ")),
+            text: prompt.content.clone(),
+        });
+    }
+    for name in &prompt.hooks.post_render {
+        let Some(text) = debug.output.text.take() else {
+            break;
+        };
+        let single = PromptHooks { post_render: vec![name.clone()], ..PromptHooks::default() };
+        let text = hooks::run_post_render(&dir, &single, &prompt.name, text)?;
+
+        debug.steps.push(render::DebugStep {
+            stage: "hook",
+            description: format!("Выполнен хук \"{}\"", name),
+            text: text.clone(),
+        });
+        debug.output.text = Some(text);
+    }
+
+    Ok(debug)
+}
+
+/// Команда для получения имен доступных скриптов хуков
+#[tauri::command]
+async fn list_hooks(app_handle: tauri::AppHandle) -> Vec<String> {
+    hooks::list_hooks(&hooks_dir(&app_handle))
+}
+
+/// Команда для подключения хуков к промпту
+/// Хуки проверяются на существование перед сохранением
+#[tauri::command]
+async fn set_prompt_hooks(
+    prompt_name: String,
+    prompt_hooks: PromptHooks,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let dir = hooks_dir(app_handle);
+        for name in prompt_hooks.pre_render.iter().chain(&prompt_hooks.post_render) {
+            hooks::hook_path(&dir, name)?;
+        }
+
+        update_prompt(state, app_handle, &prompt_name, |prompt| prompt.hooks = prompt_hooks)
+    }).await
+}
+
+/// Формирует текст промпта для отправки модели; ошибки значений параметров
+/// превращаются в ошибку валидации с перечислением полей
+fn render_checked<R: Runtime>(app_handle: &tauri::AppHandle<R>, prompt: &Prompt, values: &HashMap<String, String>) -> Result<String> {
+    checked_text(render_with_hooks(app_handle, prompt, values)?)
+}
+
+/// Текст промпта из результата подстановки или ошибка валидации с перечислением полей
+fn checked_text(output: RenderOutput) -> Result<String> {
+    if let Some(error) = output.template_error {
+        return Err(PromptToolError::Validation(format!("Ошибка шаблона: {}", error)));
+    }
+
+    output.text.ok_or_else(|| {
+        let details: Vec<String> = output.errors
+            .iter()
+            .map(|error| format!("{}: {}", error.parameter, error.message))
+            .collect();
+        PromptToolError::Validation(details.join("; "))
+    })
+}
+
+/// Команда для подстановки значений параметров в промпт
+/// Значения проверяются по ограничениям промпта; ошибки возвращаются по каждому параметру
+#[tauri::command]
+async fn render_prompt(
+    prompt_name: String,
+    values: HashMap<String, String>,
+    app_handle: tauri::AppHandle,
+) -> Result<RenderOutput> {
+    // Хуки - внешние скрипты, они выполняются вне асинхронного потока
+    run_blocking(&app_handle, move |state, app_handle| {
+        let prompt = find_prompt(state, &prompt_name)?;
+        let values = with_context(state, values)?;
+        let output = render_with_hooks(app_handle, &prompt, &values)?;
+
+        if output.text.is_some() {
+            record_usage(state, app_handle, &prompt_name)?;
+        }
+        Ok(output)
+    }).await
+}
+
+/// Фрагмент ответа модели, отправляемый фронтенду событием "execution-token"
+#[derive(Debug, Clone, Serialize)]
+struct ExecutionToken {
+    // Идентификатор выполнения, переданный в execute_prompt
+    execution_id: String,
+    // Очередной кусок текста
+    token: String,
+}
+
+/// Команда для получения списка встроенных функций шаблонов
+#[tauri::command]
+async fn list_template_functions() -> Vec<TemplateFunction> {
+    TEMPLATE_FUNCTIONS.to_vec()
+}
+
+/// Команда для выполнения промпта: подставляет параметры и отправляет текст модели
+/// Если модель не указана, используется модель из настроек.
+/// Если передан `execution_id`, ответ приходит по частям событиями "execution-token",
+/// а выполнение можно прервать командой cancel_execution
+#[tauri::command]
+async fn execute_prompt(
+    prompt_name: String,
+    values: HashMap<String, String>,
+    model: Option<String>,
+    execution_id: Option<String>,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Completion> {
+    let config = llm_settings(&state)?;
+
+    // Хуки - внешние скрипты, они выполняются вне асинхронного потока.
+    // Секреты читаются из связки ключей один раз: они же скрываются в журнале выполнений
+    let name = prompt_name.clone();
+    let (values, text, secret_bindings) = run_blocking(&app_handle, move |state, app_handle| {
+        let prompt = find_prompt(state, &name)?;
+        let values = with_context(state, values)?;
+        let secret_bindings = render::bind_secrets(&prompt.content, secrets::get_secret)?;
+        let text = checked_text(render_with_secrets(app_handle, &prompt, &secret_bindings, &values)?)?;
+        record_usage(state, app_handle, &name)?;
+        Ok((values, text, secret_bindings))
+    }).await?;
+
+    let started_at = chrono::Utc::now();
+    let timer = Instant::now();
+
+    let result = match execution_id {
+        None => llm::complete(&config, model.as_deref(), &text).await,
+        Some(execution_id) => {
+            let cancel = Arc::new(AtomicBool::new(false));
+            state.executions
+                .write()?
+                .insert(execution_id.clone(), cancel.clone());
+
+            let result = llm::complete_streaming(&config, model.as_deref(), &text, &cancel, |token| {
+                let _ = app_handle.emit("execution-token", ExecutionToken {
+                    execution_id: execution_id.clone(),
+                    token: token.to_string(),
+                });
+            }).await;
+
+            state.executions.write()?.remove(&execution_id);
+            result
+        }
+    };
+
+    let run = Run {
+        id: Run::new_id(started_at),
+        prompt_name,
+        rendered_input: render::mask_secrets(&text, &secret_bindings, SECRET_MASK),
+        parameters: values,
+        model: match &result {
+            Ok(completion) if !completion.model.is_empty() => completion.model.clone(),
+            _ => model.unwrap_or(config.model),
+        },
+        response: result.as_ref().ok().map(|completion| completion.text.clone()),
+        error: result.as_ref().err().map(|e| e.to_string()),
+        latency_ms: timer.elapsed().as_millis() as u64,
+        usage: result.as_ref().ok().and_then(|completion| completion.usage.clone()),
+        created_at: started_at,
+    };
+    // Журнал выполнений хранит текст промпта и ответ, для зашифрованной библиотеки он не ведется
+    let appended = run_blocking(&app_handle, move |state, app_handle| {
+        if !library_encrypted(state) {
+            run_store(app_handle).append(&run)?;
+        }
+        Ok(())
+    }).await;
+    if let Err(e) = appended {
+        warn!("Не удалось сохранить выполнение: {}", e);
+    }
+
+    result
+}
+
+/// Команда для выполнения цепочки промптов
+/// Шаги выполняются по очереди; ответ каждого шага подставляется в параметр следующего.
+/// Возвращаются результаты всех шагов, последний из них - итог цепочки
+#[tauri::command]
+async fn run_chain(
+    chain: PromptChain,
+    values: HashMap<String, String>,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<ChainStepResult>> {
+    chain.validate()?;
+
+    let config = llm_settings(&state)?;
+    let initial = with_context(&state, values)?;
+
+    let mut results: Vec<ChainStepResult> = Vec::with_capacity(chain.steps.len());
+    for (index, step) in chain.steps.iter().enumerate() {
+        let previous_output = results.last().map(|result| result.output.as_str());
+        let step_values = chain.step_values(index, &initial, previous_output);
+
+        // Хуки шага выполняются вне асинхронного потока
+        let prompt_name = step.prompt_name.clone();
+        let text = run_blocking(&app_handle, move |state, app_handle| {
+            let prompt = find_prompt(state, &prompt_name)?;
+            record_usage(state, app_handle, &prompt_name)?;
+            render_checked(app_handle, &prompt, &step_values)
+        }).await?;
+
+        let completion = llm::complete(&config, step.model.as_deref(), &text)
+            .await
+            .map_err(|e| PromptToolError::Execution(format!("Шаг {} ({}): {}", index + 1, step.prompt_name, e)))?;
+
+        results.push(ChainStepResult {
+            prompt_name: step.prompt_name.clone(),
+            rendered_input: text,
+            output: completion.text,
+            usage: completion.usage,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Журнал выполнений промптов в директории данных приложения
+fn run_store(app_handle: &tauri::AppHandle) -> RunStore {
+    RunStore::new(app_paths(app_handle).data_dir.join(RUNS_FILE_NAME))
+}
+
+/// Команда для получения выполнений промпта (от новых к старым)
+/// Если название не указано, возвращаются выполнения всех промптов
+#[tauri::command]
+async fn list_runs(prompt_name: Option<String>, app_handle: tauri::AppHandle) -> Result<Vec<Run>> {
+    run_blocking(&app_handle, move |_, app_handle| run_store(app_handle).list(prompt_name.as_deref())).await
+}
+
+/// Команда для удаления выполнения из журнала
+#[tauri::command]
+async fn delete_run(id: String, app_handle: tauri::AppHandle) -> Result<bool> {
+    run_blocking(&app_handle, move |_, app_handle| run_store(app_handle).delete(&id)).await
+}
+
+/// Команда для отмены потокового выполнения промпта
+/// Возвращает false, если выполнение с таким идентификатором уже завершилось
+#[tauri::command]
+async fn cancel_execution(execution_id: String, state: State<'_, AppState>) -> Result<bool> {
+    let executions = state.executions.read()?;
+
+    match executions.get(&execution_id) {
+        Some(cancel) => {
+            cancel.store(true, Ordering::SeqCst);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Настройки LLM; ключ API вида `secret:ИМЯ` берется из хранилища секретов
+fn llm_settings(state: &AppState) -> Result<LlmConfig> {
+    let mut config = state.config
+        .read()
+        .map(|config| config.llm.clone())?;
+
+    config.api_key = secrets::resolve(&config.api_key)?;
+    Ok(config)
+}
+
+/// Команда для сохранения секрета (ключа API, пароля) в системной связке ключей
+/// На секрет ссылаются директивой `{{secret:ИМЯ}}` в промпте или значением `secret:ИМЯ` в настройках
+#[tauri::command]
+async fn set_secret(name: String, value: String) -> Result<()> {
+    secrets::set_secret(&name, &value)
+}
+
+/// Команда для удаления секрета из системной связки ключей; возвращает, был ли он сохранен
+#[tauri::command]
+async fn delete_secret(name: String) -> Result<bool> {
+    secrets::delete_secret(&name)
+}
+
+/// Команда для получения списка моделей настроенного поставщика
+#[tauri::command]
+async fn list_models(state: State<'_, AppState>) -> Result<Vec<String>> {
+    let config = llm_settings(&state)?;
+
+    llm::list_models(&config).await
+}
+
+/// Команда для изменения настроек подключения к LLM
+#[tauri::command]
+async fn set_llm_config(
+    llm_config: LlmConfig,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let mut config = state.config.write()?;
+
+    config.llm = llm_config;
+    save_config(&app_handle, &config)
+}
+
+/// Команда для изменения настроек GitHub Gist (токен, публичность, адрес API)
+#[tauri::command]
+async fn set_gist_settings(
+    settings: GistSettings,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let mut config = state.config.write()?;
+
+    config.gist = settings;
+    save_config(&app_handle, &config)
+}
+
+/// Команда для публикации выбранных промптов одним Gist'ом
+/// Директивы `{{include:...}}` раскрываются, чтобы опубликованные промпты не зависели от библиотеки
+#[tauri::command]
+async fn publish_gist(
+    prompt_names: Vec<String>,
+    description: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<PublishedGist> {
+    if prompt_names.is_empty() {
+        return Err(PromptToolError::Validation("Не выбрано ни одного промпта".to_string()));
+    }
+
+    let mut settings = state.config
+        .read()
+        .map(|config| config.gist.clone())?;
+    settings.token = secrets::resolve(&settings.token)?;
+
+    let prompts = prompt_names
+        .iter()
+        .map(|name| find_prompt(&state, name))
+        .collect::<Result<Vec<Prompt>>>()?;
+    let description = description.unwrap_or_else(|| prompt_names.join(", "));
+
+    gist::publish(&settings, &prompts, &description).await
+}
+
+/// Команда для импорта промптов из Gist'а по ссылке
+#[tauri::command]
+async fn import_gist(
+    url: String,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<usize> {
+    let (mut settings, file_path, git) = state.config
+        .read()
+        .map(|config| (config.gist.clone(), config.prompt_file_path.clone(), config.git.clone()))?;
+    ensure_writable(&state, &file_path)?;
+    settings.token = secrets::resolve(&settings.token)?;
+
+    let imported = gist::fetch(&settings, &url).await?;
+    let count = imported.prompts.len();
+
+    run_blocking(&app_handle, move |state, app_handle| {
+        {
+            let mut prompts = state.prompts.write()?;
+            validation::validate_new_prompts(&imported.prompts, &prompts)?;
+            prompts.prompts.extend(imported.prompts);
+            persist_prompts(app_handle, &file_path, &prompts, &git)?;
+        }
+        sync_index(state, app_handle)
+    }).await?;
+
+    notify(&app_handle, NotificationKind::ImportCompleted, "Импорт завершен", &format!("Импортировано промптов: {}", count));
+    Ok(count)
+}
+
+/// Читает файл промптов в пуле блокирующих потоков
+async fn read_prompt_file_blocking(app_handle: &tauri::AppHandle, file_path: &str) -> Result<String> {
+    let file_path = file_path.to_string();
+    run_blocking(app_handle, move |_, _| read_prompt_file(&file_path)).await
+}
+
+/// Путь к файлу состояния синхронизации через WebDAV
+fn webdav_state_path(app_handle: &tauri::AppHandle) -> PathBuf {
+    app_paths(app_handle).data_dir.join(WEBDAV_STATE_FILE_NAME)
+}
+
+/// Путь к файлу промптов и настройки WebDAV из конфигурации
+/// Пароль вида `secret:ИМЯ` берется из хранилища секретов
+fn webdav_context(state: &AppState) -> Result<(String, WebDavSettings)> {
+    let (file_path, mut settings) = state.config
+        .read()
+        .map(|config| (config.prompt_file_path.clone(), config.webdav.clone()))?;
+
+    settings.password = secrets::resolve(&settings.password)?;
+    Ok((file_path, settings))
+}
+
+/// Заменяет файл промптов содержимым с сервера и перечитывает библиотеку
+/// Содержимое проверяется до записи, чтобы поврежденный файл на сервере не испортил локальный
+fn replace_prompt_file(state: &AppState, app_handle: &tauri::AppHandle, file_path: &str, contents: &str) -> Result<()> {
+    ensure_not_read_only(state, file_path)?;
+    let library = parse_prompts(file_path, contents)?;
+    // Правки в памяти попадают в резервную копию, а не теряются при замене файла
+    flush_prompts(state, app_handle)?;
+    write_prompt_file(file_path, contents)?;
+
+    set_library(state, library, Some(file_path))?;
+    sync_index(state, app_handle)
+}
+
+/// Команда для разблокировки зашифрованного файла промптов
+/// Ключ из парольной фразы хранится только в памяти до блокировки или выхода из приложения
+#[tauri::command]
+async fn unlock(
+    passphrase: String,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let file_path = state.config
+            .read()
+            .map(|config| config.prompt_file_path.clone())?;
+
+        let contents = read_prompt_file(&file_path)?;
+        if !encryption::is_encrypted(&contents) {
+            return Err(PromptToolError::Validation("Файл промптов не зашифрован".to_string()));
+        }
+
+        let key = encryption::key_for(&contents, &passphrase)?;
+        encryption::unlock(std::path::Path::new(&file_path), key)?;
+
+        let library = parse_prompts(&file_path, &contents)?;
+        set_library(state, library, Some(&file_path))?;
+        sync_index(state, app_handle)
+    }).await
+}
+
+/// Команда для блокировки зашифрованного файла промптов
+/// Ключ забывается, промпты выгружаются из памяти и поискового индекса
+#[tauri::command]
+async fn lock(
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let (file_path, encrypted) = state.config
+            .read()
+            .map(|config| (config.prompt_file_path.clone(), config.prompt_file_encrypted))?;
+
+        if !encrypted {
+            return Err(PromptToolError::Validation("Файл промптов не зашифрован".to_string()));
+        }
+
+        // После блокировки записать правки уже не получится
+        flush_prompts(state, app_handle)?;
+        encryption::lock(std::path::Path::new(&file_path))?;
+        set_library(state, PromptList::new(), None)?;
+        sync_index(state, app_handle)
+    }).await
+}
+
+/// Команда для включения и отключения шифрования файла промптов
+/// Включение шифрует файл ключом из `passphrase` и оставляет его разблокированным;
+/// отключение проверяет парольную фразу и записывает файл открытым текстом
+#[tauri::command]
+async fn set_prompt_file_encryption(
+    enabled: bool,
+    passphrase: String,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let (file_path, git) = state.config
+            .read()
+            .map(|config| (config.prompt_file_path.clone(), config.git.clone()))?;
+        ensure_not_read_only(state, &file_path)?;
+
+        // Файл перечитывается и перезаписывается целиком, правки в памяти должны быть в нем
+        flush_prompts(state, app_handle)?;
+
+        let path = std::path::Path::new(&file_path);
+        let contents = read_prompt_file(&file_path)?;
+
+        let library = if enabled {
+            if encryption::is_encrypted(&contents) {
+                return Err(PromptToolError::Validation("Файл промптов уже зашифрован".to_string()));
+            }
+
+            let library = parse_prompts(&file_path, &contents)?;
+            remember_contents(state, Some(&contents))?;
+            encryption::unlock(path, FileKey::generate(&passphrase)?)?;
+            if let Err(e) = persist_prompts(app_handle, &file_path, &library, &git) {
+                let _ = encryption::lock(path);
+                return Err(e);
+            }
+            library
+        } else {
+            if !encryption::is_encrypted(&contents) {
+                return Err(PromptToolError::Validation("Файл промптов не зашифрован".to_string()));
+            }
+
+            // Парольная фраза проверяется, даже если файл уже разблокирован
+            let key = encryption::key_for(&contents, &passphrase)?;
+            let plaintext = encryption::decrypt(&contents, &key)?;
+            let library: PromptList = toml::from_str(&plaintext)?;
+
+            // Без ключа save_prompts не пишет поверх зашифрованного файла, поэтому сначала
+            // заменяем шифротекст открытым текстом
+            encryption::lock(path)?;
+            let written = write_prompt_file(&file_path, &plaintext)
+                .and_then(|_| remember_contents(state, Some(&plaintext)))
+                .and_then(|_| persist_prompts(app_handle, &file_path, &library, &git));
+            if let Err(e) = written {
+                let _ = encryption::unlock(path, key);
+                return Err(e);
+            }
+            library
+        };
+
+        {
+            let mut config = state.config.write()?;
+            config.prompt_file_encrypted = enabled;
+            save_config(app_handle, &config)?;
+        }
+
+        // Индекс переезжает в память или обратно на диск; прежний закрывается до удаления его файлов
+        {
+            let mut database = state.database.write()?;
+            let index_open = database.take().is_some();
+            if enabled {
+                remove_plaintext_data(app_handle);
+            }
+            if index_open {
+                *database = Some(open_database(app_handle)?);
+            }
+        }
+
+        // После отключения шифрования заблокированный файл становится доступен
+        set_library(state, library, Some(&file_path))?;
+        sync_index(state, app_handle)
+    }).await
+}
+
+/// Команда для изменения настроек WebDAV
+/// При смене адреса состояние прошлой синхронизации сбрасывается
+#[tauri::command]
+async fn set_webdav_settings(
+    settings: WebDavSettings,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let mut config = state.config.write()?;
+
+    if config.webdav.url != settings.url {
+        SyncState::default().save(&webdav_state_path(&app_handle))?;
+    }
+    config.webdav = settings;
+    save_config(&app_handle, &config)
+}
+
+/// Команда для синхронизации файла промптов с WebDAV-сервером
+/// Изменения одной стороны переносятся на другую; если изменились обе,
+/// ничего не перезаписывается и показывается уведомление о конфликте
+#[tauri::command]
+async fn webdav_sync(state: State<'_, AppState>, app_handle: tauri::AppHandle) -> Result<SyncAction> {
+    let (file_path, settings) = webdav_context(&state)?;
+    let local = read_prompt_file_blocking(&app_handle, &file_path).await?;
+    let sync_state = SyncState::load(&webdav_state_path(&app_handle));
+
+    let result = webdav::sync(&settings, &sync_state, &local)
+        .await
+        .inspect_err(|e| notify_sync_failed(&app_handle, e))?;
+
+    if let Some(contents) = result.contents.clone() {
+        run_blocking(&app_handle, move |state, app_handle| replace_prompt_file(state, app_handle, &file_path, &contents)).await?;
+        notify_file_reloaded(&app_handle);
+    }
+    if result.action == SyncAction::Conflict {
+        notify(
+            &app_handle,
+            NotificationKind::SyncConflict,
+            "Конфликт синхронизации",
+            "Файл промптов изменен и локально, и на сервере. Выберите, какую версию оставить",
+        );
+    } else {
+        result.state.save(&webdav_state_path(&app_handle))?;
+    }
+
+    Ok(result.action)
+}
+
+/// Команда для принудительной отправки локального файла на сервер (разрешение конфликта в пользу локальной версии)
+#[tauri::command]
+async fn webdav_push(state: State<'_, AppState>, app_handle: tauri::AppHandle) -> Result<()> {
+    let (file_path, settings) = webdav_context(&state)?;
+    let local = read_prompt_file_blocking(&app_handle, &file_path).await?;
+
+    webdav::force_push(&settings, &local)
+        .await?
+        .save(&webdav_state_path(&app_handle))
+}
+
+/// Команда для принудительной загрузки файла с сервера (разрешение конфликта в пользу удаленной версии)
+#[tauri::command]
+async fn webdav_pull(state: State<'_, AppState>, app_handle: tauri::AppHandle) -> Result<()> {
+    let (file_path, settings) = webdav_context(&state)?;
+
+    let (contents, sync_state) = webdav::force_pull(&settings).await?;
+    run_blocking(&app_handle, move |state, app_handle| replace_prompt_file(state, app_handle, &file_path, &contents)).await?;
+    sync_state.save(&webdav_state_path(&app_handle))
+}
+
+/// Команда для копирования текста промпта в буфер обмена
+/// Текст приводится к виду, который корректно вставляется в другие приложения.
+/// Если передано название промпта, копирование учитывается в статистике использования,
+/// а при включенной настройке block_unresolved текст с незаполненными плейсхолдерами не копируется
+#[tauri::command]
+async fn copy_to_clipboard(
+    text: String,
+    prompt_name: Option<String>,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<ClipboardWrite> {
+    let options = state.config
+        .read()
+        .map(|config| config.paste)?;
+
+    if let Some(prompt_name) = &prompt_name {
+        if options.block_unresolved {
+            let prompt = find_prompt(&state, prompt_name)?;
+            let unresolved: Vec<String> = render::find_placeholders(&prompt.content)
+                .into_iter()
+                .filter(|name| text.contains(&format!("{{{}}}", name)) || text.contains(&format!("{{{}|", name)))
+                .collect();
+
+            if !unresolved.is_empty() {
+                return Err(PromptToolError::Validation(format!(
+                    "Не заполнены плейсхолдеры: {}",
+                    unresolved.join(", ")
+                )));
+            }
+        }
+        // Статистика использования сохраняется на диск
+        let name = prompt_name.clone();
+        run_blocking(&app_handle, move |state, app_handle| record_usage(state, app_handle, &name)).await?;
+    }
+
+    let text = clipboard::prepare_text(&text, &options);
+    let native = clipboard::write_text(&text, &options)?;
+
+    // Текст уже в буфере - сразу вставляем его в окно, из которого вызвали PromptTool
+    if options.auto_paste && native {
+        let handle = app_handle.clone();
+        tauri::async_runtime::spawn_blocking(move || paste_into_previous_window(&handle))
+            .await
+            .map_err(|e| PromptToolError::Execution(format!("Ошибка вставки текста: {}", e)))??;
+    }
+
+    Ok(ClipboardWrite { text, native, paste: options.auto_paste && !native })
+}
+
+/// Возвращает фокус окну, из которого вызвали PromptTool, и вставляет в него содержимое буфера обмена
+/// Окна PromptTool при этом скрываются; в macOS и Linux фокус возвращается именно поэтому
+fn paste_into_previous_window<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> Result<()> {
+    // Запомненное окно забирается до скрытия: потеря фокуса сбрасывает его
+    let previous = app_handle.state::<AppState>().previous_window.write()?.take();
+
+    for window in app_handle.webview_windows().values() {
+        if window.is_visible().unwrap_or(false) {
+            window.hide()
+                .map_err(|e| PromptToolError::Execution(format!("Не удалось скрыть окно: {}", e)))?;
+        }
+    }
+    #[cfg(target_os = "macos")]
+    let _ = app_handle.hide();
+
+    if let Some(previous) = previous {
+        platform::focus_window(previous);
+    }
+    std::thread::sleep(FOCUS_RESTORE_DELAY);
+    platform::simulate_paste()
+}
+
+/// Вставляет доставленный текст, если цель этого требует и текст уже в буфере обмена
+/// Если буфер заполняет фронтенд, `paste` остается установленным и вставку вызывает он
+fn paste_delivered<R: Runtime>(app_handle: &tauri::AppHandle<R>, mut delivery: Delivery) -> Result<Delivery> {
+    if delivery.paste && delivery.handled {
+        paste_into_previous_window(app_handle)?;
+        delivery.paste = false;
+    }
+    Ok(delivery)
+}
+
+/// Команда для вставки текста из буфера обмена в окно, из которого вызвали PromptTool
+/// Вызывается фронтендом после того, как он сам положил текст в буфер
+#[tauri::command]
+async fn paste_to_previous_window(app_handle: tauri::AppHandle) -> Result<()> {
+    tauri::async_runtime::spawn_blocking(move || paste_into_previous_window(&app_handle))
+        .await
+        .map_err(|e| PromptToolError::Execution(format!("Ошибка вставки текста: {}", e)))?
+}
+
+/// Команда для отправки готового промпта в выбранную цель
+/// Если цель не указана, используется цель по умолчанию из промпта, затем буфер обмена
+#[tauri::command]
+async fn send_prompt(
+    prompt_name: String,
+    values: HashMap<String, String>,
+    target: Option<OutputTargetConfig>,
+    app_handle: tauri::AppHandle,
+) -> Result<Delivery> {
+    // Хуки, HTTP-запрос, запись в файл и вставка блокирующие, поэтому выполняются вне асинхронного потока
+    run_blocking(&app_handle, move |state, app_handle| {
+        let prompt = find_prompt(state, &prompt_name)?;
+        let options = state.config
+            .read()
+            .map(|config| config.paste)?;
+
+        let values = with_context(state, values)?;
+        let text = render_checked(app_handle, &prompt, &values)?;
+        let target = target.or(prompt.output).unwrap_or_default();
+
+        let delivery = output::target_for(&target, options).deliver(&prompt_name, &text)?;
+        let delivery = paste_delivered(app_handle, delivery)?;
+
+        record_usage(state, app_handle, &prompt_name)?;
+        Ok(delivery)
+    }).await
+}
+
+/// Команда для изменения цели отправки промпта по умолчанию
+/// `None` возвращает промпт к копированию в буфер обмена
+#[tauri::command]
+async fn set_prompt_output(
+    prompt_name: String,
+    target: Option<OutputTargetConfig>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        update_prompt(state, app_handle, &prompt_name, |prompt| prompt.output = target)
+    }).await
+}
+
+/// Команда для проверки библиотеки промптов
+/// Возвращает проблемы: пустые и повторяющиеся названия, пустой текст,
+/// неиспользуемые параметры и необъявленные плейсхолдеры
+#[tauri::command]
+async fn lint_prompts(app_handle: tauri::AppHandle) -> Result<Vec<Diagnostic>> {
+    run_blocking(&app_handle, |state, _| {
+        let prompts = state.prompts.read()?;
+
+        Ok(lint_library(&prompts))
+    }).await
+}
+
+/// Команда для исправления совпадающих идентификаторов промптов
+/// Работает без поискового индекса. Статистика, история и журнал выполнений ссылаются
+/// на промпты по названию, поэтому новые идентификаторы их не затрагивают
+#[tauri::command]
+async fn repair_ids(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<IdRepair>> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let (file_path, git) = state.config
+            .read()
+            .map(|config| (config.prompt_file_path.clone(), config.git.clone()))?;
+        ensure_writable(state, &file_path)?;
+
+        let mut prompts = state.prompts.write()?;
+
+        let repairs = prompts.repair_ids();
+        if !repairs.is_empty() {
+            persist_prompts(app_handle, &file_path, &prompts, &git)?;
+        }
+        Ok(repairs)
+    }).await
+}
+
+/// Команда для поиска похожих промптов
+/// `threshold` - минимальное сходство текстов от 0 до 1, например 0.8
+#[tauri::command]
+async fn find_duplicates(threshold: f64, app_handle: tauri::AppHandle) -> Result<Vec<DuplicatePair>> {
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(PromptToolError::Validation("Порог сходства должен быть от 0 до 1".to_string()));
+    }
+
+    run_blocking(&app_handle, move |state, _| {
+        let prompts = state.prompts.read()?;
+
+        Ok(duplicates::find_duplicates(&prompts, threshold))
+    }).await
+}
+
+/// Команда для сравнения двух промптов, например вариантов одной задачи
+/// Возвращает построчную разницу текстов и различия тегов, категорий и параметров
+#[tauri::command]
+async fn diff_prompts(name_a: String, name_b: String, state: State<'_, AppState>) -> Result<PromptDiff> {
+    let prompts = state.prompts.read()?;
+
+    let find = |name: &str| prompts.prompts
+        .iter()
+        .find(|prompt| prompt.name == name)
+        .ok_or_else(|| PromptToolError::Validation(format!("Промпт \"{}\" не найден", name)));
+
+    Ok(diff::diff_prompts(find(&name_a)?, find(&name_b)?, &name_a, &name_b))
+}
+
+/// Команда для получения истории ревизий промпта, от старых к новым
+#[tauri::command]
+async fn list_prompt_revisions<R: Runtime>(prompt_name: String, app_handle: tauri::AppHandle<R>) -> Result<Vec<PromptRevision>> {
+    run_blocking(&app_handle, move |_, app_handle| revision_store(app_handle).list(&prompt_name)).await
+}
+
+/// Команда для сравнения двух ревизий одного промпта
+#[tauri::command]
+async fn diff_versions(
+    prompt_name: String,
+    rev_a: u32,
+    rev_b: u32,
+    app_handle: tauri::AppHandle,
+) -> Result<PromptDiff> {
+    run_blocking(&app_handle, move |_, app_handle| {
+        let store = revision_store(app_handle);
+        let old = store.get(&prompt_name, rev_a)?;
+        let new = store.get(&prompt_name, rev_b)?;
+
+        Ok(diff::diff_prompts(
+            &old.prompt,
+            &new.prompt,
+            &format!("{}@{}", prompt_name, rev_a),
+            &format!("{}@{}", prompt_name, rev_b),
+        ))
+    }).await
+}
+
+/// Команда для слияния правок промпта в приложении с версией, измененной во внешнем файле
+/// `base` - промпт в том виде, в каком его загрузило приложение, `ours` - отредактированный промпт.
+/// Версия из файла перечитывается с диска; результат не сохраняется, пока UI не вызовет resolve_merge
+#[tauri::command]
+async fn merge_prompt(base: Prompt, ours: Prompt, app_handle: tauri::AppHandle) -> Result<PromptMerge> {
+    run_blocking(&app_handle, move |state, _| {
+        let file_path = state.config
+            .read()
+            .map(|config| config.prompt_file_path.clone())?;
+
+        let theirs = load_prompts(&file_path)?
+            .prompts
+            .into_iter()
+            .find(|prompt| prompt.name == base.name)
+            .ok_or_else(|| PromptToolError::Validation(format!("Промпт \"{}\" удален из файла", base.name)))?;
+
+        Ok(merge::merge_prompt(&base, &ours, &theirs))
+    }).await
+}
+
+/// Команда для сохранения разрешенного слияния
+/// Библиотека перечитывается из файла, чтобы не затереть внешние изменения других промптов,
+/// и в ней заменяется промпт `prompt_name`
+#[tauri::command]
+async fn resolve_merge<R: Runtime>(
+    prompt_name: String,
+    prompt: Prompt,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        if merge::has_conflict_markers(&prompt.content) {
+            return Err(PromptToolError::Validation("В тексте промпта остались маркеры конфликта".to_string()));
+        }
+
+        let (file_path, git) = state.config
+            .read()
+            .map(|config| (config.prompt_file_path.clone(), config.git.clone()))?;
+        ensure_not_read_only(state, &file_path)?;
+
+        // Библиотека перечитывается из файла: правки других промптов, сделанные в памяти, записываются до этого
+        flush_prompts(state, app_handle)?;
+
+        {
+            let mut prompts = state.prompts.write()?;
+            ensure_no_unsaved_edits(state)?;
+
+            let contents = read_prompt_file(&file_path)?;
+            let mut library = parse_prompts(&file_path, &contents)?;
+            remember_contents(state, Some(&contents))?;
+            let index = library.prompts
+                .iter()
+                .position(|existing| existing.name == prompt_name)
+                .ok_or_else(|| PromptToolError::Validation(format!("Промпт \"{}\" удален из файла", prompt_name)))?;
+            validation::validate_prompt(&prompt, &library, Some(index))?;
+
+            let previous = std::mem::replace(&mut library.prompts[index], prompt);
+            persist_prompts(app_handle, &file_path, &library, &git)?;
+
+            record_revision(app_handle, &file_path, &previous, &library.prompts[index]);
+            *prompts = library;
+            *state.loaded_file.write()? = Some(file_path);
+        }
+
+        sync_index(state, app_handle)
+    }).await
+}
+
+/// Путь к файлу промптов и настройки git из конфигурации
+fn git_context(state: &AppState) -> Result<(PathBuf, GitSettings)> {
+    state.config
+        .read()
+        .map(|config| (PathBuf::from(&config.prompt_file_path), config.git.clone()))
+}
+
+/// Команда для изменения настроек git
+/// При включении папка с файлом промптов становится репозиторием и текущее состояние фиксируется коммитом
+#[tauri::command]
+async fn set_git_settings(
+    settings: GitSettings,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let file_path = {
+            let mut config = state.config.write()?;
+            config.git = settings.clone();
+            save_config(app_handle, &config)?;
+            config.prompt_file_path.clone()
+        };
+
+        if settings.enabled {
+            git::commit_prompts(std::path::Path::new(&file_path), &settings)?;
+        }
+        Ok(())
+    }).await
+}
+
+/// Команда для получения состояния репозитория с промптами
+#[tauri::command]
+async fn git_status(app_handle: tauri::AppHandle) -> Result<GitStatus> {
+    run_blocking(&app_handle, |state, _| {
+        let (file_path, _) = git_context(state)?;
+        git::status(&file_path)
+    }).await
+}
+
+/// Команда для получения коммитов, изменивших промпт, от новых к старым
+#[tauri::command]
+async fn git_history(prompt_name: String, limit: Option<usize>, app_handle: tauri::AppHandle) -> Result<Vec<GitCommit>> {
+    run_blocking(&app_handle, move |state, _| {
+        let (file_path, _) = git_context(state)?;
+        git::history(&file_path, &prompt_name, limit.unwrap_or(50))
+    }).await
+}
+
+/// Команда для отправки коммитов в удаленный репозиторий
+#[tauri::command]
+async fn git_push(state: State<'_, AppState>, app_handle: tauri::AppHandle) -> Result<()> {
+    let (file_path, settings) = git_context(&state)?;
+
+    // Сетевые операции git блокирующие, поэтому выполняются вне асинхронного потока
+    tauri::async_runtime::spawn_blocking(move || git::push(&file_path, &settings))
+        .await
+        .map_err(|e| PromptToolError::Execution(format!("Ошибка отправки изменений: {}", e)))?
+        .inspect_err(|e| notify_sync_failed(&app_handle, e))
+}
+
+/// Команда для получения изменений из удаленного репозитория
+/// После перемотки библиотека перечитывается из файла и переиндексируется.
+/// Если истории разошлись, показывается уведомление о конфликте синхронизации
+#[tauri::command]
+async fn git_pull(state: State<'_, AppState>, app_handle: tauri::AppHandle) -> Result<PullOutcome> {
+    let (file_path, settings) = git_context(&state)?;
+    ensure_not_read_only(&state, &file_path.to_string_lossy())?;
+
+    // Правки в памяти фиксируются коммитом до получения изменений, иначе перечитанный файл их затрет
+    run_blocking(&app_handle, |state, app_handle| flush_prompts(state, app_handle)).await?;
+
+    let path = file_path.clone();
+    let outcome = tauri::async_runtime::spawn_blocking(move || git::pull(&path, &settings))
+        .await
+        .map_err(|e| PromptToolError::Execution(format!("Ошибка получения изменений: {}", e)))?
+        .inspect_err(|e| notify_sync_failed(&app_handle, e))?;
+
+    match outcome {
+        PullOutcome::FastForward => {
+            run_blocking(&app_handle, move |state, app_handle| {
+                let file_path = file_path.to_string_lossy();
+                let reloaded = load_prompts(&file_path)?;
+                set_library(state, reloaded, Some(&file_path))?;
+                sync_index(state, app_handle)
+            }).await?;
+            notify_file_reloaded(&app_handle);
+        }
+        PullOutcome::Diverged => notify(
+            &app_handle,
+            NotificationKind::SyncConflict,
+            "Конфликт синхронизации",
+            "Локальная и удаленная истории промптов разошлись, объедините их вручную",
+        ),
+        PullOutcome::UpToDate => {}
+    }
+    Ok(outcome)
+}
+
+/// Команда для сверки списка параметров промпта с его текстом
+/// Недостающие параметры добавляются, неиспользуемые только перечисляются в ответе
+#[tauri::command]
+async fn sync_parameters<R: Runtime>(
+    prompt_name: String,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<ParameterSync> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let sync = update_prompt(state, app_handle, &prompt_name, parameters::sync_parameters)?;
+
+        if !sync.added.is_empty() {
+            sync_index(state, app_handle)?;
+        }
+        Ok(sync)
+    }).await
+}
+
+/// Команда для оценки промпта от 1 до 5; `None` снимает оценку
+#[tauri::command]
+async fn rate_prompt(
+    prompt_name: String,
+    rating: Option<u8>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        update_prompt(state, app_handle, &prompt_name, |prompt| prompt.rating = rating)?;
+
+        // Результаты с фильтром по оценке в кэше устарели
+        state.search_cache.write()?.clear();
+        Ok(())
+    }).await
+}
+
+/// Команда для замены псевдонимов промпта - коротких кодов, по которым он находится первым
+#[tauri::command]
+async fn set_prompt_aliases(
+    prompt_name: String,
+    aliases: Vec<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let aliases = aliases.iter()
+            .map(|alias| alias.trim().to_string())
+            .filter(|alias| !alias.is_empty())
+            .collect();
+        update_prompt(state, app_handle, &prompt_name, |prompt| prompt.aliases = aliases)?;
+
+        // Псевдонимы индексируются, поэтому индекс и кэш результатов обновляются
+        sync_index(state, app_handle)
+    }).await
+}
+
+/// Команда для переноса промпта в архив: он остается в библиотеке, но не попадает в поиск по умолчанию
+#[tauri::command]
+async fn archive_prompt(
+    prompt_name: String,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| set_archived(state, app_handle, &prompt_name, true)).await
+}
+
+/// Команда для возврата промпта из архива
+#[tauri::command]
+async fn unarchive_prompt(
+    prompt_name: String,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| set_archived(state, app_handle, &prompt_name, false)).await
+}
+
+/// Меняет признак архивного промпта и сбрасывает кэш результатов поиска
+fn set_archived(state: &AppState, app_handle: &tauri::AppHandle, prompt_name: &str, archived: bool) -> Result<()> {
+    update_prompt(state, app_handle, prompt_name, |prompt| prompt.archived = archived)?;
+
+    state.search_cache.write()?.clear();
+    Ok(())
+}
+
+/// Команда для изменения промпта только в памяти, например при наборе текста в редакторе
+/// Изменение проверяется так же, как при сохранении, а на диск записывается автосохранением
+/// после паузы в правках, при потере фокуса или закрытии окна
+#[tauri::command]
+async fn edit_prompt<R: Runtime>(
+    prompt_name: String,
+    prompt: Prompt,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let file_path = state.config
+            .read()
+            .map(|config| config.prompt_file_path.clone())?;
+        ensure_writable(state, &file_path)?;
+
+        {
+            let mut prompts = state.prompts.write()?;
+
+            let index = prompts.prompts
+                .iter()
+                .position(|existing| existing.name == prompt_name)
+                .ok_or_else(|| PromptToolError::Validation(format!("Промпт \"{}\" не найден", prompt_name)))?;
+            validation::validate_prompt(&prompt, &prompts, Some(index))?;
+
+            prompts.prompts[index] = prompt;
+            *state.unsaved_edit.write()? = Some(Instant::now());
+        }
+
+        // Индекс обновляется сразу, файл промптов - автосохранением
+        sync_index(state, app_handle)
+    }).await
+}
+
+/// Команда для проверки, есть ли правки промптов, еще не сохраненные на диск
+#[tauri::command]
+async fn is_dirty(state: State<'_, AppState>) -> Result<bool> {
+    state.unsaved_edit
+        .read()
+        .map(|unsaved_edit| unsaved_edit.is_some())
+}
+
+/// Команда для немедленного сохранения правок, сделанных в памяти
+/// Возвращает, было ли что сохранять
+#[tauri::command]
+async fn save_pending_edits<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+) -> Result<bool> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        flush_prompts(state, app_handle)
+    }).await
+}
+
+/// Команда для изменения задержки автосохранения в секундах (0 - только при потере фокуса и закрытии окна)
+#[tauri::command]
+async fn set_autosave_delay(
+    seconds: u64,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let update = SettingsUpdate { autosave_delay_seconds: Some(seconds), ..Default::default() };
+    apply_settings(&state, &app_handle, &update).map(|_| ())
+}
+
+/// Команда для отметки файла промптов как доступного только для чтения или снятия отметки
+/// Без `path` отметка относится к текущему файлу; несохраненные правки записываются до отметки
+#[tauri::command]
+async fn set_prompt_file_read_only<R: Runtime>(
+    path: Option<String>,
+    read_only: bool,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        flush_prompts(state, app_handle)?;
+
+        let mut config = state.config.write()?;
+
+        let path = path.unwrap_or_else(|| config.prompt_file_path.clone());
+        if read_only {
+            config.read_only_files.insert(path);
+        } else {
+            config.read_only_files.remove(&path);
+        }
+        save_config(app_handle, &config)
+    }).await
+}
+
+/// Команда для проверки, открыт ли текущий файл промптов только для чтения
+/// Учитывается и отметка в настройках, и права на запись в файловой системе
+#[tauri::command]
+async fn is_prompt_file_read_only(state: State<'_, AppState>) -> Result<bool> {
+    let config = state.config.read()?;
+
+    Ok(is_read_only(&config, &config.prompt_file_path))
+}
+
+/// Команда для получения резервных копий текущего файла промптов, начиная с самой новой
+#[tauri::command]
+async fn list_backups(app_handle: tauri::AppHandle) -> Result<Vec<Backup>> {
+    run_blocking(&app_handle, |state, app_handle| {
+        let file_path = state.config
+            .read()
+            .map(|config| config.prompt_file_path.clone())?;
+
+        backup_store(app_handle).list(std::path::Path::new(&file_path))
+    }).await
+}
+
+/// Команда для восстановления файла промптов из резервной копии
+/// Восстановление - обычное сохранение, поэтому текущее содержимое тоже попадает в резервные копии
+#[tauri::command]
+async fn restore_backup<R: Runtime>(
+    file_name: String,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<()> {
+    run_blocking(&app_handle, move |state, app_handle| {
+        let (file_path, git) = state.config
+            .read()
+            .map(|config| (config.prompt_file_path.clone(), config.git.clone()))?;
+        ensure_not_read_only(state, &file_path)?;
+
+        let contents = backup_store(app_handle).read(std::path::Path::new(&file_path), &file_name)?;
+        let library = parse_prompts(&file_path, &contents)?;
+
+        // Правки в памяти записываются в файл и вместе с ним попадают в резервные копии
+        flush_prompts(state, app_handle)?;
+
+        {
+            let mut prompts = state.prompts.write()?;
+            ensure_no_unsaved_edits(state)?;
+            // Восстановление заменяет файл целиком, в каком бы виде он ни был
+            let current = std::fs::read_to_string(&file_path).ok();
+            remember_contents(state, current.as_deref())?;
+            persist_prompts(app_handle, &file_path, &library, &git)?;
+            *prompts = library;
+            *state.loaded_file.write()? = Some(file_path);
+        }
+        sync_index(state, app_handle)
+    }).await
+}
+
+/// Команда для изменения количества хранимых резервных копий (0 - не создавать копии)
+/// Лишние копии текущего файла удаляются сразу
+#[tauri::command]
+async fn set_backup_count(
+    count: usize,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let update = SettingsUpdate { backup_count: Some(count), ..Default::default() };
+    apply_settings(&state, &app_handle, &update).map(|_| ())
+}
+
+/// Применяет изменение настроек: проверяет значения, сохраняет конфигурацию, переносит изменения
+/// в работающие части приложения и сообщает о них фронтенду событием `settings-changed`
+fn apply_settings<R: Runtime>(state: &AppState, app_handle: &tauri::AppHandle<R>, update: &SettingsUpdate) -> Result<Settings> {
+    let (change, file_path) = {
+        let mut config = state.config.write()?;
+
+        let changed = config.settings.apply(update)?;
+        if changed.is_empty() {
+            return Ok(config.settings.clone());
+        }
+        save_config(app_handle, &config)?;
+        messages::set_language(&config.settings.language);
+        (SettingsChange { settings: config.settings.clone(), changed }, config.prompt_file_path.clone())
+    };
+
+    // Индекс блокируется после освобождения конфигурации, как и в остальных командах
+    if change.changed.contains(&"fuzzy_threshold") {
+        if let Some(database) = state.database.write()?.as_mut() {
+            database.set_fuzzy_distance(change.settings.fuzzy_threshold);
+        }
+        state.search_cache.write()?.clear();
+    }
+
+    // Лишние копии текущего файла удаляются сразу
+    if change.changed.contains(&"backup_count") && change.settings.backup_count > 0 {
+        backup_store(app_handle).prune(std::path::Path::new(&file_path), change.settings.backup_count)?;
+    }
+
+    let _ = app_handle.emit("settings-changed", &change);
+    Ok(change.settings)
+}
+
+/// Команда для получения текстов ошибок на языке интерфейса: код -> текст
+#[tauri::command]
+async fn get_error_messages() -> BTreeMap<&'static str, &'static str> {
+    messages::error_catalog(messages::language())
+}
+
+/// Команда для изменения части настроек приложения
+/// Каждое поле проверяется; если хотя бы одно значение неверно, не меняется ничего.
+/// Возвращает настройки после изменения
+#[tauri::command]
+async fn update_settings<R: Runtime>(
+    settings: SettingsUpdate,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<Settings> {
+    apply_settings(&state, &app_handle, &settings)
+}
+
+/// Команда для изменения настроек подготовки текста к вставке
+#[tauri::command]
+async fn set_paste_options(
+    options: PasteOptions,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let mut config = state.config.write()?;
+
+    config.paste = options;
+    save_config(&app_handle, &config)
+}
+
+/// Окно быстрого запуска; создается при первом вызове скрытым
+/// Окно без рамки и поверх остальных, с тем же фронтендом: он отличает окно по метке
+fn launcher_window<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> Result<tauri::WebviewWindow<R>> {
+    if let Some(window) = app_handle.get_webview_window(LAUNCHER_WINDOW) {
+        return Ok(window);
+    }
+
+    tauri::WebviewWindowBuilder::new(app_handle, LAUNCHER_WINDOW, tauri::WebviewUrl::App("index.html".into()))
+        .title("Быстрый запуск")
+        .inner_size(640.0, 360.0)
+        .center()
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(false)
+        .visible(false)
+        .build()
+        .map_err(|e| PromptToolError::Execution(format!("Не удалось создать окно быстрого запуска: {}", e)))
 }
 
-// Реализация значений по умолчанию для конфигурации
-impl Default for AppConfig {
-    fn default() -> Self {
-        Self {
-            prompt_file_path: DEFAULT_PROMPT_FILE.to_string(),
-            hotkey: String::new(),
+/// Показывает или скрывает окно быстрого запуска, запоминая окно, в которое потом вставлять текст
+fn activate_launcher<R: Runtime>(app_handle: &tauri::AppHandle<R>, command: ActivationCommand) -> Result<()> {
+    let window = launcher_window(app_handle)?;
+    let active = window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false);
+
+    let result = if command == ActivationCommand::Toggle && active {
+        window.hide()
+    } else {
+        if !active {
+            *app_handle.state::<AppState>().previous_window.write()? = platform::foreground_window();
         }
+        window.center()
+            .and_then(|_| window.show())
+            .and_then(|_| window.set_focus())
+    };
+
+    result.map_err(|e| PromptToolError::Execution(format!("Ошибка при активации окна быстрого запуска: {}", e)))
+}
+
+/// Скрывает окно быстрого запуска, если оно открыто
+fn hide_launcher_window<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> Result<()> {
+    match app_handle.get_webview_window(LAUNCHER_WINDOW) {
+        Some(window) if window.is_visible().unwrap_or(false) => window.hide()
+            .map_err(|e| PromptToolError::Execution(format!("Не удалось скрыть окно: {}", e))),
+        _ => Ok(()),
     }
 }
 
-/// Состояние приложения, которое хранится в памяти
-/// Использует Mutex для безопасного доступа из разных потоков
-struct AppState {
-    config: Mutex<AppConfig>,
-    prompts: Mutex<PromptList>,
+/// Команда для включения режима быстрого запуска: главная горячая клавиша открывает
+/// компактное окно поиска вместо окна управления библиотекой
+#[tauri::command]
+async fn set_launcher_mode(
+    enabled: bool,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let mut config = state.config.write()?;
+
+    config.launcher_mode = enabled;
+    save_config(&app_handle, &config)
 }
 
-/// Команда для поиска промптов с фильтрацией
+/// Команда для показа или скрытия окна быстрого запуска (вызывается по главной горячей клавише)
 #[tauri::command]
-async fn search_prompts(
-    filter: SearchFilter,
-    state: State<'_, AppState>
-) -> Result<Vec<Prompt>> {
-    let prompts = state.prompts.lock()
-        .map_err(|_| PromptToolError::Config("Не удалось получить доступ к промптам".to_string()))?;
-    
-    Ok(prompts.search(&filter)
-        .into_iter()
-        .cloned()
-        .collect())
+async fn toggle_launcher(app_handle: tauri::AppHandle) -> Result<()> {
+    activate_launcher(&app_handle, ActivationCommand::Toggle)
 }
 
-/// Команда для получения списка всех категорий
+/// Команда для скрытия окна быстрого запуска (по Escape)
 #[tauri::command]
-async fn get_categories(
-    state: State<'_, AppState>
-) -> Result<Vec<String>> {
-    let prompts = state.prompts.lock()
-        .map_err(|_| PromptToolError::Config("Не удалось получить доступ к промптам".to_string()))?;
-    
-    Ok(prompts.get_categories()
-        .into_iter()
-        .cloned()
-        .collect())
+async fn hide_launcher(app_handle: tauri::AppHandle) -> Result<()> {
+    hide_launcher_window(&app_handle)
 }
 
-/// Команда для получения списка всех тегов
+/// Команда для поиска в окне быстрого запуска
+/// Ищет так же, как основной поиск, но возвращает несколько первых результатов в компактном виде
 #[tauri::command]
-async fn get_tags(
-    state: State<'_, AppState>
-) -> Result<Vec<String>> {
-    let prompts = state.prompts.lock()
-        .map_err(|_| PromptToolError::Config("Не удалось получить доступ к промптам".to_string()))?;
-    
-    Ok(prompts.get_tags()
-        .into_iter()
-        .cloned()
+async fn launcher_search<R: Runtime>(
+    query: String,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<Vec<LauncherItem>> {
+    let filter = SearchFilter {
+        query: Some(query),
+        categories: None,
+        tags: None,
+        date_from: None,
+        date_to: None,
+        exact: false,
+        min_rating: None,
+        sort: None,
+        include_archived: false,
+    };
+    let found = search_prompts(filter, state, app_handle).await?;
+
+    Ok(found.iter()
+        .take(LAUNCHER_RESULT_LIMIT)
+        .map(|found| LauncherItem::from_prompt(&found.prompt))
         .collect())
 }
 
-/// Команда для получения списка промптов
+/// Команда для выбора промпта в окне быстрого запуска
+/// Выбор учитывается так же, как щелчок по результату основного поиска.
+/// Возвращает параметры, значения которых нужно спросить; остальные берутся по умолчанию
 #[tauri::command]
-async fn get_prompts(
-    file_path: Option<String>,
-    state: State<'_, AppState>
-) -> Result<Vec<Prompt>> {
-    // Если путь не указан, берем из конфигурации
-    let path = file_path.unwrap_or_else(|| {
-        state.config
-            .lock()
-            .map(|config| config.prompt_file_path.clone())
-            .unwrap_or_else(|_| DEFAULT_PROMPT_FILE.to_string())
-    });
+async fn launcher_select<R: Runtime>(
+    query: String,
+    prompt_name: String,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<LauncherSelection> {
+    let prompt = find_prompt(&state, &prompt_name)?;
+
+    state.feedback.write()?.record(&query, &prompt_name);
+    save_feedback(&app_handle);
 
-    // Загружаем и возвращаем промпты
-    let prompt_list = load_prompts(&path)?;
-    Ok(prompt_list.prompts)
+    Ok(LauncherSelection::from_prompt(&prompt))
 }
 
-/// Команда для установки нового пути к файлу промптов
+/// Команда для копирования промпта из окна быстрого запуска
+/// Подставляет значения, кладет текст в буфер обмена и скрывает окно; при включенной автовставке
+/// вставляет текст в окно, из которого вызвали PromptTool.
+/// Если ОС не поддерживает нативную запись в буфер, окно остается открытым: текст кладет фронтенд,
+/// после чего вызывает paste_to_previous_window или hide_launcher
 #[tauri::command]
-async fn set_prompt_file_path(
-    path: String,
-    state: State<'_, AppState>,
+async fn launcher_copy_and_hide(
+    prompt_name: String,
+    values: HashMap<String, String>,
     app_handle: tauri::AppHandle,
-) -> Result<()> {
-    // Проверяем существование файла
-    let path_buf = PathBuf::from(&path);
-    if !path_buf.exists() {
-        return Err(PromptToolError::Config("Файл не существует".to_string()));
+) -> Result<ClipboardWrite> {
+    // Хуки и вставка в другое окно блокирующие, поэтому выполняются вне асинхронного потока
+    run_blocking(&app_handle, move |state, app_handle| {
+        let options = state.config
+            .read()
+            .map(|config| config.paste)?;
+
+        let prompt = find_prompt(state, &prompt_name)?;
+        let values = with_context(state, values)?;
+        let text = clipboard::prepare_text(&render_checked(app_handle, &prompt, &values)?, &options);
+        let native = clipboard::write_text(&text, &options)?;
+        record_usage(state, app_handle, &prompt_name)?;
+
+        if native {
+            if options.auto_paste {
+                paste_into_previous_window(app_handle)?;
+            } else {
+                hide_launcher_window(app_handle)?;
+            }
+        }
+
+        Ok(ClipboardWrite { text, native, paste: options.auto_paste && !native })
+    }).await
+}
+
+/// Путь к файлу с положением окна
+/// Мониторы относятся к компьютеру, а не к профилю, поэтому файл общий для всех профилей
+fn window_state_path<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> PathBuf {
+    app_handle.state::<AppPaths>().data_dir.join(WINDOW_STATE_FILE_NAME)
+}
+
+/// Запоминает положение, размер и монитор окна управления
+/// Свернутое окно не сохраняется: его координаты в Windows уходят за пределы экрана
+fn save_window_state<R: Runtime>(window: &tauri::Window<R>) -> Result<()> {
+    if window.is_minimized().unwrap_or(false) {
+        return Ok(());
     }
 
-    // Загружаем промпты из нового файла
-    let new_prompts = load_prompts(&path)?;
-    
-    // Обновляем состояние
-    if let Ok(mut prompts) = state.prompts.lock() {
-        *prompts = new_prompts;
+    let error = |e: tauri::Error| PromptToolError::Execution(format!("Не удалось получить положение окна: {}", e));
+    let position = window.outer_position().map_err(error)?;
+    let size = window.inner_size().map_err(error)?;
+    let previous = WindowState::load(&window_state_path(window.app_handle()));
+    let maximized = window.is_maximized().unwrap_or(false);
+
+    let state = WindowState {
+        // Развернутое окно восстанавливается развернутым, а размер остается прежним для обычного режима
+        bounds: match (&previous, maximized) {
+            (Some(previous), true) => previous.bounds,
+            _ => Rect { x: position.x, y: position.y, width: size.width, height: size.height },
+        },
+        monitor: window.current_monitor().ok().flatten().and_then(|monitor| monitor.name().cloned()),
+        maximized,
+    };
+    state.save(&window_state_path(window.app_handle()))
+}
+
+/// Возвращает окну управления положение и размер с прошлого запуска
+/// Если прежний монитор отключен, окно открывается по центру с прежним размером
+fn restore_window_state(app_handle: &tauri::AppHandle) {
+    let (Some(window), Some(saved)) = (
+        app_handle.get_webview_window("main"),
+        WindowState::load(&window_state_path(app_handle)),
+    ) else {
+        return;
+    };
+
+    let monitors: Vec<MonitorArea> = window.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| MonitorArea {
+            name: monitor.name().cloned(),
+            area: Rect {
+                x: monitor.position().x,
+                y: monitor.position().y,
+                width: monitor.size().width,
+                height: monitor.size().height,
+            },
+        })
+        .collect();
+
+    let bounds = saved.bounds;
+    let mut result = window.set_size(tauri::PhysicalSize::new(bounds.width, bounds.height));
+    if saved.position_fits(&monitors) {
+        result = result.and_then(|_| window.set_position(tauri::PhysicalPosition::new(bounds.x, bounds.y)));
+    } else {
+        result = result.and_then(|_| window.center());
+    }
+    if saved.maximized {
+        result = result.and_then(|_| window.maximize());
     }
 
-    // Обновляем конфигурацию
-    if let Ok(mut config) = state.config.lock() {
-        config.prompt_file_path = path;
-        
-        // Сохраняем обновленную конфигурацию
-        let app_dir = app_handle.path().app_config_dir()
-            .map_err(|_| PromptToolError::Config("Не удалось получить директорию конфигурации".to_string()))?;
-        
-        std::fs::create_dir_all(&app_dir)
-            .map_err(PromptToolError::Io)?;
-        
-        let config_path = app_dir.join("config.json");
-        let config_str = serde_json::to_string_pretty(&*config)
-            .map_err(|_| PromptToolError::Config("Ошибка сериализации конфигурации".to_string()))?;
-        
-        std::fs::write(config_path, config_str)
-            .map_err(PromptToolError::Io)?;
+    if let Err(e) = result {
+        warn!("Не удалось восстановить положение окна: {}", e);
     }
+}
 
-    Ok(())
+/// Закрепляет окно управления поверх остальных окон или снимает закрепление по конфигурации
+fn apply_always_on_top<R: Runtime>(app_handle: &tauri::AppHandle<R>) {
+    let always_on_top = app_handle.state::<AppState>().config
+        .read()
+        .map(|config| config.always_on_top)
+        .unwrap_or(false);
+
+    if let Some(window) = app_handle.get_webview_window("main") {
+        if let Err(e) = window.set_always_on_top(always_on_top) {
+            warn!("Не удалось закрепить окно: {}", e);
+        }
+    }
 }
 
-/// Команда для установки новой горячей клавиши
+/// Команда для закрепления окна управления поверх остальных окон
 #[tauri::command]
-async fn set_hotkey(
-    new_hotkey: String,
+async fn set_always_on_top<R: Runtime>(
+    enabled: bool,
     state: State<'_, AppState>,
-    app_handle: tauri::AppHandle,
+    app_handle: tauri::AppHandle<R>,
 ) -> Result<()> {
-    if let Ok(mut config) = state.config.lock() {
-        config.hotkey = new_hotkey;
-        
-        let app_dir = app_handle.path().app_config_dir()
-            .map_err(|_| PromptToolError::Config("Не удалось получить директорию конфигурации".to_string()))?;
-        
-        std::fs::create_dir_all(&app_dir)
-            .map_err(PromptToolError::Io)?;
-        
-        let config_path = app_dir.join("config.json");
-        let config_str = serde_json::to_string_pretty(&*config)
-            .map_err(|_| PromptToolError::Config("Ошибка сериализации конфигурации".to_string()))?;
-        
-        std::fs::write(config_path, config_str)
-            .map_err(PromptToolError::Io)?;
+    {
+        let mut config = state.config.write()?;
+        config.always_on_top = enabled;
+        save_config(&app_handle, &config)?;
     }
 
+    apply_always_on_top(&app_handle);
     Ok(())
 }
 
+/// Команда для изменения уровней журнала без перезапуска
 #[tauri::command]
-async fn open_prompt_file_dialog(app_handle: tauri::AppHandle) -> Result<String> {
-    let file_path = app_handle.dialog()
-        .file()
-        .set_title("Выберите файл с промптами")
-        .add_filter("TOML", &["toml"])
-        .blocking_pick_file()
-        .ok_or_else(|| PromptToolError::Config("Файл не выбран".to_string()))?;
+async fn set_log_levels<R: Runtime>(
+    levels: LogLevels,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle<R>,
+) -> Result<()> {
+    levels.validate()?;
+    if let Some(logging) = app_handle.try_state::<Logging>() {
+        logging.set_levels(&levels)?;
+    }
 
-    Ok(file_path.into_path().unwrap().to_string_lossy().into_owned())
+    let mut config = state.config.write()?;
+    config.log_levels = levels;
+    save_config(&app_handle, &config)
 }
 
-/// Команда для получения текущей конфигурации
+/// Команда для получения последних строк журнала, чтобы приложить их к сообщению об ошибке
 #[tauri::command]
-async fn get_config(state: State<'_, AppState>) -> Result<AppConfig> {
-    state.config
-        .lock()
-        .map(|config| config.clone())
-        .map_err(|_| PromptToolError::Config("Ошибка получения конфигурации".to_string()))
+async fn get_recent_logs(app_handle: tauri::AppHandle, lines: Option<usize>) -> Result<Vec<String>> {
+    let log_dir = match app_handle.try_state::<Logging>() {
+        Some(logging) => logging.log_dir().to_path_buf(),
+        None => resolve_log_dir(&app_handle)?,
+    };
+    logging::recent_logs(&log_dir, lines.unwrap_or(DEFAULT_RECENT_LINES))
 }
 
 /// Команда для сворачивания окна приложения
 #[tauri::command]
 async fn minimize_window(window: tauri::Window) {
     if let Err(e) = window.minimize() {
-        eprintln!("Ошибка при сворачивании окна: {}", e);
+        error!("Ошибка при сворачивании окна: {}", e);
+    }
+}
+
+/// Время выполнения одного этапа запуска
+#[derive(Debug, Clone, Serialize)]
+struct StartupPhase {
+    // Название этапа
+    name: String,
+    // Длительность этапа в миллисекундах
+    duration_ms: u64,
+    // Выполнялся ли этап в фоне, после показа окна
+    background: bool,
+    // Текст ошибки, если этап завершился неудачно
+    error: Option<String>,
+}
+
+/// Отчет о запуске приложения для поиска регрессий холодного старта
+#[derive(Debug, Clone, Default, Serialize)]
+struct StartupReport {
+    // Этапы в порядке выполнения
+    phases: Vec<StartupPhase>,
+    // Время от старта процесса до готовности окна, в миллисекундах
+    critical_path_ms: u64,
+    // Время от старта процесса до завершения фоновой инициализации, в миллисекундах
+    total_ms: Option<u64>,
+}
+
+/// Выполняет этап запуска, замеряя его время и записывая результат в отчет
+fn run_startup_phase<T>(
+    state: &AppState,
+    name: &str,
+    background: bool,
+    phase: impl FnOnce() -> Result<T>,
+) -> Option<T> {
+    let started = Instant::now();
+    let result = phase();
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let error = result.as_ref().err().map(|e| e.to_string());
+    if let Some(error) = &error {
+        error!("Ошибка на этапе запуска {}: {}", name, error);
+    }
+
+    match state.startup_report.write() {
+        Ok(mut report) => report.phases.push(StartupPhase {
+            name: name.to_string(),
+            duration_ms,
+            background,
+            error,
+        }),
+        Err(e) => error!("Не удалось записать этап запуска {} в отчет: {}", name, e),
+    }
+
+    result.ok()
+}
+
+/// Команда для получения отчета о времени запуска
+#[tauri::command]
+async fn startup_report(state: State<'_, AppState>) -> Result<StartupReport> {
+    state.startup_report
+        .read()
+        .map(|report| report.clone())
+}
+
+/// Определяет директории состояния приложения
+/// По умолчанию используются пользовательские директории ОС, `--config-dir` переопределяет их
+fn resolve_app_paths(app_handle: &tauri::AppHandle) -> Result<AppPaths> {
+    if let Some(config_dir) = parse_config_dir_arg(std::env::args().skip(1)) {
+        return Ok(AppPaths::from_override(config_dir));
+    }
+
+    let config_dir = app_handle.path().app_config_dir()
+        .map_err(|_| PromptToolError::Config("Не удалось получить директорию конфигурации".to_string()))?;
+    let data_dir = app_handle.path().app_data_dir()
+        .map_err(|_| PromptToolError::Config("Не удалось получить директорию данных".to_string()))?;
+
+    Ok(AppPaths { config_dir, data_dir })
+}
+
+/// Директория журналов: директория журналов ОС, а при `--config-dir` - поддиректория `logs` в ней
+fn resolve_log_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf> {
+    if let Some(config_dir) = parse_config_dir_arg(std::env::args().skip(1)) {
+        return Ok(config_dir.join("logs"));
+    }
+
+    app_handle.path().app_log_dir()
+        .map_err(|_| PromptToolError::Config("Не удалось получить директорию журналов".to_string()))
+}
+
+/// Запоминает имя каждой вызванной команды, чтобы перечислить последние команды в отчете о падении
+fn with_command_trail<R: Runtime>(
+    handler: impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        crash::record_command(invoke.message.command());
+        handler(invoke)
+    }
+}
+
+/// Если прошлый запуск завершился паникой, предлагает открыть отчет о падении
+fn offer_crash_report(app_handle: &tauri::AppHandle) {
+    let crash_dir = app_handle.state::<AppPaths>().data_dir.join(CRASH_DIR_NAME);
+    let Some(report) = crash::take_pending_report(&crash_dir) else {
+        return;
+    };
+
+    let handle = app_handle.clone();
+    app_handle.dialog()
+        .message(format!(
+            "Прошлый запуск PromptTool завершился с ошибкой. Отчет сохранен в {}; его можно приложить к сообщению об ошибке.",
+            report.display()
+        ))
+        .title("Приложение аварийно завершилось")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom("Открыть отчет".to_string(), "Закрыть".to_string()))
+        .show(move |open| {
+            if open {
+                if let Err(e) = handle.opener().open_path(report.to_string_lossy(), None::<&str>) {
+                    warn!("Не удалось открыть отчет о падении: {}", e);
+                }
+            }
+        });
+}
+
+/// Подключает журнал до загрузки конфигурации; уровни из конфигурации применяются при ее загрузке
+/// Без журнала приложение продолжает работать, сообщения остаются только в stderr
+fn initialize_logging(app_handle: &tauri::AppHandle) {
+    let logging = resolve_log_dir(app_handle)
+        .and_then(|log_dir| Logging::init(&log_dir, &LogLevels::default()));
+
+    match logging {
+        Ok(logging) => {
+            info!("Журнал: {}", logging.log_dir().display());
+            app_handle.manage(logging);
+        }
+        Err(e) => eprintln!("Не удалось подключить журнал: {}", e),
     }
 }
 
 /// Инициализация приложения
+/// На критическом пути только загрузка конфигурации, остальное выполняется в фоне
 fn initialize_app(app_handle: &tauri::AppHandle) -> Result<()> {
-    // Создаем директорию prompts если её нет
-    let prompt_dir = PathBuf::from("prompts");
-    if !prompt_dir.exists() {
-        std::fs::create_dir_all(&prompt_dir)
-            .map_err(PromptToolError::Io)?;
+    initialize_logging(app_handle);
+    let paths = resolve_app_paths(app_handle)?;
+    crash::install_panic_hook(paths.data_dir.join(CRASH_DIR_NAME));
+    app_handle.manage(paths);
+
+    let state = app_handle.state::<AppState>();
+    let registry = ProfileRegistry::load(&profiles_path(app_handle));
+    *state.profile.write()? = registry.active;
+
+    run_startup_phase(&state, "config", false, || load_config(app_handle))
+        .ok_or_else(|| PromptToolError::Config("Не удалось загрузить конфигурацию".to_string()))?;
+
+    state.startup_report.write()?.critical_path_ms = state.started_at.elapsed().as_millis() as u64;
+
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || initialize_background(&app_handle));
+
+    Ok(())
+}
+
+/// Показывает, скрывает или активирует главное окно по команде извне
+fn activate_window(app_handle: &tauri::AppHandle, command: ActivationCommand) {
+    let launcher_mode = app_handle.state::<AppState>().config
+        .read()
+        .map(|config| config.launcher_mode)
+        .unwrap_or(false);
+    if launcher_mode {
+        if let Err(e) = activate_launcher(app_handle, command) {
+            error!("Ошибка при активации окна быстрого запуска: {}", e);
+        }
+        return;
     }
 
-    // Создаем default.toml если его нет
-    let default_file = prompt_dir.join("default.toml");
-    if !default_file.exists() {
-        let default_content = r#"prompts = [
-    { name = "Example Prompt", content = "This is an example prompt", parameters = ["param1"] }
-]"#;
-        std::fs::write(&default_file, default_content)
-            .map_err(PromptToolError::Io)?;
+    activate_main_window(app_handle, command);
+}
+
+/// Показывает, скрывает или активирует окно управления библиотекой
+fn activate_main_window(app_handle: &tauri::AppHandle, command: ActivationCommand) {
+    let Some(window) = app_handle.get_webview_window("main") else {
+        return;
+    };
+
+    let active = window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false);
+
+    let result = if command == ActivationCommand::Toggle && active {
+        window.hide()
+    } else {
+        // Запоминаем окно, в которое потом вставлять текст
+        if !active {
+            match app_handle.state::<AppState>().previous_window.write() {
+                Ok(mut previous) => *previous = platform::foreground_window(),
+                Err(e) => error!("Не удалось запомнить активное окно: {}", e),
+            }
+        }
+        window.show()
+            .and_then(|_| window.unminimize())
+            .and_then(|_| window.set_focus())
+    };
+
+    if let Err(e) = result {
+        error!("Ошибка при активации окна: {}", e);
     }
+}
 
-    // Создаем конфигурационный файл если его нет
-    let app_dir = app_handle.path().app_config_dir()
-        .map_err(|_| PromptToolError::Config("Не удалось получить директорию конфигурации".to_string()))?;
+/// Обрабатывает повторный запуск приложения: вместо второго процесса, который боролся бы
+/// за блокировку индекса, активируется окно уже запущенного.
+/// Путь к файлу из аргументов передается фронтенду событием `import-requested`
+fn handle_second_instance(app_handle: &tauri::AppHandle, args: Vec<String>, cwd: String) {
+    activate_main_window(app_handle, ActivationCommand::Show);
+
+    if let Some(path) = activation::import_path_arg(args.iter().skip(1), std::path::Path::new(&cwd)) {
+        let _ = app_handle.emit("import-requested", path.to_string_lossy().into_owned());
+    }
+}
+
+/// Запускает прием команд `prompt-tool-cli toggle/show` через локальный сокет
+/// Нужен там, где глобальная горячая клавиша недоступна (например, часть композиторов Wayland)
+#[cfg(unix)]
+fn start_activation_listener(app_handle: &tauri::AppHandle) {
+    let handle = app_handle.clone();
+    let path = prompt_tool_lib::activation::socket_path();
+
+    if let Err(e) = prompt_tool_lib::activation::listen(&path, move |command| activate_window(&handle, command)) {
+        error!("Резервная активация окна недоступна: {}", e);
+    }
+}
+
+#[cfg(not(unix))]
+fn start_activation_listener(_app_handle: &tauri::AppHandle) {}
+
+/// Обрабатывает текст, присланный через системный сервис:
+/// сохраняет его новым промптом или запоминает как значение {selection}
+fn handle_selection(app_handle: &tauri::AppHandle, text: String) -> Result<()> {
+    let state = app_handle.state::<AppState>();
+    let (action, file_path, git) = state.config
+        .read()
+        .map(|config| (config.selection_action, config.prompt_file_path.clone(), config.git.clone()))?;
+
+    match action {
+        SelectionAction::NewPrompt => {
+            ensure_writable(&state, &file_path)?;
+            let prompt = platform::prompt_from_selection(&text);
+            {
+                let mut prompts = state.prompts.write()?;
+                validation::validate_prompt(&prompt, &prompts, None)?;
+                prompts.prompts.push(prompt.clone());
+                persist_prompts(app_handle, &file_path, &prompts, &git)?;
+            }
+            sync_index(&state, app_handle)?;
+            let _ = app_handle.emit("prompt-captured", prompt);
+        }
+        SelectionAction::Variable => {
+            *state.selection.write()? = Some(text.clone());
+            let _ = app_handle.emit("selection-received", text);
+        }
+    }
+
+    activate_window(app_handle, ActivationCommand::Show);
+    Ok(())
+}
+
+/// Подключает системный сервис передачи выделенного текста, если он есть в ОС
+fn register_selection_service(app_handle: &tauri::AppHandle) {
+    let handle = app_handle.clone();
+    platform::register_selection_service(move |text| {
+        // Обработка пишет на диск, поэтому не задерживаем вызвавший поток системы
+        let handle = handle.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = handle_selection(&handle, text) {
+                error!("Ошибка обработки выделенного текста: {}", e);
+            }
+        });
+    });
+}
+
+/// Команда для выбора действия с текстом, присланным через системный сервис
+#[tauri::command]
+async fn set_selection_action(
+    action: SelectionAction,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let mut config = state.config.write()?;
+
+    config.selection_action = action;
+    save_config(&app_handle, &config)
+}
+
+/// Создает конфигурационный файл, если его нет, и загружает конфигурацию в состояние
+fn load_config<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> Result<()> {
+    let app_dir = app_paths(app_handle).config_dir.clone();
     
     if !app_dir.exists() {
         std::fs::create_dir_all(&app_dir)
@@ -236,35 +4738,412 @@ fn initialize_app(app_handle: &tauri::AppHandle) -> Result<()> {
         let default_config = AppConfig::default();
         let config_str = serde_json::to_string_pretty(&default_config)
             .map_err(|_| PromptToolError::Config("Ошибка сериализации конфигурации".to_string()))?;
-        std::fs::write(config_path, config_str)
+        std::fs::write(&config_path, config_str)
             .map_err(PromptToolError::Io)?;
     }
 
+    // Загружаем сохраненную конфигурацию; поврежденный файл не мешает запуску
+    let state = app_handle.state::<AppState>();
+    let mut config = read_config(app_handle, &config_path)?;
+    resolve_default_prompt_file(app_handle, &mut config)?;
+
+    state.search_cache.write()?.set_limits(config.search_cache_limits);
+
+    messages::set_language(&config.settings.language);
+
+    if let Some(logging) = app_handle.try_state::<Logging>() {
+        if let Err(e) = logging.set_levels(&config.log_levels) {
+            warn!("Не удалось применить уровни журнала: {}", e);
+        }
+    }
+
+    *state.config.write()? = config;
+
+    Ok(())
+}
+
+/// Подставляет абсолютный путь к файлу промптов по умолчанию в директории данных профиля
+/// Путь старых версий, относительный рабочей директории, переносится при обновлении конфигурации
+fn resolve_default_prompt_file<R: Runtime>(app_handle: &tauri::AppHandle<R>, config: &mut AppConfig) -> Result<()> {
+    if !config.prompt_file_path.is_empty() {
+        return Ok(());
+    }
+
+    config.prompt_file_path = app_paths(app_handle).default_prompt_file().to_string_lossy().into_owned();
+    save_config(app_handle, config)
+}
+
+/// Читает config.json, обновляя конфигурацию старых версий
+/// Поля, которые не удалось разобрать, заменяются значениями по умолчанию, остальные настройки сохраняются.
+/// Если файл пришлось изменить, прежнее содержимое остается в резервной копии рядом с ним
+fn read_config<R: Runtime>(app_handle: &tauri::AppHandle<R>, config_path: &std::path::Path) -> Result<AppConfig> {
+    let contents = std::fs::read_to_string(config_path)
+        .map_err(PromptToolError::Io)?;
+    let Ok(serde_json::Value::Object(mut fields)) = serde_json::from_str(&contents) else {
+        // Следующее сохранение настроек перезапишет файл, поэтому прежнее содержимое копируется
+        let backup = legacy::backup_path(config_path, chrono::Utc::now());
+        std::fs::copy(config_path, &backup)
+            .map_err(PromptToolError::Io)?;
+        warn!(
+            "Файл конфигурации {} поврежден, используются настройки по умолчанию; копия сохранена в {}",
+            config_path.display(),
+            backup.display()
+        );
+        return Ok(AppConfig::default());
+    };
+
+    let default_file = app_paths(app_handle).default_prompt_file();
+    let context = MigrationContext {
+        default_prompt_file: &default_file,
+        legacy_prompt_file: std::path::Path::new(LEGACY_PROMPT_FILE),
+    };
+    let original = fields.clone();
+    let applied = config_migrations::migrate_config(&mut fields, &context)
+        .unwrap_or_else(|e| {
+            warn!("Конфигурация не обновлена: {}", e);
+            Vec::new()
+        });
+    for step in &applied {
+        info!("Конфигурация обновлена: {}", step);
+    }
+
+    let (config, dropped): (AppConfig, _) = config_migrations::deserialize_lenient(fields.clone())?;
+    if !dropped.is_empty() {
+        warn!("Неверные значения настроек заменены значениями по умолчанию: {}", dropped.join(", "));
+    }
+
+    if fields != original || !dropped.is_empty() {
+        let backup = legacy::backup_path(config_path, chrono::Utc::now());
+        std::fs::copy(config_path, &backup)
+            .map_err(PromptToolError::Io)?;
+        save_config(app_handle, &config)?;
+    }
+    Ok(config)
+}
+
+/// Создает файл промптов по умолчанию с примером, если конфигурация указывает на него, а файла еще нет
+fn ensure_starter_content<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> Result<()> {
+    let default_file = app_paths(app_handle).default_prompt_file();
+    let configured = app_handle.state::<AppState>().config
+        .read()
+        .map(|config| config.prompt_file_path == default_file.to_string_lossy())?;
+
+    if configured && !default_file.exists() {
+        if let Some(prompt_dir) = default_file.parent() {
+            std::fs::create_dir_all(prompt_dir)
+                .map_err(PromptToolError::Io)?;
+        }
+
+        let default_content = r#"prompts = [
+    { name = "Example Prompt", content = "This is an example prompt", parameters = ["param1"] }
+]"#;
+        std::fs::write(&default_file, default_content)
+            .map_err(PromptToolError::Io)?;
+    }
+
+    Ok(())
+}
+
+/// Загружает историю поиска, статистику, переменные сессии и выборы результатов активного профиля
+fn load_user_data<R: Runtime>(app_handle: &tauri::AppHandle<R>) -> Result<()> {
+    let state = app_handle.state::<AppState>();
+    let size = state.config.read()?.search_history_size;
+
+    let history = SearchHistory::load(&search_history_path(app_handle), size);
+    *state.search_history.write()? = history;
+
+    let usage = UsageStats::load(&usage_path(app_handle));
+    *state.usage.write()? = usage;
+
+    let session = SessionVariables::load(&session_path(app_handle));
+    *state.session.write()? = session;
+
+    let feedback = ClickFeedback::load(&feedback_path(app_handle));
+    *state.feedback.write()? = feedback;
     Ok(())
 }
 
+/// Читает файл промптов из конфигурации, при необходимости обновляя его формат
+/// Зашифрованный файл загружается командой unlock, до этого библиотека пуста и не записывается
+fn load_active_library(state: &AppState) -> Result<()> {
+    let (path, encrypted) = state.config
+        .read()
+        .map(|config| (config.prompt_file_path.clone(), config.prompt_file_encrypted))?;
+
+    // Пока файл не прочитан, пустая библиотека не должна попасть в него при первой правке
+    set_library(state, PromptList::new(), None)?;
+    if encrypted {
+        return Ok(());
+    }
+
+    migrate_prompt_file(state, &path)?;
+    let library = load_prompts(&path)?;
+    set_library(state, library, Some(&path))
+}
+
+/// Фоновая часть инициализации: стартовые файлы, загрузка промптов и открытие индекса
+/// По завершении отправляет во frontend событие `startup-complete`
+fn initialize_background(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<AppState>();
+
+    run_startup_phase(&state, "starter_content", true, || ensure_starter_content(app_handle));
+
+    run_startup_phase(&state, "load_history", true, || load_user_data(app_handle));
+
+    run_startup_phase(&state, "load_prompts", true, || load_active_library(&state));
+
+    let index_enabled = state.config
+        .read()
+        .map(|config| config.index_enabled)
+        .unwrap_or(true);
+
+    // Открываем (или создаем) поисковый индекс, если он не отключен в настройках
+    if index_enabled {
+        run_startup_phase(&state, "open_index", true, || {
+            let database = open_database(app_handle)?;
+            let prompt_count = state.prompts
+                .read()
+                .map(|prompts| prompts.prompts.len())
+                .unwrap_or_default();
+
+            // Языки стемминга поменялись с прошлого запуска или индекс не соответствует
+            // загруженным промптам - переиндексируем
+            let is_stale = database.needs_reindex()
+                || !database.num_docs().is_ok_and(|count| count == prompt_count as u64);
+
+            *state.database.write()? = Some(database);
+
+            if is_stale {
+                sync_index(&state, app_handle)?;
+            }
+            Ok(())
+        });
+    }
+
+    match state.startup_report.write() {
+        Ok(mut report) => report.total_ms = Some(state.started_at.elapsed().as_millis() as u64),
+        Err(e) => error!("Не удалось записать время запуска в отчет: {}", e),
+    }
+
+    let _ = app_handle.emit("startup-complete", ());
+}
+
 fn main() {
-    tauri::Builder::default()
+    let mut builder = tauri::Builder::default();
+    // Экземпляры с отдельной директорией конфигурации (--config-dir) независимы друг от друга
+    if parse_config_dir_arg(std::env::args().skip(1)).is_none() {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app_handle, args, cwd| {
+            handle_second_instance(app_handle, args, cwd);
+        }));
+    }
+
+    builder
         .setup(|app| {
-            initialize_app(&app.handle())?;
+            initialize_app(app.handle())?;
+            offer_crash_report(app.handle());
+            register_prompt_hotkeys(app.handle());
+            apply_text_expander(app.handle());
+            restore_window_state(app.handle());
+            apply_always_on_top(app.handle());
+            spawn_idle_monitor(app.handle().clone());
+            spawn_autosave_worker(app.handle().clone());
+            start_activation_listener(app.handle());
+            register_selection_service(app.handle());
             Ok(())
         })
-        .manage(AppState {
-            config: Mutex::new(AppConfig::default()),
-            prompts: Mutex::new(PromptList::new()),
+        .manage(AppState::new())
+        .on_window_event(|window, event| {
+            // Правки в памяти сохраняются, когда пользователь уходит из окна или закрывает его
+            // При потере фокуса запись идет в фоне, чтобы не задерживать главный поток;
+            // при закрытии окна - сразу, чтобы правки не потерялись при выходе
+            if matches!(event, tauri::WindowEvent::Focused(false) | tauri::WindowEvent::CloseRequested { .. }) {
+                let app_handle = window.app_handle().clone();
+                let flush = move || {
+                    if let Err(e) = flush_prompts(&app_handle.state::<AppState>(), &app_handle) {
+                        warn!("Не удалось сохранить промпты: {}", e);
+                    }
+                };
+                if matches!(event, tauri::WindowEvent::Focused(false)) {
+                    tauri::async_runtime::spawn_blocking(flush);
+                } else {
+                    flush();
+                }
+                if window.label() == "main" {
+                    if let Err(e) = save_window_state(window) {
+                        warn!("Не удалось сохранить положение окна: {}", e);
+                    }
+                }
+            }
+            // Пользователь ушел из окна сам - запомненное окно для вставки больше не актуально
+            if matches!(event, tauri::WindowEvent::Focused(false)) {
+                if let Err(e) = window.app_handle().state::<AppState>().previous_window.write().map(|mut previous| *previous = None) {
+                    error!("Не удалось сбросить запомненное окно: {}", e);
+                }
+                // Окно быстрого запуска закрывается, как только теряет фокус
+                if window.label() == LAUNCHER_WINDOW {
+                    let _ = window.hide();
+                }
+            }
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(with_command_trail(tauri::generate_handler![
             get_prompts,
+            get_prompt_detail,
             set_prompt_file_path,
+            recover_prompt_file,
+            confirm_prompt_file_recovery,
             set_hotkey,
+            list_profiles,
+            switch_profile,
+            set_prompt_hotkey,
+            set_text_expander,
             open_prompt_file_dialog,
             get_config,
             search_prompts,
+            suggest,
             get_categories,
             get_tags,
-            minimize_window
-        ])
+            reindex_prompts,
+            rebuild_index,
+            set_index_enabled,
+            set_synonyms,
+            set_stemming_languages,
+            set_field_boosts,
+            startup_report,
+            get_recent_logs,
+            set_log_levels,
+            get_search_history,
+            clear_search_history,
+            suggest_search_history,
+            get_memory_stats,
+            set_search_cache_limits,
+            set_reader_cache_blocks,
+            export_json_schemas,
+            get_library_schema,
+            validate_file,
+            export_stats_report,
+            import_prompts,
+            import_prompt_directory,
+            import_awesome_prompts,
+            detect_importable_sources,
+            import_from_source,
+            render_prompt,
+            list_template_functions,
+            execute_prompt,
+            cancel_execution,
+            list_runs,
+            run_chain,
+            delete_run,
+            set_llm_config,
+            list_models,
+            count_tokens,
+            estimate_cost,
+            set_model_price,
+            set_selection_action,
+            set_notification_enabled,
+            get_do_not_disturb,
+            set_do_not_disturb,
+            copy_to_clipboard,
+            send_prompt,
+            choose_output_file_dialog,
+            set_prompt_output,
+            sync_parameters,
+            rate_prompt,
+            set_prompt_aliases,
+            archive_prompt,
+            unarchive_prompt,
+            lint_prompts,
+            find_duplicates,
+            diff_prompts,
+            list_prompt_revisions,
+            diff_versions,
+            merge_prompt,
+            resolve_merge,
+            set_git_settings,
+            git_status,
+            git_history,
+            git_push,
+            git_pull,
+            get_query_macros,
+            set_query_macro,
+            set_gist_settings,
+            publish_gist,
+            import_gist,
+            record_result_click,
+            clear_click_feedback,
+            split_library_by_category,
+            export_obsidian_vault,
+            migrate_library,
+            export_vscode_snippets,
+            export_launcher_snippets,
+            export_espanso,
+            preview_chatgpt_import,
+            add_prompts,
+            repair_ids,
+            unlock,
+            lock,
+            set_prompt_file_encryption,
+            edit_prompt,
+            is_dirty,
+            save_pending_edits,
+            set_autosave_delay,
+            set_prompt_file_read_only,
+            is_prompt_file_read_only,
+            list_backups,
+            restore_backup,
+            set_backup_count,
+            set_secret,
+            delete_secret,
+            set_webdav_settings,
+            webdav_sync,
+            webdav_push,
+            webdav_pull,
+            export_search_config,
+            import_search_config,
+            get_session_vars,
+            set_session_var,
+            remove_session_var,
+            clear_session_vars,
+            debug_render,
+            list_hooks,
+            set_prompt_hooks,
+            set_paste_options,
+            minimize_window,
+            paste_to_previous_window,
+            set_launcher_mode,
+            toggle_launcher,
+            hide_launcher,
+            launcher_search,
+            launcher_select,
+            launcher_copy_and_hide,
+            set_always_on_top,
+            update_settings,
+            get_error_messages
+        ]))
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app_handle, shortcut, event| {
+                    if event.state() != ShortcutState::Pressed {
+                        return;
+                    }
+                    // Подстановка может запускать хуки, поэтому не задерживаем поток событий
+                    let handle = app_handle.clone();
+                    let shortcut = *shortcut;
+                    std::thread::spawn(move || {
+                        if let Err(e) = run_prompt_hotkey(&handle, &shortcut) {
+                            error!("Ошибка вызова промпта по горячей клавише: {}", e);
+                        }
+                    });
+                })
+                .build(),
+        )
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+// Сквозные проверки команд на тестовом рантайме Tauri, без окна и системных плагинов
+#[cfg(test)]
+#[path = "../tests/commands/mod.rs"]
+mod command_tests;
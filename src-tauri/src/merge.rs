@@ -0,0 +1,194 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::diff::matched_lines;
+use crate::prompt::Prompt;
+
+/// Начало конфликта: далее идут строки версии из приложения
+pub const CONFLICT_OURS: &str = "<<<<<<< ours";
+
+/// Разделитель версий в конфликте
+pub const CONFLICT_SEPARATOR: &str = "=======";
+
+/// Конец конфликта: перед ним строки версии из файла
+pub const CONFLICT_THEIRS: &str = ">>>>>>> theirs";
+
+/// Результат слияния текста
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TextMerge {
+    /// Объединенный текст; конфликтующие фрагменты обрамлены маркерами
+    pub text: String,
+
+    /// Количество конфликтующих фрагментов
+    pub conflicts: usize,
+}
+
+/// Результат трехстороннего слияния промпта
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptMerge {
+    /// Объединенный промпт; текст может содержать маркеры конфликтов
+    pub prompt: Prompt,
+
+    /// Количество конфликтующих фрагментов текста
+    pub conflicts: usize,
+
+    /// Поля, измененные обеими сторонами по-разному; в них оставлено значение из приложения
+    pub conflicting_fields: Vec<&'static str>,
+}
+
+impl PromptMerge {
+    /// Слияние прошло без конфликтов и результат можно сохранять как есть
+    pub fn is_clean(&self) -> bool {
+        self.conflicts == 0 && self.conflicting_fields.is_empty()
+    }
+}
+
+/// Есть ли в тексте неразрешенные маркеры конфликтов
+pub fn has_conflict_markers(text: &str) -> bool {
+    text.lines().any(|line| {
+        line.starts_with(CONFLICT_OURS) || line == CONFLICT_SEPARATOR || line.starts_with(CONFLICT_THEIRS)
+    })
+}
+
+/// Разрешает фрагмент, измененный хотя бы одной стороной
+/// Возвращает `None`, если стороны изменили фрагмент по-разному
+fn resolve_chunk<'a>(base: &[&'a str], ours: &[&'a str], theirs: &[&'a str]) -> Option<Vec<&'a str>> {
+    if ours == theirs || theirs == base {
+        Some(ours.to_vec())
+    } else if ours == base {
+        Some(theirs.to_vec())
+    } else {
+        None
+    }
+}
+
+/// Трехстороннее построчное слияние текста (diff3)
+/// Строки, измененные только одной стороной, принимаются автоматически;
+/// фрагменты, измененные обеими сторонами по-разному, обрамляются маркерами конфликта
+pub fn merge_text(base: &str, ours: &str, theirs: &str) -> TextMerge {
+    let base_lines: Vec<&str> = base.lines().collect();
+    let our_lines: Vec<&str> = ours.lines().collect();
+    let their_lines: Vec<&str> = theirs.lines().collect();
+
+    let our_matches = matched_lines(&base_lines, &our_lines);
+    let their_matches = matched_lines(&base_lines, &their_lines);
+
+    let mut merged: Vec<&str> = Vec::new();
+    let mut conflicts = 0;
+    let (mut b, mut o, mut t) = (0, 0, 0);
+
+    loop {
+        // Строки, не измененные ни одной из сторон, переносятся как есть
+        let mut stable = 0;
+        while b + stable < base_lines.len()
+            && our_matches[b + stable] == Some(o + stable)
+            && their_matches[b + stable] == Some(t + stable)
+        {
+            stable += 1;
+        }
+        if stable > 0 {
+            merged.extend_from_slice(&base_lines[b..b + stable]);
+            b += stable;
+            o += stable;
+            t += stable;
+            continue;
+        }
+
+        // Ближайшая строка базы, сохранившаяся в обеих версиях, завершает измененный фрагмент
+        let next = (b..base_lines.len()).find_map(|index| match (our_matches[index], their_matches[index]) {
+            (Some(our_index), Some(their_index)) => Some((index, our_index, their_index)),
+            _ => None,
+        });
+        let (next_b, next_o, next_t) = next.unwrap_or((base_lines.len(), our_lines.len(), their_lines.len()));
+
+        let base_chunk = &base_lines[b..next_b];
+        let our_chunk = &our_lines[o..next_o];
+        let their_chunk = &their_lines[t..next_t];
+        match resolve_chunk(base_chunk, our_chunk, their_chunk) {
+            Some(lines) => merged.extend(lines),
+            None => {
+                conflicts += 1;
+                merged.push(CONFLICT_OURS);
+                merged.extend_from_slice(our_chunk);
+                merged.push(CONFLICT_SEPARATOR);
+                merged.extend_from_slice(their_chunk);
+                merged.push(CONFLICT_THEIRS);
+            }
+        }
+
+        if next.is_none() {
+            break;
+        }
+        (b, o, t) = (next_b, next_o, next_t);
+    }
+
+    let mut text = merged.join("\n");
+    if !text.is_empty() && (ours.ends_with('\n') || theirs.ends_with('\n')) {
+        text.push('\n');
+    }
+
+    TextMerge { text, conflicts }
+}
+
+/// Слияние множеств: элемент остается, если он есть в обеих версиях или добавлен одной из сторон;
+/// удаление любой стороной убирает элемент
+fn merge_sets(base: &HashSet<String>, ours: &HashSet<String>, theirs: &HashSet<String>) -> HashSet<String> {
+    ours.iter()
+        .chain(theirs)
+        .filter(|item| (ours.contains(*item) && theirs.contains(*item)) || !base.contains(*item))
+        .cloned()
+        .collect()
+}
+
+/// Слияние поля целиком: берется измененное значение; если изменили обе стороны по-разному,
+/// остается значение из приложения, а поле отмечается как конфликтующее
+fn merge_field<T: PartialEq + Clone>(
+    field: &'static str,
+    base: &T,
+    ours: &T,
+    theirs: &T,
+    conflicting: &mut Vec<&'static str>,
+) -> T {
+    if ours == base {
+        theirs.clone()
+    } else {
+        if theirs != base && theirs != ours {
+            conflicting.push(field);
+        }
+        ours.clone()
+    }
+}
+
+/// Трехстороннее слияние промпта: `base` - версия, с которой начиналось редактирование,
+/// `ours` - правки в приложении, `theirs` - версия, измененная во внешнем файле
+pub fn merge_prompt(base: &Prompt, ours: &Prompt, theirs: &Prompt) -> PromptMerge {
+    let content = merge_text(&base.content, &ours.content, &theirs.content);
+    let mut conflicting_fields = Vec::new();
+
+    // Все поля, кроме текста и множеств, сливаются целиком; новое поле промпта нужно добавить и сюда
+    let mut prompt = ours.clone();
+    prompt.name = merge_field("name", &base.name, &ours.name, &theirs.name, &mut conflicting_fields);
+    prompt.content = content.text;
+    prompt.parameters = merge_field("parameters", &base.parameters, &ours.parameters, &theirs.parameters, &mut conflicting_fields);
+    prompt.constraints = merge_field("constraints", &base.constraints, &ours.constraints, &theirs.constraints, &mut conflicting_fields);
+    prompt.output = merge_field("output", &base.output, &ours.output, &theirs.output, &mut conflicting_fields);
+    prompt.hooks = merge_field("hooks", &base.hooks, &ours.hooks, &theirs.hooks, &mut conflicting_fields);
+    prompt.categories = merge_sets(&base.categories, &ours.categories, &theirs.categories);
+    prompt.tags = merge_sets(&base.tags, &ours.tags, &theirs.tags);
+    prompt.aliases = merge_field("aliases", &base.aliases, &ours.aliases, &theirs.aliases, &mut conflicting_fields);
+    prompt.rating = merge_field("rating", &base.rating, &ours.rating, &theirs.rating, &mut conflicting_fields);
+    prompt.archived = merge_field("archived", &base.archived, &ours.archived, &theirs.archived, &mut conflicting_fields);
+    prompt.author = merge_field("author", &base.author, &ours.author, &theirs.author, &mut conflicting_fields);
+    prompt.source_url = merge_field("source_url", &base.source_url, &ours.source_url, &theirs.source_url, &mut conflicting_fields);
+    prompt.license = merge_field("license", &base.license, &ours.license, &theirs.license, &mut conflicting_fields);
+    prompt.models = merge_field("models", &base.models, &ours.models, &theirs.models, &mut conflicting_fields);
+    prompt.notes = merge_field("notes", &base.notes, &ours.notes, &theirs.notes, &mut conflicting_fields);
+    prompt.updated_at = ours.updated_at.max(theirs.updated_at);
+
+    PromptMerge {
+        prompt,
+        conflicts: content.conflicts,
+        conflicting_fields,
+    }
+}
@@ -0,0 +1,81 @@
+//! Каталог сообщений об ошибках на языках интерфейса.
+//! Ошибка передается фронтенду кодом, сообщением на языке из настроек и подробностями;
+//! по коду фронтенд может подобрать свой текст.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Язык сообщений
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Ru,
+    En,
+}
+
+impl Language {
+    /// Язык по коду из настроек; неизвестный код - русский
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            "en" => Language::En,
+            _ => Language::Ru,
+        }
+    }
+}
+
+/// Текущий язык сообщений процесса: ошибка сериализуется без доступа к конфигурации
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+/// Задает язык сообщений по коду из настроек
+pub fn set_language(code: &str) {
+    let value = match Language::from_code(code) {
+        Language::Ru => 0,
+        Language::En => 1,
+    };
+    LANGUAGE.store(value, Ordering::Relaxed);
+}
+
+/// Текущий язык сообщений
+pub fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        1 => Language::En,
+        _ => Language::Ru,
+    }
+}
+
+/// Код ошибки, русский и английский тексты
+const ERROR_MESSAGES: &[(&str, &str, &str)] = &[
+    ("io", "Не удалось прочитать или записать файл", "Failed to read or write a file"),
+    ("toml_parse", "Файл промптов содержит ошибку TOML", "The prompt file is not valid TOML"),
+    ("parse", "Не удалось разобрать файл промптов", "Failed to parse the prompt file"),
+    ("config", "Ошибка конфигурации", "Configuration error"),
+    ("validation", "Неверные данные", "Invalid input"),
+    ("search", "Ошибка поиска", "Search failed"),
+    ("execution", "Ошибка выполнения", "Execution failed"),
+    ("git", "Ошибка git", "Git operation failed"),
+    ("locked", "Ресурс занят другим процессом", "The resource is locked by another process"),
+    ("read_only", "Файл промптов открыт только для чтения", "The prompt file is read-only"),
+    ("state", "Данные приложения недоступны, перезапустите приложение", "Application data is unavailable, please restart the app"),
+    ("conflict", "Файл промптов изменен другой программой", "The prompt file was changed by another program"),
+];
+
+/// Текст ошибки по коду; для неизвестного кода - общий текст
+pub fn error_message(code: &str, language: Language) -> &'static str {
+    let (ru, en) = ERROR_MESSAGES
+        .iter()
+        .find(|(known, _, _)| *known == code)
+        .map(|(_, ru, en)| (*ru, *en))
+        .unwrap_or(("Непредвиденная ошибка", "Unexpected error"));
+
+    match language {
+        Language::Ru => ru,
+        Language::En => en,
+    }
+}
+
+/// Все тексты ошибок на языке: код -> текст
+pub fn error_catalog(language: Language) -> BTreeMap<&'static str, &'static str> {
+    ERROR_MESSAGES
+        .iter()
+        .map(|(code, _, _)| (*code, error_message(code, language)))
+        .collect()
+}
@@ -0,0 +1,161 @@
+//! Версии формата файла промптов и их последовательное обновление.
+//!
+//! Версия хранится в ключе `schema_version` в начале файла; файл без него имеет версию 0.
+//! При чтении файл старой версии обновляется в памяти, на диск обновленный формат попадает
+//! при следующем сохранении или явно через `migrate_file`, который сначала делает резервную копию.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use toml::{Table, Value};
+
+use crate::encryption;
+use crate::error::{PromptToolError, Result};
+use crate::file_io::{parse_prompts, read_prompt_file, save_prompts};
+use crate::legacy::{backup_path, upgrade_table};
+
+/// Ключ версии формата в файле промптов
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// Версия формата, которую пишет текущая версия приложения
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// Шаг обновления формата до версии `version`
+struct Migration {
+    version: u32,
+    description: &'static str,
+    apply: fn(&mut Table, DateTime<Utc>) -> Result<()>,
+}
+
+/// Шаги обновления по возрастанию версий
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Недостающие идентификаторы, даты, категории, теги и параметры",
+        apply: fill_missing_fields,
+    },
+    Migration {
+        version: 2,
+        description: "Параметры-строки заменены описаниями параметров",
+        apply: structure_parameters,
+    },
+];
+
+fn fill_missing_fields(library: &mut Table, now: DateTime<Utc>) -> Result<()> {
+    upgrade_table(library, now).map(|_| ())
+}
+
+/// `parameters = ["lang"]` -> `parameters = [{ name = "lang", kind = "string", required = false }]`
+fn structure_parameters(library: &mut Table, _now: DateTime<Utc>) -> Result<()> {
+    let prompts = library
+        .get_mut("prompts")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_table_mut);
+
+    for prompt in prompts {
+        let Some(parameters) = prompt.get_mut("parameters").and_then(Value::as_array_mut) else {
+            continue;
+        };
+
+        for parameter in parameters.iter_mut() {
+            if let Value::String(name) = parameter {
+                let mut spec = Table::new();
+                spec.insert("name".to_string(), Value::String(std::mem::take(name)));
+                spec.insert("kind".to_string(), Value::String("string".to_string()));
+                spec.insert("required".to_string(), Value::Boolean(false));
+                *parameter = Value::Table(spec);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Версия формата библиотеки; без ключа версии - 0
+pub fn schema_version(library: &Table) -> Result<u32> {
+    match library.get(SCHEMA_VERSION_KEY) {
+        None => Ok(0),
+        Some(Value::Integer(version)) => u32::try_from(*version)
+            .map_err(|_| PromptToolError::Validation(format!("Недопустимая версия формата: {}", version))),
+        Some(other) => Err(PromptToolError::Validation(format!("Недопустимая версия формата: {}", other))),
+    }
+}
+
+/// Обновляет библиотеку до текущей версии формата; возвращает описания выполненных шагов
+/// Файл более новой версии, чем знает приложение, не читается, чтобы не потерять незнакомые поля при сохранении
+pub fn migrate_table(library: &mut Table, now: DateTime<Utc>) -> Result<Vec<&'static str>> {
+    let version = schema_version(library)?;
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(PromptToolError::Validation(format!(
+            "Файл промптов создан более новой версией приложения (формат {}, поддерживается до {})",
+            version, CURRENT_SCHEMA_VERSION
+        )));
+    }
+
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|migration| migration.version > version) {
+        (migration.apply)(library, now)?;
+        applied.push(migration.description);
+    }
+
+    library.insert(SCHEMA_VERSION_KEY.to_string(), Value::Integer(CURRENT_SCHEMA_VERSION as i64));
+    Ok(applied)
+}
+
+/// Итог обновления формата файла на диске
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SchemaMigration {
+    /// Обновленный файл
+    pub path: PathBuf,
+
+    /// Версия формата до обновления
+    pub from_version: u32,
+
+    /// Версия формата после обновления
+    pub to_version: u32,
+
+    /// Резервная копия файла до обновления
+    pub backup: PathBuf,
+
+    /// Описания выполненных шагов
+    pub applied: Vec<String>,
+}
+
+/// Обновляет формат файла промптов на диске, предварительно сохранив резервную копию
+/// Возвращает `None`, если файл уже в текущем формате. Зашифрованный файл обновляется
+/// в памяти при чтении и попадает на диск в новом формате при следующем сохранении
+pub fn migrate_file(path: &Path, now: DateTime<Utc>) -> Result<Option<SchemaMigration>> {
+    let contents = read_prompt_file(&path.to_string_lossy())?;
+    if contents.trim().is_empty() || encryption::is_encrypted(&contents) {
+        return Ok(None);
+    }
+
+    let mut library: Table = match toml::from_str(&contents) {
+        Ok(library) => library,
+        // Ошибку с указанием строки и записи сообщит загрузка файла
+        Err(_) => return Ok(None),
+    };
+    let from_version = schema_version(&library)?;
+    if from_version == CURRENT_SCHEMA_VERSION {
+        return Ok(None);
+    }
+
+    let upgraded = parse_prompts(&path.to_string_lossy(), &contents)?;
+    let applied = migrate_table(&mut library, now)?;
+
+    let backup = backup_path(path, now);
+    fs::copy(path, &backup)
+        .map_err(PromptToolError::Io)?;
+    save_prompts(&path.to_string_lossy(), &upgraded)?;
+
+    Ok(Some(SchemaMigration {
+        path: path.to_path_buf(),
+        from_version,
+        to_version: CURRENT_SCHEMA_VERSION,
+        backup,
+        applied: applied.into_iter().map(str::to_string).collect(),
+    }))
+}
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+/// События, о которых приложение сообщает системными уведомлениями
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// Импорт промптов завершен
+    ImportCompleted,
+    /// Запуск по расписанию завершен
+    ScheduledRunFinished,
+    /// При синхронизации обнаружен конфликт
+    SyncConflict,
+    /// Не удалось создать резервную копию
+    BackupFailed,
+    /// Промпт скопирован по горячей клавише, пока окно скрыто
+    PromptCopied,
+    /// Файл промптов перечитан после изменений на другой стороне синхронизации
+    FileReloaded,
+    /// Создана резервная копия файла промптов
+    BackupCreated,
+    /// Синхронизация завершилась ошибкой
+    SyncFailed,
+}
+
+/// Включение уведомлений по типам событий
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    #[serde(default = "enabled")]
+    pub import_completed: bool,
+
+    #[serde(default = "enabled")]
+    pub scheduled_run_finished: bool,
+
+    #[serde(default = "enabled")]
+    pub sync_conflict: bool,
+
+    #[serde(default = "enabled")]
+    pub backup_failed: bool,
+
+    #[serde(default = "enabled")]
+    pub prompt_copied: bool,
+
+    #[serde(default = "enabled")]
+    pub file_reloaded: bool,
+
+    // Резервная копия создается при каждом сохранении, поэтому по умолчанию уведомление выключено
+    #[serde(default)]
+    pub backup_created: bool,
+
+    #[serde(default = "enabled")]
+    pub sync_failed: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            import_completed: true,
+            scheduled_run_finished: true,
+            sync_conflict: true,
+            backup_failed: true,
+            prompt_copied: true,
+            file_reloaded: true,
+            backup_created: false,
+            sync_failed: true,
+        }
+    }
+}
+
+impl NotificationSettings {
+    /// Включены ли уведомления о событии указанного типа
+    pub fn is_enabled(&self, kind: NotificationKind) -> bool {
+        match kind {
+            NotificationKind::ImportCompleted => self.import_completed,
+            NotificationKind::ScheduledRunFinished => self.scheduled_run_finished,
+            NotificationKind::SyncConflict => self.sync_conflict,
+            NotificationKind::BackupFailed => self.backup_failed,
+            NotificationKind::PromptCopied => self.prompt_copied,
+            NotificationKind::FileReloaded => self.file_reloaded,
+            NotificationKind::BackupCreated => self.backup_created,
+            NotificationKind::SyncFailed => self.sync_failed,
+        }
+    }
+
+    /// Включает или выключает уведомления о событии указанного типа
+    pub fn set_enabled(&mut self, kind: NotificationKind, enabled: bool) {
+        let flag = match kind {
+            NotificationKind::ImportCompleted => &mut self.import_completed,
+            NotificationKind::ScheduledRunFinished => &mut self.scheduled_run_finished,
+            NotificationKind::SyncConflict => &mut self.sync_conflict,
+            NotificationKind::BackupFailed => &mut self.backup_failed,
+            NotificationKind::PromptCopied => &mut self.prompt_copied,
+            NotificationKind::FileReloaded => &mut self.file_reloaded,
+            NotificationKind::BackupCreated => &mut self.backup_created,
+            NotificationKind::SyncFailed => &mut self.sync_failed,
+        };
+        *flag = enabled;
+    }
+}
+
+/// Режим "Не беспокоить": пока он активен, уведомления и всплывающие окна не показываются
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DndSettings {
+    /// Режим включен вручную в приложении
+    #[serde(default)]
+    pub manual: bool,
+
+    /// Учитывать системный режим фокусирования / "Не беспокоить"
+    #[serde(default = "enabled")]
+    pub follow_system: bool,
+}
+
+impl Default for DndSettings {
+    fn default() -> Self {
+        Self {
+            manual: false,
+            follow_system: true,
+        }
+    }
+}
+
+impl DndSettings {
+    /// Нужно ли сейчас подавлять уведомления
+    /// `system_active` - состояние системного режима, `None`, если его не удалось определить
+    pub fn is_active(&self, system_active: Option<bool>) -> bool {
+        self.manual || (self.follow_system && system_active == Some(true))
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::error::{PromptToolError, Result};
+use crate::prompt::{Prompt, PromptList};
+
+/// Папка хранилища с заметками категорий
+pub const CATEGORIES_FOLDER: &str = "Категории";
+
+/// Итог экспорта хранилища
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VaultExport {
+    /// Сколько заметок промптов записано
+    pub prompts: usize,
+
+    /// Сколько заметок категорий записано
+    pub categories: usize,
+}
+
+/// Свойства заметки (YAML frontmatter), которые Obsidian показывает и индексирует
+#[derive(Serialize)]
+struct Frontmatter<'a> {
+    created: String,
+    updated: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    categories: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+}
+
+/// Имя заметки: символы, недопустимые в именах заметок Obsidian, заменяются на `_`
+fn note_name(name: &str) -> String {
+    let stem: String = name
+        .trim()
+        .chars()
+        .map(|c| if "\\/:*?\"<>|#^[]".contains(c) || c.is_control() { '_' } else { c })
+        .collect();
+
+    if stem.trim().is_empty() { "_".to_string() } else { stem }
+}
+
+/// Тег Obsidian: пробелы заменяются на `-`, прочие недопустимые символы на `_`
+/// Тег из одних цифр Obsidian не распознает, поэтому к нему добавляется `_`
+pub fn obsidian_tag(tag: &str) -> String {
+    let tag: String = tag
+        .trim()
+        .chars()
+        .map(|c| match c {
+            c if c.is_whitespace() => '-',
+            c if c.is_alphanumeric() || c == '-' || c == '_' || c == '/' => c,
+            _ => '_',
+        })
+        .collect();
+
+    if tag.chars().all(|c| c.is_ascii_digit()) { format!("_{}", tag) } else { tag }
+}
+
+/// Назначает уникальные (без учета регистра) имена заметок
+fn unique_names<'a>(names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut used = HashSet::new();
+    names
+        .map(|name| {
+            let stem = note_name(name);
+            let mut unique = stem.clone();
+            let mut counter = 2;
+            while !used.insert(unique.to_lowercase()) {
+                unique = format!("{} {}", stem, counter);
+                counter += 1;
+            }
+            unique
+        })
+        .collect()
+}
+
+/// Вики-ссылка на заметку с отображаемым исходным названием
+fn wiki_link(target: &str, title: &str) -> String {
+    format!("[[{}|{}]]", target, title.replace(['|', '[', ']'], " "))
+}
+
+/// Ограждение блока кода, длиннее любой последовательности обратных кавычек в тексте
+fn code_fence(content: &str) -> String {
+    let longest = content
+        .split(|c: char| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+/// Заметка промпта: свойства, ссылки на категории, теги, параметры и текст шаблона
+fn prompt_note(prompt: &Prompt, category_notes: &BTreeMap<&str, String>) -> Result<String> {
+    let mut categories: Vec<&str> = prompt.categories.iter().map(String::as_str).collect();
+    categories.sort();
+    let mut tags: Vec<String> = prompt.tags.iter().map(|tag| obsidian_tag(tag)).collect();
+    tags.sort();
+
+    let frontmatter = Frontmatter {
+        created: prompt.created_at.to_rfc3339(),
+        updated: prompt.updated_at.to_rfc3339(),
+        categories: categories.clone(),
+        tags: tags.clone(),
+    };
+    let yaml = serde_yaml::to_string(&frontmatter)
+        .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации свойств заметки: {}", e)))?;
+
+    let mut note = String::new();
+    let _ = writeln!(note, "---");
+    let _ = write!(note, "{}", yaml);
+    let _ = writeln!(note, "---");
+    let _ = writeln!(note);
+    let _ = writeln!(note, "# {}", prompt.name);
+    let _ = writeln!(note);
+
+    if !categories.is_empty() {
+        let links: Vec<String> = categories
+            .iter()
+            .map(|category| wiki_link(&format!("{}/{}", CATEGORIES_FOLDER, category_notes[category]), category))
+            .collect();
+        let _ = writeln!(note, "Категории: {}", links.join(", "));
+    }
+    if !tags.is_empty() {
+        let hashtags: Vec<String> = tags.iter().map(|tag| format!("#{}", tag)).collect();
+        let _ = writeln!(note, "Теги: {}", hashtags.join(" "));
+    }
+    if !categories.is_empty() || !tags.is_empty() {
+        let _ = writeln!(note);
+    }
+
+    if !prompt.parameters.is_empty() {
+        let _ = writeln!(note, "## Параметры");
+        let _ = writeln!(note);
+        for parameter in &prompt.parameters {
+            if parameter.description.is_empty() {
+                let _ = writeln!(note, "- `{}`", parameter.name);
+            } else {
+                let _ = writeln!(note, "- `{}` - {}", parameter.name, parameter.description);
+            }
+        }
+        let _ = writeln!(note);
+    }
+
+    // Текст шаблона в блоке кода, чтобы разметка и `#` внутри промпта не превращались в заголовки и теги
+    let fence = code_fence(&prompt.content);
+    let _ = writeln!(note, "## Шаблон");
+    let _ = writeln!(note);
+    let _ = writeln!(note, "{}", fence);
+    let _ = writeln!(note, "{}", prompt.content);
+    let _ = writeln!(note, "{}", fence);
+
+    Ok(note)
+}
+
+/// Заметка категории со ссылками на ее промпты
+fn category_note(category: &str, prompts: &[&str]) -> String {
+    let mut note = String::new();
+    let _ = writeln!(note, "# {}", category);
+    let _ = writeln!(note);
+    for target in prompts {
+        let _ = writeln!(note, "- [[{}]]", target);
+    }
+    note
+}
+
+/// Записывает библиотеку в `output_dir` как хранилище Obsidian: по заметке на промпт
+/// и по заметке на категорию в папке `Категории`. Промпты ссылаются на свои категории
+/// вики-ссылками, теги записываются как `#теги`, поэтому граф Obsidian связывает промпты
+/// через категории и теги. Существующие заметки с теми же именами перезаписываются,
+/// остальные файлы в директории не трогаются
+pub fn export_vault(library: &PromptList, output_dir: &Path) -> Result<VaultExport> {
+    let categories_dir = output_dir.join(CATEGORIES_FOLDER);
+    fs::create_dir_all(&categories_dir)
+        .map_err(PromptToolError::Io)?;
+
+    let mut category_names: Vec<&str> = library.get_categories().into_iter().map(String::as_str).collect();
+    category_names.sort();
+    let category_notes: BTreeMap<&str, String> = category_names
+        .iter()
+        .copied()
+        .zip(unique_names(category_names.iter().copied()))
+        .collect();
+    let prompt_notes = unique_names(library.prompts.iter().map(|prompt| prompt.name.as_str()));
+
+    let mut members: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (prompt, note) in library.prompts.iter().zip(&prompt_notes) {
+        fs::write(output_dir.join(format!("{}.md", note)), prompt_note(prompt, &category_notes)?)
+            .map_err(PromptToolError::Io)?;
+
+        for category in &prompt.categories {
+            members.entry(category.as_str()).or_default().push(note.as_str());
+        }
+    }
+
+    for (category, note) in &category_notes {
+        let mut prompts = members.remove(category).unwrap_or_default();
+        prompts.sort();
+        fs::write(categories_dir.join(format!("{}.md", note)), category_note(category, &prompts))
+            .map_err(PromptToolError::Io)?;
+    }
+
+    Ok(VaultExport { prompts: prompt_notes.len(), categories: category_notes.len() })
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::clipboard::{self, PasteOptions};
+use crate::error::{PromptToolError, Result};
+use crate::secrets;
+
+/// Заголовок записи в файле по умолчанию
+pub const DEFAULT_FILE_HEADER: &str = "## {datetime} {prompt}";
+
+/// Сколько ждать ответа вебхука
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Куда отправить готовый текст промпта
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum OutputTargetConfig {
+    /// Буфер обмена
+    #[default]
+    Clipboard,
+    /// Буфер обмена с последующей вставкой в активное окно
+    Paste,
+    /// Дописать текст в конец файла под заголовком с временем отправки
+    File {
+        /// Путь к файлу; если файла нет, он будет создан
+        path: String,
+        /// Шаблон заголовка записи: {date}, {time}, {datetime}, {prompt}.
+        /// Пустая строка - записывать без заголовка
+        #[serde(default = "default_file_header")]
+        header: String,
+    },
+    /// Отправить промпт POST-запросом с JSON-телом на вебхук (n8n, Zapier, свой сервер)
+    #[serde(alias = "http")]
+    Webhook {
+        /// Адрес вебхука
+        url: String,
+        /// Дополнительные заголовки запроса, например для авторизации.
+        /// Настройки хранятся в файле промптов, поэтому значения - ссылки на секреты `secret:ИМЯ`,
+        /// которые подставляются при отправке
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        headers: HashMap<String, String>,
+    },
+    /// Стандартный вывод (для консольной утилиты)
+    Stdout,
+}
+
+fn default_file_header() -> String {
+    DEFAULT_FILE_HEADER.to_string()
+}
+
+/// Результат отправки текста
+#[derive(Debug, Clone, Serialize)]
+pub struct Delivery {
+    /// Отправленный текст (для буфера обмена - после подготовки к вставке)
+    pub text: String,
+    /// Доставлен ли текст полностью; если нет, его кладет в буфер фронтенд
+    pub handled: bool,
+    /// Нужно ли после копирования вставить текст в окно, из которого вызвали PromptTool.
+    /// Если буфер заполняет фронтенд, после записи он вызывает вставку отдельной командой
+    pub paste: bool,
+}
+
+impl Delivery {
+    fn handled(text: &str) -> Self {
+        Self { text: text.to_string(), handled: true, paste: false }
+    }
+}
+
+/// Место, куда отправляется готовый текст промпта
+pub trait OutputTarget {
+    /// Отправляет текст промпта `prompt_name`; вызывать вне асинхронного контекста,
+    /// т.к. HTTP-запрос блокирующий
+    fn deliver(&self, prompt_name: &str, text: &str) -> Result<Delivery>;
+}
+
+/// Буфер обмена; при `paste` или включенной в настройках автовставке текст после копирования
+/// вставляется в окно, из которого вызвали PromptTool
+pub struct ClipboardTarget {
+    pub options: PasteOptions,
+    pub paste: bool,
+}
+
+impl OutputTarget for ClipboardTarget {
+    fn deliver(&self, _prompt_name: &str, text: &str) -> Result<Delivery> {
+        let text = clipboard::prepare_text(text, &self.options);
+        let handled = clipboard::write_text(&text, &self.options)?;
+
+        Ok(Delivery { text, handled, paste: self.paste || self.options.auto_paste })
+    }
+}
+
+/// Дописывает текст в конец файла, отделяя записи пустой строкой
+pub struct FileTarget {
+    pub path: String,
+    pub header: String,
+}
+
+/// Подставляет в шаблон заголовка время отправки и название промпта
+pub fn format_header(template: &str, prompt_name: &str, time: DateTime<Local>) -> String {
+    template
+        .replace("{datetime}", &time.format("%Y-%m-%d %H:%M:%S").to_string())
+        .replace("{date}", &time.format("%Y-%m-%d").to_string())
+        .replace("{time}", &time.format("%H:%M:%S").to_string())
+        .replace("{prompt}", prompt_name)
+}
+
+/// Формирует запись для файла: заголовок (если задан) и текст с переводом строки в конце
+pub fn file_entry(header: &str, text: &str) -> String {
+    let ending = if text.ends_with('\n') { "" } else { "\n" };
+
+    if header.is_empty() {
+        format!("{}{}", text, ending)
+    } else {
+        format!("{}\n\n{}{}", header.trim_end(), text, ending)
+    }
+}
+
+impl OutputTarget for FileTarget {
+    fn deliver(&self, prompt_name: &str, text: &str) -> Result<Delivery> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        let separator = if file.metadata()?.len() == 0 { "" } else { "\n" };
+
+        let header = format_header(&self.header, prompt_name, Local::now());
+        write!(file, "{}{}", separator, file_entry(&header, text))?;
+
+        Ok(Delivery::handled(text))
+    }
+}
+
+/// Тело запроса к вебхуку
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload<'a> {
+    /// Название промпта
+    pub prompt: &'a str,
+    /// Готовый текст промпта
+    pub text: &'a str,
+    /// Время отправки
+    pub sent_at: DateTime<Utc>,
+}
+
+/// Отправляет промпт POST-запросом с JSON-телом
+pub struct WebhookTarget {
+    pub url: String,
+    pub headers: HashMap<String, String>,
+}
+
+impl OutputTarget for WebhookTarget {
+    fn deliver(&self, prompt_name: &str, text: &str) -> Result<Delivery> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .map_err(|e| PromptToolError::Execution(format!("Ошибка создания HTTP-клиента: {}", e)))?;
+
+        let mut request = client
+            .post(&self.url)
+            .json(&WebhookPayload { prompt: prompt_name, text, sent_at: Utc::now() });
+        for (name, value) in &self.headers {
+            request = request.header(name, secrets::resolve(value)?);
+        }
+
+        let response = request
+            .send()
+            .map_err(|e| PromptToolError::Execution(format!("Ошибка отправки на {}: {}", self.url, e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(PromptToolError::Execution(format!("{} ответил {}", self.url, status)));
+        }
+
+        Ok(Delivery::handled(text))
+    }
+}
+
+/// Печатает текст в стандартный вывод
+pub struct StdoutTarget;
+
+impl OutputTarget for StdoutTarget {
+    fn deliver(&self, _prompt_name: &str, text: &str) -> Result<Delivery> {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(text.as_bytes())?;
+        if !text.ends_with('\n') {
+            stdout.write_all(b"\n")?;
+        }
+        stdout.flush()?;
+
+        Ok(Delivery::handled(text))
+    }
+}
+
+/// Создает цель отправки по ее настройкам
+pub fn target_for(config: &OutputTargetConfig, options: PasteOptions) -> Box<dyn OutputTarget + Send> {
+    match config {
+        OutputTargetConfig::Clipboard => Box::new(ClipboardTarget { options, paste: false }),
+        OutputTargetConfig::Paste => Box::new(ClipboardTarget { options, paste: true }),
+        OutputTargetConfig::File { path, header } => Box::new(FileTarget { path: path.clone(), header: header.clone() }),
+        OutputTargetConfig::Webhook { url, headers } => Box::new(WebhookTarget { url: url.clone(), headers: headers.clone() }),
+        OutputTargetConfig::Stdout => Box::new(StdoutTarget),
+    }
+}
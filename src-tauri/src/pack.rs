@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+/// Имя файла манифеста внутри пакета `.ptpack`
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Манифест пакета промптов `.ptpack`
+/// Пакет - это архив с библиотеками промптов и этим манифестом в корне
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct PackManifest {
+    /// Уникальное имя пакета
+    pub name: String,
+
+    /// Версия пакета в формате semver
+    pub version: String,
+
+    /// Краткое описание содержимого пакета
+    #[serde(default)]
+    pub description: String,
+
+    /// Автор или команда, поддерживающая пакет
+    #[serde(default)]
+    pub author: Option<String>,
+
+    /// Пути к файлам библиотек (TOML или JSON) относительно корня пакета
+    pub libraries: Vec<String>,
+}
@@ -0,0 +1,138 @@
+//! Постраничная выдача библиотеки: список отдает краткие описания промптов страницами,
+//! полный текст запрашивается отдельно для выбранного промпта. Так библиотека из десятков
+//! тысяч промптов не сериализуется в ответ одной команды целиком.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+use crate::prompt::Prompt;
+
+/// Размер страницы, если запрос его не задает
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Наибольший размер страницы
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+/// Сколько символов текста промпта попадает в краткое описание
+pub const SUMMARY_CONTENT_CHARS: usize = 200;
+
+/// Разделитель позиции и идентификатора в курсоре
+const CURSOR_SEPARATOR: char = ':';
+
+/// Запрос страницы
+/// `cursor` из прошлого ответа продолжает список после последнего полученного промпта,
+/// даже если перед ним добавили или удалили промпты; без курсора страница начинается с `offset`.
+/// Курсор имеет вид `<позиция>:<идентификатор>`: позиция следует сразу за последним промптом
+/// страницы, идентификатор - его id (пустой, если id нет)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PageRequest {
+    pub offset: usize,
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+}
+
+/// Страница списка
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Сколько всего элементов в списке
+    pub total: usize,
+    /// Позиция первого элемента страницы в списке
+    pub offset: usize,
+    /// Курсор следующей страницы; None - страница последняя
+    pub next_cursor: Option<String>,
+}
+
+/// Краткое описание промпта для списков
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PromptSummary {
+    pub id: String,
+    pub name: String,
+    /// Теги по алфавиту
+    pub tags: Vec<String>,
+    /// Начало текста промпта
+    pub content: String,
+    /// Текст обрезан; полный текст возвращает get_prompt_detail
+    pub truncated: bool,
+    pub archived: bool,
+}
+
+impl PromptSummary {
+    pub fn from_prompt(prompt: &Prompt) -> Self {
+        let mut tags: Vec<String> = prompt.tags.iter().cloned().collect();
+        tags.sort();
+
+        let truncated = prompt.content.chars().count() > SUMMARY_CONTENT_CHARS;
+        let content = if truncated {
+            prompt.content.chars().take(SUMMARY_CONTENT_CHARS).collect()
+        } else {
+            prompt.content.clone()
+        };
+
+        Self {
+            id: prompt.id.clone(),
+            name: prompt.name.clone(),
+            tags,
+            content,
+            truncated,
+            archived: prompt.archived,
+        }
+    }
+}
+
+impl PageRequest {
+    /// Размер страницы
+    pub fn limit(&self) -> Result<usize> {
+        match self.limit {
+            None => Ok(DEFAULT_PAGE_SIZE),
+            Some(limit) if (1..=MAX_PAGE_SIZE).contains(&limit) => Ok(limit),
+            Some(_) => Err(PromptToolError::Validation(format!(
+                "limit: должно быть от 1 до {}",
+                MAX_PAGE_SIZE
+            ))),
+        }
+    }
+
+    /// Позиция первого промпта страницы
+    fn start(&self, prompts: &[Prompt]) -> Result<usize> {
+        let Some(cursor) = &self.cursor else {
+            return Ok(self.offset.min(prompts.len()));
+        };
+
+        let (end, id) = cursor.split_once(CURSOR_SEPARATOR)
+            .and_then(|(end, id)| Some((end.parse::<usize>().ok()?, id)))
+            .ok_or_else(|| PromptToolError::Validation(format!("Недопустимый курсор: {}", cursor)))?;
+        if id.is_empty() {
+            return Ok(end.min(prompts.len()));
+        }
+
+        // Идентификаторы могут повторяться: из промптов с этим id выбирается ближайший к
+        // прежней позиции, так вставки и удаления перед курсором не сбивают страницу
+        prompts.iter()
+            .enumerate()
+            .filter(|(_, prompt)| prompt.id == id)
+            .map(|(position, _)| position + 1)
+            .min_by_key(|position| position.abs_diff(end))
+            .ok_or_else(|| PromptToolError::Validation(
+                "Курсор устарел: промпт удален, запросите список с начала".to_string()
+            ))
+    }
+}
+
+/// Страница кратких описаний промптов
+pub fn page_of_prompts(prompts: &[Prompt], request: &PageRequest) -> Result<Page<PromptSummary>> {
+    let limit = request.limit()?;
+    let start = request.start(prompts)?;
+    let end = (start + limit).min(prompts.len());
+
+    let next_cursor = (end < prompts.len())
+        .then(|| format!("{}{}{}", end, CURSOR_SEPARATOR, prompts[end - 1].id));
+
+    Ok(Page {
+        items: prompts[start..end].iter().map(PromptSummary::from_prompt).collect(),
+        total: prompts.len(),
+        offset: start,
+        next_cursor,
+    })
+}
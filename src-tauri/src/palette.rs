@@ -0,0 +1,71 @@
+//! Окно быстрого запуска (режим командной палитры): компактный поиск по промптам
+//! в отдельном окне без рамки поверх остальных окон, независимом от окна управления библиотекой.
+
+use serde::Serialize;
+
+use crate::parameters::ParameterSpec;
+use crate::prompt::Prompt;
+
+/// Метка окна быстрого запуска
+pub const LAUNCHER_WINDOW: &str = "launcher";
+
+/// Сколько результатов показывает окно быстрого запуска
+pub const LAUNCHER_RESULT_LIMIT: usize = 8;
+
+/// Наибольшая длина превью текста промпта в символах
+const PREVIEW_CHARS: usize = 80;
+
+/// Результат поиска в окне быстрого запуска: только то, что помещается в одну строку списка
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LauncherItem {
+    /// Название промпта
+    pub name: String,
+    /// Начало текста промпта в одну строку
+    pub preview: String,
+    /// Псевдонимы промпта
+    pub aliases: Vec<String>,
+}
+
+impl LauncherItem {
+    pub fn from_prompt(prompt: &Prompt) -> Self {
+        Self {
+            name: prompt.name.clone(),
+            preview: preview(&prompt.content),
+            aliases: prompt.aliases.clone(),
+        }
+    }
+}
+
+/// Выбранный промпт и параметры, значения которых нужно спросить перед копированием
+#[derive(Debug, Clone, Serialize)]
+pub struct LauncherSelection {
+    /// Название промпта
+    pub name: String,
+    /// Параметры без значения по умолчанию; если список пуст, промпт можно копировать сразу
+    pub inputs: Vec<ParameterSpec>,
+}
+
+impl LauncherSelection {
+    pub fn from_prompt(prompt: &Prompt) -> Self {
+        Self {
+            name: prompt.name.clone(),
+            inputs: prompt.parameters
+                .iter()
+                .filter(|parameter| parameter.default.is_none())
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Первые символы текста с пробелами и переводами строк, схлопнутыми в один пробел
+fn preview(content: &str) -> String {
+    let text = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= PREVIEW_CHARS {
+        return text;
+    }
+
+    let mut preview: String = text.chars().take(PREVIEW_CHARS).collect();
+    preview.push('…');
+    preview
+}
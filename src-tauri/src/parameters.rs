@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::prompt::Prompt;
+use crate::render::find_placeholders;
+
+/// Тип значения параметра; по нему интерфейс выбирает поле ввода
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterKind {
+    /// Произвольный текст
+    #[default]
+    String,
+    /// Число, целое или дробное
+    Number,
+    /// Логическое значение: true/false
+    Bool,
+    /// Одно из перечисленных значений, например `kind = { enum = ["ru", "en"] }`
+    Enum(Vec<String>),
+}
+
+/// Описание параметра промпта
+/// В старых файлах параметр задан просто именем: `parameters = ["lang"]`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParameterSpec {
+    /// Имя параметра, совпадающее с плейсхолдером в тексте промпта
+    pub name: String,
+
+    /// Тип значения
+    #[serde(default)]
+    pub kind: ParameterKind,
+
+    /// Обязательно ли передавать значение
+    #[serde(default)]
+    pub required: bool,
+
+    /// Подсказка для пользователя
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+
+    /// Значение, подставляемое, если вызывающий его не передал
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+/// Параметр в том виде, в каком он записан в файле
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum RawParameter {
+    /// Только имя параметра
+    Name(String),
+    /// Полное описание параметра
+    Full(FullParameter),
+}
+
+/// Полное описание параметра в файле
+#[derive(Deserialize, JsonSchema)]
+struct FullParameter {
+    /// Имя параметра, совпадающее с плейсхолдером в тексте промпта
+    name: String,
+
+    /// Тип значения
+    #[serde(default)]
+    kind: ParameterKind,
+
+    /// Обязательно ли передавать значение
+    #[serde(default)]
+    required: bool,
+
+    /// Подсказка для пользователя
+    #[serde(default)]
+    description: String,
+
+    /// Значение, подставляемое, если вызывающий его не передал
+    #[serde(default)]
+    default: Option<String>,
+}
+
+impl From<&str> for ParameterSpec {
+    fn from(name: &str) -> Self {
+        Self::from(name.to_string())
+    }
+}
+
+impl From<String> for ParameterSpec {
+    fn from(name: String) -> Self {
+        Self {
+            name,
+            kind: ParameterKind::default(),
+            required: false,
+            description: String::new(),
+            default: None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ParameterSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Ok(match RawParameter::deserialize(deserializer)? {
+            RawParameter::Name(name) => Self::from(name),
+            RawParameter::Full(full) => Self {
+                name: full.name,
+                kind: full.kind,
+                required: full.required,
+                description: full.description,
+                default: full.default,
+            },
+        })
+    }
+}
+
+// Схема описывает обе формы записи параметра, которые принимает десериализация
+impl JsonSchema for ParameterSpec {
+    fn schema_name() -> String {
+        "ParameterSpec".to_string()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        RawParameter::json_schema(generator)
+    }
+}
+
+impl ParameterSpec {
+    /// Проверяет значение на соответствие типу параметра
+    /// Отсутствующее или пустое значение считается ошибкой только для обязательного параметра
+    pub fn check(&self, value: Option<&str>) -> Vec<ParameterError> {
+        let value = match value.filter(|value| !value.is_empty()) {
+            Some(value) => value,
+            None if self.required => {
+                return vec![ParameterError::new(&self.name, "required", "Значение обязательно".to_string())];
+            }
+            None => return Vec::new(),
+        };
+
+        let error = match &self.kind {
+            ParameterKind::String => None,
+            ParameterKind::Number => value.trim().parse::<f64>().is_err()
+                .then(|| ParameterError::new(&self.name, "not-a-number", "Значение должно быть числом".to_string())),
+            ParameterKind::Bool => parse_bool(value).is_none()
+                .then(|| ParameterError::new(&self.name, "not-a-bool", "Значение должно быть true или false".to_string())),
+            ParameterKind::Enum(options) => (!options.iter().any(|option| option == value))
+                .then(|| ParameterError::new(
+                    &self.name,
+                    "not-allowed",
+                    format!("Допустимые значения: {}", options.join(", ")),
+                )),
+        };
+
+        error.into_iter().collect()
+    }
+}
+
+/// Результат сверки списка параметров с текстом промпта
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ParameterSync {
+    /// Параметры, найденные в тексте и добавленные в список
+    pub added: Vec<String>,
+
+    /// Объявленные параметры, которых нет в тексте; из списка не удаляются
+    pub orphaned: Vec<String>,
+}
+
+/// Имена параметров, на которые ссылается текст промпта (`{param}`, `{param|upper}`)
+/// Переменные Tera и директивы `{{include:...}}` не учитываются
+pub fn detect_parameters(content: &str) -> Vec<String> {
+    find_placeholders(content)
+}
+
+/// Добавляет в список параметров промпта плейсхолдеры из текста и сообщает
+/// о параметрах, которые в тексте не используются
+pub fn sync_parameters(prompt: &mut Prompt) -> ParameterSync {
+    let detected = detect_parameters(&prompt.content);
+
+    let added: Vec<String> = detected
+        .iter()
+        .filter(|name| !prompt.parameters.iter().any(|spec| &spec.name == *name))
+        .cloned()
+        .collect();
+    let orphaned = prompt.parameters
+        .iter()
+        .filter(|spec| !detected.contains(&spec.name))
+        .map(|spec| spec.name.clone())
+        .collect();
+
+    if !added.is_empty() {
+        let mut parameters = prompt.parameters.clone();
+        parameters.extend(added.iter().map(|name| ParameterSpec::from(name.as_str())));
+        prompt.update(prompt.content.clone(), parameters);
+    }
+
+    ParameterSync { added, orphaned }
+}
+
+/// Дополняет значения параметров значениями по умолчанию
+/// Явно переданные значения не меняются, даже пустые
+pub fn with_defaults(prompt: &Prompt, values: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut values = values.clone();
+    for spec in &prompt.parameters {
+        if let Some(default) = &spec.default {
+            values.entry(spec.name.clone()).or_insert_with(|| default.clone());
+        }
+    }
+    values
+}
+
+/// Разбирает логическое значение; кроме true/false принимаются yes/no и 1/0
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "yes" | "1" => Some(true),
+        "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Ограничения на значение параметра промпта
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ParameterConstraint {
+    /// Регулярное выражение, которому должно целиком соответствовать значение
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    /// Минимальная длина значения в символах
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+
+    /// Максимальная длина значения в символах
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+
+    /// Допустимые значения; пустой список - без ограничения
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed: Vec<String>,
+}
+
+/// Ошибка значения конкретного параметра
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParameterError {
+    /// Имя параметра
+    pub parameter: String,
+
+    /// Машиночитаемый код ошибки, например `too-long`
+    pub code: &'static str,
+
+    /// Описание ошибки для пользователя
+    pub message: String,
+}
+
+impl ParameterError {
+    fn new(parameter: &str, code: &'static str, message: String) -> Self {
+        Self {
+            parameter: parameter.to_string(),
+            code,
+            message,
+        }
+    }
+}
+
+impl ParameterConstraint {
+    /// Проверяет значение и возвращает все нарушенные ограничения
+    pub fn check(&self, parameter: &str, value: &str) -> Vec<ParameterError> {
+        let mut errors = Vec::new();
+        let length = value.chars().count();
+
+        if let Some(min_length) = self.min_length.filter(|min| length < *min) {
+            errors.push(ParameterError::new(
+                parameter,
+                "too-short",
+                format!("Значение короче {} символов", min_length),
+            ));
+        }
+
+        if let Some(max_length) = self.max_length.filter(|max| length > *max) {
+            errors.push(ParameterError::new(
+                parameter,
+                "too-long",
+                format!("Значение длиннее {} символов", max_length),
+            ));
+        }
+
+        if !self.allowed.is_empty() && !self.allowed.iter().any(|allowed| allowed == value) {
+            errors.push(ParameterError::new(
+                parameter,
+                "not-allowed",
+                format!("Допустимые значения: {}", self.allowed.join(", ")),
+            ));
+        }
+
+        if let Some(pattern) = &self.pattern {
+            match Regex::new(&format!("^(?:{})$", pattern)) {
+                Ok(regex) if !regex.is_match(value) => errors.push(ParameterError::new(
+                    parameter,
+                    "pattern-mismatch",
+                    format!("Значение не соответствует шаблону {}", pattern),
+                )),
+                Ok(_) => {}
+                Err(e) => errors.push(ParameterError::new(
+                    parameter,
+                    "invalid-pattern",
+                    format!("Некорректное регулярное выражение {}: {}", pattern, e),
+                )),
+            }
+        }
+
+        errors
+    }
+}
+
+/// Проверяет переданные значения параметров по типам и ограничениям промпта
+/// Сначала проверяются типы и обязательность в порядке объявления параметров,
+/// затем ограничения; ограничения применяются только к переданным значениям
+pub fn validate_values(prompt: &Prompt, values: &HashMap<String, String>) -> Vec<ParameterError> {
+    let mut errors: Vec<ParameterError> = prompt.parameters
+        .iter()
+        .flat_map(|spec| spec.check(values.get(&spec.name).map(String::as_str)))
+        .collect();
+
+    let mut names: Vec<&String> = prompt.constraints.keys().collect();
+    names.sort();
+
+    errors.extend(
+        names.into_iter()
+            .filter_map(|name| Some((name, values.get(name)?)))
+            .flat_map(|(name, value)| prompt.constraints[name].check(name, value)),
+    );
+    errors
+}
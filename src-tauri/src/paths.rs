@@ -0,0 +1,107 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{PromptToolError, Result};
+
+/// Аргумент командной строки для явного указания директории конфигурации
+pub const CONFIG_DIR_ARG: &str = "--config-dir";
+
+/// Файл промптов по умолчанию в старых версиях: путь отсчитывался от рабочей директории процесса,
+/// поэтому зависел от способа запуска
+pub const LEGACY_PROMPT_FILE: &str = "prompts/default.toml";
+
+/// Файл промптов по умолчанию внутри директории данных
+const DEFAULT_PROMPT_FILE: &str = "prompts/default.toml";
+
+/// Имя файла-метки владельца внутри защищаемой директории
+const OWNER_FILE_NAME: &str = ".owner";
+
+/// Директории, в которых приложение хранит свое состояние
+#[derive(Debug, Clone)]
+pub struct AppPaths {
+    /// Директория с config.json
+    pub config_dir: PathBuf,
+
+    /// Директория с данными приложения (индекс, история и т.д.)
+    pub data_dir: PathBuf,
+}
+
+impl AppPaths {
+    /// Директории внутри явно указанной директории конфигурации:
+    /// конфигурация лежит в ней самой, данные - в поддиректории `data`
+    pub fn from_override(config_dir: PathBuf) -> Self {
+        Self {
+            data_dir: config_dir.join("data"),
+            config_dir,
+        }
+    }
+
+    /// Абсолютный путь к файлу промптов по умолчанию
+    pub fn default_prompt_file(&self) -> PathBuf {
+        self.data_dir.join(DEFAULT_PROMPT_FILE)
+    }
+}
+
+/// Переносит файл промптов по умолчанию со старого места, если на новом его еще нет
+/// Старый файл копируется и остается на месте как резервная копия. Возвращает true, если файл перенесен
+pub fn migrate_legacy_prompt_file(legacy: &Path, target: &Path) -> Result<bool> {
+    if target.exists() || !legacy.is_file() {
+        return Ok(false);
+    }
+
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir)
+            .map_err(PromptToolError::Io)?;
+    }
+    fs::copy(legacy, target)
+        .map_err(PromptToolError::Io)?;
+    Ok(true)
+}
+
+/// Ищет значение `--config-dir` в аргументах командной строки
+/// Поддерживаются формы `--config-dir <path>` и `--config-dir=<path>`
+pub fn parse_config_dir_arg<I, S>(args: I) -> Option<PathBuf>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let arg = arg.as_ref();
+        if arg == CONFIG_DIR_ARG {
+            return args.next().map(|value| PathBuf::from(value.as_ref()));
+        }
+        if let Some(value) = arg.strip_prefix(CONFIG_DIR_ARG).and_then(|rest| rest.strip_prefix('=')) {
+            return Some(PathBuf::from(value));
+        }
+    }
+    None
+}
+
+/// Имя текущего пользователя ОС
+pub fn current_user() -> String {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Закрепляет директорию за текущим пользователем
+/// При первом обращении записывает имя пользователя в файл-метку; если директория уже
+/// принадлежит другому пользователю, возвращает ошибку, чтобы не испортить чужие данные.
+pub fn claim_directory(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir)
+        .map_err(PromptToolError::Io)?;
+
+    let owner_path = dir.join(OWNER_FILE_NAME);
+    let user = current_user();
+
+    match fs::read_to_string(&owner_path) {
+        Ok(owner) if owner.trim() != user => Err(PromptToolError::Config(format!(
+            "Директория {} используется пользователем {}",
+            dir.display(),
+            owner.trim()
+        ))),
+        Ok(_) => Ok(()),
+        Err(_) => fs::write(&owner_path, &user).map_err(PromptToolError::Io),
+    }
+}
@@ -0,0 +1,250 @@
+//! Интеграция с возможностями конкретных ОС.
+//!
+//! В macOS приложение регистрирует сервис "Send selection to PromptTool": выделенный
+//! в любом приложении текст становится новым промптом или значением переменной `{selection}`.
+//! Сам пункт меню объявляется в `Info.plist` (ключ `NSServices`), здесь - его обработчик.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+use crate::prompt::Prompt;
+
+/// Имя переменной шаблона, в которую подставляется присланный текст
+pub const SELECTION_VARIABLE: &str = "selection";
+
+/// Максимальная длина названия промпта, созданного из выделенного текста
+const CAPTURED_NAME_MAX_CHARS: usize = 60;
+
+/// Что делать с текстом, присланным через системный сервис
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionAction {
+    /// Сохранить текст как новый промпт
+    #[default]
+    NewPrompt,
+    /// Запомнить текст как значение `{selection}` для следующей подстановки
+    Variable,
+}
+
+/// Создает промпт из выделенного текста; название - первая непустая строка
+pub fn prompt_from_selection(text: &str) -> Prompt {
+    let first_line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+
+    let mut name: String = first_line.chars().take(CAPTURED_NAME_MAX_CHARS).collect();
+    if first_line.chars().count() > CAPTURED_NAME_MAX_CHARS {
+        name.push('…');
+    }
+    if name.is_empty() {
+        name = "Выделенный текст".to_string();
+    }
+
+    Prompt::new(name, text.to_string(), Vec::new(), HashSet::new(), HashSet::new())
+}
+
+/// Регистрирует обработчик системного сервиса передачи выделенного текста
+/// Возвращает `false`, если на текущей ОС такой интеграции нет.
+/// В macOS должна вызываться из главного потока (например, в `setup`)
+pub fn register_selection_service<F>(handler: F) -> bool
+where
+    F: Fn(String) + Send + Sync + 'static,
+{
+    #[cfg(target_os = "macos")]
+    {
+        macos::register_services(Box::new(handler))
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = handler;
+        false
+    }
+}
+
+/// Окно другого приложения, активное до вызова PromptTool (HWND в Windows)
+/// В macOS и Linux фокус возвращается сам, когда окно PromptTool скрывается
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForeignWindow(isize);
+
+/// Запоминает активное сейчас окно; `None`, если на текущей ОС это не поддерживается
+pub fn foreground_window() -> Option<ForeignWindow> {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+        // SAFETY: функция без аргументов, возвращает нулевой HWND, если активного окна нет
+        let hwnd = unsafe { GetForegroundWindow() };
+        (!hwnd.is_null()).then(|| ForeignWindow(hwnd as isize))
+    }
+
+    #[cfg(not(windows))]
+    {
+        None
+    }
+}
+
+/// Возвращает фокус запомненному окну; `false`, если система отказала
+pub fn focus_window(window: ForeignWindow) -> bool {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
+
+        // SAFETY: HWND получен из GetForegroundWindow; для закрытого окна вызов просто вернет 0
+        unsafe { SetForegroundWindow(window.0 as _) != 0 }
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = window;
+        false
+    }
+}
+
+/// Нажимает сочетание вставки в активном окне: Cmd+V в macOS, Ctrl+V в остальных ОС
+pub fn simulate_paste() -> Result<()> {
+    use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+
+    #[cfg(target_os = "macos")]
+    let modifier = Key::Meta;
+    #[cfg(not(target_os = "macos"))]
+    let modifier = Key::Control;
+
+    let error = |e: &dyn std::fmt::Display| PromptToolError::Execution(format!("Не удалось вставить текст: {}", e));
+
+    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| error(&e))?;
+    enigo.key(modifier, Direction::Press).map_err(|e| error(&e))?;
+    let pressed = enigo.key(Key::Unicode('v'), Direction::Click);
+    // Модификатор отпускается в любом случае, иначе он "залипнет" у пользователя
+    enigo.key(modifier, Direction::Release).map_err(|e| error(&e))?;
+    pressed.map_err(|e| error(&e))
+}
+
+/// Включен ли системный режим "Не беспокоить" / фокусирования
+/// Возвращает `None`, если на текущей ОС состояние определить не удается
+pub fn system_do_not_disturb() -> Option<bool> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::focus_mode_active()
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // GNOME: при включенном "Не беспокоить" баннеры уведомлений отключены
+        let output = std::process::Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        match String::from_utf8_lossy(&output.stdout).trim() {
+            "false" => Some(true),
+            "true" => Some(false),
+            _ => None,
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::ffi::{CStr, CString};
+    use std::os::raw::c_char;
+    use std::sync::OnceLock;
+
+    use objc::declare::ClassDecl;
+    use objc::runtime::{Object, Sel};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    type SelectionHandler = Box<dyn Fn(String) + Send + Sync>;
+
+    static HANDLER: OnceLock<SelectionHandler> = OnceLock::new();
+
+    /// Имя класса-провайдера; должно быть уникальным в процессе
+    const PROVIDER_CLASS: &str = "PromptToolServiceProvider";
+
+    /// Тип данных выделенного текста в буфере сервиса
+    const PLAIN_TEXT_TYPE: &str = "public.utf8-plain-text";
+
+    /// Метод `sendSelection:userData:error:`, имя которого указано в NSMessage в Info.plist
+    extern "C" fn send_selection(
+        _this: &Object,
+        _cmd: Sel,
+        pasteboard: *mut Object,
+        _user_data: *mut Object,
+        _error: *mut *mut Object,
+    ) {
+        let Ok(type_name) = CString::new(PLAIN_TEXT_TYPE) else {
+            return;
+        };
+
+        // SAFETY: pasteboard - NSPasteboard, переданный системой; строки живут до конца вызова
+        let text = unsafe {
+            let ns_type: *mut Object = msg_send![class!(NSString), stringWithUTF8String: type_name.as_ptr()];
+            let ns_text: *mut Object = msg_send![pasteboard, stringForType: ns_type];
+            if ns_text.is_null() {
+                return;
+            }
+            let utf8: *const c_char = msg_send![ns_text, UTF8String];
+            if utf8.is_null() {
+                return;
+            }
+            CStr::from_ptr(utf8).to_string_lossy().into_owned()
+        };
+
+        if let Some(handler) = HANDLER.get() {
+            handler(text);
+        }
+    }
+
+    /// Режим фокусирования (macOS 12+) активен, если в файле утверждений есть записи
+    pub fn focus_mode_active() -> Option<bool> {
+        let home = std::env::var_os("HOME")?;
+        let path = std::path::Path::new(&home).join("Library/DoNotDisturb/DB/Assertions.json");
+        let contents = std::fs::read_to_string(path).ok()?;
+        let value: serde_json::Value = serde_json::from_str(&contents).ok()?;
+
+        let active = value["data"]
+            .as_array()?
+            .iter()
+            .any(|entry| entry["storeAssertionRecords"].as_array().is_some_and(|records| !records.is_empty()));
+        Some(active)
+    }
+
+    pub fn register_services(handler: SelectionHandler) -> bool {
+        if HANDLER.set(handler).is_err() {
+            return false;
+        }
+
+        let Some(mut decl) = ClassDecl::new(PROVIDER_CLASS, class!(NSObject)) else {
+            return false;
+        };
+
+        // SAFETY: сигнатура соответствует селектору sendSelection:userData:error:
+        unsafe {
+            decl.add_method(
+                sel!(sendSelection:userData:error:),
+                send_selection as extern "C" fn(&Object, Sel, *mut Object, *mut Object, *mut *mut Object),
+            );
+        }
+        let provider_class = decl.register();
+
+        // SAFETY: вызывается из главного потока; провайдер живет до конца работы приложения
+        unsafe {
+            let provider: *mut Object = msg_send![provider_class, new];
+            let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+            let _: () = msg_send![app, setServicesProvider: provider];
+        }
+
+        true
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+
+/// Цена модели в долларах США за миллион токенов
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    /// Цена входных токенов (текст промпта)
+    pub input_per_million: f64,
+
+    /// Цена выходных токенов (ответ модели)
+    pub output_per_million: f64,
+}
+
+/// Оценка стоимости одного выполнения промпта
+#[derive(Debug, Clone, Serialize)]
+pub struct CostEstimate {
+    /// Запись таблицы цен, по которой выполнен расчет
+    pub priced_as: String,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub input_cost: f64,
+    pub output_cost: f64,
+    pub total_cost: f64,
+}
+
+/// Таблица цен по умолчанию; пользователь может изменить ее в конфигурации
+pub fn default_price_table() -> HashMap<String, ModelPrice> {
+    let price = |input_per_million, output_per_million| ModelPrice { input_per_million, output_per_million };
+
+    HashMap::from([
+        ("gpt-4o".to_string(), price(2.5, 10.0)),
+        ("gpt-4o-mini".to_string(), price(0.15, 0.6)),
+        ("gpt-4.1".to_string(), price(2.0, 8.0)),
+        ("gpt-4.1-mini".to_string(), price(0.4, 1.6)),
+        ("gpt-4.1-nano".to_string(), price(0.1, 0.4)),
+        ("o3-mini".to_string(), price(1.1, 4.4)),
+        ("gpt-3.5-turbo".to_string(), price(0.5, 1.5)),
+    ])
+}
+
+/// Ищет цену модели: сначала точное совпадение, затем самый длинный префикс,
+/// чтобы "gpt-4o-2024-08-06" считалась по цене "gpt-4o"
+pub fn find_price<'a>(table: &'a HashMap<String, ModelPrice>, model: &str) -> Option<(&'a str, &'a ModelPrice)> {
+    if let Some((name, price)) = table.get_key_value(model) {
+        return Some((name.as_str(), price));
+    }
+
+    table.iter()
+        .filter(|(name, _)| model.starts_with(name.as_str()))
+        .max_by_key(|(name, _)| name.len())
+        .map(|(name, price)| (name.as_str(), price))
+}
+
+/// Рассчитывает стоимость по количеству входных и ожидаемых выходных токенов
+pub fn estimate_cost(
+    table: &HashMap<String, ModelPrice>,
+    model: &str,
+    input_tokens: usize,
+    output_tokens: usize,
+) -> Result<CostEstimate> {
+    let (priced_as, price) = find_price(table, model)
+        .ok_or_else(|| PromptToolError::Config(format!("Нет цены для модели \"{}\"", model)))?;
+
+    let input_cost = input_tokens as f64 * price.input_per_million / 1_000_000.0;
+    let output_cost = output_tokens as f64 * price.output_per_million / 1_000_000.0;
+
+    Ok(CostEstimate {
+        priced_as: priced_as.to_string(),
+        input_tokens,
+        output_tokens,
+        input_cost,
+        output_cost,
+        total_cost: input_cost + output_cost,
+    })
+}
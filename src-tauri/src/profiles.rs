@@ -0,0 +1,111 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+use crate::file_io::write_atomic;
+use crate::paths::AppPaths;
+
+/// Профиль, который есть всегда; его файлы лежат прямо в директориях приложения,
+/// поэтому установки без профилей продолжают работать как раньше
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Имя файла со списком профилей в директории конфигурации приложения
+pub const PROFILES_FILE_NAME: &str = "profiles.json";
+
+/// Поддиректория, в которой лежат файлы остальных профилей
+const PROFILES_DIR_NAME: &str = "profiles";
+
+/// Наибольшая длина имени профиля
+const MAX_NAME_LEN: usize = 64;
+
+/// Список профилей и активный профиль
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileRegistry {
+    // Профиль, с которым работает приложение
+    pub active: String,
+    // Все созданные профили, включая профиль по умолчанию
+    pub profiles: BTreeSet<String>,
+}
+
+impl Default for ProfileRegistry {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE.to_string(),
+            profiles: BTreeSet::from([DEFAULT_PROFILE.to_string()]),
+        }
+    }
+}
+
+impl ProfileRegistry {
+    /// Загружает список профилей; без файла или при поврежденном файле остается только профиль по умолчанию
+    pub fn load(path: &Path) -> Self {
+        let mut registry: Self = fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        registry.profiles.insert(DEFAULT_PROFILE.to_string());
+        if !registry.profiles.contains(&registry.active) {
+            registry.active = DEFAULT_PROFILE.to_string();
+        }
+        registry
+    }
+
+    /// Сохраняет список профилей
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(PromptToolError::Io)?;
+        }
+
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|_| PromptToolError::Config("Ошибка сериализации списка профилей".to_string()))?;
+        write_atomic(path, contents.as_bytes())
+    }
+
+    /// Делает профиль активным, добавляя его в список, если его еще нет
+    /// Возвращает true, если профиль создан
+    pub fn activate(&mut self, name: &str) -> Result<bool> {
+        validate_name(name)?;
+
+        let created = self.profiles.insert(name.to_string());
+        self.active = name.to_string();
+        Ok(created)
+    }
+}
+
+/// Проверяет имя профиля: оно становится именем директории, поэтому допускаются
+/// только буквы, цифры, `-` и `_`
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(PromptToolError::Validation(format!(
+            "Имя профиля должно содержать от 1 до {} символов",
+            MAX_NAME_LEN
+        )));
+    }
+
+    if !name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        return Err(PromptToolError::Validation(format!(
+            "Недопустимое имя профиля \"{}\": разрешены буквы, цифры, \"-\" и \"_\"",
+            name
+        )));
+    }
+
+    Ok(())
+}
+
+/// Директории конфигурации и данных профиля
+/// У профиля по умолчанию это сами директории приложения, у остальных - `profiles/<имя>` внутри них
+pub fn profile_paths(base: &AppPaths, name: &str) -> AppPaths {
+    if name == DEFAULT_PROFILE {
+        return base.clone();
+    }
+
+    AppPaths {
+        config_dir: base.config_dir.join(PROFILES_DIR_NAME).join(name),
+        data_dir: base.data_dir.join(PROFILES_DIR_NAME).join(name),
+    }
+}
@@ -1,23 +1,54 @@
 use serde::{Serialize, Deserialize};
-use std::collections::HashSet;
+use schemars::JsonSchema;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use chrono::{DateTime, Utc};
+use crate::database::{Suggestion, SuggestionKind};
+use crate::hooks::PromptHooks;
+use crate::output::OutputTargetConfig;
+use crate::parameters::{ParameterConstraint, ParameterSpec};
+
+/// Наименьшая оценка промпта
+pub const MIN_RATING: u8 = 1;
+
+/// Наибольшая оценка промпта
+pub const MAX_RATING: u8 = 5;
 
 /// Основная структура для хранения промпта
 /// Содержит всю необходимую информацию о промпте, включая метаданные
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 pub struct Prompt {
     /// Название промпта, используется для быстрой идентификации
     pub name: String,
+
+    /// Постоянный идентификатор промпта; не меняется при переименовании
+    /// В файлах старого формата отсутствует и заполняется командой `migrate_library`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
     
     /// Содержание промпта - сам шаблон текста
     pub content: String,
     
     /// Список параметров, которые можно заменить в шаблоне
-    /// Например, если в content есть {param1}, то "param1" должен быть в этом списке
-    pub parameters: Vec<String>,
+    /// Например, если в content есть {param1}, то параметр "param1" должен быть в этом списке.
+    /// Параметр задается именем или таблицей с типом, обязательностью и описанием
+    pub parameters: Vec<ParameterSpec>,
+
+    /// Ограничения на значения параметров по их именам (регулярное выражение, длина, список значений)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub constraints: HashMap<String, ParameterConstraint>,
+
+    /// Куда по умолчанию отправлять готовый текст; если не указано, используется буфер обмена
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<OutputTargetConfig>,
+
+    /// Скрипты, выполняемые до и после подстановки параметров
+    #[serde(default, skip_serializing_if = "PromptHooks::is_empty")]
+    pub hooks: PromptHooks,
     
     /// Категории, к которым относится промпт
     /// Используется HashSet для быстрого поиска и уникальности категорий
+    #[serde(default)]
     pub categories: HashSet<String>,
     
     /// Время создания промпта
@@ -32,12 +63,45 @@ pub struct Prompt {
     
     /// Теги для поиска
     /// Используются для более гибкой категоризации, чем основные категории
+    #[serde(default)]
     pub tags: HashSet<String>,
+
+    /// Псевдонимы - короткие коды вроде `cm`, по которым промпт находится первым
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+
+    /// Оценка промпта от MIN_RATING до MAX_RATING; `None` - промпт еще не оценен
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+
+    /// Промпт выведен из употребления: хранится в библиотеке, но не попадает в поиск по умолчанию
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+
+    /// Автор промпта
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub author: String,
+
+    /// Ссылка на источник, откуда промпт был взят
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub source_url: String,
+
+    /// Лицензия, под которой распространяется промпт, например `CC0-1.0`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub license: String,
+
+    /// Модели, для которых промпт рекомендован
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<String>,
+
+    /// Произвольные заметки к промпту
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
 }
 
 /// Коллекция промптов
 /// Используется для хранения и управления группой промптов
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct PromptList {
     /// Список всех промптов в коллекции
     pub prompts: Vec<Prompt>,
@@ -54,10 +118,16 @@ impl PromptList {
     /// Поиск промптов по заданному фильтру
     /// Возвращает список промптов, соответствующих критериям поиска
     pub fn search(&self, filter: &SearchFilter) -> Vec<&Prompt> {
-        self.prompts
+        let mut found: Vec<&Prompt> = self.prompts
             .iter()
             .filter(|prompt| prompt.matches_filter(filter))
-            .collect()
+            .collect();
+
+        // Совпадения по псевдониму идут первыми
+        if let Some(query) = &filter.query {
+            found.sort_by_key(|prompt| !prompt.matches_alias(query));
+        }
+        found
     }
 
     /// Получает список всех уникальных категорий из всех промптов
@@ -69,6 +139,86 @@ impl PromptList {
             .collect()
     }
 
+    /// Приблизительный объем памяти, занимаемый промптами, в байтах
+    pub fn approximate_size(&self) -> usize {
+        self.prompts
+            .iter()
+            .map(|p| {
+                std::mem::size_of::<Prompt>()
+                    + p.name.capacity()
+                    + p.content.capacity()
+                    + p.parameters.iter().map(|spec| spec.name.capacity() + spec.description.capacity()).sum::<usize>()
+                    + p.categories.iter().map(String::capacity).sum::<usize>()
+                    + p.tags.iter().map(String::capacity).sum::<usize>()
+                    + p.author.capacity()
+                    + p.source_url.capacity()
+                    + p.license.capacity()
+                    + p.models.iter().map(String::capacity).sum::<usize>()
+                    + p.notes.capacity()
+            })
+            .sum()
+    }
+
+    /// Подсказки автодополнения без поискового индекса
+    /// Возвращает названия, в которых какое-либо слово начинается с префикса, и подходящие теги
+    pub fn suggest(&self, prefix: &str, limit: usize) -> Vec<Suggestion> {
+        let prefix = prefix.trim().to_lowercase();
+        if prefix.is_empty() {
+            return Vec::new();
+        }
+
+        let mut seen = HashSet::new();
+        let mut suggestions = Vec::new();
+
+        for prompt in &self.prompts {
+            let title_matches = prompt.name
+                .split_whitespace()
+                .any(|word| word.to_lowercase().starts_with(&prefix));
+            if title_matches && seen.insert((SuggestionKind::Title, prompt.name.clone())) {
+                suggestions.push(Suggestion { text: prompt.name.clone(), kind: SuggestionKind::Title });
+            }
+
+            for tag in prompt.tags.iter().filter(|tag| tag.to_lowercase().starts_with(&prefix)) {
+                if seen.insert((SuggestionKind::Tag, tag.clone())) {
+                    suggestions.push(Suggestion { text: tag.clone(), kind: SuggestionKind::Tag });
+                }
+            }
+        }
+
+        suggestions.truncate(limit);
+        suggestions
+    }
+
+    /// Исправляет совпадающие идентификаторы промптов (после ручной правки файла или слияния)
+    /// Первый промпт с идентификатором сохраняет его, следующие получают `<id>-2`, `<id>-3` и т.д.,
+    /// поэтому повторный запуск на той же библиотеке дает тот же результат. Пустые идентификаторы
+    /// не считаются совпадением - их заполняет `migrate_library`
+    pub fn repair_ids(&mut self) -> Vec<IdRepair> {
+        let mut used: HashSet<String> = self.prompts.iter().map(|p| p.id.clone()).collect();
+        let mut seen = HashSet::new();
+        let mut repairs = Vec::new();
+
+        for prompt in &mut self.prompts {
+            if prompt.id.is_empty() || seen.insert(prompt.id.clone()) {
+                continue;
+            }
+
+            let mut counter = 2;
+            let mut id = format!("{}-{}", prompt.id, counter);
+            while used.contains(&id) {
+                counter += 1;
+                id = format!("{}-{}", prompt.id, counter);
+            }
+            used.insert(id.clone());
+            seen.insert(id.clone());
+
+            let old_id = std::mem::replace(&mut prompt.id, id.clone());
+            repairs.push(IdRepair { prompt_name: prompt.name.clone(), old_id, new_id: id });
+        }
+
+        repairs
+    }
+
     /// Получает список всех уникальных тегов из всех промптов
     /// Используется для построения облака тегов и фильтров
     pub fn get_tags(&self) -> HashSet<&String> {
@@ -97,6 +247,83 @@ pub struct SearchFilter {
     
     /// Конечная дата для фильтрации по времени обновления
     pub date_to: Option<DateTime<Utc>>,
+
+    /// Точный поиск: весь запрос считается одной фразой, как если бы он был в кавычках
+    #[serde(default)]
+    pub exact: bool,
+
+    /// Минимальная оценка промпта
+    #[serde(default)]
+    pub min_rating: Option<u8>,
+
+    /// Порядок результатов; если не задан, используется порядок по умолчанию из настроек
+    #[serde(default)]
+    pub sort: Option<SortOrder>,
+
+    /// Искать также среди архивных промптов
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+/// Порядок результатов поиска
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// По релевантности запросу
+    #[default]
+    Relevance,
+
+    /// Сначала промпты с высокой оценкой, неоцененные - в конце
+    Rating,
+}
+
+/// Поднимает в начало промпты, псевдоним которых совпадает с запросом
+/// Сортировка устойчивая, порядок остальных результатов сохраняется
+pub fn aliases_first(prompts: &mut [Prompt], query: &str) {
+    prompts.sort_by_key(|prompt| !prompt.matches_alias(query));
+}
+
+impl SortOrder {
+    /// Упорядочивает найденные промпты; при равной оценке сохраняется порядок по релевантности
+    pub fn apply(self, prompts: &mut [Prompt]) {
+        if self == SortOrder::Rating {
+            prompts.sort_by_key(|prompt| std::cmp::Reverse(prompt.rating.unwrap_or(0)));
+        }
+    }
+}
+
+impl SearchFilter {
+    /// Текстовый запрос в виде, пригодном для поискового индекса
+    /// В режиме точного поиска запрос оборачивается в кавычки, чтобы индекс искал фразу
+    pub fn index_query(&self) -> Option<String> {
+        let query = self.query.as_deref()?.trim();
+        if query.is_empty() {
+            return None;
+        }
+
+        if self.exact {
+            Some(format!("\"{}\"", query.replace('"', " ")))
+        } else {
+            Some(query.to_string())
+        }
+    }
+}
+
+/// Промпт, получивший новый идентификатор при исправлении совпадений
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IdRepair {
+    pub prompt_name: String,
+    pub old_id: String,
+    pub new_id: String,
+}
+
+/// Счетчик, различающий идентификаторы, созданные в одну и ту же наносекунду
+static ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Новый идентификатор промпта на основе текущего времени
+pub fn new_prompt_id() -> String {
+    let nanos = Utc::now().timestamp_nanos_opt().unwrap_or_default();
+    format!("{:x}-{:x}", nanos, ID_COUNTER.fetch_add(1, Ordering::Relaxed))
 }
 
 impl Prompt {
@@ -105,25 +332,66 @@ impl Prompt {
     pub fn new(
         name: String, 
         content: String, 
-        parameters: Vec<String>,
+        parameters: Vec<ParameterSpec>,
         categories: HashSet<String>,
         tags: HashSet<String>,
     ) -> Self {
         let now = Utc::now();
         Prompt {
             name,
+            id: new_prompt_id(),
             content,
             parameters,
+            constraints: HashMap::new(),
+            output: None,
+            hooks: PromptHooks::default(),
             categories,
             tags,
+            aliases: Vec::new(),
             created_at: now,
             updated_at: now,
+            rating: None,
+            archived: false,
+            author: String::new(),
+            source_url: String::new(),
+            license: String::new(),
+            models: Vec::new(),
+            notes: String::new(),
+        }
+    }
+
+    /// Заполняет автора и источник, если они еще не указаны
+    /// Используется при импорте, чтобы промпты из сообщества сохраняли происхождение
+    pub fn fill_provenance(&mut self, author: &str, source_url: &str) {
+        if self.author.is_empty() {
+            self.author = author.trim().to_string();
+        }
+        if self.source_url.is_empty() {
+            self.source_url = source_url.trim().to_string();
         }
     }
 
+    /// Совпадает ли запрос целиком с одним из псевдонимов (без учета регистра и пробелов по краям)
+    pub fn matches_alias(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        !query.is_empty() && self.aliases.iter().any(|alias| alias.trim().to_lowercase() == query)
+    }
+
+    /// Метаданные промпта одной строкой для поиска: автор, источник, лицензия, модели и заметки
+    pub fn metadata_text(&self) -> String {
+        [&self.author, &self.source_url, &self.license]
+            .into_iter()
+            .chain(&self.models)
+            .chain([&self.notes])
+            .filter(|value| !value.is_empty())
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     /// Обновляет содержимое промпта и его параметры
     /// Автоматически обновляет время последнего изменения
-    pub fn update(&mut self, content: String, parameters: Vec<String>) {
+    pub fn update(&mut self, content: String, parameters: Vec<ParameterSpec>) {
         self.content = content;
         self.parameters = parameters;
         self.updated_at = Utc::now();
@@ -146,12 +414,33 @@ impl Prompt {
     /// Проверяет, соответствует ли промпт заданному фильтру поиска
     /// Возвращает true, если промпт соответствует всем заданным критериям
     pub fn matches_filter(&self, filter: &SearchFilter) -> bool {
-        // Проверяем текстовый поиск по имени и содержимому
-        if let Some(query) = &filter.query {
-            let query_lower = query.to_lowercase();
-            if !self.name.to_lowercase().contains(&query_lower) &&
-               !self.content.to_lowercase().contains(&query_lower) {
-                return false;
+        // Архивные промпты ищутся только по явному запросу
+        if self.archived && !filter.include_archived {
+            return false;
+        }
+
+        // Проверяем текстовый поиск по псевдонимам, имени, содержимому и метаданным
+        if let Some(query) = filter.query.as_ref().filter(|query| !self.matches_alias(query)) {
+            let (phrases, rest) = split_query_phrases(query, filter.exact);
+
+            // Фразы в кавычках должны встречаться целиком, как отдельные слова
+            let name = normalize_whitespace(&self.name.to_lowercase());
+            let content = normalize_whitespace(&self.content.to_lowercase());
+            let metadata = normalize_whitespace(&self.metadata_text().to_lowercase());
+            for phrase in &phrases {
+                if !contains_phrase(&name, phrase) && !contains_phrase(&content, phrase) && !contains_phrase(&metadata, phrase) {
+                    return false;
+                }
+            }
+
+            // Остальная часть запроса ищется как подстрока
+            if !rest.is_empty() {
+                let query_lower = rest.to_lowercase();
+                if !self.name.to_lowercase().contains(&query_lower) &&
+                   !self.content.to_lowercase().contains(&query_lower) &&
+                   !metadata.contains(&query_lower) {
+                    return false;
+                }
             }
         }
 
@@ -182,6 +471,56 @@ impl Prompt {
             }
         }
 
+        // Проверяем минимальную оценку; неоцененные промпты ее не проходят
+        if let Some(min_rating) = filter.min_rating {
+            if self.rating.is_none_or(|rating| rating < min_rating) {
+                return false;
+            }
+        }
+
         true
     }
 }
+
+/// Разделяет запрос на фразы в кавычках и остальной текст
+/// В режиме `exact` весь запрос целиком считается одной фразой
+fn split_query_phrases(query: &str, exact: bool) -> (Vec<String>, String) {
+    if exact {
+        let phrase = normalize_whitespace(&query.replace('"', " ").to_lowercase());
+        return (if phrase.is_empty() { Vec::new() } else { vec![phrase] }, String::new());
+    }
+
+    let mut phrases = Vec::new();
+    let mut rest = Vec::new();
+
+    // Четные части - вне кавычек, нечетные - внутри
+    for (i, part) in query.split('"').enumerate() {
+        let part = normalize_whitespace(part);
+        if part.is_empty() {
+            continue;
+        }
+        if i % 2 == 1 {
+            phrases.push(part.to_lowercase());
+        } else {
+            rest.push(part);
+        }
+    }
+
+    (phrases, rest.join(" "))
+}
+
+/// Схлопывает последовательности пробельных символов в один пробел
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Проверяет, что фраза встречается в тексте как отдельные слова,
+/// то есть не является частью более длинного слова
+fn contains_phrase(text: &str, phrase: &str) -> bool {
+    text.match_indices(phrase).any(|(start, _)| {
+        let end = start + phrase.len();
+        let before_ok = !matches!(text[..start].chars().next_back(), Some(c) if c.is_alphanumeric());
+        let after_ok = !matches!(text[end..].chars().next(), Some(c) if c.is_alphanumeric());
+        before_ok && after_ok
+    })
+}
@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::error::{PromptToolError, Result};
+use crate::prompt::SearchFilter;
+
+/// Максимальная глубина вложенности макросов; защищает от циклов вида `@a -> @b -> @a`
+pub const MAX_EXPANSION_DEPTH: usize = 8;
+
+/// Макросы по умолчанию
+/// `@new` - промпты, измененные за последнюю неделю
+pub fn default_macros() -> BTreeMap<String, String> {
+    BTreeMap::from([("new".to_string(), "updated:7d".to_string())])
+}
+
+/// Допустимое имя макроса: латинские буквы, цифры, `_` и `-`
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Проверяет имя и раскрытие макроса перед сохранением
+pub fn validate_macro(name: &str, expansion: &str, macros: &BTreeMap<String, String>) -> Result<()> {
+    if !is_valid_name(name) {
+        return Err(PromptToolError::Validation(format!(
+            "Имя макроса \"{}\" может содержать только латинские буквы, цифры, _ и -",
+            name
+        )));
+    }
+
+    let mut updated = macros.clone();
+    updated.insert(name.to_string(), expansion.to_string());
+    expand_query(&format!("@{}", name), &updated).map(|_| ())
+}
+
+fn expand_words(query: &str, macros: &BTreeMap<String, String>, depth: usize, output: &mut Vec<String>) -> Result<()> {
+    for word in query.split_whitespace() {
+        let expansion = word
+            .strip_prefix('@')
+            .and_then(|name| macros.get(name).map(|expansion| (name, expansion)));
+
+        match expansion {
+            Some((name, _)) if depth >= MAX_EXPANSION_DEPTH => {
+                return Err(PromptToolError::Validation(format!(
+                    "Макрос @{} раскрывается сам в себя",
+                    name
+                )));
+            }
+            Some((_, expansion)) => expand_words(expansion, macros, depth + 1, output)?,
+            // Неизвестные @слова остаются в запросе как обычный текст
+            None => output.push(word.to_string()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Заменяет макросы `@имя` в запросе их раскрытием; вложенные макросы раскрываются рекурсивно
+pub fn expand_query(query: &str, macros: &BTreeMap<String, String>) -> Result<String> {
+    let mut words = Vec::new();
+    expand_words(query, macros, 0, &mut words)?;
+    Ok(words.join(" "))
+}
+
+/// Разбирает длительность вида `7d`, `12h` или `2w`
+/// Отрицательные и не представимые длительности не принимаются
+fn parse_period(value: &str) -> Option<Duration> {
+    let unit = value.chars().last()?;
+    let amount: i64 = value[..value.len() - unit.len_utf8()].parse().ok()?;
+    if amount < 0 {
+        return None;
+    }
+
+    match unit {
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        'w' => Duration::try_weeks(amount),
+        _ => None,
+    }
+}
+
+/// Раскрывает макросы в запросе фильтра и переносит директивы в поля фильтра:
+/// `tag:имя` и `category:имя` добавляют тег или категорию, `updated:7d` ограничивает дату изменения.
+/// Остальные слова остаются текстовым запросом
+pub fn apply_macros(filter: &mut SearchFilter, macros: &BTreeMap<String, String>, now: DateTime<Utc>) -> Result<()> {
+    let Some(query) = filter.query.as_deref() else {
+        return Ok(());
+    };
+
+    let expanded = expand_query(query, macros)?;
+    let mut words = Vec::new();
+
+    for word in expanded.split_whitespace() {
+        if let Some(tag) = word.strip_prefix("tag:").filter(|tag| !tag.is_empty()) {
+            filter.tags.get_or_insert_with(Vec::new).push(tag.to_string());
+        } else if let Some(category) = word.strip_prefix("category:").filter(|category| !category.is_empty()) {
+            filter.categories.get_or_insert_with(Vec::new).push(category.to_string());
+        } else if let Some(period) = word.strip_prefix("updated:") {
+            let invalid = || {
+                PromptToolError::Validation(format!("Некорректный период \"{}\", ожидается например 7d, 12h или 2w", period))
+            };
+            let from = parse_period(period)
+                .and_then(|duration| now.checked_sub_signed(duration))
+                .ok_or_else(invalid)?;
+            filter.date_from = Some(filter.date_from.map_or(from, |date_from| date_from.max(from)));
+        } else {
+            words.push(word);
+        }
+    }
+
+    filter.query = (!words.is_empty()).then(|| words.join(" "));
+    Ok(())
+}
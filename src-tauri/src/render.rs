@@ -0,0 +1,741 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::error::{PromptToolError, Result};
+use crate::parameters::{validate_values, with_defaults, ParameterError};
+use crate::prompt::{Prompt, PromptList};
+
+/// Директива включения другого промпта: `{{include:название}}`
+/// После `{{` допускаются пробелы, как в выражениях Tera: `{{ include:название }}`
+const INCLUDE_DIRECTIVE: &str = "include:";
+
+/// Конец директивы включения
+const INCLUDE_CLOSE: &str = "}}";
+
+/// Директива секрета из системной связки ключей: `{{secret:ИМЯ}}`
+const SECRET_DIRECTIVE: &str = "secret:";
+
+/// Префикс служебных переменных, в которые переносятся значения секретов
+const SECRET_VARIABLE_PREFIX: &str = "__secret_";
+
+/// Встроенная функция шаблона, применяемая как `{param|name}` или `{param|name:arg}`
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct TemplateFunction {
+    /// Имя функции
+    pub name: &'static str,
+
+    /// Описание аргумента, если функция его принимает
+    pub argument: Option<&'static str>,
+
+    /// Что делает функция
+    pub description: &'static str,
+
+    /// Пример использования
+    pub example: &'static str,
+}
+
+/// Все встроенные функции шаблонов
+pub const TEMPLATE_FUNCTIONS: &[TemplateFunction] = &[
+    TemplateFunction {
+        name: "upper",
+        argument: None,
+        description: "Переводит текст в верхний регистр",
+        example: "{name|upper}",
+    },
+    TemplateFunction {
+        name: "lower",
+        argument: None,
+        description: "Переводит текст в нижний регистр",
+        example: "{name|lower}",
+    },
+    TemplateFunction {
+        name: "trim",
+        argument: None,
+        description: "Убирает пробелы и переводы строк по краям",
+        example: "{text|trim}",
+    },
+    TemplateFunction {
+        name: "slugify",
+        argument: None,
+        description: "Превращает текст в идентификатор из букв, цифр и дефисов",
+        example: "{title|slugify}",
+    },
+    TemplateFunction {
+        name: "truncate",
+        argument: Some("максимальное количество символов"),
+        description: "Обрезает текст до указанной длины, добавляя многоточие",
+        example: "{text|truncate:200}",
+    },
+    TemplateFunction {
+        name: "json-escape",
+        argument: None,
+        description: "Экранирует текст для вставки внутрь строки JSON",
+        example: "\"{text|json-escape}\"",
+    },
+    TemplateFunction {
+        name: "default",
+        argument: Some("значение по умолчанию"),
+        description: "Подставляет значение, если параметр не передан",
+        example: "{language|default:русский}",
+    },
+];
+
+/// Функция шаблона вместе с аргументом
+struct Filter<'a> {
+    name: &'a str,
+    argument: Option<&'a str>,
+}
+
+/// Разобранный плейсхолдер `{name|filter|filter:arg}`
+struct Placeholder<'a> {
+    name: &'a str,
+    filters: Vec<Filter<'a>>,
+}
+
+/// Разбирает содержимое фигурных скобок; `None`, если это не плейсхолдер
+/// (например, фигурные скобки в коде или неизвестная функция)
+fn parse_placeholder(inner: &str) -> Option<Placeholder<'_>> {
+    let mut parts = inner.split('|');
+    let name = parts.next()?.trim();
+    if !is_placeholder_name(name) {
+        return None;
+    }
+
+    let filters = parts
+        .map(|part| {
+            let (filter_name, argument) = match part.split_once(':') {
+                Some((filter_name, argument)) => (filter_name.trim(), Some(argument)),
+                None => (part.trim(), None),
+            };
+            TEMPLATE_FUNCTIONS
+                .iter()
+                .any(|function| function.name == filter_name)
+                .then_some(Filter { name: filter_name, argument })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(Placeholder { name, filters })
+}
+
+/// Превращает текст в идентификатор: буквы и цифры в нижнем регистре, остальное - дефисы
+pub(crate) fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Обрезает текст до `max_chars` символов с многоточием
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Экранирует текст для вставки внутрь строки JSON (без внешних кавычек)
+fn json_escape(text: &str) -> String {
+    let quoted = serde_json::Value::String(text.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// Применяет функции к значению параметра; `None` - значение так и не определено
+fn apply_filters(value: Option<&String>, filters: &[Filter]) -> Option<String> {
+    let mut value = value.cloned();
+
+    for filter in filters {
+        value = match (filter.name, value) {
+            ("default", None) => filter.argument.map(str::to_string),
+            (_, None) => None,
+            ("upper", Some(v)) => Some(v.to_uppercase()),
+            ("lower", Some(v)) => Some(v.to_lowercase()),
+            ("trim", Some(v)) => Some(v.trim().to_string()),
+            ("slugify", Some(v)) => Some(slugify(&v)),
+            ("truncate", Some(v)) => match filter.argument.and_then(|arg| arg.trim().parse().ok()) {
+                Some(max_chars) => Some(truncate(&v, max_chars)),
+                None => Some(v),
+            },
+            ("json-escape", Some(v)) => Some(json_escape(&v)),
+            (_, Some(v)) => Some(v),
+        };
+    }
+
+    value
+}
+
+/// Ошибка шаблона с позицией в тексте промпта, если ее удалось определить
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TemplateError {
+    /// Описание ошибки
+    pub message: String,
+
+    /// Номер строки (с 1)
+    pub line: Option<usize>,
+
+    /// Номер столбца (с 1)
+    pub column: Option<usize>,
+}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "{} (строка {}, столбец {})", self.message, line, column),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Имя шаблона внутри движка Tera
+const TEMPLATE_NAME: &str = "prompt";
+
+/// Префикс служебных переменных, в которые переносятся значения простых плейсхолдеров
+const LEGACY_VARIABLE_PREFIX: &str = "__placeholder_";
+
+/// Конец конструкции Tera, если текст начинается с `{{`, `{%` или `{#`
+fn tera_block_close(text: &str) -> Option<&'static str> {
+    match text.get(..2)? {
+        "{{" => Some("}}"),
+        "{%" => Some("%}"),
+        "{#" => Some("#}"),
+        _ => None,
+    }
+}
+
+/// Шаблон после перевода простых плейсхолдеров в переменные Tera
+struct ConvertedTemplate {
+    /// Текст шаблона для Tera
+    template: String,
+
+    /// Служебные переменные и их заранее вычисленные значения
+    bindings: Vec<(String, String)>,
+
+    /// Имена плейсхолдеров, для которых не нашлось значения, в порядке появления
+    unresolved: Vec<String>,
+}
+
+/// Переводит простые плейсхолдеры `{param|filter}` в переменные Tera.
+/// Значение плейсхолдера вычисляется заранее и передается в контекст, поэтому
+/// пользовательский текст никогда не разбирается как шаблон. Плейсхолдеры без значения
+/// и конструкции Tera (`{{ }}`, `{% %}`, `{# #}`) остаются как есть
+fn convert_placeholders(template: &str, values: &HashMap<String, String>) -> ConvertedTemplate {
+    let mut output = String::with_capacity(template.len());
+    let mut bindings = Vec::new();
+    let mut unresolved: Vec<String> = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let tail = &rest[start..];
+
+        if let Some(close) = tera_block_close(tail) {
+            let end = tail[2..].find(close).map_or(tail.len(), |end| end + 2 + close.len());
+            output.push_str(&tail[..end]);
+            rest = &tail[end..];
+            continue;
+        }
+
+        let after = &tail[1..];
+        let placeholder = after.find('}').and_then(|end| Some((end, parse_placeholder(&after[..end])?)));
+
+        match placeholder {
+            Some((end, placeholder)) => {
+                match apply_filters(values.get(placeholder.name), &placeholder.filters) {
+                    Some(value) => {
+                        let variable = format!("{}{}", LEGACY_VARIABLE_PREFIX, bindings.len());
+                        output.push_str(&format!("{{{{ {} }}}}", variable));
+                        bindings.push((variable, value));
+                    }
+                    // Без значения плейсхолдер остается текстом; одиночные скобки Tera не трогает
+                    None => {
+                        output.push_str(&tail[..end + 2]);
+                        if !unresolved.iter().any(|name| name == placeholder.name) {
+                            unresolved.push(placeholder.name.to_string());
+                        }
+                    }
+                }
+                rest = &after[end + 1..];
+            }
+            // Не плейсхолдер (например, фигурные скобки в коде): оставляем скобку и ищем дальше
+            None => {
+                output.push('{');
+                rest = after;
+            }
+        }
+    }
+
+    output.push_str(rest);
+    ConvertedTemplate {
+        template: output,
+        bindings,
+        unresolved,
+    }
+}
+
+/// Имена простых плейсхолдеров `{param}` в шаблоне в порядке первого появления
+pub fn find_placeholders(template: &str) -> Vec<String> {
+    convert_placeholders(template, &HashMap::new()).unresolved
+}
+
+/// Простой плейсхолдер при переводе шаблона в другой формат
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlaceholderRef<'a> {
+    pub name: &'a str,
+
+    /// Аргумент функции `default`, если она указана
+    pub default: Option<&'a str>,
+}
+
+/// Переписывает шаблон для другого формата: текст вне простых плейсхолдеров
+/// (включая конструкции Tera) проходит через `text`, каждый плейсхолдер - через `placeholder`
+pub fn rewrite_placeholders(
+    template: &str,
+    mut text: impl FnMut(&str) -> String,
+    mut placeholder: impl FnMut(PlaceholderRef<'_>) -> String,
+) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let tail = &rest[start..];
+
+        if let Some(close) = tera_block_close(tail) {
+            let end = tail[2..].find(close).map_or(tail.len(), |end| end + 2 + close.len());
+            output.push_str(&text(&rest[..start + end]));
+            rest = &tail[end..];
+            continue;
+        }
+
+        let after = &tail[1..];
+        let Some((end, parsed)) = after.find('}').and_then(|end| Some((end, parse_placeholder(&after[..end])?))) else {
+            output.push_str(&text(&rest[..start + 1]));
+            rest = after;
+            continue;
+        };
+
+        output.push_str(&text(&rest[..start]));
+        let default = parsed.filters
+            .iter()
+            .find(|filter| filter.name == "default")
+            .and_then(|filter| filter.argument);
+        output.push_str(&placeholder(PlaceholderRef { name: parsed.name, default }));
+        rest = &after[end + 1..];
+    }
+
+    output.push_str(&text(rest));
+    output
+}
+
+/// Фильтр Tera `json_escape`, аналог функции `json-escape` простых плейсхолдеров
+fn tera_json_escape(value: &tera::Value, _args: &HashMap<String, tera::Value>) -> tera::Result<tera::Value> {
+    let text = match value {
+        tera::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    Ok(tera::Value::String(json_escape(&text)))
+}
+
+/// Превращает ошибку Tera в ошибку шаблона, извлекая позицию из сообщения разборщика (`--> строка:столбец`)
+fn template_error(error: tera::Error) -> TemplateError {
+    let mut messages = vec![error.to_string()];
+    let mut source = std::error::Error::source(&error);
+    while let Some(inner) = source {
+        messages.push(inner.to_string());
+        source = inner.source();
+    }
+    let message = messages.join(": ");
+
+    let position = message
+        .split("-->")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|position| position.split_once(':'))
+        .and_then(|(line, column)| Some((line.parse().ok()?, column.parse().ok()?)));
+
+    TemplateError {
+        message: messages.last().cloned().unwrap_or_default(),
+        line: position.map(|(line, _)| line),
+        column: position.map(|(_, column)| column),
+    }
+}
+
+/// Формирует текст по шаблону промпта.
+///
+/// Поддерживаются простые плейсхолдеры `{param}` со встроенными функциями (`{text|trim|truncate:200}`)
+/// и синтаксис Tera: `{{ param }}`, `{% if %}`, `{% for %}`, фильтры Tera.
+/// Простые плейсхолдеры без переданного значения остаются в тексте как есть
+pub fn render_template(template: &str, values: &HashMap<String, String>) -> std::result::Result<String, TemplateError> {
+    render_with_unresolved(template, values).map(|(text, _)| text)
+}
+
+/// Формирует текст по шаблону и возвращает имена плейсхолдеров, оставшихся без значения
+fn render_with_unresolved(
+    template: &str,
+    values: &HashMap<String, String>,
+) -> std::result::Result<(String, Vec<String>), TemplateError> {
+    let converted = convert_placeholders(template, values);
+
+    let mut tera = tera::Tera::default();
+    tera.register_filter("json_escape", tera_json_escape);
+    tera.add_raw_template(TEMPLATE_NAME, &converted.template)
+        .map_err(template_error)?;
+
+    let mut context = tera::Context::new();
+    for (name, value) in values {
+        context.insert(name.as_str(), value);
+    }
+    for (name, value) in &converted.bindings {
+        context.insert(name.as_str(), value);
+    }
+
+    let text = tera.render(TEMPLATE_NAME, &context)
+        .map_err(template_error)?;
+    Ok((text, converted.unresolved))
+}
+
+/// Имя параметра: буквы, цифры, `_` и `-`
+fn is_placeholder_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Ищет директиву `{{директива` (пробелы после `{{` допускаются)
+/// Возвращает позицию `{{` и позицию сразу после названия директивы
+fn find_directive(text: &str, directive: &str) -> Option<(usize, usize)> {
+    let mut offset = 0;
+    while let Some(found) = text[offset..].find("{{") {
+        let start = offset + found;
+        let inner = &text[start + 2..];
+        let trimmed = inner.trim_start_matches([' ', '\t']);
+        if trimmed.starts_with(directive) {
+            return Some((start, start + 2 + (inner.len() - trimmed.len()) + directive.len()));
+        }
+        offset = start + 2;
+    }
+    None
+}
+
+/// Заменяет директивы `{{include:название}}` в тексте промпта содержимым указанных промптов
+/// Включения раскрываются рекурсивно; цикл или ссылка на несуществующий промпт дают ошибку
+pub fn expand_includes(prompt: &Prompt, library: &PromptList) -> Result<String> {
+    let mut stack = vec![prompt.name.clone()];
+    expand_content(&prompt.content, library, &mut stack)
+}
+
+fn expand_content(content: &str, library: &PromptList, stack: &mut Vec<String>) -> Result<String> {
+    let mut output = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some((start, name_start)) = find_directive(rest, INCLUDE_DIRECTIVE) {
+        let after = &rest[name_start..];
+        let Some(end) = after.find(INCLUDE_CLOSE) else {
+            break;
+        };
+
+        let name = after[..end].trim();
+        if stack.iter().any(|included| included == name) {
+            return Err(PromptToolError::Validation(format!(
+                "Циклическое включение промптов: {} -> {}",
+                stack.join(" -> "),
+                name
+            )));
+        }
+
+        let included = library.prompts
+            .iter()
+            .find(|prompt| prompt.name == name)
+            .ok_or_else(|| PromptToolError::Validation(format!(
+                "Промпт \"{}\" включает несуществующий промпт \"{}\"",
+                stack.last().map(String::as_str).unwrap_or_default(),
+                name
+            )))?;
+
+        stack.push(name.to_string());
+        let expanded = expand_content(&included.content, library, stack)?;
+        stack.pop();
+
+        output.push_str(&rest[..start]);
+        output.push_str(&expanded);
+        rest = &after[end + INCLUDE_CLOSE.len()..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Шаблон, в котором директивы секретов заменены переменными Tera
+#[derive(Clone, Default)]
+pub struct SecretBindings {
+    /// Текст шаблона
+    pub template: String,
+
+    /// Служебные переменные и значения секретов; добавляются к значениям параметров
+    pub values: HashMap<String, String>,
+}
+
+/// Заменяет директивы `{{secret:ИМЯ}}` переменными Tera, значения которых возвращает `lookup`
+/// Значение секрета попадает в контекст шаблона, а не в его текст, поэтому никогда не разбирается как шаблон.
+/// Каждый секрет запрашивается один раз, сколько бы раз он ни встречался
+pub fn bind_secrets(template: &str, mut lookup: impl FnMut(&str) -> Result<String>) -> Result<SecretBindings> {
+    let mut output = String::with_capacity(template.len());
+    let mut variables: HashMap<String, String> = HashMap::new();
+    let mut values = HashMap::new();
+    let mut rest = template;
+
+    while let Some((start, name_start)) = find_directive(rest, SECRET_DIRECTIVE) {
+        let after = &rest[name_start..];
+        let Some(end) = after.find(INCLUDE_CLOSE) else {
+            break;
+        };
+
+        let name = after[..end].trim();
+        let variable = match variables.get(name) {
+            Some(variable) => variable.clone(),
+            None => {
+                let variable = format!("{}{}", SECRET_VARIABLE_PREFIX, variables.len());
+                values.insert(variable.clone(), lookup(name)?);
+                variables.insert(name.to_string(), variable.clone());
+                variable
+            }
+        };
+
+        output.push_str(&rest[..start]);
+        output.push_str(&format!("{{{{ {} }}}}", variable));
+        rest = &after[end + INCLUDE_CLOSE.len()..];
+    }
+
+    output.push_str(rest);
+    Ok(SecretBindings { template: output, values })
+}
+
+/// Заменяет в готовом тексте значения секретов маской, например для журнала выполнений
+/// Длинные значения заменяются первыми, чтобы секрет, входящий в другой, не оставил хвост
+pub fn mask_secrets(text: &str, bindings: &SecretBindings, mask: &str) -> String {
+    let mut secrets: Vec<&str> = bindings.values
+        .values()
+        .map(String::as_str)
+        .filter(|value| !value.is_empty())
+        .collect();
+    secrets.sort_by_key(|value| std::cmp::Reverse(value.len()));
+
+    secrets.into_iter().fold(text.to_string(), |text, secret| text.replace(secret, mask))
+}
+
+/// Результат подстановки параметров в промпт
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderOutput {
+    /// Итоговый текст; `None`, если значения параметров не прошли проверку или шаблон содержит ошибку
+    pub text: Option<String>,
+
+    /// Ошибки значений по параметрам
+    pub errors: Vec<ParameterError>,
+
+    /// Ошибка разбора или выполнения шаблона
+    pub template_error: Option<TemplateError>,
+
+    /// Плейсхолдеры, оставшиеся в тексте без значения (опечатка в имени или пропущенное значение)
+    pub warnings: Vec<RenderWarning>,
+}
+
+/// Предупреждение о плейсхолдере, оставшемся в тексте без значения
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RenderWarning {
+    /// Имя плейсхолдера
+    pub placeholder: String,
+
+    /// Описание для пользователя
+    pub message: String,
+}
+
+impl RenderOutput {
+    /// Есть ли в тексте плейсхолдеры без значения
+    pub fn has_unresolved(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+}
+
+/// Формирует итоговый текст промпта из его шаблона и значений параметров
+/// Для непереданных параметров используются их значения по умолчанию
+pub fn render_prompt(prompt: &Prompt, values: &HashMap<String, String>) -> std::result::Result<String, TemplateError> {
+    render_template(&prompt.content, &with_defaults(prompt, values))
+}
+
+/// Проверяет значения параметров по ограничениям промпта и, если ошибок нет, формирует текст
+/// Для непереданных параметров используются их значения по умолчанию
+pub fn render_validated(prompt: &Prompt, values: &HashMap<String, String>) -> RenderOutput {
+    let values = &with_defaults(prompt, values);
+    let errors = validate_values(prompt, values);
+    if !errors.is_empty() {
+        return RenderOutput { text: None, errors, template_error: None, warnings: Vec::new() };
+    }
+
+    match render_with_unresolved(&prompt.content, values) {
+        Ok((text, unresolved)) => {
+            let warnings = unresolved
+                .into_iter()
+                .map(|placeholder| RenderWarning {
+                    message: format!("Для плейсхолдера {{{}}} не передано значение", placeholder),
+                    placeholder,
+                })
+                .collect();
+            RenderOutput { text: Some(text), errors, template_error: None, warnings }
+        }
+        Err(error) => RenderOutput { text: None, errors, template_error: Some(error), warnings: Vec::new() },
+    }
+}
+
+/// Шаг разворачивания шаблона в режиме отладки
+#[derive(Debug, Clone, Serialize)]
+pub struct DebugStep {
+    /// Этап: `source`, `include`, `values`, `placeholder`, `template`, `hook`
+    pub stage: &'static str,
+
+    /// Что произошло на шаге
+    pub description: String,
+
+    /// Текст после шага
+    pub text: String,
+}
+
+/// Пошаговое разворачивание шаблона и итог подстановки
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderDebug {
+    /// Шаги в порядке выполнения
+    pub steps: Vec<DebugStep>,
+
+    /// Итог, совпадающий с обычной подстановкой
+    pub output: RenderOutput,
+}
+
+/// Описание значения для шагов отладки
+fn describe_value(value: Option<&str>) -> String {
+    match value {
+        Some(value) => format!("{:?}", value),
+        None => "нет значения".to_string(),
+    }
+}
+
+/// Раскрывает включения верхнего уровня по одному, записывая шаг на каждое
+fn debug_includes(prompt: &Prompt, library: &PromptList, steps: &mut Vec<DebugStep>) -> Result<String> {
+    let mut text = prompt.content.clone();
+
+    while let Some((start, after)) = find_directive(&text, INCLUDE_DIRECTIVE) {
+        let Some(end) = text[after..].find(INCLUDE_CLOSE).map(|end| after + end) else {
+            break;
+        };
+
+        let name = text[after..end].trim().to_string();
+        let directive = text[start..end + INCLUDE_CLOSE.len()].to_string();
+        let mut stack = vec![prompt.name.clone()];
+        let expanded = expand_content(&directive, library, &mut stack)?;
+
+        text.replace_range(start..end + INCLUDE_CLOSE.len(), &expanded);
+        steps.push(DebugStep {
+            stage: "include",
+            description: format!("{} заменено текстом промпта \"{}\" (вложенные включения тоже раскрыты)", directive, name),
+            text: text.clone(),
+        });
+    }
+
+    Ok(text)
+}
+
+/// Подставляет простые плейсхолдеры по одному, записывая значение после каждой функции
+fn debug_placeholders(template: &str, values: &HashMap<String, String>, steps: &mut Vec<DebugStep>) {
+    let mut done = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        done.push_str(&rest[..start]);
+        let tail = &rest[start..];
+
+        if let Some(close) = tera_block_close(tail) {
+            let end = tail[2..].find(close).map_or(tail.len(), |end| end + 2 + close.len());
+            done.push_str(&tail[..end]);
+            rest = &tail[end..];
+            continue;
+        }
+
+        let after = &tail[1..];
+        let Some((end, placeholder)) = after.find('}').and_then(|end| Some((end, parse_placeholder(&after[..end])?))) else {
+            done.push('{');
+            rest = after;
+            continue;
+        };
+
+        let source = &tail[..end + 2];
+        let mut value = values.get(placeholder.name).cloned();
+        let mut trace = vec![format!("{} = {}", placeholder.name, describe_value(value.as_deref()))];
+        for filter in &placeholder.filters {
+            value = apply_filters(value.as_ref(), std::slice::from_ref(filter));
+            let label = match filter.argument {
+                Some(argument) => format!("{}:{}", filter.name, argument),
+                None => filter.name.to_string(),
+            };
+            trace.push(format!("{} -> {}", label, describe_value(value.as_deref())));
+        }
+
+        rest = &after[end + 1..];
+        let description = match &value {
+            Some(value) => {
+                done.push_str(value);
+                format!("{}: {}", source, trace.join(", "))
+            }
+            None => {
+                done.push_str(source);
+                format!("{}: {}; плейсхолдер оставлен как есть", source, trace.join(", "))
+            }
+        };
+        steps.push(DebugStep { stage: "placeholder", description, text: format!("{}{}", done, rest) });
+    }
+}
+
+/// Разворачивает шаблон пошагово: включения, значения параметров (с учетом значений
+/// по умолчанию), каждый плейсхолдер с его функциями и, наконец, конструкции Tera.
+/// Итог совпадает с `render_validated` для промпта с раскрытыми включениями
+pub fn debug_render(prompt: &Prompt, library: &PromptList, values: &HashMap<String, String>) -> Result<RenderDebug> {
+    let mut steps = vec![DebugStep {
+        stage: "source",
+        description: "Исходный текст промпта".to_string(),
+        text: prompt.content.clone(),
+    }];
+
+    let content = debug_includes(prompt, library, &mut steps)?;
+
+    let passed = values;
+    let values = with_defaults(prompt, passed);
+    let mut names: Vec<&String> = values.keys().collect();
+    names.sort();
+    let listed: Vec<String> = names
+        .into_iter()
+        .map(|name| {
+            let default = if passed.contains_key(name) { "" } else { " (по умолчанию)" };
+            format!("{} = {:?}{}", name, values[name], default)
+        })
+        .collect();
+    steps.push(DebugStep {
+        stage: "values",
+        description: format!("Значения параметров: {}", if listed.is_empty() { "нет".to_string() } else { listed.join(", ") }),
+        text: content.clone(),
+    });
+
+    debug_placeholders(&content, &values, &mut steps);
+
+    let expanded = Prompt { content, ..prompt.clone() };
+    let output = render_validated(&expanded, &values);
+    if let Some(text) = &output.text {
+        steps.push(DebugStep {
+            stage: "template",
+            description: "Выполнены конструкции Tera ({{ }}, {% %})".to_string(),
+            text: text.clone(),
+        });
+    }
+
+    Ok(RenderDebug { steps, output })
+}
@@ -0,0 +1,95 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+
+use crate::prompt::PromptList;
+use crate::usage::UsageStats;
+
+/// Сколько строк выводить в рейтингах отчета
+const TOP_LIMIT: usize = 10;
+
+/// Экранирует символы, ломающие ячейки Markdown-таблицы
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Формирует Markdown-отчет по библиотеке: общие показатели, популярные теги,
+/// часто используемые промпты и рост библиотеки по месяцам
+pub fn build_stats_report(list: &PromptList, usage: &UsageStats, generated_at: DateTime<Utc>) -> String {
+    let mut report = String::new();
+
+    let _ = writeln!(report, "# Статистика библиотеки промптов");
+    let _ = writeln!(report);
+    let _ = writeln!(report, "_Сформировано {}_", generated_at.format("%Y-%m-%d %H:%M UTC"));
+    let _ = writeln!(report);
+
+    let total_chars: usize = list.prompts.iter().map(|p| p.content.chars().count()).sum();
+    let average_chars = if list.prompts.is_empty() { 0 } else { total_chars / list.prompts.len() };
+
+    let _ = writeln!(report, "## Общие показатели");
+    let _ = writeln!(report);
+    let _ = writeln!(report, "| Показатель | Значение |");
+    let _ = writeln!(report, "|---|---|");
+    let _ = writeln!(report, "| Промптов | {} |", list.prompts.len());
+    let _ = writeln!(report, "| Категорий | {} |", list.get_categories().len());
+    let _ = writeln!(report, "| Тегов | {} |", list.get_tags().len());
+    let _ = writeln!(report, "| С параметрами | {} |", list.prompts.iter().filter(|p| !p.parameters.is_empty()).count());
+    let _ = writeln!(report, "| Средняя длина, символов | {} |", average_chars);
+    let _ = writeln!(report);
+
+    let mut tag_counts: HashMap<&str, usize> = HashMap::new();
+    for tag in list.prompts.iter().flat_map(|p| p.tags.iter()) {
+        *tag_counts.entry(tag.as_str()).or_default() += 1;
+    }
+    let mut top_tags: Vec<(&str, usize)> = tag_counts.into_iter().collect();
+    top_tags.sort_by(|(a_tag, a), (b_tag, b)| b.cmp(a).then(a_tag.cmp(b_tag)));
+
+    let _ = writeln!(report, "## Популярные теги");
+    let _ = writeln!(report);
+    if top_tags.is_empty() {
+        let _ = writeln!(report, "Теги не используются.");
+    } else {
+        let _ = writeln!(report, "| Тег | Промптов |");
+        let _ = writeln!(report, "|---|---|");
+        for (tag, count) in top_tags.iter().take(TOP_LIMIT) {
+            let _ = writeln!(report, "| {} | {} |", cell(tag), count);
+        }
+    }
+    let _ = writeln!(report);
+
+    let _ = writeln!(report, "## Часто используемые промпты");
+    let _ = writeln!(report);
+    let most_used = usage.most_used(TOP_LIMIT);
+    if most_used.is_empty() {
+        let _ = writeln!(report, "Данных об использовании пока нет.");
+    } else {
+        let _ = writeln!(report, "| Промпт | Использований | Последний раз |");
+        let _ = writeln!(report, "|---|---|---|");
+        for (name, entry) in most_used {
+            let _ = writeln!(report, "| {} | {} | {} |", cell(name), entry.count, entry.last_used.format("%Y-%m-%d"));
+        }
+    }
+    let _ = writeln!(report);
+
+    let mut by_month: BTreeMap<String, usize> = BTreeMap::new();
+    for prompt in &list.prompts {
+        *by_month.entry(prompt.created_at.format("%Y-%m").to_string()).or_default() += 1;
+    }
+
+    let _ = writeln!(report, "## Рост библиотеки");
+    let _ = writeln!(report);
+    if by_month.is_empty() {
+        let _ = writeln!(report, "Библиотека пуста.");
+    } else {
+        let _ = writeln!(report, "| Месяц | Добавлено | Всего |");
+        let _ = writeln!(report, "|---|---|---|");
+        let mut total = 0;
+        for (month, added) in by_month {
+            total += added;
+            let _ = writeln!(report, "| {} | {} | {} |", month, added, total);
+        }
+    }
+
+    report
+}
@@ -0,0 +1,122 @@
+//! История изменений промптов.
+//!
+//! При каждом сохранении измененного промпта его снимок дописывается строкой JSON в файл истории.
+//! Ревизии нумеруются отдельно для каждого промпта, начиная с 1; первой ревизией становится
+//! состояние промпта до первого изменения.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+use crate::prompt::Prompt;
+
+/// Снимок промпта после сохранения
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptRevision {
+    /// Название промпта
+    pub prompt_name: String,
+
+    /// Номер ревизии среди ревизий этого промпта
+    pub revision: u32,
+
+    /// Момент сохранения
+    pub saved_at: DateTime<Utc>,
+
+    /// Промпт в момент сохранения
+    pub prompt: Prompt,
+}
+
+/// История ревизий в файле формата JSON Lines
+#[derive(Debug, Clone)]
+pub struct RevisionStore {
+    path: PathBuf,
+}
+
+impl RevisionStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Путь к файлу истории
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Все ревизии в порядке записи; поврежденные строки пропускаются
+    fn read_all(&self) -> Result<Vec<PromptRevision>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(PromptToolError::Io(e)),
+        };
+
+        Ok(contents
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// Ревизии промпта от старых к новым
+    pub fn list(&self, prompt_name: &str) -> Result<Vec<PromptRevision>> {
+        Ok(self.read_all()?
+            .into_iter()
+            .filter(|revision| revision.prompt_name == prompt_name)
+            .collect())
+    }
+
+    /// Ревизия промпта по номеру
+    pub fn get(&self, prompt_name: &str, revision: u32) -> Result<PromptRevision> {
+        self.list(prompt_name)?
+            .into_iter()
+            .find(|entry| entry.revision == revision)
+            .ok_or_else(|| PromptToolError::Validation(format!(
+                "Ревизия {} промпта \"{}\" не найдена",
+                revision, prompt_name
+            )))
+    }
+
+    /// Дописывает снимок промпта в историю и возвращает номер новой ревизии
+    pub fn record(&self, prompt: &Prompt) -> Result<u32> {
+        let revision = self.list(&prompt.name)?
+            .last()
+            .map_or(1, |last| last.revision + 1);
+
+        let entry = PromptRevision {
+            prompt_name: prompt.name.clone(),
+            revision,
+            saved_at: Utc::now(),
+            prompt: prompt.clone(),
+        };
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(PromptToolError::Io)?;
+        }
+
+        let line = serde_json::to_string(&entry)
+            .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации ревизии: {}", e)))?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(PromptToolError::Io)?;
+
+        writeln!(file, "{}", line)
+            .map_err(PromptToolError::Io)?;
+        Ok(revision)
+    }
+
+    /// Записывает изменение промпта: при первом изменении сохраняет и исходное состояние,
+    /// чтобы его можно было сравнить с новым
+    pub fn record_change(&self, before: &Prompt, after: &Prompt) -> Result<u32> {
+        if before.name == after.name && self.list(&before.name)?.is_empty() {
+            self.record(before)?;
+        }
+        self.record(after)
+    }
+}
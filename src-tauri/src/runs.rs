@@ -0,0 +1,142 @@
+//! Журнал выполнений промптов через LLM.
+//!
+//! Каждое выполнение сохраняется строкой JSON в файле журнала, чтобы итерации над промптом
+//! можно было сравнить и повторить: текст запроса, параметры, модель, ответ, время и токены.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+use crate::llm::Usage;
+
+/// Одно выполнение промпта
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Run {
+    /// Уникальный идентификатор выполнения
+    pub id: String,
+
+    /// Название выполненного промпта
+    pub prompt_name: String,
+
+    /// Текст, отправленный модели после подстановки параметров
+    pub rendered_input: String,
+
+    /// Значения параметров
+    pub parameters: HashMap<String, String>,
+
+    /// Модель, которая отвечала
+    pub model: String,
+
+    /// Ответ модели; `None`, если выполнение завершилось ошибкой
+    pub response: Option<String>,
+
+    /// Текст ошибки, если выполнение не удалось
+    pub error: Option<String>,
+
+    /// Время ответа в миллисекундах
+    pub latency_ms: u64,
+
+    /// Расход токенов, если сервер его сообщил
+    pub usage: Option<Usage>,
+
+    /// Момент выполнения
+    pub created_at: DateTime<Utc>,
+}
+
+impl Run {
+    /// Идентификатор на основе времени выполнения
+    pub fn new_id(created_at: DateTime<Utc>) -> String {
+        format!("{:x}", created_at.timestamp_nanos_opt().unwrap_or_default())
+    }
+}
+
+/// Журнал выполнений в файле формата JSON Lines
+#[derive(Debug, Clone)]
+pub struct RunStore {
+    path: PathBuf,
+}
+
+impl RunStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Путь к файлу журнала
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Дописывает выполнение в конец журнала
+    pub fn append(&self, run: &Run) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(PromptToolError::Io)?;
+        }
+
+        let line = serde_json::to_string(run)
+            .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации выполнения: {}", e)))?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(PromptToolError::Io)?;
+
+        writeln!(file, "{}", line)
+            .map_err(PromptToolError::Io)
+    }
+
+    /// Все выполнения из журнала в порядке записи; поврежденные строки пропускаются
+    fn read_all(&self) -> Result<Vec<Run>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(PromptToolError::Io(e)),
+        };
+
+        Ok(contents
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// Выполнения промпта (или всех промптов, если имя не указано), от новых к старым
+    pub fn list(&self, prompt_name: Option<&str>) -> Result<Vec<Run>> {
+        let mut runs: Vec<Run> = self.read_all()?
+            .into_iter()
+            .filter(|run| match prompt_name {
+                Some(name) => run.prompt_name == name,
+                None => true,
+            })
+            .collect();
+
+        runs.reverse();
+        Ok(runs)
+    }
+
+    /// Удаляет выполнение; возвращает `false`, если его нет в журнале
+    pub fn delete(&self, id: &str) -> Result<bool> {
+        let runs = self.read_all()?;
+        let remaining: Vec<&Run> = runs.iter().filter(|run| run.id != id).collect();
+        if remaining.len() == runs.len() {
+            return Ok(false);
+        }
+
+        let mut contents = String::new();
+        for run in remaining {
+            let line = serde_json::to_string(run)
+                .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации выполнения: {}", e)))?;
+            contents.push_str(&line);
+            contents.push('\n');
+        }
+
+        fs::write(&self.path, contents)
+            .map_err(PromptToolError::Io)?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,149 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use schemars::schema_for;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{PromptToolError, Result};
+use crate::file_io;
+use crate::migrations::{self, SCHEMA_VERSION_KEY};
+use crate::pack::PackManifest;
+use crate::prompt::PromptList;
+
+/// Имя файла со схемой библиотеки промптов (общей для TOML и JSON)
+pub const LIBRARY_SCHEMA_FILE: &str = "prompt-library.schema.json";
+
+/// Имя файла со схемой манифеста `.ptpack`
+pub const PACK_MANIFEST_SCHEMA_FILE: &str = "ptpack-manifest.schema.json";
+
+/// JSON Schema формата библиотеки промптов
+/// TOML и JSON имеют одинаковую структуру, поэтому схема у них общая
+pub fn library_schema() -> Value {
+    serde_json::to_value(schema_for!(PromptList)).unwrap_or_default()
+}
+
+/// JSON Schema манифеста пакета `.ptpack`
+pub fn pack_manifest_schema() -> Value {
+    serde_json::to_value(schema_for!(PackManifest)).unwrap_or_default()
+}
+
+/// Записывает все схемы в указанную директорию и возвращает пути созданных файлов
+pub fn export_schemas(output_dir: &str) -> Result<Vec<PathBuf>> {
+    let output_dir = Path::new(output_dir);
+    fs::create_dir_all(output_dir)
+        .map_err(PromptToolError::Io)?;
+
+    let schemas = [
+        (LIBRARY_SCHEMA_FILE, library_schema()),
+        (PACK_MANIFEST_SCHEMA_FILE, pack_manifest_schema()),
+    ];
+
+    let mut written = Vec::with_capacity(schemas.len());
+    for (file_name, schema) in schemas {
+        let path = output_dir.join(file_name);
+        let contents = serde_json::to_string_pretty(&schema)
+            .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации схемы: {}", e)))?;
+
+        fs::write(&path, contents)
+            .map_err(PromptToolError::Io)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Ошибка, найденная при проверке файла по схеме
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaError {
+    /// Путь к неверному значению в формате JSON Pointer, например `/prompts/0/name`
+    /// Пустой, если ошибка относится ко всему файлу (например, синтаксическая)
+    pub path: String,
+
+    /// Описание ошибки
+    pub message: String,
+}
+
+impl SchemaError {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { path: path.into(), message: message.into() }
+    }
+}
+
+/// Проверяет библиотеку промптов, представленную в виде JSON, по схеме библиотеки
+/// Возвращает все найденные ошибки; пустой список означает, что библиотека корректна.
+pub fn validate_library(library: &Value) -> Result<Vec<SchemaError>> {
+    let schema = library_schema();
+    let validator = jsonschema::validator_for(&schema)
+        .map_err(|e| PromptToolError::Config(format!("Некорректная схема библиотеки: {}", e)))?;
+
+    Ok(validator.iter_errors(library)
+        .map(|error| SchemaError::new(error.instance_path.to_string(), error.to_string()))
+        .collect())
+}
+
+/// Проверяет файл библиотеки (TOML или JSON) по схеме библиотеки
+/// Синтаксические ошибки возвращаются в списке вместе с позицией, а не ошибкой функции,
+/// поэтому файл можно проверить целиком до импорта. Файлы старого формата проверяются
+/// после обновления в памяти, так же как при загрузке.
+pub fn validate_file(path: &Path) -> Result<Vec<SchemaError>> {
+    let file_path = path.to_string_lossy();
+    let contents = file_io::read_prompt_file(&file_path)?;
+
+    let is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let library = if is_json {
+        match serde_json::from_str(&contents) {
+            Ok(library) => library,
+            Err(e) => {
+                let message = format!("Ошибка разбора JSON (строка {}, столбец {}): {}", e.line(), e.column(), e);
+                return Ok(vec![SchemaError::new("", message)]);
+            }
+        }
+    } else {
+        let contents = file_io::decrypt_contents(&file_path, &contents)?;
+        if contents.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut table: toml::Table = match toml::from_str(&contents) {
+            Ok(table) => table,
+            Err(e) => return Ok(vec![toml_syntax_error(&contents, &e)]),
+        };
+        if let Err(e) = migrations::migrate_table(&mut table, Utc::now()) {
+            return Ok(vec![SchemaError::new(format!("/{}", SCHEMA_VERSION_KEY), e.to_string())]);
+        }
+
+        toml_to_json(toml::Value::Table(table))
+    };
+
+    validate_library(&library)
+}
+
+/// Синтаксическая ошибка TOML с номером строки и столбца
+fn toml_syntax_error(contents: &str, error: &toml::de::Error) -> SchemaError {
+    let message = match error.span() {
+        Some(span) => {
+            let (line, column) = file_io::line_column(contents, span.start);
+            format!("Ошибка разбора TOML (строка {}, столбец {}): {}", line, column, error.message())
+        }
+        None => format!("Ошибка разбора TOML: {}", error.message()),
+    };
+
+    SchemaError::new("", message)
+}
+
+/// Преобразует значение TOML в JSON; даты и время становятся строками RFC 3339, как в схеме
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(string) => Value::String(string),
+        toml::Value::Integer(integer) => Value::from(integer),
+        toml::Value::Float(float) => serde_json::Number::from_f64(float).map_or(Value::Null, Value::Number),
+        toml::Value::Boolean(boolean) => Value::Bool(boolean),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(array) => Value::Array(array.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table.into_iter().map(|(key, value)| (key, toml_to_json(value))).collect()
+        ),
+    }
+}
@@ -0,0 +1,139 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tantivy::schema::{FieldType, Schema};
+
+use crate::database::{build_schema, parse_language, FieldBoosts};
+use crate::error::{PromptToolError, Result};
+
+/// Версия формата файла с настройками поиска
+pub const SEARCH_CONFIG_VERSION: u32 = 1;
+
+/// Поле схемы индекса в экспортированных настройках
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaField {
+    /// Имя поля
+    pub name: String,
+
+    /// Тип значения: `text` или `u64`
+    pub kind: String,
+
+    /// Токенизатор текстового поля
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokenizer: Option<String>,
+
+    /// Индексируется ли поле
+    pub indexed: bool,
+
+    /// Хранится ли значение в индексе
+    pub stored: bool,
+
+    /// Есть ли у поля колоночное хранилище для диапазонов и сортировки
+    pub fast: bool,
+}
+
+/// Настройки анализатора и ранжирования поискового индекса
+/// Экспортируются в JSON, чтобы поиск вел себя одинаково на разных установках
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchConfig {
+    /// Версия формата файла
+    pub version: u32,
+
+    /// Использовать ли поисковый индекс
+    pub index_enabled: bool,
+
+    /// Языки стемминга анализатора `multilang`
+    pub stemming_languages: Vec<String>,
+
+    /// Группы синонимов
+    pub synonyms: Vec<String>,
+
+    /// Веса полей при ранжировании
+    pub field_boosts: FieldBoosts,
+
+    /// Схема индекса; записывается для справки, при импорте не применяется,
+    /// т.к. определяется версией приложения
+    #[serde(default)]
+    pub schema: Vec<SchemaField>,
+}
+
+/// Описывает поля схемы индекса
+pub fn describe_schema(schema: &Schema) -> Vec<SchemaField> {
+    schema
+        .fields()
+        .map(|(_, entry)| {
+            let (kind, tokenizer) = match entry.field_type() {
+                FieldType::Str(options) => (
+                    "text".to_string(),
+                    options.get_indexing_options().map(|indexing| indexing.tokenizer().to_string()),
+                ),
+                FieldType::U64(_) => ("u64".to_string(), None),
+                other => (format!("{:?}", other.value_type()).to_lowercase(), None),
+            };
+
+            SchemaField {
+                name: entry.name().to_string(),
+                kind,
+                tokenizer,
+                indexed: entry.is_indexed(),
+                stored: entry.is_stored(),
+                fast: entry.is_fast(),
+            }
+        })
+        .collect()
+}
+
+impl SearchConfig {
+    /// Собирает настройки для экспорта вместе с описанием текущей схемы индекса
+    pub fn new(index_enabled: bool, stemming_languages: Vec<String>, synonyms: Vec<String>, field_boosts: FieldBoosts) -> Self {
+        Self {
+            version: SEARCH_CONFIG_VERSION,
+            index_enabled,
+            stemming_languages,
+            synonyms,
+            field_boosts,
+            schema: describe_schema(&build_schema()),
+        }
+    }
+
+    /// Проверяет настройки перед применением
+    pub fn validate(&self) -> Result<()> {
+        if self.version > SEARCH_CONFIG_VERSION {
+            return Err(PromptToolError::Validation(format!(
+                "Настройки поиска созданы более новой версией приложения (формат {})",
+                self.version
+            )));
+        }
+
+        if let Some(unknown) = self.stemming_languages.iter().find(|name| parse_language(name).is_none()) {
+            return Err(PromptToolError::Validation(format!("Неподдерживаемый язык стемминга: {}", unknown)));
+        }
+
+        let boosts = [self.field_boosts.title, self.field_boosts.tags, self.field_boosts.text];
+        if !boosts.iter().all(|boost| boost.is_finite() && *boost >= 0.0) {
+            return Err(PromptToolError::Validation("Вес поля должен быть неотрицательным числом".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Читает и проверяет настройки из JSON-файла
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let config: Self = serde_json::from_str(&contents)
+            .map_err(|e| PromptToolError::Config(format!("Некорректный файл настроек поиска: {}", e)))?;
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Записывает настройки в JSON-файл
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации настроек поиска: {}", e)))?;
+
+        fs::write(path, contents)
+            .map_err(PromptToolError::Io)
+    }
+}
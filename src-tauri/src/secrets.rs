@@ -0,0 +1,72 @@
+//! Хранилище секретов (ключей API, паролей, значений параметров) в системной связке ключей:
+//! Keychain в macOS, Credential Manager в Windows, Secret Service в Linux.
+//!
+//! Секреты не записываются ни в файл промптов, ни в config.json: промпт ссылается на секрет
+//! директивой `{{secret:ИМЯ}}`, а настройки - значением `secret:ИМЯ`.
+
+use crate::error::{PromptToolError, Result};
+
+/// Имя сервиса, под которым секреты хранятся в связке ключей
+pub const SERVICE: &str = "PromptTool";
+
+/// Префикс значения настройки, ссылающегося на секрет: `secret:openai`
+pub const SECRET_REFERENCE_PREFIX: &str = "secret:";
+
+/// Проверяет имя секрета: буквы, цифры, `_`, `-` и `.`
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.');
+
+    if !valid {
+        return Err(PromptToolError::Validation(format!(
+            "Недопустимое имя секрета \"{}\": разрешены буквы, цифры, _, - и .",
+            name
+        )));
+    }
+    Ok(())
+}
+
+fn entry(name: &str) -> Result<keyring::Entry> {
+    validate_name(name)?;
+    keyring::Entry::new(SERVICE, name)
+        .map_err(|e| PromptToolError::Config(format!("Связка ключей недоступна: {}", e)))
+}
+
+/// Сохраняет секрет, заменяя прежнее значение
+pub fn set_secret(name: &str, value: &str) -> Result<()> {
+    entry(name)?
+        .set_password(value)
+        .map_err(|e| PromptToolError::Config(format!("Не удалось сохранить секрет \"{}\": {}", name, e)))
+}
+
+/// Значение секрета
+pub fn get_secret(name: &str) -> Result<String> {
+    entry(name)?
+        .get_password()
+        .map_err(|e| match e {
+            keyring::Error::NoEntry => PromptToolError::Validation(format!("Секрет \"{}\" не найден", name)),
+            e => PromptToolError::Config(format!("Не удалось прочитать секрет \"{}\": {}", name, e)),
+        })
+}
+
+/// Удаляет секрет; возвращает, был ли он сохранен
+pub fn delete_secret(name: &str) -> Result<bool> {
+    match entry(name)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(PromptToolError::Config(format!("Не удалось удалить секрет \"{}\": {}", name, e))),
+    }
+}
+
+/// Имя секрета, если значение настройки ссылается на него (`secret:ИМЯ`)
+pub fn reference(value: &str) -> Option<&str> {
+    value.trim().strip_prefix(SECRET_REFERENCE_PREFIX).map(str::trim)
+}
+
+/// Значение настройки: ссылка `secret:ИМЯ` заменяется секретом, остальные значения возвращаются как есть
+pub fn resolve(value: &str) -> Result<String> {
+    match reference(value) {
+        Some(name) => get_secret(name),
+        None => Ok(value.to_string()),
+    }
+}
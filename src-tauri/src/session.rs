@@ -0,0 +1,93 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+
+/// Переменная сессии
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionVariable {
+    /// Значение, подставляемое в шаблоны
+    pub value: String,
+
+    /// Закрепленная переменная сохраняется между запусками
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// Переменные текущей сессии, например номер задачи, над которой идет работа
+/// Шаблоны ссылаются на них как на обычные параметры; при выходе сохраняются
+/// только закрепленные переменные
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionVariables {
+    variables: BTreeMap<String, SessionVariable>,
+}
+
+impl SessionVariables {
+    /// Загружает закрепленные переменные; отсутствующий или поврежденный файл дает пустую сессию
+    pub fn load(path: &Path) -> Self {
+        let mut session: Self = fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        session.variables.retain(|_, variable| variable.pinned);
+        session
+    }
+
+    /// Сохраняет закрепленные переменные в файл
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(PromptToolError::Io)?;
+        }
+
+        let pinned = Self {
+            variables: self.variables
+                .iter()
+                .filter(|(_, variable)| variable.pinned)
+                .map(|(name, variable)| (name.clone(), variable.clone()))
+                .collect(),
+        };
+        let contents = serde_json::to_string_pretty(&pinned)
+            .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации переменных сессии: {}", e)))?;
+
+        fs::write(path, contents)
+            .map_err(PromptToolError::Io)
+    }
+
+    /// Задает значение переменной
+    pub fn set(&mut self, name: &str, value: String, pinned: bool) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(PromptToolError::Validation("Имя переменной сессии не может быть пустым".to_string()));
+        }
+
+        self.variables.insert(name.to_string(), SessionVariable { value, pinned });
+        Ok(())
+    }
+
+    /// Удаляет переменную; возвращает false, если ее не было
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.variables.remove(name).is_some()
+    }
+
+    /// Удаляет все переменные, кроме закрепленных
+    pub fn clear_unpinned(&mut self) {
+        self.variables.retain(|_, variable| variable.pinned);
+    }
+
+    /// Все переменные по именам
+    pub fn variables(&self) -> &BTreeMap<String, SessionVariable> {
+        &self.variables
+    }
+
+    /// Дополняет значения параметров переменными сессии; явно переданные значения важнее
+    pub fn apply(&self, values: &mut HashMap<String, String>) {
+        for (name, variable) in &self.variables {
+            values.entry(name.clone()).or_insert_with(|| variable.value.clone());
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+
+use crate::backups::DEFAULT_BACKUP_COUNT;
+use crate::error::{PromptToolError, Result};
+use crate::prompt::SortOrder;
+
+/// Языки интерфейса
+pub const SUPPORTED_LANGUAGES: &[&str] = &["ru", "en"];
+
+/// Наибольшее число результатов поиска
+pub const MAX_RESULT_LIMIT: usize = 1000;
+
+/// Наибольшее расстояние нечеткого поиска; большие расстояния индекс не поддерживает
+pub const MAX_FUZZY_THRESHOLD: u8 = 2;
+
+/// Наибольшее число резервных копий файла промптов
+pub const MAX_BACKUP_COUNT: usize = 100;
+
+/// Наибольшая задержка автосохранения в секундах
+pub const MAX_AUTOSAVE_DELAY_SECONDS: u64 = 3600;
+
+/// Общие настройки приложения
+/// В config.json хранятся на верхнем уровне, рядом с остальными полями конфигурации
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Settings {
+    /// Сколько результатов показывать в поиске
+    #[serde(default = "default_result_limit")]
+    pub result_limit: usize,
+
+    /// Порядок результатов, если запрос его не задает
+    #[serde(default)]
+    pub default_sort: SortOrder,
+
+    /// Язык интерфейса
+    #[serde(default = "default_language")]
+    pub language: String,
+
+    /// Через сколько секунд после последней правки сохранять изменения в памяти на диск
+    /// (0 - только при потере фокуса и закрытии окна)
+    #[serde(default = "default_autosave_delay_seconds")]
+    pub autosave_delay_seconds: u64,
+
+    /// Сколько последних резервных копий файла промптов хранить (0 - не создавать копии)
+    #[serde(default = "default_backup_count")]
+    pub backup_count: usize,
+
+    /// Сколько опечаток допускать в слове запроса (0 - только точные совпадения слов)
+    #[serde(default = "default_fuzzy_threshold")]
+    pub fuzzy_threshold: u8,
+}
+
+fn default_result_limit() -> usize {
+    50
+}
+
+fn default_language() -> String {
+    "ru".to_string()
+}
+
+fn default_autosave_delay_seconds() -> u64 {
+    3
+}
+
+fn default_backup_count() -> usize {
+    DEFAULT_BACKUP_COUNT
+}
+
+fn default_fuzzy_threshold() -> u8 {
+    0
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            result_limit: default_result_limit(),
+            default_sort: SortOrder::default(),
+            language: default_language(),
+            autosave_delay_seconds: default_autosave_delay_seconds(),
+            backup_count: default_backup_count(),
+            fuzzy_threshold: default_fuzzy_threshold(),
+        }
+    }
+}
+
+/// Изменение части настроек; незаданные поля остаются прежними
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SettingsUpdate {
+    pub result_limit: Option<usize>,
+    pub default_sort: Option<SortOrder>,
+    pub language: Option<String>,
+    pub autosave_delay_seconds: Option<u64>,
+    pub backup_count: Option<usize>,
+    pub fuzzy_threshold: Option<u8>,
+}
+
+/// Событие `settings-changed`: новые настройки и имена изменившихся полей
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsChange {
+    pub settings: Settings,
+    pub changed: Vec<&'static str>,
+}
+
+impl Settings {
+    /// Проверяет все поля; в ошибке перечисляются все неверные значения
+    pub fn validate(&self) -> Result<()> {
+        let mut errors = Vec::new();
+
+        if !(1..=MAX_RESULT_LIMIT).contains(&self.result_limit) {
+            errors.push(format!("result_limit: должно быть от 1 до {}", MAX_RESULT_LIMIT));
+        }
+        if !SUPPORTED_LANGUAGES.contains(&self.language.as_str()) {
+            errors.push(format!(
+                "language: неизвестный язык \"{}\", доступны {}",
+                self.language,
+                SUPPORTED_LANGUAGES.join(", ")
+            ));
+        }
+        if self.autosave_delay_seconds > MAX_AUTOSAVE_DELAY_SECONDS {
+            errors.push(format!("autosave_delay_seconds: не больше {}", MAX_AUTOSAVE_DELAY_SECONDS));
+        }
+        if self.backup_count > MAX_BACKUP_COUNT {
+            errors.push(format!("backup_count: не больше {}", MAX_BACKUP_COUNT));
+        }
+        if self.fuzzy_threshold > MAX_FUZZY_THRESHOLD {
+            errors.push(format!("fuzzy_threshold: не больше {}", MAX_FUZZY_THRESHOLD));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(PromptToolError::Validation(errors.join("; ")))
+        }
+    }
+
+    /// Применяет изменение, если все новые значения верны; иначе настройки не меняются
+    /// Возвращает имена полей, значения которых действительно изменились
+    pub fn apply(&mut self, update: &SettingsUpdate) -> Result<Vec<&'static str>> {
+        let mut next = self.clone();
+        if let Some(value) = update.result_limit {
+            next.result_limit = value;
+        }
+        if let Some(value) = update.default_sort {
+            next.default_sort = value;
+        }
+        if let Some(value) = &update.language {
+            next.language = value.clone();
+        }
+        if let Some(value) = update.autosave_delay_seconds {
+            next.autosave_delay_seconds = value;
+        }
+        if let Some(value) = update.backup_count {
+            next.backup_count = value;
+        }
+        if let Some(value) = update.fuzzy_threshold {
+            next.fuzzy_threshold = value;
+        }
+        next.validate()?;
+
+        let changed = [
+            ("result_limit", self.result_limit != next.result_limit),
+            ("default_sort", self.default_sort != next.default_sort),
+            ("language", self.language != next.language),
+            ("autosave_delay_seconds", self.autosave_delay_seconds != next.autosave_delay_seconds),
+            ("backup_count", self.backup_count != next.backup_count),
+            ("fuzzy_threshold", self.fuzzy_threshold != next.fuzzy_threshold),
+        ]
+        .into_iter()
+        .filter_map(|(field, changed)| changed.then_some(field))
+        .collect();
+
+        *self = next;
+        Ok(changed)
+    }
+}
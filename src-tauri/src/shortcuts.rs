@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tauri_plugin_global_shortcut::Shortcut;
+
+use crate::error::{PromptToolError, Result};
+
+/// Промпт, привязанный к глобальной горячей клавише
+/// По нажатию промпт подставляется со значениями по умолчанию и копируется в буфер обмена
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptHotkey {
+    /// Идентификатор промпта; привязка переживает переименование
+    pub prompt_id: String,
+
+    /// Вставлять ли текст в активное окно сразу после копирования
+    #[serde(default)]
+    pub auto_paste: bool,
+}
+
+/// Разбирает сочетание клавиш вида `Ctrl+Shift+1`
+pub fn parse_shortcut(shortcut: &str) -> Result<Shortcut> {
+    shortcut.parse::<Shortcut>()
+        .map_err(|e| PromptToolError::Validation(format!("Неверное сочетание клавиш \"{}\": {}", shortcut, e)))
+}
+
+/// Ищет привязку для нажатого сочетания
+/// Сочетания сравниваются после разбора, поэтому `ctrl+1` и `Control+1` совпадают
+pub fn find_binding<'a>(
+    bindings: &'a BTreeMap<String, PromptHotkey>,
+    pressed: &Shortcut,
+) -> Option<&'a PromptHotkey> {
+    bindings.iter()
+        .find(|(shortcut, _)| parse_shortcut(shortcut).is_ok_and(|parsed| parsed.id() == pressed.id()))
+        .map(|(_, binding)| binding)
+}
+
+/// Проверяет, что сочетание не занято главной горячей клавишей или другим промптом
+pub fn check_conflicts(
+    bindings: &BTreeMap<String, PromptHotkey>,
+    main_hotkey: &str,
+    shortcut: &str,
+    prompt_id: &str,
+) -> Result<()> {
+    let parsed = parse_shortcut(shortcut)?;
+
+    if parse_shortcut(main_hotkey).is_ok_and(|main| main.id() == parsed.id()) {
+        return Err(PromptToolError::Validation(format!(
+            "Сочетание \"{}\" уже используется для вызова окна",
+            shortcut
+        )));
+    }
+
+    match find_binding(bindings, &parsed) {
+        Some(binding) if binding.prompt_id != prompt_id => Err(PromptToolError::Validation(format!(
+            "Сочетание \"{}\" уже привязано к другому промпту",
+            shortcut
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Сочетания, привязанные к промпту
+pub fn shortcuts_for<'a>(bindings: &'a BTreeMap<String, PromptHotkey>, prompt_id: &str) -> Vec<&'a str> {
+    bindings.iter()
+        .filter(|(_, binding)| binding.prompt_id == prompt_id)
+        .map(|(shortcut, _)| shortcut.as_str())
+        .collect()
+}
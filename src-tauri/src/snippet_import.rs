@@ -0,0 +1,286 @@
+//! Перенос сниппетов из других менеджеров: Espanso, TextExpander, Alfred и Raycast.
+//!
+//! `detect_sources` ищет данные этих программ в стандартных местах домашней директории,
+//! `import_source` превращает найденный источник в промпты.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{PromptToolError, Result};
+use crate::parameters::ParameterSpec;
+use crate::prompt::{Prompt, PromptList};
+
+// Директории с правилами Espanso относительно домашней директории (Linux, macOS, Windows)
+const ESPANSO_MATCH_DIRS: &[&str] = &[
+    ".config/espanso/match",
+    "Library/Application Support/espanso/match",
+    "AppData/Roaming/espanso/match",
+];
+
+// Сниппеты Alfred относительно домашней директории
+const ALFRED_SNIPPETS_DIR: &str = "Library/Application Support/Alfred/Alfred.alfredpreferences/snippets";
+
+// Директории, в которых обычно оказываются файлы экспорта TextExpander и Raycast
+const EXPORT_DIRS: &[&str] = &["Downloads", "Documents", "Desktop"];
+
+/// Программа, из которой переносятся сниппеты
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    /// Директория `match` с YAML-правилами Espanso
+    Espanso,
+    /// CSV-экспорт TextExpander (аббревиатура, текст, название)
+    TextExpander,
+    /// Директория сниппетов Alfred (JSON-файл на каждый сниппет)
+    Alfred,
+    /// JSON-экспорт сниппетов Raycast
+    Raycast,
+}
+
+/// Найденный на диске источник сниппетов
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportableSource {
+    pub kind: SourceKind,
+    pub path: PathBuf,
+
+    /// Сколько промптов получится при импорте
+    pub prompt_count: usize,
+}
+
+/// Ищет данные других менеджеров сниппетов в домашней директории
+/// Источники, из которых не удалось извлечь ни одного промпта, не возвращаются
+pub fn detect_sources(home: &Path) -> Vec<ImportableSource> {
+    let mut candidates: Vec<(SourceKind, PathBuf)> = ESPANSO_MATCH_DIRS
+        .iter()
+        .map(|dir| (SourceKind::Espanso, home.join(dir)))
+        .filter(|(_, path)| path.is_dir())
+        .collect();
+
+    let alfred = home.join(ALFRED_SNIPPETS_DIR);
+    if alfred.is_dir() {
+        candidates.push((SourceKind::Alfred, alfred));
+    }
+
+    for dir in EXPORT_DIRS.iter().map(|dir| home.join(dir)) {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()).filter(|path| path.is_file()) {
+            if let Some(kind) = export_kind(&path) {
+                candidates.push((kind, path));
+            }
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter_map(|(kind, path)| {
+            let prompt_count = import_source(kind, &path).ok()?.prompts.len();
+            (prompt_count > 0).then_some(ImportableSource { kind, path, prompt_count })
+        })
+        .collect()
+}
+
+/// Определяет по имени файла, является ли он экспортом TextExpander или Raycast
+fn export_kind(path: &Path) -> Option<SourceKind> {
+    let file_name = path.file_name()?.to_str()?.to_lowercase();
+    let extension = path.extension()?.to_str()?.to_lowercase();
+
+    match extension.as_str() {
+        "csv" if file_name.contains("textexpander") => Some(SourceKind::TextExpander),
+        "json" if file_name.contains("raycast") || file_name == "snippets.json" => Some(SourceKind::Raycast),
+        _ => None,
+    }
+}
+
+/// Импортирует сниппеты из источника; к промптам добавляется тег с именем программы
+pub fn import_source(kind: SourceKind, path: &Path) -> Result<PromptList> {
+    let prompts = match kind {
+        SourceKind::Espanso => import_espanso(path)?,
+        SourceKind::TextExpander => import_textexpander(path)?,
+        SourceKind::Alfred => import_alfred(path)?,
+        SourceKind::Raycast => import_raycast(path)?,
+    };
+
+    Ok(PromptList { prompts })
+}
+
+fn snippet(name: &str, content: &str, parameters: Vec<String>, source_tag: &str) -> Prompt {
+    Prompt::new(
+        name.to_string(),
+        content.to_string(),
+        parameters.into_iter().map(ParameterSpec::from).collect(),
+        HashSet::new(),
+        HashSet::from([source_tag.to_string()]),
+    )
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(PromptToolError::Io)
+}
+
+/// Все файлы с указанными расширениями в директории и ее поддиректориях
+fn files_with_extensions(dir: &Path, extensions: &[&str], files: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(PromptToolError::Io)?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            files_with_extensions(&path, extensions, files)?;
+        } else if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| extensions.contains(&ext.to_lowercase().as_str()))
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Заменяет переменные Espanso `{{name}}` на плейсхолдеры `{name}` и возвращает их имена
+fn convert_espanso_variables(text: &str) -> (String, Vec<String>) {
+    let mut output = String::with_capacity(text.len());
+    let mut parameters = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + end].trim();
+
+        output.push_str(&rest[..start]);
+        output.push('{');
+        output.push_str(name);
+        output.push('}');
+        if !parameters.iter().any(|p| p == name) {
+            parameters.push(name.to_string());
+        }
+        rest = &rest[start + 2 + end + 2..];
+    }
+
+    output.push_str(rest);
+    (output, parameters)
+}
+
+/// Правила Espanso: `matches: [{trigger | triggers, replace, label}]`
+/// Правила без текстовой замены (картинки, формы) пропускаются
+fn import_espanso(dir: &Path) -> Result<Vec<Prompt>> {
+    let mut files = Vec::new();
+    files_with_extensions(dir, &["yml", "yaml"], &mut files)?;
+
+    let mut prompts = Vec::new();
+    for file in files {
+        let config: serde_yaml::Value = serde_yaml::from_str(&read(&file)?)
+            .map_err(|e| PromptToolError::Config(format!("{}: {}", file.display(), e)))?;
+
+        let Some(matches) = config.get("matches").and_then(|m| m.as_sequence()) else {
+            continue;
+        };
+
+        for rule in matches {
+            let Some(replace) = rule.get("replace").and_then(|r| r.as_str()) else {
+                continue;
+            };
+
+            let trigger = rule.get("trigger")
+                .and_then(|t| t.as_str())
+                .or_else(|| rule.get("triggers")
+                    .and_then(|t| t.as_sequence())
+                    .and_then(|t| t.first())
+                    .and_then(|t| t.as_str()));
+            let Some(name) = rule.get("label").and_then(|l| l.as_str()).or(trigger) else {
+                continue;
+            };
+
+            let (content, parameters) = convert_espanso_variables(replace);
+            prompts.push(snippet(name, &content, parameters, "espanso"));
+        }
+    }
+
+    Ok(prompts)
+}
+
+/// CSV-экспорт TextExpander без заголовка: аббревиатура, текст, название (необязательно)
+fn import_textexpander(path: &Path) -> Result<Vec<Prompt>> {
+    let contents = read(path)?;
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+
+    let mut prompts = Vec::new();
+    for record in reader.records() {
+        let record = record
+            .map_err(|e| PromptToolError::Config(format!("{}: {}", path.display(), e)))?;
+
+        let (Some(abbreviation), Some(content)) = (record.get(0), record.get(1)) else {
+            continue;
+        };
+        let name = record.get(2)
+            .map(str::trim)
+            .filter(|label| !label.is_empty())
+            .unwrap_or(abbreviation);
+
+        prompts.push(snippet(name, content, Vec::new(), "textexpander"));
+    }
+
+    Ok(prompts)
+}
+
+/// Сниппеты Alfred: `snippets/<коллекция>/<сниппет>.json` с объектом `alfredsnippet`
+/// Имя коллекции становится категорией
+fn import_alfred(dir: &Path) -> Result<Vec<Prompt>> {
+    let mut files = Vec::new();
+    files_with_extensions(dir, &["json"], &mut files)?;
+
+    let mut prompts = Vec::new();
+    for file in files {
+        let Ok(value) = serde_json::from_str::<Value>(&read(&file)?) else {
+            continue;
+        };
+        let item = &value["alfredsnippet"];
+        let Some(content) = item["snippet"].as_str() else {
+            continue;
+        };
+        let name = item["name"].as_str()
+            .filter(|name| !name.is_empty())
+            .or_else(|| item["keyword"].as_str())
+            .unwrap_or("Alfred snippet");
+
+        let mut prompt = snippet(name, content, Vec::new(), "alfred");
+        if let Some(collection) = file.parent().filter(|parent| *parent != dir).and_then(|p| p.file_name()) {
+            prompt.categories.insert(collection.to_string_lossy().into_owned());
+        }
+        prompts.push(prompt);
+    }
+
+    Ok(prompts)
+}
+
+/// JSON-экспорт Raycast: массив объектов `{name, text, keyword}`
+fn import_raycast(path: &Path) -> Result<Vec<Prompt>> {
+    let value: Value = serde_json::from_str(&read(path)?)
+        .map_err(|e| PromptToolError::Config(format!("{}: {}", path.display(), e)))?;
+
+    let items = value.as_array()
+        .ok_or_else(|| PromptToolError::Config(format!("{}: ожидался массив сниппетов", path.display())))?;
+
+    Ok(items
+        .iter()
+        .filter_map(|item| {
+            let content = item["text"].as_str()?;
+            let name = item["name"].as_str().or_else(|| item["keyword"].as_str())?;
+            Some(snippet(name, content, Vec::new(), "raycast"))
+        })
+        .collect())
+}
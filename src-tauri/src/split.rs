@@ -0,0 +1,166 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+use crate::prompt::{Prompt, PromptList};
+
+/// Имя файла для промптов без категорий
+pub const UNCATEGORIZED_FILE_STEM: &str = "uncategorized";
+
+/// Что делать с промптом, у которого несколько категорий
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SharedPromptStrategy {
+    /// Промпт записывается в файл каждой своей категории
+    #[default]
+    Duplicate,
+    /// Промпт записывается один раз, в файл первой по алфавиту категории;
+    /// в файлах остальных категорий остается комментарий со ссылкой на него
+    Reference,
+}
+
+/// Файл, созданный при разбиении библиотеки
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SplitFile {
+    /// Категория; `None` для промптов без категорий
+    pub category: Option<String>,
+
+    pub path: PathBuf,
+
+    /// Сколько промптов записано в файл (без ссылок)
+    pub prompts: usize,
+}
+
+/// Имя файла для категории: символы, недопустимые в именах файлов, заменяются на `_`
+fn file_stem(category: &str) -> String {
+    let stem: String = category
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' || c == ' ' { c } else { '_' })
+        .collect();
+
+    if stem.trim().is_empty() { "_".to_string() } else { stem }
+}
+
+/// Содержимое файла категории в формате библиотеки
+/// Ссылки на промпты из других файлов записываются комментариями TOML перед промптами
+fn file_contents(prompts: Vec<Prompt>, references: &[(String, String)]) -> Result<String> {
+    let mut contents = String::new();
+    for (name, file_name) in references {
+        contents.push_str(&format!("# См. также: \"{}\" в {}\n", name, file_name));
+    }
+    if !references.is_empty() {
+        contents.push('\n');
+    }
+
+    let library = PromptList { prompts };
+    contents.push_str(&toml::to_string_pretty(&library)
+        .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации: {}", e)))?);
+    Ok(contents)
+}
+
+/// Один и тот же ли это файл; несуществующий файл не совпадает ни с каким
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Записывает каждую категорию библиотеки в отдельный TOML-файл в `output_dir`
+/// Промпты без категорий попадают в `uncategorized.toml`. Категории сохраняются в самих промптах,
+/// поэтому импорт полученной директории восстанавливает библиотеку.
+/// В непустую директорию файлы записываются только с `overwrite`; файл `active_file`
+/// (текущий файл промптов) не перезаписывается никогда
+pub fn split_by_category(
+    library: &PromptList,
+    output_dir: &Path,
+    strategy: SharedPromptStrategy,
+    overwrite: bool,
+    active_file: &Path,
+) -> Result<Vec<SplitFile>> {
+    let is_empty = !fs::read_dir(output_dir).is_ok_and(|mut entries| entries.next().is_some());
+    if !overwrite && !is_empty {
+        return Err(PromptToolError::Validation(format!(
+            "Директория \"{}\" не пуста: подтвердите перезапись файлов в ней",
+            output_dir.display()
+        )));
+    }
+
+    fs::create_dir_all(output_dir)
+        .map_err(PromptToolError::Io)?;
+
+    // Категория -> промпты; BTreeMap дает файлы в алфавитном порядке, None (без категорий) - первым
+    let mut groups: BTreeMap<Option<String>, Vec<&Prompt>> = BTreeMap::new();
+    // Ссылки для стратегии Reference: категория -> (промпт, категория, где он записан)
+    let mut references: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+
+    for prompt in &library.prompts {
+        let mut categories: Vec<&String> = prompt.categories.iter().collect();
+        categories.sort();
+
+        let Some((first, rest)) = categories.split_first() else {
+            groups.entry(None).or_default().push(prompt);
+            continue;
+        };
+
+        groups.entry(Some((*first).clone())).or_default().push(prompt);
+        for category in rest {
+            match strategy {
+                SharedPromptStrategy::Duplicate => groups.entry(Some((*category).clone())).or_default().push(prompt),
+                SharedPromptStrategy::Reference => {
+                    groups.entry(Some((*category).clone())).or_default();
+                    references.entry((*category).clone()).or_default().push((prompt.name.clone(), (*first).clone()));
+                }
+            }
+        }
+    }
+
+    // Имена файлов назначаются заранее, чтобы ссылки указывали на реальные файлы
+    let mut used = HashSet::new();
+    let file_names: BTreeMap<Option<String>, String> = groups
+        .keys()
+        .map(|category| {
+            let stem = category.as_deref().map_or(UNCATEGORIZED_FILE_STEM.to_string(), file_stem);
+            let mut file_name = format!("{}.toml", stem);
+            let mut counter = 2;
+            while !used.insert(file_name.to_lowercase()) {
+                file_name = format!("{}-{}.toml", stem, counter);
+                counter += 1;
+            }
+            (category.clone(), file_name)
+        })
+        .collect();
+
+    if let Some(file_name) = file_names.values().find(|file_name| same_file(&output_dir.join(file_name), active_file)) {
+        return Err(PromptToolError::Validation(format!(
+            "Файл {} - текущий файл промптов, выберите другую директорию",
+            file_name
+        )));
+    }
+
+    let mut files = Vec::with_capacity(groups.len());
+    for (category, prompts) in groups {
+        let refs: Vec<(String, String)> = category
+            .as_ref()
+            .and_then(|category| references.get(category))
+            .map(|refs| {
+                refs.iter()
+                    .map(|(name, home)| (name.clone(), file_names[&Some(home.clone())].clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let path = output_dir.join(&file_names[&category]);
+        let count = prompts.len();
+        fs::write(&path, file_contents(prompts.into_iter().cloned().collect(), &refs)?)
+            .map_err(PromptToolError::Io)?;
+
+        files.push(SplitFile { category, path, prompts: count });
+    }
+
+    Ok(files)
+}
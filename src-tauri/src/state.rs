@@ -0,0 +1,40 @@
+//! Доступ к общему состоянию приложения из команд.
+//! Данные защищены `RwLock`: команды, которые только читают, выполняются одновременно.
+//! Если поток паниковал, удерживая блокировку, доступ не приводит к панике и не пропускается
+//! молча, а возвращает `PromptToolError::State`.
+
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::error::{PromptToolError, Result};
+
+/// Часть состояния приложения под блокировкой чтения-записи
+#[derive(Debug)]
+pub struct StateCell<T> {
+    /// Название данных для сообщения об ошибке
+    name: &'static str,
+    lock: RwLock<T>,
+}
+
+impl<T> StateCell<T> {
+    pub fn new(name: &'static str, value: T) -> Self {
+        Self {
+            name,
+            lock: RwLock::new(value),
+        }
+    }
+
+    /// Доступ на чтение; читателей может быть несколько одновременно
+    pub fn read(&self) -> Result<RwLockReadGuard<'_, T>> {
+        self.lock.read().map_err(|_| self.poisoned())
+    }
+
+    /// Исключительный доступ на запись
+    pub fn write(&self) -> Result<RwLockWriteGuard<'_, T>> {
+        self.lock.write().map_err(|_| self.poisoned())
+    }
+
+    fn poisoned(&self) -> PromptToolError {
+        tracing::error!("{}: блокировка повреждена после сбоя в другом потоке", self.name);
+        PromptToolError::State(format!("{}: данные недоступны после сбоя в другой операции", self.name))
+    }
+}
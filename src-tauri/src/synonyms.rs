@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+/// Словарь синонимов для расширения поисковых запросов
+/// Каждое слово группы считается равноценным остальным словам этой группы
+#[derive(Debug, Clone, Default)]
+pub struct SynonymMap {
+    /// Слово в нижнем регистре -> все слова его группы (включая само слово)
+    groups: HashMap<String, Vec<String>>,
+}
+
+impl SynonymMap {
+    /// Строит словарь из правил вида `"js = javascript"` или `"резюме, summary, tl;dr"`
+    /// Пустые и некорректные правила (меньше двух слов) пропускаются
+    pub fn from_rules(rules: &[String]) -> Self {
+        let mut map = Self::default();
+
+        for rule in rules {
+            let group: Vec<String> = rule
+                .split(['=', ','])
+                .map(|word| word.trim().to_lowercase())
+                .filter(|word| !word.is_empty())
+                .collect();
+
+            if group.len() < 2 {
+                continue;
+            }
+
+            for word in &group {
+                let entry = map.groups.entry(word.clone()).or_default();
+                for synonym in &group {
+                    if !entry.contains(synonym) {
+                        entry.push(synonym.clone());
+                    }
+                }
+            }
+        }
+
+        map
+    }
+
+    /// Пустой ли словарь
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Расширяет запрос: каждое слово, у которого есть синонимы, заменяется группой `(слово синоним ...)`
+    /// Слова со служебным синтаксисом запроса (`title:`, кавычки, `+`/`-`) и слова внутри
+    /// фраз в кавычках не изменяются: группа внутри фразы сломала бы поиск точного совпадения
+    pub fn expand_query(&self, query: &str) -> String {
+        if self.is_empty() {
+            return query.to_string();
+        }
+
+        let mut in_phrase = false;
+        query
+            .split_whitespace()
+            .map(|token| {
+                let quoted = in_phrase;
+                if token.matches('"').count() % 2 == 1 {
+                    in_phrase = !in_phrase;
+                }
+
+                let is_plain = !quoted && token.chars().all(|c| c.is_alphanumeric() || c == '_');
+                match self.groups.get(&token.to_lowercase()).filter(|_| is_plain) {
+                    Some(group) => format!("({})", group.iter().map(|word| quote_if_needed(word)).collect::<Vec<_>>().join(" ")),
+                    None => token.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Синонимы из нескольких слов или со спецсимволами берутся в кавычки как фраза
+fn quote_if_needed(word: &str) -> String {
+    if word.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        word.to_string()
+    } else {
+        format!("\"{}\"", word.replace('"', ""))
+    }
+}
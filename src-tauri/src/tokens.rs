@@ -0,0 +1,38 @@
+//! Подсчет токенов в тексте промптов.
+//!
+//! Для известных моделей OpenAI используется их собственная кодировка, для остальных
+//! (например, локальных моделей Ollama) - cl100k_base как приблизительная оценка.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tiktoken_rs::CoreBPE;
+
+use crate::error::{PromptToolError, Result};
+
+/// Загруженные кодировки по названию модели; построение кодировки занимает заметное время
+static ENCODERS: OnceLock<Mutex<HashMap<String, Arc<CoreBPE>>>> = OnceLock::new();
+
+/// Кодировка для модели; неизвестные модели считаются кодировкой cl100k_base
+fn encoder_for(model: &str) -> Result<Arc<CoreBPE>> {
+    let encoders = ENCODERS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut encoders = encoders.lock()
+        .map_err(|_| PromptToolError::Config("Не удалось получить доступ к кодировкам токенов".to_string()))?;
+
+    if let Some(encoder) = encoders.get(model) {
+        return Ok(encoder.clone());
+    }
+
+    let encoder = tiktoken_rs::get_bpe_from_model(model)
+        .or_else(|_| tiktoken_rs::cl100k_base())
+        .map(Arc::new)
+        .map_err(|e| PromptToolError::Config(format!("Не удалось загрузить кодировку токенов: {}", e)))?;
+
+    encoders.insert(model.to_string(), encoder.clone());
+    Ok(encoder)
+}
+
+/// Количество токенов в тексте для указанной модели
+pub fn count_tokens(text: &str, model: &str) -> Result<usize> {
+    Ok(encoder_for(model)?.encode_with_special_tokens(text).len())
+}
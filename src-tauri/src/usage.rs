@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+
+/// Статистика использования одного промпта
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageEntry {
+    /// Сколько раз промпт копировали, подставляли или выполняли
+    pub count: u64,
+
+    /// Когда промпт использовался последний раз
+    pub last_used: DateTime<Utc>,
+}
+
+/// Статистика использования промптов по названиям, сохраняемая между запусками
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageStats {
+    prompts: HashMap<String, UsageEntry>,
+}
+
+impl UsageStats {
+    /// Загружает статистику из файла; отсутствующий или поврежденный файл дает пустую статистику
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Сохраняет статистику в файл
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(PromptToolError::Io)?;
+        }
+
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации статистики: {}", e)))?;
+
+        fs::write(path, contents)
+            .map_err(PromptToolError::Io)
+    }
+
+    /// Отмечает использование промпта
+    pub fn record(&mut self, prompt_name: &str) {
+        let now = Utc::now();
+        self.prompts
+            .entry(prompt_name.to_string())
+            .and_modify(|entry| {
+                entry.count += 1;
+                entry.last_used = now;
+            })
+            .or_insert(UsageEntry { count: 1, last_used: now });
+    }
+
+    /// Статистика конкретного промпта
+    pub fn get(&self, prompt_name: &str) -> Option<&UsageEntry> {
+        self.prompts.get(prompt_name)
+    }
+
+    /// Самые используемые промпты, от частых к редким
+    pub fn most_used(&self, limit: usize) -> Vec<(&str, &UsageEntry)> {
+        let mut entries: Vec<(&str, &UsageEntry)> = self.prompts
+            .iter()
+            .map(|(name, entry)| (name.as_str(), entry))
+            .collect();
+
+        entries.sort_by(|(a_name, a), (b_name, b)| b.count.cmp(&a.count).then(a_name.cmp(b_name)));
+        entries.truncate(limit);
+        entries
+    }
+}
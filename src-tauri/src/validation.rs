@@ -0,0 +1,202 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::error::{PromptToolError, Result};
+use crate::output::OutputTargetConfig;
+use crate::secrets;
+use crate::prompt::{Prompt, PromptList, MAX_RATING, MIN_RATING};
+
+/// Максимальная длина названия промпта в символах
+pub const MAX_NAME_CHARS: usize = 200;
+
+/// Максимальный размер текста промпта в байтах
+pub const MAX_CONTENT_BYTES: usize = 256 * 1024;
+
+/// Максимальная длина тега или категории в символах
+pub const MAX_TAG_CHARS: usize = 64;
+
+/// Ошибка конкретного поля промпта
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    /// Название поля, например `name` или `tags`
+    pub field: &'static str,
+
+    /// Описание ошибки для пользователя
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(field: &'static str, message: String) -> Self {
+        Self { field, message }
+    }
+}
+
+/// Ключ для сравнения названий: без пробелов по краям и без учета регистра, как в проверке библиотеки
+fn name_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Проверяет метку (тег или категорию): непустая, без запятых, переводов строк
+/// и пробелов по краям, не длиннее MAX_TAG_CHARS
+fn check_label(field: &'static str, label: &str, errors: &mut Vec<FieldError>) {
+    let message = if label.trim().is_empty() {
+        Some("пустое значение".to_string())
+    } else if label.trim() != label {
+        Some(format!("\"{}\" начинается или заканчивается пробелом", label))
+    } else if label.chars().any(|c| c == ',' || c.is_control()) {
+        Some(format!("\"{}\" содержит запятую или управляющий символ", label))
+    } else if label.chars().count() > MAX_TAG_CHARS {
+        Some(format!("\"{}\" длиннее {} символов", label, MAX_TAG_CHARS))
+    } else {
+        None
+    };
+
+    errors.extend(message.map(|message| FieldError::new(field, message)));
+}
+
+/// Проверяет поля промпта без учета остальных промптов библиотеки
+pub fn check_prompt(prompt: &Prompt) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    if prompt.name.trim().is_empty() {
+        errors.push(FieldError::new("name", "Название не может быть пустым".to_string()));
+    } else if prompt.name.chars().count() > MAX_NAME_CHARS {
+        errors.push(FieldError::new("name", format!("Название длиннее {} символов", MAX_NAME_CHARS)));
+    }
+
+    if prompt.content.len() > MAX_CONTENT_BYTES {
+        errors.push(FieldError::new(
+            "content",
+            format!("Текст больше {} КБ", MAX_CONTENT_BYTES / 1024),
+        ));
+    }
+
+    let mut tags: Vec<&String> = prompt.tags.iter().collect();
+    tags.sort();
+    for tag in tags {
+        check_label("tags", tag, &mut errors);
+    }
+
+    let mut aliases = HashSet::new();
+    for alias in &prompt.aliases {
+        check_label("aliases", alias, &mut errors);
+        if !aliases.insert(name_key(alias)) {
+            errors.push(FieldError::new("aliases", format!("Псевдоним \"{}\" указан дважды", alias)));
+        }
+    }
+
+    let mut categories: Vec<&String> = prompt.categories.iter().collect();
+    categories.sort();
+    for category in categories {
+        check_label("categories", category, &mut errors);
+    }
+
+    if let Some(rating) = prompt.rating {
+        if !(MIN_RATING..=MAX_RATING).contains(&rating) {
+            errors.push(FieldError::new("rating", format!("Оценка должна быть от {} до {}", MIN_RATING, MAX_RATING)));
+        }
+    }
+
+    let mut parameters = HashSet::new();
+    for spec in &prompt.parameters {
+        if spec.name.trim().is_empty() {
+            errors.push(FieldError::new("parameters", "Имя параметра не может быть пустым".to_string()));
+        } else if !parameters.insert(spec.name.as_str()) {
+            errors.push(FieldError::new("parameters", format!("Параметр \"{}\" объявлен дважды", spec.name)));
+        }
+    }
+
+    // Заголовки вебхука сохраняются в файл промптов, поэтому ключи и токены в них - только ссылки на секреты
+    if let Some(OutputTargetConfig::Webhook { headers, .. }) = &prompt.output {
+        let mut names: Vec<&String> = headers.keys().collect();
+        names.sort();
+        for name in names {
+            if secrets::reference(&headers[name]).is_none() {
+                errors.push(FieldError::new(
+                    "output",
+                    format!("Значение заголовка \"{}\" укажите ссылкой на секрет: secret:ИМЯ", name),
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+/// Собирает ошибки полей в одну ошибку валидации вида "поле: сообщение; ..."
+fn into_result(prefix: Option<&str>, errors: Vec<FieldError>) -> Result<()> {
+    if errors.is_empty() {
+        return Ok(());
+    }
+
+    let details: Vec<String> = errors
+        .iter()
+        .map(|error| format!("{}: {}", error.field, error.message))
+        .collect();
+    let details = details.join("; ");
+
+    Err(PromptToolError::Validation(match prefix {
+        Some(prefix) => format!("{}: {}", prefix, details),
+        None => details,
+    }))
+}
+
+/// Проверяет промпт перед сохранением в библиотеку
+/// `replacing` - позиция изменяемого промпта, чтобы он не конфликтовал сам с собой по названию
+pub fn validate_prompt(prompt: &Prompt, library: &PromptList, replacing: Option<usize>) -> Result<()> {
+    let mut errors = check_prompt(prompt);
+
+    let key = name_key(&prompt.name);
+    let duplicate = library.prompts
+        .iter()
+        .enumerate()
+        .any(|(index, existing)| Some(index) != replacing && name_key(&existing.name) == key);
+    if !key.is_empty() && duplicate {
+        errors.push(FieldError::new("name", format!("Промпт \"{}\" уже существует", prompt.name.trim())));
+    }
+
+    // Псевдоним должен однозначно указывать на один промпт
+    for alias in &prompt.aliases {
+        let owner = library.prompts
+            .iter()
+            .enumerate()
+            .find(|(index, existing)| Some(*index) != replacing && existing.matches_alias(alias));
+        if let Some((_, owner)) = owner {
+            errors.push(FieldError::new("aliases", format!("Псевдоним \"{}\" уже занят промптом \"{}\"", alias, owner.name)));
+        }
+    }
+
+    into_result(None, errors)
+}
+
+/// Проверяет пачку новых промптов (например, при импорте) перед добавлением в библиотеку
+/// Названия не должны повторяться ни в библиотеке, ни внутри пачки
+pub fn validate_new_prompts(prompts: &[Prompt], library: &PromptList) -> Result<()> {
+    let mut seen: HashSet<String> = library.prompts.iter().map(|prompt| name_key(&prompt.name)).collect();
+    let mut seen_aliases: HashSet<String> = library.prompts
+        .iter()
+        .flat_map(|prompt| prompt.aliases.iter().map(|alias| name_key(alias)))
+        .collect();
+
+    for prompt in prompts {
+        let mut errors = check_prompt(prompt);
+
+        let key = name_key(&prompt.name);
+        if !key.is_empty() && !seen.insert(key) {
+            errors.push(FieldError::new("name", "Промпт с таким названием уже существует".to_string()));
+        }
+        // Повторы внутри самого промпта уже найдены в check_prompt
+        let aliases: HashSet<String> = prompt.aliases.iter().map(|alias| name_key(alias)).collect();
+        for alias in aliases {
+            if seen_aliases.contains(&alias) {
+                errors.push(FieldError::new("aliases", format!("Псевдоним \"{}\" уже занят", alias)));
+            }
+            seen_aliases.insert(alias);
+        }
+
+        into_result(Some(prompt.name.trim()), errors)?;
+    }
+
+    Ok(())
+}
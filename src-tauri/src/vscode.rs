@@ -0,0 +1,93 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::error::{PromptToolError, Result};
+use crate::prompt::Prompt;
+use crate::render::{rewrite_placeholders, slugify};
+
+/// Сниппет в формате файла `.code-snippets`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VsCodeSnippet {
+    /// Что набрать в редакторе, чтобы вставить сниппет
+    pub prefix: String,
+
+    /// Строки текста с позициями табуляции `$1`, `${2:значение}`
+    pub body: Vec<String>,
+
+    pub description: String,
+
+    /// Идентификаторы языков через запятую; без них сниппет доступен везде
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+/// Экранирует символы, имеющие особый смысл в синтаксисе сниппетов
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('$', "\\$").replace('}', "\\}")
+}
+
+/// Текст сниппета: параметры становятся позициями табуляции в порядке первого появления,
+/// повторные вхождения параметра получают тот же номер и редактируются вместе.
+/// Значение по умолчанию берется из функции `default` или из описания параметра
+pub fn snippet_body(prompt: &Prompt) -> String {
+    let mut stops: HashMap<String, usize> = HashMap::new();
+
+    rewrite_placeholders(&prompt.content, escape, |placeholder| {
+        let next = stops.len() + 1;
+        let stop = *stops.entry(placeholder.name.to_string()).or_insert(next);
+
+        let default = placeholder.default.map(str::to_string).or_else(|| {
+            prompt.parameters
+                .iter()
+                .find(|spec| spec.name == placeholder.name)
+                .and_then(|spec| spec.default.clone())
+        });
+
+        match default {
+            Some(default) => format!("${{{}:{}}}", stop, escape(&default)),
+            None => format!("${}", stop),
+        }
+    })
+}
+
+/// Сниппеты для промптов, по одному на промпт, с ключом по названию промпта
+/// `scope` - идентификаторы языков VS Code через запятую (например, `rust,python`)
+pub fn build_snippets(prompts: &[&Prompt], scope: Option<&str>) -> BTreeMap<String, VsCodeSnippet> {
+    let scope = scope
+        .map(|scope| {
+            scope.split(',')
+                .map(str::trim)
+                .filter(|language| !language.is_empty())
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .filter(|scope| !scope.is_empty());
+
+    prompts
+        .iter()
+        .map(|prompt| {
+            let prefix = slugify(&prompt.name);
+            let snippet = VsCodeSnippet {
+                prefix: if prefix.is_empty() { "prompt".to_string() } else { prefix },
+                body: snippet_body(prompt).split('\n').map(|line| line.trim_end_matches('\r').to_string()).collect(),
+                description: prompt.name.clone(),
+                scope: scope.clone(),
+            };
+            (prompt.name.clone(), snippet)
+        })
+        .collect()
+}
+
+/// Записывает сниппеты промптов в файл `.code-snippets`; возвращает количество сниппетов
+pub fn export_snippets(prompts: &[&Prompt], scope: Option<&str>, path: &Path) -> Result<usize> {
+    let snippets = build_snippets(prompts, scope);
+    let contents = serde_json::to_string_pretty(&snippets)
+        .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации сниппетов: {}", e)))?;
+
+    fs::write(path, contents)
+        .map_err(PromptToolError::Io)?;
+    Ok(snippets.len())
+}
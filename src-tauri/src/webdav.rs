@@ -0,0 +1,301 @@
+//! Синхронизация файла промптов с WebDAV-сервером (Nextcloud, ownCloud и другие).
+//!
+//! Конфликты определяются по версии удаленного файла (ETag, а если сервер его не отдает,
+//! Last-Modified) и хешу локального файла на момент последней синхронизации: если с тех пор
+//! изменились обе стороны, файл не перезаписывается ни в одну сторону.
+
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderName, ETAG, IF_MATCH, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+
+/// Настройки подключения к WebDAV
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WebDavSettings {
+    /// Полный адрес файла на сервере,
+    /// например `https://cloud.example.com/remote.php/dav/files/user/prompts.toml`
+    #[serde(default)]
+    pub url: String,
+
+    #[serde(default)]
+    pub username: String,
+
+    /// Пароль или пароль приложения Nextcloud
+    #[serde(default)]
+    pub password: String,
+}
+
+impl WebDavSettings {
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        if self.username.is_empty() {
+            request
+        } else {
+            request.basic_auth(&self.username, Some(&self.password))
+        }
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.url.trim().is_empty() {
+            return Err(PromptToolError::Config("Не указан адрес файла на WebDAV-сервере".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Версия удаленного файла
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteVersion {
+    #[serde(default)]
+    pub etag: Option<String>,
+
+    #[serde(default)]
+    pub last_modified: Option<String>,
+}
+
+impl RemoteVersion {
+    fn from_response(response: &Response) -> Self {
+        let header = |name: HeaderName| {
+            response.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+
+        Self {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        }
+    }
+}
+
+/// Состояние на момент последней успешной синхронизации
+/// Хранится в директории данных, чтобы при следующем запуске отличать свои изменения от чужих
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncState {
+    /// Версия удаленного файла; `None`, если файла на сервере не было
+    pub remote: Option<RemoteVersion>,
+
+    /// Хеш локального файла
+    pub local_hash: Option<String>,
+
+    pub synced_at: Option<DateTime<Utc>>,
+}
+
+impl SyncState {
+    /// Загружает состояние из файла; отсутствующий или поврежденный файл означает, что синхронизаций не было
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Сохраняет состояние в файл
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(PromptToolError::Io)?;
+        }
+
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации состояния синхронизации: {}", e)))?;
+
+        fs::write(path, contents)
+            .map_err(PromptToolError::Io)
+    }
+
+    fn synced(remote: Option<RemoteVersion>, contents: &str) -> Self {
+        Self {
+            remote,
+            local_hash: Some(content_hash(contents)),
+            synced_at: Some(Utc::now()),
+        }
+    }
+}
+
+/// Хеш содержимого (FNV-1a, 64 бита); в отличие от `DefaultHasher` не меняется между версиями Rust
+pub fn content_hash(contents: &str) -> String {
+    let hash = contents.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Что нужно сделать при синхронизации
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncAction {
+    /// Обе стороны не менялись
+    None,
+    /// Изменился только локальный файл
+    Push,
+    /// Изменился только удаленный файл
+    Pull,
+    /// Изменились обе стороны
+    Conflict,
+}
+
+/// Выбирает действие по тому, какие стороны изменились с последней синхронизации
+pub fn plan(local_changed: bool, remote_changed: bool) -> SyncAction {
+    match (local_changed, remote_changed) {
+        (false, false) => SyncAction::None,
+        (true, false) => SyncAction::Push,
+        (false, true) => SyncAction::Pull,
+        (true, true) => SyncAction::Conflict,
+    }
+}
+
+/// Результат синхронизации
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyncResult {
+    /// Выполненное действие; `Conflict` означает, что ни одна сторона не изменена
+    pub action: SyncAction,
+
+    /// Новое содержимое локального файла, если оно было получено с сервера
+    #[serde(skip)]
+    pub contents: Option<String>,
+
+    /// Состояние для следующей синхронизации
+    #[serde(skip)]
+    pub state: SyncState,
+}
+
+async fn send(request: RequestBuilder, url: &str) -> Result<Response> {
+    request
+        .send()
+        .await
+        .map_err(|e| PromptToolError::Execution(format!("Ошибка запроса к {}: {}", url, e)))
+}
+
+fn unexpected(status: StatusCode, url: &str) -> PromptToolError {
+    PromptToolError::Execution(format!("WebDAV-сервер ответил {} на запрос к {}", status, url))
+}
+
+/// Текущая версия удаленного файла; `None`, если файла нет
+async fn remote_version(client: &reqwest::Client, settings: &WebDavSettings) -> Result<Option<RemoteVersion>> {
+    let response = send(settings.authorize(client.head(&settings.url)), &settings.url).await?;
+    match response.status() {
+        StatusCode::NOT_FOUND => Ok(None),
+        status if status.is_success() => Ok(Some(RemoteVersion::from_response(&response))),
+        status => Err(unexpected(status, &settings.url)),
+    }
+}
+
+/// Отправляет файл на сервер
+/// Если `expected` задан, сервер примет файл, только если его версия не менялась (If-Match);
+/// если не задан - только если файла еще нет (If-None-Match: *). `force` отключает проверку
+async fn upload(
+    client: &reqwest::Client,
+    settings: &WebDavSettings,
+    contents: &str,
+    expected: Option<&RemoteVersion>,
+    force: bool,
+) -> Result<Option<SyncState>> {
+    let mut request = settings.authorize(client.put(&settings.url)).body(contents.to_string());
+    if !force {
+        request = match expected.and_then(|version| version.etag.as_deref()) {
+            Some(etag) => request.header(IF_MATCH, etag),
+            None if expected.is_none() => request.header(IF_NONE_MATCH, "*"),
+            // Сервер без ETag: условная запись невозможна, версия уже сверена по Last-Modified
+            None => request,
+        };
+    }
+
+    let response = send(request, &settings.url).await?;
+    match response.status() {
+        StatusCode::PRECONDITION_FAILED => Ok(None),
+        status if status.is_success() => {
+            // Не все серверы возвращают ETag в ответ на PUT, тогда узнаем версию отдельным запросом
+            let mut version = RemoteVersion::from_response(&response);
+            if version.etag.is_none() && version.last_modified.is_none() {
+                version = remote_version(client, settings).await?.unwrap_or_default();
+            }
+            Ok(Some(SyncState::synced(Some(version), contents)))
+        }
+        status => Err(unexpected(status, &settings.url)),
+    }
+}
+
+/// Загружает файл с сервера
+async fn download(client: &reqwest::Client, settings: &WebDavSettings) -> Result<(String, RemoteVersion)> {
+    let response = send(settings.authorize(client.get(&settings.url)), &settings.url).await?;
+    if !response.status().is_success() {
+        return Err(unexpected(response.status(), &settings.url));
+    }
+
+    let version = RemoteVersion::from_response(&response);
+    let contents = response
+        .text()
+        .await
+        .map_err(|e| PromptToolError::Execution(format!("Ошибка чтения ответа {}: {}", settings.url, e)))?;
+    Ok((contents, version))
+}
+
+/// Синхронизирует локальный файл с удаленным
+/// Изменения одной стороны переносятся на другую; если изменились обе и содержимое различается,
+/// возвращается `Conflict` и ничего не перезаписывается
+pub async fn sync(settings: &WebDavSettings, state: &SyncState, local: &str) -> Result<SyncResult> {
+    settings.check()?;
+    let client = reqwest::Client::new();
+
+    let remote = remote_version(&client, settings).await?;
+    let local_changed = state.local_hash.as_deref() != Some(content_hash(local).as_str());
+    let remote_changed = remote != state.remote;
+
+    let conflict = || SyncResult { action: SyncAction::Conflict, contents: None, state: state.clone() };
+
+    match plan(local_changed, remote_changed) {
+        SyncAction::None => Ok(SyncResult { action: SyncAction::None, contents: None, state: state.clone() }),
+        SyncAction::Push => match upload(&client, settings, local, remote.as_ref(), false).await? {
+            Some(state) => Ok(SyncResult { action: SyncAction::Push, contents: None, state }),
+            // Файл на сервере изменился между проверкой версии и записью
+            None => Ok(conflict()),
+        },
+        SyncAction::Pull | SyncAction::Conflict if remote.is_none() => {
+            // Файл удален с сервера: восстанавливаем его из локальной копии
+            match upload(&client, settings, local, None, false).await? {
+                Some(state) => Ok(SyncResult { action: SyncAction::Push, contents: None, state }),
+                None => Ok(conflict()),
+            }
+        }
+        action => {
+            let (contents, version) = download(&client, settings).await?;
+            let state = SyncState::synced(Some(version), &contents);
+
+            if contents == local {
+                // Обе стороны пришли к одинаковому содержимому
+                Ok(SyncResult { action: SyncAction::None, contents: None, state })
+            } else if action == SyncAction::Pull {
+                Ok(SyncResult { action: SyncAction::Pull, contents: Some(contents), state })
+            } else {
+                Ok(conflict())
+            }
+        }
+    }
+}
+
+/// Принудительно отправляет локальный файл на сервер, перезаписывая удаленный (разрешение конфликта)
+pub async fn force_push(settings: &WebDavSettings, local: &str) -> Result<SyncState> {
+    settings.check()?;
+    let client = reqwest::Client::new();
+
+    upload(&client, settings, local, None, true)
+        .await?
+        .ok_or_else(|| PromptToolError::Execution("WebDAV-сервер отклонил запись файла".to_string()))
+}
+
+/// Принудительно загружает файл с сервера, заменяя локальный (разрешение конфликта)
+/// Возвращает новое содержимое и состояние синхронизации
+pub async fn force_pull(settings: &WebDavSettings) -> Result<(String, SyncState)> {
+    settings.check()?;
+    let client = reqwest::Client::new();
+
+    let (contents, version) = download(&client, settings).await?;
+    let state = SyncState::synced(Some(version), &contents);
+    Ok((contents, state))
+}
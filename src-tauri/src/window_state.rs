@@ -0,0 +1,88 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PromptToolError, Result};
+use crate::file_io::write_atomic;
+
+/// Имя файла с положением окна в директории данных приложения
+pub const WINDOW_STATE_FILE_NAME: &str = "window_state.json";
+
+/// Сколько пикселей окна должно оставаться на мониторе, чтобы его можно было перетащить за заголовок
+const MIN_VISIBLE: i32 = 48;
+
+/// Прямоугольник в физических пикселях
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    /// Пересекаются ли прямоугольники хотя бы на MIN_VISIBLE пикселей по каждой оси
+    fn overlaps(&self, other: &Rect) -> bool {
+        let overlap = |start: i32, len: u32, other_start: i32, other_len: u32| {
+            let end = start.saturating_add(len as i32).min(other_start.saturating_add(other_len as i32));
+            end - start.max(other_start) >= MIN_VISIBLE
+        };
+        overlap(self.x, self.width, other.x, other.width) && overlap(self.y, self.height, other.y, other.height)
+    }
+}
+
+/// Монитор, доступный при запуске
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonitorArea {
+    /// Имя монитора в системе; может отсутствовать
+    pub name: Option<String>,
+    /// Область монитора в общем пространстве рабочего стола
+    pub area: Rect,
+}
+
+/// Положение и размер окна на момент закрытия
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowState {
+    /// Положение и размер окна в физических пикселях
+    pub bounds: Rect,
+    /// Монитор, на котором было окно
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<String>,
+    /// Было ли окно развернуто
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+impl WindowState {
+    /// Загружает сохраненное положение окна; без файла или при поврежденном файле возвращает None
+    pub fn load(path: &Path) -> Option<Self> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+    }
+
+    /// Сохраняет положение окна
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(PromptToolError::Io)?;
+        }
+
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| PromptToolError::Config(format!("Ошибка сериализации положения окна: {}", e)))?;
+        write_atomic(path, contents.as_bytes())
+    }
+
+    /// Можно ли вернуть окно на прежнее место при текущих мониторах
+    /// Нельзя, если монитор, на котором было окно, отключен или окно оказалось бы за пределами
+    /// всех мониторов; тогда восстанавливается только размер, а окно открывается по центру
+    pub fn position_fits(&self, monitors: &[MonitorArea]) -> bool {
+        let monitor_present = match &self.monitor {
+            Some(name) => monitors.iter().any(|monitor| monitor.name.as_deref() == Some(name)),
+            None => true,
+        };
+
+        monitor_present && monitors.iter().any(|monitor| self.bounds.overlaps(&monitor.area))
+    }
+}
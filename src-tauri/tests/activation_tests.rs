@@ -0,0 +1,43 @@
+#[cfg(all(test, unix))]
+mod tests {
+    use prompt_tool_lib::activation::{import_path_arg, listen, send_command, ActivationCommand};
+    use std::path::{Path, PathBuf};
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_activation_command_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("activation.sock");
+
+        let (sender, receiver) = mpsc::channel();
+        listen(&path, move |command| {
+            let _ = sender.send(command);
+        }).unwrap();
+
+        send_command(&path, ActivationCommand::Toggle).unwrap();
+        send_command(&path, ActivationCommand::Show).unwrap();
+
+        let timeout = Duration::from_secs(5);
+        assert_eq!(receiver.recv_timeout(timeout).unwrap(), ActivationCommand::Toggle);
+        assert_eq!(receiver.recv_timeout(timeout).unwrap(), ActivationCommand::Show);
+
+        // Второй экземпляр не может занять тот же сокет
+        assert!(listen(&path, |_| {}).is_err());
+    }
+
+    #[test]
+    fn test_import_path_from_second_launch() {
+        let cwd = Path::new("/home/user/Downloads");
+
+        assert_eq!(import_path_arg(["prompts.json"], cwd), Some(PathBuf::from("/home/user/Downloads/prompts.json")));
+        assert_eq!(import_path_arg(["/tmp/awesome.csv"], cwd), Some(PathBuf::from("/tmp/awesome.csv")));
+
+        // Флаги и значение --config-dir путем к файлу не считаются
+        let args = ["--config-dir", "/etc/prompt-tool", "--verbose", "team.json"];
+        assert_eq!(import_path_arg(args, cwd), Some(PathBuf::from("/home/user/Downloads/team.json")));
+        assert_eq!(import_path_arg(["--config-dir=/etc/prompt-tool"], cwd), None);
+        assert_eq!(import_path_arg(Vec::<String>::new(), cwd), None);
+    }
+}
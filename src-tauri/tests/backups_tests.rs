@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use prompt_tool_lib::backups::BackupStore;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_backups_rotate() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("default.toml");
+        let other = temp_dir.path().join("work.toml");
+        let store = BackupStore::new(temp_dir.path().join("backups"));
+        let start = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+
+        // Файла еще нет - копировать нечего
+        assert_eq!(store.create(&source, start, 3).unwrap(), None);
+
+        for version in 1..=5 {
+            fs::write(&source, format!("version {}", version)).unwrap();
+            store.create(&source, start + Duration::milliseconds(version * 1500), 3).unwrap();
+        }
+        fs::write(&other, "other").unwrap();
+        store.create(&other, start, 3).unwrap();
+
+        // Хранятся три последние копии, копии другого файла не учитываются
+        let backups = store.list(&source).unwrap();
+        assert_eq!(backups.len(), 3);
+        assert_eq!(backups[0].created_at, start + Duration::milliseconds(7500));
+        assert_eq!(store.read(&source, &backups[0].file_name).unwrap(), "version 5");
+        assert_eq!(store.read(&source, &backups[2].file_name).unwrap(), "version 3");
+        assert_eq!(store.list(&other).unwrap().len(), 1);
+
+        assert_eq!(store.prune(&source, 1).unwrap(), 2);
+        assert_eq!(store.list(&source).unwrap().len(), 1);
+
+        // Отключенные копии не создаются
+        assert_eq!(store.create(&source, start + Duration::hours(1), 0).unwrap(), None);
+    }
+
+    #[test]
+    fn test_unchanged_file_is_not_copied_again() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("default.toml");
+        let store = BackupStore::new(temp_dir.path().join("backups"));
+        let start = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+
+        fs::write(&source, "version 1").unwrap();
+        assert!(store.create(&source, start, 3).unwrap().is_some());
+
+        // Повторные попытки сохранения того же файла не вытесняют старые копии
+        for second in 1..=5 {
+            assert_eq!(store.create(&source, start + Duration::seconds(second), 3).unwrap(), None);
+        }
+        assert_eq!(store.list(&source).unwrap().len(), 1);
+
+        fs::write(&source, "version 2").unwrap();
+        assert!(store.create(&source, start + Duration::minutes(1), 3).unwrap().is_some());
+        assert_eq!(store.newest(&source).unwrap().unwrap().created_at, start + Duration::minutes(1));
+    }
+
+    #[test]
+    fn test_read_rejects_unknown_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("default.toml");
+        fs::write(&source, "secret").unwrap();
+        let store = BackupStore::new(temp_dir.path().join("backups"));
+
+        assert!(store.read(&source, "../default.toml").is_err());
+        assert!(store.read(&source, "default.20261016-120000.000.toml").is_err());
+    }
+}
@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::cache::{CacheLimits, LruCache};
+
+    #[test]
+    fn test_lru_evicts_least_recently_used() {
+        let mut cache = LruCache::new(CacheLimits { max_entries: 2, max_bytes: 1024 });
+
+        cache.insert("a".to_string(), vec![1u8]);
+        cache.insert("b".to_string(), vec![2u8]);
+
+        // Обращение к "a" делает самой давней запись "b"
+        assert_eq!(cache.get(&"a".to_string()), Some(vec![1u8]));
+        cache.insert("c".to_string(), vec![3u8]);
+
+        assert!(cache.get(&"b".to_string()).is_none());
+        assert!(cache.get(&"a".to_string()).is_some());
+        assert!(cache.get(&"c".to_string()).is_some());
+
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.evictions, 1);
+    }
+
+    #[test]
+    fn test_lru_respects_byte_limit() {
+        let mut cache = LruCache::new(CacheLimits { max_entries: 100, max_bytes: 16 });
+
+        cache.insert(1, vec![0u8; 10]);
+        cache.insert(2, vec![0u8; 10]);
+
+        // Вместе записи превышают лимит, остается только последняя
+        assert!(cache.get(&1).is_none());
+        assert!(cache.get(&2).is_some());
+
+        // Слишком большое значение не кэшируется вовсе
+        cache.insert(3, vec![0u8; 32]);
+        assert!(cache.get(&3).is_none());
+        assert!(cache.stats().bytes <= 16);
+    }
+
+    #[test]
+    fn test_lru_oversized_value_drops_stale_entry() {
+        let mut cache = LruCache::new(CacheLimits { max_entries: 100, max_bytes: 16 });
+
+        cache.insert(1, vec![0u8; 4]);
+        assert!(cache.get(&1).is_some());
+
+        // Новое значение не помещается в кэш, прежнее для того же ключа устарело
+        cache.insert(1, vec![0u8; 32]);
+        assert!(cache.get(&1).is_none());
+        assert_eq!(cache.stats().entries, 0);
+        assert_eq!(cache.stats().bytes, 0);
+    }
+
+    #[test]
+    fn test_lru_counts_key_size() {
+        let mut cache = LruCache::new(CacheLimits { max_entries: 100, max_bytes: 16 });
+
+        // Значение мало, но вместе с длинным ключом запись больше лимита
+        cache.insert("x".repeat(20), vec![0u8; 1]);
+        assert_eq!(cache.stats().entries, 0);
+
+        cache.insert("key".to_string(), vec![0u8; 4]);
+        assert_eq!(cache.stats().bytes, 7);
+    }
+}
@@ -0,0 +1,29 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::chain::{ChainStep, PromptChain};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_chain_step_values() {
+        let chain: PromptChain = serde_json::from_str(r#"{
+            "name": "summarize-translate",
+            "steps": [
+                { "prompt_name": "Summarize" },
+                { "prompt_name": "Translate", "input_parameter": "text", "values": { "language": "en" } }
+            ]
+        }"#).unwrap();
+        chain.validate().unwrap();
+
+        let initial = HashMap::from([("input".to_string(), "long text".to_string())]);
+
+        let first = chain.step_values(0, &initial, None);
+        assert_eq!(first["input"], "long text");
+
+        let second = chain.step_values(1, &initial, Some("summary"));
+        assert_eq!(second["text"], "summary");
+        assert_eq!(second["language"], "en");
+
+        let empty = PromptChain { name: "empty".to_string(), steps: Vec::<ChainStep>::new() };
+        assert!(empty.validate().is_err());
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::chatgpt::{extract_user_messages, propose_prompts, ChatGptImportOptions, CHATGPT_TAG};
+    use prompt_tool_lib::prompt::{Prompt, PromptList};
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    fn export() -> serde_json::Value {
+        json!([
+            {
+                "title": "Code Review",
+                "mapping": {
+                    "b": { "message": { "author": { "role": "user" }, "create_time": 1700000100.0,
+                        "content": { "content_type": "text", "parts": ["Review this diff for bugs"] } } },
+                    "a": { "message": { "author": { "role": "user" }, "create_time": 1700000000.5,
+                        "content": { "content_type": "text", "parts": ["Summarize the PR"] } } },
+                    "c": { "message": { "author": { "role": "assistant" }, "create_time": 1700000050.0,
+                        "content": { "content_type": "text", "parts": ["Sure"] } } },
+                    "root": { "message": null }
+                }
+            },
+            {
+                "title": "",
+                "mapping": {
+                    "x": { "message": { "author": { "role": "user" }, "create_time": null,
+                        "content": { "content_type": "text", "parts": ["Summarize the PR", { "asset": "image" }] } } },
+                    "y": { "message": { "author": { "role": "user" },
+                        "content": { "content_type": "text", "parts": ["hi"] } } }
+                }
+            }
+        ])
+    }
+
+    #[test]
+    fn test_extract_user_messages() {
+        let messages = extract_user_messages(&export()).unwrap();
+        let texts: Vec<&str> = messages.iter().map(|message| message.text.as_str()).collect();
+        assert_eq!(texts, vec!["Summarize the PR", "Review this diff for bugs", "Summarize the PR", "hi"]);
+        assert_eq!(messages[0].conversation, "Code Review");
+        assert_eq!(messages[0].created_at.unwrap().timestamp(), 1700000000);
+
+        assert!(extract_user_messages(&json!({})).is_err());
+    }
+
+    #[test]
+    fn test_propose_prompts() {
+        let library = PromptList {
+            prompts: vec![Prompt::new("Summarize the PR".to_string(), "x".to_string(), Vec::new(), HashSet::new(), HashSet::new())],
+        };
+        let options = ChatGptImportOptions { min_length: 5, ..ChatGptImportOptions::default() };
+
+        let prompts = propose_prompts(extract_user_messages(&export()).unwrap(), &options, &library).unwrap();
+        let names: Vec<&str> = prompts.iter().map(|prompt| prompt.name.as_str()).collect();
+        assert_eq!(names, vec!["Summarize the PR (2)", "Review this diff for bugs"]);
+        assert_eq!(prompts[0].tags, HashSet::from([CHATGPT_TAG.to_string(), "code-review".to_string()]));
+        assert_eq!(prompts[0].created_at.timestamp(), 1700000000);
+
+        let filtered = ChatGptImportOptions { pattern: Some("(?i)review".to_string()), ..ChatGptImportOptions::default() };
+        let prompts = propose_prompts(extract_user_messages(&export()).unwrap(), &filtered, &PromptList::new()).unwrap();
+        assert_eq!(prompts.len(), 1);
+
+        let invalid = ChatGptImportOptions { pattern: Some("(".to_string()), ..ChatGptImportOptions::default() };
+        assert!(propose_prompts(Vec::new(), &invalid, &PromptList::new()).is_err());
+    }
+}
@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::clipboard::{html_clipboard_format, prepare_text, LineEnding, PasteOptions};
+
+    #[test]
+    fn test_prepare_text_normalizes_line_endings() {
+        let options = PasteOptions { line_endings: LineEnding::Crlf, ..PasteOptions::default() };
+        assert_eq!(prepare_text("\u{FEFF}a\r\nb\rc\nd", &options), "a\r\nb\r\nc\r\nd");
+
+        let options = PasteOptions { line_endings: LineEnding::Lf, strip_bom: false, ..PasteOptions::default() };
+        assert_eq!(prepare_text("\u{FEFF}a\r\nb", &options), "\u{FEFF}a\nb");
+    }
+
+    #[test]
+    fn test_html_clipboard_format_offsets() {
+        let html = html_clipboard_format("Привет <мир>\r\nвторая строка");
+
+        let offset = |name: &str| -> usize {
+            let line = html.lines().find(|line| line.starts_with(name)).unwrap();
+            line[name.len() + 1..].parse().unwrap()
+        };
+
+        let fragment = &html[offset("StartFragment")..offset("EndFragment")];
+        assert!(fragment.contains("Привет &lt;мир&gt;<br>"));
+        assert!(html[offset("StartHTML")..].starts_with("<html>"));
+        assert_eq!(offset("EndHTML"), html.len());
+    }
+}
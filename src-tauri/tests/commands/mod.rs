@@ -0,0 +1,512 @@
+//! Сквозные проверки команд: обработчики вызываются через IPC тестового рантайма Tauri
+//! с временными директорией конфигурации, библиотекой и поисковым индексом.
+//! Подключается из main.rs, т.к. команды объявлены в бинарном крейте
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+use tauri::ipc::{CallbackFn, InvokeBody};
+use tauri::test::{get_ipc_response, mock_builder, mock_context, noop_assets, MockRuntime, INVOKE_KEY};
+use tauri::webview::InvokeRequest;
+use tauri::{App, WebviewWindow, WebviewWindowBuilder};
+use tempfile::TempDir;
+
+use super::*;
+
+const LIBRARY: &str = r#"
+schema_version = 2
+
+[[prompts]]
+name = "Commit"
+content = "Write a commit message for {changes}"
+parameters = ["changes"]
+categories = ["git"]
+tags = ["vcs"]
+
+[[prompts]]
+name = "Review"
+content = "Review this code for bugs"
+parameters = []
+categories = ["dev"]
+tags = ["code"]
+
+[[prompts]]
+name = "Translate"
+content = "Translate {text} to {lang}"
+parameters = ["text"]
+categories = ["writing"]
+tags = []
+"#;
+
+/// Приложение на тестовом рантайме с временными файлами
+struct Harness {
+    app: App<MockRuntime>,
+    webview: WebviewWindow<MockRuntime>,
+    library: PathBuf,
+    // Удаляется последним, после закрытия индекса
+    _dir: TempDir,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let dir = TempDir::new().unwrap();
+        let library = dir.path().join("prompts.toml");
+        fs::write(&library, LIBRARY).unwrap();
+
+        let app = mock_builder()
+            .manage(AppState::new())
+            .manage(AppPaths::from_override(dir.path().join("config")))
+            .invoke_handler(tauri::generate_handler![
+                get_prompts,
+                get_prompt_detail,
+                set_prompt_file_path,
+                recover_prompt_file,
+                confirm_prompt_file_recovery,
+                search_prompts,
+                set_synonyms,
+                sync_parameters,
+                resolve_merge,
+                list_prompt_revisions,
+                edit_prompt,
+                is_dirty,
+                save_pending_edits,
+                restore_backup,
+                set_prompt_file_read_only,
+                is_prompt_file_read_only,
+                list_profiles,
+                switch_profile,
+                set_prompt_hotkey,
+                launcher_search,
+                launcher_select,
+                update_settings,
+            ])
+            .build(mock_context(noop_assets()))
+            .unwrap();
+        let webview = WebviewWindowBuilder::new(&app, "main", Default::default()).build().unwrap();
+
+        // Индекс открывается так же, как на этапе open_index при запуске
+        let database = open_database(app.handle()).unwrap();
+        *app.state::<AppState>().database.write().unwrap() = Some(database);
+
+        Self { app, webview, library, _dir: dir }
+    }
+
+    /// Вызывает команду так же, как фронтенд; аргументы в camelCase
+    fn invoke(&self, cmd: &str, args: Value) -> std::result::Result<Value, String> {
+        let request = InvokeRequest {
+            cmd: cmd.to_string(),
+            callback: CallbackFn(0),
+            error: CallbackFn(1),
+            url: "http://tauri.localhost".parse().unwrap(),
+            body: InvokeBody::Json(args),
+            headers: Default::default(),
+            invoke_key: INVOKE_KEY.to_string(),
+        };
+
+        get_ipc_response(&self.webview, request)
+            .map(|body| body.deserialize::<Value>().unwrap())
+            .map_err(|error| error.to_string())
+    }
+
+    fn search(&self, query: &str) -> Vec<String> {
+        names(&self.invoke("search_prompts", json!({ "filter": { "query": query } })).unwrap())
+    }
+
+    /// Имена промптов открытой библиотеки, все на одной странице
+    fn listed(&self) -> Vec<String> {
+        names(&self.invoke("get_prompts", json!({})).unwrap()["items"])
+    }
+
+    /// Промпт целиком; у промптов тестовой библиотеки нет идентификаторов, поэтому ищем по имени
+    fn detail(&self, name: &str) -> Value {
+        self.invoke("get_prompt_detail", json!({ "id": name })).unwrap()
+    }
+
+    fn saved(&self) -> PromptList {
+        load_prompts(&self.library.to_string_lossy()).unwrap()
+    }
+}
+
+fn names(prompts: &Value) -> Vec<String> {
+    prompts.as_array()
+        .unwrap()
+        .iter()
+        .map(|prompt| prompt["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_load_edit_search_save_reload() {
+    let harness = Harness::new();
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+    assert_eq!(harness.search("commit"), vec!["Commit"]);
+
+    // Правка: промпт заменяется целиком, как при сохранении из редактора
+    let mut edited = harness.detail("Commit");
+    edited["content"] = json!("Write a changelog entry for {changes}");
+    harness.invoke("resolve_merge", json!({ "promptName": "Commit", "prompt": edited })).unwrap();
+
+    // Индекс обновлен сразу после правки
+    assert_eq!(harness.search("changelog"), vec!["Commit"]);
+    assert!(harness.search("message").is_empty());
+
+    // Правка записана в файл и в историю ревизий
+    assert_eq!(harness.saved().prompts[0].content, "Write a changelog entry for {changes}");
+    let revisions = harness.invoke("list_prompt_revisions", json!({ "promptName": "Commit" })).unwrap();
+    assert_eq!(revisions.as_array().unwrap().len(), 2);
+
+    // Повторная загрузка файла дает ту же библиотеку и тот же поиск
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+    assert_eq!(harness.search("changelog"), vec!["Commit"]);
+    assert_eq!(harness.listed(), vec!["Commit", "Review", "Translate"]);
+
+    let config = harness.app.state::<AppState>().config.read().unwrap().prompt_file_path.clone();
+    assert_eq!(PathBuf::from(config), harness.library);
+}
+
+#[test]
+fn test_synonyms_change_search_results() {
+    let harness = Harness::new();
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+    assert!(harness.search("defects").is_empty());
+
+    // Тот же запрос после изменения синонимов не берется из кэша
+    harness.invoke("set_synonyms", json!({ "rules": ["defects = bugs"] })).unwrap();
+    assert_eq!(harness.search("defects"), vec!["Review"]);
+
+    harness.invoke("set_synonyms", json!({ "rules": [] })).unwrap();
+    assert!(harness.search("defects").is_empty());
+}
+
+#[test]
+fn test_sync_parameters_persists() {
+    let harness = Harness::new();
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+
+    let sync = harness.invoke("sync_parameters", json!({ "promptName": "Translate" })).unwrap();
+    assert_eq!(sync["added"], json!(["lang"]));
+
+    let saved = harness.saved();
+    let parameters: Vec<&str> = saved.prompts[2].parameters.iter().map(|spec| spec.name.as_str()).collect();
+    assert_eq!(parameters, vec!["text", "lang"]);
+}
+
+#[test]
+fn test_command_errors_reach_frontend() {
+    let harness = Harness::new();
+
+    let missing = harness.invoke("set_prompt_file_path", json!({ "path": harness.library.with_extension("missing") }));
+    assert!(missing.unwrap_err().contains("Файл не существует"));
+
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+    let unknown = harness.invoke("sync_parameters", json!({ "promptName": "Nope" }));
+    assert!(unknown.unwrap_err().contains("не найден"));
+
+    // Неудачная команда не меняет файл
+    assert_eq!(fs::read_to_string(&harness.library).unwrap(), LIBRARY);
+}
+
+#[test]
+fn test_edits_in_memory_until_flushed() {
+    let harness = Harness::new();
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+    assert_eq!(harness.invoke("is_dirty", json!({})).unwrap(), json!(false));
+
+    let mut edited = harness.detail("Review");
+    edited["content"] = json!("Review this code for races");
+    harness.invoke("edit_prompt", json!({ "promptName": "Review", "prompt": edited })).unwrap();
+
+    // Правка видна только в памяти, но поиск уже находит новый текст
+    assert_eq!(harness.invoke("is_dirty", json!({})).unwrap(), json!(true));
+    assert_eq!(fs::read_to_string(&harness.library).unwrap(), LIBRARY);
+    assert_eq!(harness.search("races"), vec!["Review"]);
+    assert!(harness.search("bugs").is_empty());
+
+    assert_eq!(harness.invoke("save_pending_edits", json!({})).unwrap(), json!(true));
+    assert_eq!(harness.invoke("is_dirty", json!({})).unwrap(), json!(false));
+    assert_eq!(harness.saved().prompts[1].content, "Review this code for races");
+    assert_eq!(harness.search("races"), vec!["Review"]);
+
+    // Сохранять больше нечего
+    assert_eq!(harness.invoke("save_pending_edits", json!({})).unwrap(), json!(false));
+}
+
+#[test]
+fn test_read_only_file_rejects_changes() {
+    let harness = Harness::new();
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+    assert_eq!(harness.invoke("is_prompt_file_read_only", json!({})).unwrap(), json!(false));
+
+    harness.invoke("set_prompt_file_read_only", json!({ "readOnly": true })).unwrap();
+    assert_eq!(harness.invoke("is_prompt_file_read_only", json!({})).unwrap(), json!(true));
+
+    // Изменяющие команды отказывают до правок в памяти
+    let mut edited = harness.detail("Review");
+    edited["content"] = json!("Review this code for races");
+    let error = harness.invoke("edit_prompt", json!({ "promptName": "Review", "prompt": edited.clone() })).unwrap_err();
+    assert!(error.contains("read_only"), "{}", error);
+    assert!(harness.invoke("sync_parameters", json!({ "promptName": "Translate" })).unwrap_err().contains("read_only"));
+    assert!(harness.invoke("resolve_merge", json!({ "promptName": "Review", "prompt": edited })).unwrap_err().contains("read_only"));
+
+    assert_eq!(harness.invoke("is_dirty", json!({})).unwrap(), json!(false));
+    assert_eq!(harness.detail("Review")["content"], json!("Review this code for bugs"));
+    assert_eq!(fs::read_to_string(&harness.library).unwrap(), LIBRARY);
+
+    // Поиск и чтение продолжают работать, после снятия отметки правки снова сохраняются
+    assert_eq!(harness.search("commit"), vec!["Commit"]);
+    harness.invoke("set_prompt_file_read_only", json!({ "readOnly": false })).unwrap();
+    harness.invoke("sync_parameters", json!({ "promptName": "Translate" })).unwrap();
+    assert_eq!(harness.saved().prompts[2].parameters.len(), 2);
+}
+
+#[test]
+fn test_profiles_keep_separate_libraries() {
+    let harness = Harness::new();
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+
+    // Конфигурация профиля work с отдельным файлом промптов и горячей клавишей
+    let dir = harness.library.parent().unwrap().to_path_buf();
+    let work_library = dir.join("work.toml");
+    fs::write(&work_library, "schema_version = 2\n\n[[prompts]]\nname = \"Standup\"\ncontent = \"Summarize yesterday\"\n").unwrap();
+    let work_config = dir.join("config").join("profiles").join("work");
+    fs::create_dir_all(&work_config).unwrap();
+    fs::write(
+        work_config.join("config.json"),
+        json!({ "prompt_file_path": work_library, "hotkey": "Ctrl+Alt+W" }).to_string(),
+    ).unwrap();
+
+    let hotkey = harness.invoke("switch_profile", json!({ "name": "work" })).unwrap();
+    assert_eq!(hotkey, json!("Ctrl+Alt+W"));
+    assert_eq!(harness.listed(), vec!["Standup"]);
+    assert_eq!(harness.search("summarize"), vec!["Standup"]);
+    assert!(harness.search("commit").is_empty());
+
+    let profiles = harness.invoke("list_profiles", json!({})).unwrap();
+    assert_eq!(profiles, json!({ "active": "work", "profiles": ["default", "work"] }));
+
+    // Профиль по умолчанию возвращается к своей библиотеке и индексу
+    assert_eq!(harness.invoke("switch_profile", json!({ "name": "default" })).unwrap(), json!(""));
+    assert_eq!(harness.search("commit"), vec!["Commit"]);
+    assert!(harness.search("summarize").is_empty());
+
+    assert!(harness.invoke("switch_profile", json!({ "name": "../escape" })).unwrap_err().contains("Недопустимое имя"));
+    assert_eq!(harness.invoke("list_profiles", json!({})).unwrap()["active"], json!("default"));
+}
+
+#[test]
+fn test_prompt_hotkeys_stored_in_config() {
+    let harness = Harness::new();
+    // Привязка хранит идентификатор промпта, поэтому он должен быть в файле
+    let library = LIBRARY
+        .replace("name = \"Commit\"", "name = \"Commit\"\nid = \"commit\"")
+        .replace("name = \"Review\"", "name = \"Review\"\nid = \"review\"");
+    fs::write(&harness.library, library).unwrap();
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+    let hotkeys = || harness.app.state::<AppState>().config.read().unwrap().prompt_hotkeys.clone();
+
+    harness.invoke("set_prompt_hotkey", json!({ "promptName": "Commit", "shortcut": "Ctrl+Alt+1", "autoPaste": true })).unwrap();
+    assert_eq!(hotkeys()["Ctrl+Alt+1"], PromptHotkey { prompt_id: "commit".to_string(), auto_paste: true });
+
+    // Сочетание другого промпта не перехватывается
+    let taken = harness.invoke("set_prompt_hotkey", json!({ "promptName": "Review", "shortcut": "alt+ctrl+1", "autoPaste": false }));
+    assert!(taken.unwrap_err().contains("другому промпту"));
+
+    // Новое сочетание заменяет прежнее, `null` снимает привязку
+    harness.invoke("set_prompt_hotkey", json!({ "promptName": "Commit", "shortcut": "Ctrl+Alt+2", "autoPaste": false })).unwrap();
+    assert_eq!(hotkeys().keys().collect::<Vec<_>>(), vec!["Ctrl+Alt+2"]);
+    harness.invoke("set_prompt_hotkey", json!({ "promptName": "Commit", "shortcut": null, "autoPaste": false })).unwrap();
+    assert!(hotkeys().is_empty());
+
+    let missing_id = harness.invoke("set_prompt_hotkey", json!({ "promptName": "Translate", "shortcut": "Ctrl+Alt+3", "autoPaste": false }));
+    assert!(missing_id.unwrap_err().contains("нет идентификатора"));
+}
+
+#[test]
+fn test_launcher_search_and_select() {
+    let harness = Harness::new();
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+
+    let items = harness.invoke("launcher_search", json!({ "query": "commit" })).unwrap();
+    assert_eq!(names(&items), vec!["Commit"]);
+    assert_eq!(items[0]["preview"], "Write a commit message for {changes}");
+
+    // Значение без умолчания нужно спросить, выбор запоминается для ранжирования
+    let selection = harness.invoke("launcher_select", json!({ "query": "commit", "promptName": "Commit" })).unwrap();
+    assert_eq!(selection["inputs"][0]["name"], "changes");
+    assert!(harness.app.state::<AppState>().feedback.read().unwrap().clicks("commit", "Commit") > 0.0);
+
+    let missing = harness.invoke("launcher_select", json!({ "query": "x", "promptName": "Missing" }));
+    assert!(missing.is_err());
+}
+
+#[test]
+fn test_new_profile_gets_absolute_default_file() {
+    let harness = Harness::new();
+    harness.invoke("switch_profile", json!({ "name": "fresh" })).unwrap();
+
+    let path = PathBuf::from(harness.app.state::<AppState>().config.read().unwrap().prompt_file_path.clone());
+    assert!(path.is_absolute());
+    assert!(path.starts_with(harness.library.parent().unwrap().join("config")));
+    assert_eq!(harness.listed(), vec!["Example Prompt"]);
+}
+
+#[test]
+fn test_update_settings_validates_and_applies() {
+    let harness = Harness::new();
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+    assert_eq!(harness.search("").len(), 3);
+
+    // Ошибка приходит объектом с кодом, текстом и подробностями
+    let invalid = harness.invoke("update_settings", json!({ "settings": { "result_limit": 2, "language": "xx" } }));
+    let invalid: Value = serde_json::from_str(&invalid.unwrap_err()).unwrap();
+    assert_eq!(invalid["code"], "validation");
+    assert!(invalid["message"].as_str().is_some_and(|message| !message.is_empty()));
+    assert!(invalid["details"].as_str().unwrap().contains("language"));
+    assert_eq!(harness.search("").len(), 3);
+
+    let settings = harness.invoke("update_settings", json!({ "settings": { "result_limit": 2 } })).unwrap();
+    assert_eq!(settings["result_limit"], 2);
+    assert_eq!(harness.search("").len(), 2);
+
+    // Настройки сохраняются в config.json на верхнем уровне
+    let config = fs::read_to_string(harness.library.parent().unwrap().join("config").join("config.json")).unwrap();
+    assert_eq!(serde_json::from_str::<Value>(&config).unwrap()["result_limit"], 2);
+}
+
+#[test]
+fn test_prompts_paged_with_detail() {
+    let harness = Harness::new();
+    let library = LIBRARY.replace("name = \"Review\"", "name = \"Review\"\nid = \"review\"");
+    fs::write(&harness.library, library).unwrap();
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+
+    // Курсор - позиция и идентификатор последнего промпта страницы, без него - смещение
+    let first = harness.invoke("get_prompts", json!({ "page": { "limit": 2 } })).unwrap();
+    assert_eq!(names(&first["items"]), vec!["Commit", "Review"]);
+    assert_eq!(first["total"], json!(3));
+    assert_eq!(first["next_cursor"], json!("2:review"));
+
+    let second = harness.invoke("get_prompts", json!({ "page": { "limit": 2, "cursor": "2:review" } })).unwrap();
+    assert_eq!(names(&second["items"]), vec!["Translate"]);
+    assert_eq!(second["offset"], json!(2));
+    assert_eq!(second["next_cursor"], Value::Null);
+
+    // Список по пути читает файл, а не открытую библиотеку
+    let by_path = harness.invoke("get_prompts", json!({ "filePath": harness.library, "page": { "offset": 1 } })).unwrap();
+    assert_eq!(names(&by_path["items"]), vec!["Review", "Translate"]);
+
+    let detail = harness.detail("review");
+    assert_eq!(detail["content"], json!("Review this code for bugs"));
+    assert_eq!(detail["parameters"], json!([]));
+    assert!(harness.invoke("get_prompt_detail", json!({ "id": "missing" })).unwrap_err().contains("validation"));
+    assert!(harness.invoke("get_prompts", json!({ "page": { "limit": 0 } })).is_err());
+}
+
+#[test]
+fn test_unparsed_library_rejects_changes() {
+    let harness = Harness::new();
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+
+    // Профиль, файл промптов которого не разбирается
+    let dir = harness.library.parent().unwrap().to_path_buf();
+    let broken_library = dir.join("broken.toml");
+    let broken = "schema_version = 2\n\n[[prompts]]\nname = \"Standup\ncontent = \"x\"\n";
+    fs::write(&broken_library, broken).unwrap();
+    let broken_config = dir.join("config").join("profiles").join("broken");
+    fs::create_dir_all(&broken_config).unwrap();
+    fs::write(broken_config.join("config.json"), json!({ "prompt_file_path": broken_library }).to_string()).unwrap();
+
+    assert!(harness.invoke("switch_profile", json!({ "name": "broken" })).is_err());
+    assert!(harness.listed().is_empty());
+
+    // Правка пустой библиотеки записала бы ее поверх файла
+    let error = harness.invoke("sync_parameters", json!({ "promptName": "Standup" })).unwrap_err();
+    assert!(error.contains("не загружена"), "{}", error);
+    assert_eq!(harness.invoke("save_pending_edits", json!({})).unwrap(), json!(false));
+    assert_eq!(fs::read_to_string(&broken_library).unwrap(), broken);
+}
+
+#[test]
+fn test_recovered_library_read_only_until_confirmed() {
+    let harness = Harness::new();
+    let contents = "schema_version = 2\n\n[[prompts]]\nname = \"Standup\"\ncontent = \"Plan for {day}\"\nparameters = []\n\n[[prompts]]\nname = \"Broken\ncontent = \"x\"\n";
+    fs::write(&harness.library, contents).unwrap();
+
+    let broken = harness.invoke("recover_prompt_file", json!({ "path": harness.library })).unwrap();
+    assert_eq!(broken.as_array().unwrap().len(), 1);
+    assert_eq!(harness.listed(), vec!["Standup"]);
+
+    // До подтверждения поврежденная запись остается в файле
+    let error = harness.invoke("sync_parameters", json!({ "promptName": "Standup" })).unwrap_err();
+    assert!(error.contains("режиме восстановления"), "{}", error);
+    assert_eq!(fs::read_to_string(&harness.library).unwrap(), contents);
+
+    harness.invoke("confirm_prompt_file_recovery", json!({})).unwrap();
+    harness.invoke("sync_parameters", json!({ "promptName": "Standup" })).unwrap();
+    assert_eq!(harness.saved().prompts.len(), 1);
+    assert!(harness.invoke("confirm_prompt_file_recovery", json!({})).is_err());
+}
+
+#[test]
+fn test_corrupt_config_is_backed_up() {
+    let harness = Harness::new();
+    let config_dir = harness.library.parent().unwrap().join("config");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config_path = config_dir.join("config.json");
+    fs::write(&config_path, "{ \"prompt_file_path\": ").unwrap();
+
+    // Поврежденный файл заменяется настройками по умолчанию, но не теряется
+    read_config(harness.app.handle(), &config_path).unwrap();
+
+    let backups: Vec<String> = fs::read_dir(&config_dir).unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("config.json.") && name.ends_with(".bak"))
+        .collect();
+    assert_eq!(backups.len(), 1);
+    assert_eq!(fs::read_to_string(config_dir.join(&backups[0])).unwrap(), "{ \"prompt_file_path\": ");
+}
+
+#[test]
+fn test_external_change_is_not_overwritten() {
+    let harness = Harness::new();
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+
+    // Другая программа дописала промпт после загрузки
+    let external = format!("{}\n[[prompts]]\nname = \"External\"\ncontent = \"x\"\nparameters = []\n", LIBRARY);
+    fs::write(&harness.library, &external).unwrap();
+
+    let error = harness.invoke("sync_parameters", json!({ "promptName": "Translate" })).unwrap_err();
+    assert!(error.contains("conflict"), "{}", error);
+    assert_eq!(fs::read_to_string(&harness.library).unwrap(), external);
+
+    // После перечитывания файла правка сохраняется вместе с внешним промптом
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+    harness.invoke("sync_parameters", json!({ "promptName": "Translate" })).unwrap();
+    assert_eq!(harness.saved().prompts.len(), 4);
+}
+
+#[test]
+fn test_restore_keeps_unsaved_edits_in_backups() {
+    let harness = Harness::new();
+    harness.invoke("set_prompt_file_path", json!({ "path": harness.library })).unwrap();
+
+    // Сохранение создает резервную копию исходного файла
+    harness.invoke("sync_parameters", json!({ "promptName": "Translate" })).unwrap();
+    let source = harness.library.as_path();
+    let original = backup_store(harness.app.handle()).newest(source).unwrap().unwrap();
+
+    let mut edited = harness.detail("Review");
+    edited["content"] = json!("Review this code for races");
+    harness.invoke("edit_prompt", json!({ "promptName": "Review", "prompt": edited })).unwrap();
+
+    harness.invoke("restore_backup", json!({ "fileName": original.file_name })).unwrap();
+    assert_eq!(harness.invoke("is_dirty", json!({})).unwrap(), json!(false));
+    assert_eq!(harness.saved().prompts[1].content, "Review this code for bugs");
+    assert_eq!(harness.detail("Review")["content"], json!("Review this code for bugs"));
+
+    // Правка из памяти записана перед восстановлением и осталась в резервных копиях
+    let store = backup_store(harness.app.handle());
+    let newest = store.newest(source).unwrap().unwrap();
+    assert!(store.read(source, &newest.file_name).unwrap().contains("Review this code for races"));
+}
@@ -0,0 +1,29 @@
+// Общие помощники интеграционных тестов.
+// Каждый файл тестов подключает модуль через `mod common;` и использует только часть функций
+#![allow(dead_code)]
+
+use prompt_tool_lib::parameters::ParameterSpec;
+use prompt_tool_lib::prompt::Prompt;
+use std::collections::HashSet;
+
+/// Промпт без параметров, категорий и тегов
+pub fn prompt(name: &str, content: &str) -> Prompt {
+    Prompt::new(name.to_string(), content.to_string(), Vec::new(), HashSet::new(), HashSet::new())
+}
+
+/// Промпт с тегами
+pub fn tagged(name: &str, content: &str, tags: &[&str]) -> Prompt {
+    let mut prompt = prompt(name, content);
+    prompt.tags = strings(tags);
+    prompt
+}
+
+/// Множество строк для категорий и тегов
+pub fn strings(values: &[&str]) -> HashSet<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+/// Параметры строкового типа с указанными именами
+pub fn parameters(names: &[&str]) -> Vec<ParameterSpec> {
+    names.iter().map(|name| ParameterSpec::from(*name)).collect()
+}
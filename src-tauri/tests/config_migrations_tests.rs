@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::config_migrations::{
+        config_version, deserialize_lenient, migrate_config, MigrationContext, APP_VERSION, APP_VERSION_KEY,
+        CONFIG_VERSION_KEY, CURRENT_CONFIG_VERSION,
+    };
+    use prompt_tool_lib::settings::Settings;
+    use serde_json::{json, Map, Value};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("ожидался объект"),
+        }
+    }
+
+    #[test]
+    fn test_migrate_unversioned_config_moves_default_prompt_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let legacy = temp_dir.path().join("cwd").join("default.toml");
+        let target = temp_dir.path().join("data").join("prompts").join("default.toml");
+        fs::create_dir_all(legacy.parent().unwrap()).unwrap();
+        fs::write(&legacy, "prompts = []").unwrap();
+        let context = MigrationContext { default_prompt_file: &target, legacy_prompt_file: &legacy };
+
+        let mut config = object(json!({ "prompt_file_path": "prompts/default.toml", "result_limit": 20 }));
+        assert_eq!(config_version(&config).unwrap(), 0);
+
+        let applied = migrate_config(&mut config, &context).unwrap();
+        assert_eq!(applied.len(), 1);
+        assert_eq!(config["prompt_file_path"], json!(target.to_string_lossy()));
+        assert_eq!(config["result_limit"], json!(20));
+        assert_eq!(config[CONFIG_VERSION_KEY], json!(CURRENT_CONFIG_VERSION));
+        assert_eq!(config[APP_VERSION_KEY], json!(APP_VERSION));
+        assert_eq!(fs::read_to_string(&target).unwrap(), "prompts = []");
+
+        // Повторное обновление ничего не меняет
+        assert!(migrate_config(&mut config, &context).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_migrate_rejects_newer_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("default.toml");
+        let context = MigrationContext { default_prompt_file: &target, legacy_prompt_file: &target };
+
+        let mut config = object(json!({ CONFIG_VERSION_KEY: CURRENT_CONFIG_VERSION + 1 }));
+        assert!(migrate_config(&mut config, &context).is_err());
+        assert_eq!(config[CONFIG_VERSION_KEY], json!(CURRENT_CONFIG_VERSION + 1));
+    }
+
+    #[test]
+    fn test_deserialize_lenient_keeps_valid_fields() {
+        let config = object(json!({ "result_limit": 20, "language": 5, "unknown": true }));
+        let (settings, dropped): (Settings, _) = deserialize_lenient(config).unwrap();
+
+        assert_eq!(settings.result_limit, 20);
+        assert_eq!(settings.language, Settings::default().language);
+        assert_eq!(dropped, vec!["language".to_string()]);
+
+        let (settings, dropped): (Settings, _) = deserialize_lenient(object(json!({ "backup_count": 7 }))).unwrap();
+        assert_eq!(settings.backup_count, 7);
+        assert!(dropped.is_empty());
+    }
+}
@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use prompt_tool_lib::crash::{record_command, take_pending_report, CrashReport, RECENT_COMMANDS_LIMIT};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_report_is_written_and_offered_once() {
+        for index in 0..RECENT_COMMANDS_LIMIT + 5 {
+            record_command(&format!("command_{}", index));
+        }
+
+        let time = Utc.with_ymd_and_hms(2026, 3, 1, 12, 30, 0).unwrap();
+        let report = CrashReport::new(
+            "index out of range".to_string(),
+            Some("src/database.rs:42".to_string()),
+            "0: main".to_string(),
+            time,
+        );
+        assert_eq!(report.recent_commands.len(), RECENT_COMMANDS_LIMIT);
+        assert_eq!(report.recent_commands.last().map(String::as_str), Some("command_24"));
+        assert_eq!(report.recent_commands.first().map(String::as_str), Some("command_5"));
+
+        let temp_dir = TempDir::new().unwrap();
+        let crash_dir = temp_dir.path().join("crashes");
+        assert_eq!(take_pending_report(&crash_dir), None);
+
+        let path = report.write(&crash_dir).unwrap();
+        assert!(path.ends_with("crash-20260301-123000.txt"));
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.contains("index out of range"));
+        assert!(text.contains("src/database.rs:42"));
+        assert!(text.contains("command_24"));
+        assert!(text.contains(&report.app_version));
+
+        assert_eq!(take_pending_report(&crash_dir), Some(path.clone()));
+        assert_eq!(take_pending_report(&crash_dir), None);
+        assert!(path.exists());
+    }
+}
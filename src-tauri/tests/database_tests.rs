@@ -1,13 +1,14 @@
 #[cfg(test)]
 mod tests {
-    use prompt_tool_lib::database::{Database, Record};
+    use prompt_tool_lib::database::{dedup_hits, Database, DateField, DateRange, QuerySyntaxError, Record, SuggestionKind, DEFAULT_READER_CACHE_BLOCKS};
+    use prompt_tool_lib::synonyms::SynonymMap;
     use serial_test::serial;
     use tantivy::IndexWriter;
     use tempfile::TempDir;
 
     fn create_test_database() -> (Database, TempDir) {
         let temp_dir = TempDir::new().unwrap();
-        let db = Database::new(temp_dir.path().to_str().unwrap());
+        let db = Database::new(temp_dir.path().to_str().unwrap()).unwrap();
         (db, temp_dir)
     }
 
@@ -29,6 +30,8 @@ mod tests {
             title: "Test Title".to_string(),
             tags: vec!["tag1".to_string(), "tag2".to_string()],
             text: "Test text".to_string(),
+            metadata: String::new(),
+            aliases: Vec::new(),
             created_at: 1000,  // фиксированное время для тестов
             updated_at: 1000,
         };
@@ -37,6 +40,65 @@ mod tests {
         assert!(result.is_ok(), "Failed to add record");
     }
 
+    #[test]
+    #[serial]
+    fn test_add_records() {
+        let (db, _temp_dir) = create_test_database();
+        clear_index(&db).unwrap();
+
+        let records = (1..=100)
+            .map(|id| Record {
+                id,
+                title: format!("Title {}", id),
+                tags: vec!["bulk".to_string()],
+                text: format!("Bulk text {}", id),
+                metadata: String::new(),
+                aliases: Vec::new(),
+                created_at: 1000,
+                updated_at: 1000,
+            })
+            .collect();
+
+        db.add_records(records).unwrap();
+
+        // Все записи должны быть доступны после одного коммита
+        assert!(db.get_record_by_id(1).unwrap().is_some(), "First record should exist");
+        assert!(db.get_record_by_id(100).unwrap().is_some(), "Last record should exist");
+        assert_eq!(db.get_record_by_id(42).unwrap().unwrap().title, "Title 42");
+    }
+
+    #[test]
+    #[serial]
+    fn test_replace_all_records() {
+        let (db, _temp_dir) = create_test_database();
+        clear_index(&db).unwrap();
+
+        db.add_record(Record {
+            id: 1,
+            title: "Old Title".to_string(),
+            tags: vec![],
+            text: "Old text".to_string(),
+            metadata: String::new(),
+            aliases: Vec::new(),
+            created_at: 1000,
+            updated_at: 1000,
+        }).unwrap();
+
+        db.replace_all_records(vec![Record {
+            id: 2,
+            title: "New Title".to_string(),
+            tags: vec![],
+            text: "New text".to_string(),
+            metadata: String::new(),
+            aliases: Vec::new(),
+            created_at: 1000,
+            updated_at: 1000,
+        }]).unwrap();
+
+        assert!(db.get_record_by_id(1).unwrap().is_none(), "Old record should be removed");
+        assert!(db.get_record_by_id(2).unwrap().is_some(), "New record should exist");
+    }
+
     #[test]
     #[serial]
     fn test_get_record_by_id() {
@@ -48,6 +110,8 @@ mod tests {
             title: "Test Title".to_string(),
             tags: vec!["tag1".to_string(), "tag2".to_string()],
             text: "Test text".to_string(),
+            metadata: String::new(),
+            aliases: Vec::new(),
             created_at: 1000,
             updated_at: 1000,
         };
@@ -77,6 +141,8 @@ mod tests {
             title: "Original Title".to_string(),
             tags: vec!["tag1".to_string()],
             text: "Original text".to_string(),
+            metadata: String::new(),
+            aliases: Vec::new(),
             created_at: 1000,
             updated_at: 1000,
         };
@@ -106,6 +172,8 @@ mod tests {
             title: "Test Title".to_string(),
             tags: vec!["tag1".to_string()],
             text: "Test text".to_string(),
+            metadata: String::new(),
+            aliases: Vec::new(),
             created_at: 1000,
             updated_at: 1000,
         };
@@ -134,6 +202,8 @@ mod tests {
                 title: "First Title".to_string(),
                 tags: vec!["tag1".to_string()],
                 text: "First test text".to_string(),
+                metadata: String::new(),
+                aliases: Vec::new(),
                 created_at: 1000,
                 updated_at: 1000,
             },
@@ -142,6 +212,8 @@ mod tests {
                 title: "Second Title".to_string(),
                 tags: vec!["tag2".to_string()],
                 text: "Second test text".to_string(),
+                metadata: "Ada Lovelace\nCC0-1.0".to_string(),
+                aliases: Vec::new(),
                 created_at: 1000,
                 updated_at: 1000,
             },
@@ -160,6 +232,10 @@ mod tests {
         let results = db.search("tag2").unwrap();
         assert!(!results.is_empty(), "Should find records with tag2");
         assert!(results.iter().any(|r| r.contains("Second")), "Results should contain record with tag2");
+
+        // Поиск по метаданным
+        let results = db.search("Lovelace").unwrap();
+        assert_eq!(results, vec!["Second test text"]);
     }
 
     #[test]
@@ -174,6 +250,8 @@ mod tests {
                 title: "Тестовый заголовок".to_string(),
                 tags: vec!["тест".to_string(), "русский".to_string()],
                 text: "Это тестовый текст на русском языке".to_string(),
+                metadata: String::new(),
+                aliases: Vec::new(),
                 created_at: 1000,
                 updated_at: 1000,
             },
@@ -182,6 +260,8 @@ mod tests {
                 title: "Mixed language заголовок".to_string(),
                 tags: vec!["test".to_string(), "mixed".to_string()],
                 text: "This is a mixed текст with русскими словами".to_string(),
+                metadata: String::new(),
+                aliases: Vec::new(),
                 created_at: 1000,
                 updated_at: 1000,
             },
@@ -203,4 +283,303 @@ mod tests {
         let results = db.search("русский").unwrap();
         assert!(!results.is_empty(), "Should find records with Russian tags");
     }
+
+    #[test]
+    #[serial]
+    fn test_search_with_date_range() {
+        let (db, _temp_dir) = create_test_database();
+        clear_index(&db).unwrap();
+
+        db.add_records(vec![
+            Record {
+                id: 1,
+                title: "Old prompt".to_string(),
+                tags: vec![],
+                text: "Old review text".to_string(),
+                metadata: String::new(),
+                aliases: Vec::new(),
+                created_at: 1000,
+                updated_at: 1000,
+            },
+            Record {
+                id: 2,
+                title: "Fresh prompt".to_string(),
+                tags: vec![],
+                text: "Fresh review text".to_string(),
+                metadata: String::new(),
+                aliases: Vec::new(),
+                created_at: 1000,
+                updated_at: 5000,
+            },
+        ]).unwrap();
+
+        let range = DateRange { field: DateField::UpdatedAt, from: Some(2000), to: None };
+
+        // Диапазон вместе с текстовым запросом
+        let results = db.search_with_range("review", Some(range)).unwrap();
+        assert_eq!(results, vec!["Fresh review text"]);
+
+        // Только диапазон, без текста
+        let ids = db.search_ids("", Some(range), 10).unwrap();
+        assert_eq!(ids, vec![2]);
+
+        // Верхняя граница включительная
+        let range = DateRange { field: DateField::UpdatedAt, from: None, to: Some(1000) };
+        assert_eq!(db.search_ids("", Some(range), 10).unwrap(), vec![1]);
+    }
+
+    #[test]
+    #[serial]
+    fn test_suggest() {
+        let (db, _temp_dir) = create_test_database();
+        clear_index(&db).unwrap();
+
+        db.add_records(vec![
+            Record {
+                id: 1,
+                title: "Conventional commit message".to_string(),
+                tags: vec!["git".to_string()],
+                text: "Write a commit message".to_string(),
+                metadata: String::new(),
+                aliases: Vec::new(),
+                created_at: 1000,
+                updated_at: 1000,
+            },
+            Record {
+                id: 2,
+                title: "Code review".to_string(),
+                tags: vec!["review".to_string(), "commits".to_string()],
+                text: "Review this code".to_string(),
+                metadata: String::new(),
+                aliases: Vec::new(),
+                created_at: 1000,
+                updated_at: 1000,
+            },
+        ]).unwrap();
+
+        // Префикс совпадает с началом слова в середине названия и с тегом
+        let suggestions = db.suggest("Comm", 10).unwrap();
+        assert!(suggestions.iter().any(|s| s.kind == SuggestionKind::Title && s.text == "Conventional commit message"));
+        assert!(suggestions.iter().any(|s| s.kind == SuggestionKind::Tag && s.text == "commits"));
+        assert!(!suggestions.iter().any(|s| s.text == "Code review"));
+
+        // Лимит соблюдается
+        assert_eq!(db.suggest("c", 1).unwrap().len(), 1);
+
+        // Пустой префикс ничего не возвращает
+        assert!(db.suggest("", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn test_search_with_synonyms() {
+        let (mut db, _temp_dir) = create_test_database();
+        clear_index(&db).unwrap();
+
+        db.add_record(Record {
+            id: 1,
+            title: "Refactoring".to_string(),
+            tags: vec![],
+            text: "Refactor this javascript function".to_string(),
+            metadata: String::new(),
+            aliases: Vec::new(),
+            created_at: 1000,
+            updated_at: 1000,
+        }).unwrap();
+
+        // Без синонимов сокращение не находит запись
+        assert!(db.search("js").unwrap().is_empty());
+
+        db.set_synonyms(SynonymMap::from_rules(&["js = javascript".to_string()]));
+        let results = db.search("js").unwrap();
+        assert_eq!(results, vec!["Refactor this javascript function"]);
+    }
+
+    #[test]
+    #[serial]
+    fn test_fuzzy_search_tolerates_typos() {
+        let (mut db, _temp_dir) = create_test_database();
+        clear_index(&db).unwrap();
+
+        db.add_record(Record {
+            id: 1,
+            title: "Refactoring".to_string(),
+            tags: vec![],
+            text: "Refactor this javascript function".to_string(),
+            metadata: String::new(),
+            aliases: Vec::new(),
+            created_at: 1000,
+            updated_at: 1000,
+        }).unwrap();
+
+        // По умолчанию опечатка не прощается
+        assert!(db.search("javascrpt").unwrap().is_empty());
+
+        db.set_fuzzy_distance(1);
+        assert_eq!(db.search("javascrpt").unwrap(), vec!["Refactor this javascript function"]);
+        assert!(db.search("jvscrpt").unwrap().is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn test_title_boost_ranks_title_matches_first() {
+        let (db, _temp_dir) = create_test_database();
+        clear_index(&db).unwrap();
+
+        db.add_records(vec![
+            Record {
+                id: 1,
+                title: "Generic helper".to_string(),
+                tags: vec![],
+                text: "Translate the text. Translate it carefully, translate every sentence.".to_string(),
+                metadata: String::new(),
+                aliases: Vec::new(),
+                created_at: 1000,
+                updated_at: 1000,
+            },
+            Record {
+                id: 2,
+                title: "Translate".to_string(),
+                tags: vec![],
+                text: "Some long body about languages and style".to_string(),
+                metadata: String::new(),
+                aliases: Vec::new(),
+                created_at: 1000,
+                updated_at: 1000,
+            },
+        ]).unwrap();
+
+        let ids = db.search_ids("translate", None, 10).unwrap();
+        assert_eq!(ids.first(), Some(&2), "Title match should rank above body matches");
+    }
+
+    #[test]
+    #[serial]
+    fn test_alias_ranks_first() {
+        let (db, _temp_dir) = create_test_database();
+        clear_index(&db).unwrap();
+
+        db.add_records(vec![
+            Record {
+                id: 1,
+                title: "Cm cm cm".to_string(),
+                tags: vec!["cm".to_string()],
+                text: "cm cm cm".to_string(),
+                metadata: String::new(),
+                aliases: Vec::new(),
+                created_at: 1000,
+                updated_at: 1000,
+            },
+            Record {
+                id: 2,
+                title: "Conventional commit message".to_string(),
+                tags: vec![],
+                text: "Write a commit message".to_string(),
+                metadata: String::new(),
+                aliases: vec!["CM".to_string(), "commit-msg".to_string()],
+                created_at: 1000,
+                updated_at: 1000,
+            },
+        ]).unwrap();
+
+        assert_eq!(db.search_ids("cm", None, 10).unwrap().first(), Some(&2));
+        assert_eq!(db.search_ids("commit-msg", None, 10).unwrap().first(), Some(&2));
+        assert_eq!(db.get_record_by_id(2).unwrap().unwrap().aliases, vec!["CM", "commit-msg"]);
+    }
+
+    #[test]
+    #[serial]
+    fn test_search_with_invalid_syntax_does_not_panic() {
+        let (db, _temp_dir) = create_test_database();
+        clear_index(&db).unwrap();
+
+        db.add_record(Record {
+            id: 1,
+            title: "Summary".to_string(),
+            tags: vec![],
+            text: "Summarize the title of the article".to_string(),
+            metadata: String::new(),
+            aliases: Vec::new(),
+            created_at: 1000,
+            updated_at: 1000,
+        }).unwrap();
+
+        // Незакрытая скобка разбирается как обычные слова
+        let results = db.search("title:(").unwrap();
+        assert_eq!(results, vec!["Summarize the title of the article"]);
+
+        // Запрос из одних служебных символов не приводит к панике, а дает ошибку синтаксиса
+        let error = db.search(":(\"").unwrap_err();
+        assert!(error.is::<QuerySyntaxError>(), "Expected a syntax error, got {}", error);
+    }
+
+    #[test]
+    #[serial]
+    fn test_search_ids_deduplicates_records() {
+        let (db, _temp_dir) = create_test_database();
+        clear_index(&db).unwrap();
+
+        let record = || Record {
+            id: 7,
+            title: "Review".to_string(),
+            tags: vec![],
+            text: "Review the code".to_string(),
+            metadata: String::new(),
+            aliases: Vec::new(),
+            created_at: 1000,
+            updated_at: 1000,
+        };
+        // Повторное добавление без удаления оставляет в индексе два документа с одним id
+        db.add_record(record()).unwrap();
+        db.add_record(record()).unwrap();
+
+        assert_eq!(db.search_ids("review", None, 10).unwrap(), vec![7]);
+        assert_eq!(dedup_hits(vec![3, 1, 3, 2, 1]), vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn test_in_memory_database_searches_without_files() {
+        let db = Database::in_memory(&[tantivy::tokenizer::Language::English]).unwrap();
+        assert!(!db.needs_reindex());
+
+        let record = Record {
+            id: 7,
+            title: "Secret plan".to_string(),
+            tags: vec!["private".to_string()],
+            text: "Encrypted library text".to_string(),
+            metadata: String::new(),
+            aliases: Vec::new(),
+            created_at: 1000,
+            updated_at: 1000,
+        };
+        db.replace_all_records(vec![record]).unwrap();
+
+        assert_eq!(db.num_docs().unwrap(), 1);
+        assert_eq!(db.search_ids("library", None, 10).unwrap(), vec![7]);
+    }
+
+    #[test]
+    fn test_reader_cache_is_capped() {
+        let mut db = Database::in_memory(&[tantivy::tokenizer::Language::English]).unwrap();
+        assert_eq!(db.reader_cache_stats().max_entries, DEFAULT_READER_CACHE_BLOCKS);
+
+        let record = Record {
+            id: 1,
+            title: "Cached".to_string(),
+            tags: vec![],
+            text: "Reader cache text".to_string(),
+            metadata: String::new(),
+            aliases: Vec::new(),
+            created_at: 1000,
+            updated_at: 1000,
+        };
+        db.replace_all_records(vec![record]).unwrap();
+
+        // Без кэша поиск работает, а блоки документов в памяти не остаются
+        db.set_reader_cache_blocks(0).unwrap();
+        assert_eq!(db.search("cache").unwrap(), vec!["Reader cache text"]);
+        let stats = db.reader_cache_stats();
+        assert_eq!(stats.entries, 0);
+        assert_eq!(stats.max_bytes, 0);
+    }
 }
@@ -0,0 +1,50 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{parameters, tagged};
+    use prompt_tool_lib::diff::{diff_prompts, unified_diff};
+    use prompt_tool_lib::parameters::{ParameterKind, ParameterSpec};
+
+    #[test]
+    fn test_unified_diff() {
+        assert_eq!(unified_diff("a\nb", "a\nb", "v1", "v2"), "");
+
+        assert_eq!(
+            unified_diff("a\nb\nc", "a\nB\nc\nd", "v1", "v2"),
+            "--- v1\n+++ v2\n@@ -1,3 +1,4 @@\n a\n-b\n+B\n c\n+d\n"
+        );
+
+        // Изменения, далекие друг от друга, попадают в разные фрагменты
+        let old: Vec<String> = (1..=20).map(|line| line.to_string()).collect();
+        let mut new = old.clone();
+        new[1] = "two".to_string();
+        new[17] = "eighteen".to_string();
+        let diff = unified_diff(&old.join("\n"), &new.join("\n"), "v1", "v2");
+        assert_eq!(diff.matches("@@ -").count(), 2);
+        assert!(diff.contains("@@ -1,5 +1,5 @@\n"));
+    }
+
+    #[test]
+    fn test_diff_prompts() {
+        let mut old = tagged("Review", "Review {code}", &["review", "git"]);
+        old.parameters = parameters(&["code", "lang"]);
+
+        let mut number = ParameterSpec::from("lang");
+        number.kind = ParameterKind::Number;
+        let mut new = tagged("Review", "Review {code} in {lang}", &["review", "go"]);
+        new.parameters = vec!["code".into(), number, "style".into()];
+
+        let diff = diff_prompts(&old, &new, "a", "b");
+        assert!(diff.content.contains("-Review {code}\n+Review {code} in {lang}\n"));
+        assert_eq!(diff.tags.added, vec!["go"]);
+        assert_eq!(diff.tags.removed, vec!["git"]);
+        assert!(diff.categories.is_empty());
+        assert_eq!(diff.parameters.added, vec![ParameterSpec::from("style")]);
+        assert!(diff.parameters.removed.is_empty());
+        assert_eq!(diff.parameters.changed.len(), 1);
+        assert_eq!(diff.parameters.changed[0].after.kind, ParameterKind::Number);
+
+        assert!(diff_prompts(&old, &old, "a", "a").is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::prompt;
+    use prompt_tool_lib::duplicates::{find_duplicates, shingles, similarity};
+    use prompt_tool_lib::prompt::PromptList;
+
+    #[test]
+    fn test_similarity() {
+        let a = shingles("Review this code for bugs and style issues");
+        let b = shingles("review this code, for bugs and style issues!");
+        assert_eq!(similarity(&a, &b), 1.0);
+
+        let c = shingles("Write a haiku about autumn");
+        assert_eq!(similarity(&a, &c), 0.0);
+        assert_eq!(shingles("Hi").len(), 1);
+        assert!(shingles("  ").is_empty());
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let list = PromptList {
+            prompts: vec![
+                prompt("Review", "Review this {language} code for bugs, style issues and missing tests"),
+                prompt("Haiku", "Write a haiku about autumn"),
+                prompt("Review copy", "Review this {language} code for bugs, style issues and missing docs"),
+                prompt("Empty", ""),
+                prompt("Empty too", ""),
+            ],
+        };
+
+        let pairs = find_duplicates(&list, 0.7);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!((pairs[0].first.as_str(), pairs[0].second.as_str()), ("Review", "Review copy"));
+        assert!(pairs[0].similarity < 1.0);
+
+        assert!(find_duplicates(&list, 0.95).is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::encryption::{self, FileKey};
+    use prompt_tool_lib::file_io::{load_prompts, save_prompts};
+    use prompt_tool_lib::prompt::{Prompt, PromptList};
+    use std::collections::HashSet;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const LIBRARY: &str = r#"
+[[prompts]]
+name = "Secret"
+content = "Confidential {topic}"
+parameters = ["topic"]
+"#;
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let key = FileKey::generate("correct horse").unwrap();
+        let encrypted = encryption::encrypt(LIBRARY, &key).unwrap();
+
+        assert!(encryption::is_encrypted(&encrypted));
+        assert!(!encrypted.contains("Confidential"));
+        assert_eq!(encryption::decrypt(&encrypted, &key).unwrap(), LIBRARY);
+
+        // Каждое шифрование использует новый nonce
+        assert_ne!(encryption::encrypt(LIBRARY, &key).unwrap(), encrypted);
+    }
+
+    #[test]
+    fn test_wrong_passphrase_rejected() {
+        let key = FileKey::generate("correct horse").unwrap();
+        let encrypted = encryption::encrypt(LIBRARY, &key).unwrap();
+
+        assert!(encryption::key_for(&encrypted, "correct horse").is_ok());
+        assert!(encryption::key_for(&encrypted, "wrong").is_err());
+        assert!(FileKey::generate("").is_err());
+
+        // Измененный шифротекст не проходит проверку подлинности
+        let start = encrypted.rfind(':').unwrap() + 1;
+        let replacement = if encrypted[start..].starts_with('A') { "B" } else { "A" };
+        let mut tampered = encrypted.clone();
+        tampered.replace_range(start..start + 1, replacement);
+        assert!(encryption::decrypt(&tampered, &key).is_err());
+    }
+
+    #[test]
+    fn test_transparent_load_and_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("secret.toml");
+        let file_path = path.to_string_lossy().to_string();
+        fs::write(&path, LIBRARY).unwrap();
+
+        // Включение шифрования: ключ регистрируется, сохранение шифрует файл
+        let library = load_prompts(&file_path).unwrap();
+        encryption::unlock(&path, FileKey::generate("passphrase").unwrap()).unwrap();
+        save_prompts(&file_path, &library).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(encryption::is_encrypted(&contents));
+        assert_eq!(load_prompts(&file_path).unwrap().prompts[0].name, "Secret");
+
+        // Заблокированный файл не читается и не перезаписывается открытым текстом
+        assert!(encryption::lock(&path).unwrap());
+        assert!(load_prompts(&file_path).is_err());
+        let mut changed = PromptList::new();
+        changed.prompts.push(Prompt::new("Other".to_string(), "text".to_string(), Vec::new(), HashSet::new(), HashSet::new()));
+        assert!(save_prompts(&file_path, &changed).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+
+        // Повторная разблокировка парольной фразой
+        let key = encryption::key_for(&contents, "passphrase").unwrap();
+        encryption::unlock(&path, key).unwrap();
+        assert_eq!(load_prompts(&file_path).unwrap().prompts.len(), 1);
+        encryption::lock(&path).unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{prompt, tagged};
+    use prompt_tool_lib::espanso::{build_matches, export_matches, FormField};
+    use prompt_tool_lib::parameters::{ParameterKind, ParameterSpec};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parameters_become_form_fields() {
+        let mut translate = tagged("Translate", "Translate {text} to {lang}, tone {tone|default:neutral}; {text|upper}", &["writing"]);
+        translate.parameters = vec![ParameterSpec::from("text"), ParameterSpec::from("lang"), ParameterSpec::from("tone")];
+        translate.parameters[1].kind = ParameterKind::Enum(vec!["en".to_string(), "ru".to_string()]);
+        let greeting = prompt("Greeting", "Hello!");
+
+        let file = build_matches(&[&translate, &greeting], ":");
+        let rule = &file.matches[0];
+        assert_eq!(rule.trigger, ":writing-translate");
+        assert_eq!(rule.form.as_deref(), Some("Translate [[text]] to [[lang]], tone [[tone]]; [[text]]"));
+        assert_eq!(rule.replace, None);
+
+        let fields: Vec<&str> = rule.form_fields.keys().map(String::as_str).collect();
+        assert_eq!(fields, vec!["lang", "tone"]);
+        assert_eq!(rule.form_fields["lang"], FormField {
+            kind: Some("choice".to_string()),
+            default: None,
+            values: vec!["en".to_string(), "ru".to_string()],
+        });
+        assert_eq!(rule.form_fields["tone"].default.as_deref(), Some("neutral"));
+
+        assert_eq!(file.matches[1].replace.as_deref(), Some("Hello!"));
+        assert_eq!(file.matches[1].form, None);
+    }
+
+    #[test]
+    fn test_export_yaml() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("prompts.yml");
+        let review = prompt("Review", "Review {code}");
+
+        assert_eq!(export_matches(&[&review], ";", &path).unwrap(), 1);
+
+        let yaml: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let rule = &yaml["matches"][0];
+        assert_eq!(rule["trigger"].as_str(), Some(";review"));
+        assert_eq!(rule["label"].as_str(), Some("Review"));
+        assert_eq!(rule["form"].as_str(), Some("Review [[code]]"));
+        assert!(rule.get("form_fields").is_none());
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::expander::{ExpanderSettings, KeyInput, Trigger, TriggerBuffer, DEFAULT_TRIGGER_PREFIX};
+
+    fn type_text(buffer: &mut TriggerBuffer, text: &str) {
+        for c in text.chars() {
+            assert_eq!(buffer.push(KeyInput::Char(c), DEFAULT_TRIGGER_PREFIX), None);
+        }
+    }
+
+    #[test]
+    fn test_trigger_on_terminator() {
+        let mut buffer = TriggerBuffer::new();
+        type_text(&mut buffer, "see ;;cm");
+
+        let trigger = buffer.push(KeyInput::Terminator, DEFAULT_TRIGGER_PREFIX);
+        assert_eq!(trigger, Some(Trigger { alias: "cm".to_string(), erase: 5 }));
+
+        // После срабатывания буфер пуст
+        assert_eq!(buffer.push(KeyInput::Terminator, DEFAULT_TRIGGER_PREFIX), None);
+    }
+
+    #[test]
+    fn test_backspace_and_reset() {
+        let mut buffer = TriggerBuffer::new();
+        type_text(&mut buffer, ";;cmx");
+        buffer.push(KeyInput::Backspace, DEFAULT_TRIGGER_PREFIX);
+        assert_eq!(buffer.push(KeyInput::Terminator, DEFAULT_TRIGGER_PREFIX).unwrap().alias, "cm");
+
+        // Курсор переместился - набранное раньше не считается
+        type_text(&mut buffer, ";;c");
+        buffer.push(KeyInput::Reset, DEFAULT_TRIGGER_PREFIX);
+        type_text(&mut buffer, "m");
+        assert_eq!(buffer.push(KeyInput::Terminator, DEFAULT_TRIGGER_PREFIX), None);
+    }
+
+    #[test]
+    fn test_no_trigger_without_prefix_or_alias() {
+        let mut buffer = TriggerBuffer::new();
+        type_text(&mut buffer, "plain");
+        assert_eq!(buffer.push(KeyInput::Terminator, DEFAULT_TRIGGER_PREFIX), None);
+
+        type_text(&mut buffer, ";;");
+        assert_eq!(buffer.push(KeyInput::Terminator, DEFAULT_TRIGGER_PREFIX), None);
+
+        // Свой префикс
+        type_text(&mut buffer, "//км");
+        assert_eq!(buffer.push(KeyInput::Terminator, "//"), Some(Trigger { alias: "км".to_string(), erase: 5 }));
+    }
+
+    #[test]
+    fn test_settings_validation() {
+        assert!(ExpanderSettings::default().validate().is_ok());
+        assert!(!ExpanderSettings::default().enabled);
+
+        let empty = ExpanderSettings { enabled: true, trigger_prefix: String::new() };
+        assert!(empty.validate().is_err());
+        let spaced = ExpanderSettings { enabled: true, trigger_prefix: "; ".to_string() };
+        assert!(spaced.validate().is_err());
+    }
+}
@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::feedback::ClickFeedback;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rerank_by_clicks() {
+        let mut feedback = ClickFeedback::default();
+        let results = vec!["Review", "Commit", "Summary"];
+
+        // Без выборов порядок не меняется
+        assert_eq!(feedback.rerank("code review", results.clone(), |name| *name), results);
+
+        feedback.record("Code  Review", "Summary");
+        feedback.record("code review", "Summary");
+        feedback.record("code review", "Summary");
+        assert_eq!(feedback.clicks("code review", "Summary"), 3.0);
+
+        let reranked = feedback.rerank("code review", results.clone(), |name| *name);
+        assert_eq!(reranked, vec!["Summary", "Review", "Commit"]);
+
+        // Похожий запрос тоже учитывает выборы, непохожий - нет
+        assert!(feedback.clicks("review code please", "Summary") > 0.0);
+        assert_eq!(feedback.clicks("translate", "Summary"), 0.0);
+        assert_eq!(feedback.rerank("translate", results.clone(), |name| *name), results);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("feedback.json");
+
+        let mut feedback = ClickFeedback::default();
+        feedback.record("review", "Review");
+        feedback.save(&path).unwrap();
+
+        assert_eq!(ClickFeedback::load(&path).clicks("review", "Review"), 1.0);
+        assert_eq!(ClickFeedback::load(&temp_dir.path().join("missing.json")).clicks("review", "Review"), 0.0);
+    }
+}
@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::file_io::{line_column, load_prompts, parse_prompts, parse_prompts_recovering, save_prompts, write_atomic};
+    use prompt_tool_lib::parameters::ParameterSpec;
+    use prompt_tool_lib::prompt::{Prompt, PromptList};
+    use std::collections::HashSet;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn library() -> PromptList {
+        let review = Prompt::new(
+            "Review".to_string(),
+            "Review {code}\nwith \"quotes\" and ''' triple quotes".to_string(),
+            vec![ParameterSpec { description: "Multi-line\ndescription".to_string(), ..ParameterSpec::from("code") }],
+            HashSet::from(["dev".to_string()]),
+            HashSet::from(["code".to_string(), "review".to_string()]),
+        );
+        PromptList {
+            prompts: vec![
+                review,
+                Prompt::new("Пустой".to_string(), String::new(), Vec::new(), HashSet::new(), HashSet::new()),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_save_load_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("prompts.toml");
+        let file_path = path.to_string_lossy();
+
+        let saved = library();
+        save_prompts(&file_path, &saved).unwrap();
+        let loaded = load_prompts(&file_path).unwrap();
+
+        assert_eq!(loaded.prompts.len(), 2);
+        for (loaded, saved) in loaded.prompts.iter().zip(&saved.prompts) {
+            assert_eq!(loaded.id, saved.id);
+            assert_eq!(loaded.name, saved.name);
+            assert_eq!(loaded.content, saved.content);
+            assert_eq!(loaded.parameters, saved.parameters);
+            assert_eq!(loaded.categories, saved.categories);
+            assert_eq!(loaded.tags, saved.tags);
+            assert_eq!(loaded.created_at, saved.created_at);
+        }
+
+        // Повторное сохранение загруженной библиотеки дает тот же файл
+        let first = fs::read_to_string(&path).unwrap();
+        save_prompts(&file_path, &loaded).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), first);
+    }
+
+    #[test]
+    fn test_atomic_write_leaves_no_temp_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("prompts.toml");
+
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+
+        let files: Vec<_> = fs::read_dir(temp_dir.path()).unwrap().collect();
+        assert_eq!(files.len(), 1);
+    }
+
+    #[test]
+    fn test_failed_write_keeps_original() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("prompts.toml");
+        fs::write(&path, "original").unwrap();
+
+        // Запись в несуществующую директорию не трогает исходный файл
+        let missing = temp_dir.path().join("missing").join("prompts.toml");
+        assert!(write_atomic(&missing, b"new").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "original");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_atomic_write_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("prompts.toml");
+        fs::write(&path, "original").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_atomic(&path, b"new").unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o644);
+    }
+
+    const BROKEN_ENTRY: &str = r#"schema_version = 2
+
+[[prompts]]
+name = "Commit"
+content = "Write a commit message"
+parameters = []
+
+[[prompts]]
+name = "Review"
+parameters = []
+
+[[prompts]]
+name = "Translate"
+content = "Translate {text}"
+parameters = ["text"]
+"#;
+
+    #[test]
+    fn test_line_column() {
+        assert_eq!(line_column("a\nbc\nd", 0), (1, 1));
+        assert_eq!(line_column("a\nbc\nd", 4), (2, 3));
+        assert_eq!(line_column("я\nю", 100), (2, 2));
+    }
+
+    #[test]
+    fn test_parse_error_names_entry() {
+        let error = parse_prompts("prompts.toml", BROKEN_ENTRY).unwrap_err().to_string();
+        assert!(error.contains("строка 8"), "{}", error);
+        assert!(error.contains("промпт #2 «Review»"), "{}", error);
+        assert!(error.contains("content"), "{}", error);
+    }
+
+    #[test]
+    fn test_recover_broken_entry() {
+        let recovered = parse_prompts_recovering("prompts.toml", BROKEN_ENTRY).unwrap();
+        let names: Vec<&str> = recovered.prompts.prompts.iter().map(|prompt| prompt.name.as_str()).collect();
+        assert_eq!(names, vec!["Commit", "Translate"]);
+
+        assert_eq!(recovered.broken.len(), 1);
+        assert_eq!(recovered.broken[0].entry, Some(2));
+        assert_eq!(recovered.broken[0].name.as_deref(), Some("Review"));
+        assert_eq!(recovered.broken[0].line, 8);
+    }
+
+    #[test]
+    fn test_recover_syntax_error() {
+        let contents = BROKEN_ENTRY.replace("content = \"Translate {text}\"", "content = \"Translate {text}");
+
+        // Без восстановления синтаксическая ошибка останавливает загрузку с указанием строки
+        let error = parse_prompts("prompts.toml", &contents).unwrap_err().to_string();
+        assert!(error.contains("строка 14"), "{}", error);
+
+        let recovered = parse_prompts_recovering("prompts.toml", &contents).unwrap();
+        let names: Vec<&str> = recovered.prompts.prompts.iter().map(|prompt| prompt.name.as_str()).collect();
+        assert_eq!(names, vec!["Commit"]);
+
+        let broken: Vec<(Option<usize>, Option<&str>, usize)> = recovered.broken.iter()
+            .map(|diagnostic| (diagnostic.entry, diagnostic.name.as_deref(), diagnostic.line))
+            .collect();
+        assert_eq!(broken[0], (Some(2), Some("Review"), 8));
+        assert_eq!(broken[1].0, Some(3));
+        assert_eq!(broken[1].1, Some("Translate"));
+        assert_eq!(broken[1].2, 14);
+    }
+}
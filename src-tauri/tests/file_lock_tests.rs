@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::error::PromptToolError;
+    use prompt_tool_lib::file_io::{load_prompts, save_prompts};
+    use prompt_tool_lib::file_lock::{is_lock_file, lock, lock_exclusive, lock_path, LockMode};
+    use prompt_tool_lib::prompt::PromptList;
+    use std::fs;
+    use std::path::Path;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    const SHORT: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn test_lock_path() {
+        assert_eq!(lock_path(Path::new("/data/prompts.toml")), Path::new("/data/.prompts.toml.lock"));
+        assert!(is_lock_file(&lock_path(Path::new("prompts.toml"))));
+        assert!(!is_lock_file(Path::new("prompts.toml")));
+        assert!(!is_lock_file(Path::new(".lock")));
+    }
+
+    #[test]
+    fn test_shared_and_exclusive_locks() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("prompts.toml");
+
+        // Читатели не мешают друг другу, но мешают писателю
+        let first = lock(&path, LockMode::Shared, SHORT).unwrap();
+        let second = lock(&path, LockMode::Shared, SHORT).unwrap();
+        let error = lock(&path, LockMode::Exclusive, SHORT).unwrap_err();
+        assert!(matches!(error, PromptToolError::Locked(_)));
+
+        drop(first);
+        drop(second);
+        let writer = lock(&path, LockMode::Exclusive, SHORT).unwrap();
+        assert!(matches!(lock(&path, LockMode::Shared, SHORT), Err(PromptToolError::Locked(_))));
+
+        // После снятия блокировки файл снова доступен
+        drop(writer);
+        assert!(lock(&path, LockMode::Shared, SHORT).is_ok());
+    }
+
+    #[test]
+    fn test_locked_file_is_not_written() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("prompts.toml");
+        let file_path = path.to_string_lossy();
+        fs::write(&path, "").unwrap();
+
+        let held = lock_exclusive(&path).unwrap();
+        let error = save_prompts(&file_path, &PromptList::new()).unwrap_err();
+        assert!(matches!(error, PromptToolError::Locked(_)));
+        assert!(error.to_string().contains("занят"));
+        assert!(load_prompts(&file_path).is_err());
+
+        drop(held);
+        save_prompts(&file_path, &PromptList::new()).unwrap();
+        assert!(load_prompts(&file_path).unwrap().prompts.is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::gist::{gist_id, gist_payload, prompts_from_files, GIST_FILE_NAME};
+    use prompt_tool_lib::prompt::Prompt;
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_gist_id() {
+        assert_eq!(gist_id("https://gist.github.com/octocat/aa5a315d61ae9438b18d").as_deref(), Some("aa5a315d61ae9438b18d"));
+        assert_eq!(gist_id("https://gist.github.com/octocat/aa5a315d/#file-prompts-toml").as_deref(), Some("aa5a315d"));
+        assert_eq!(gist_id("aa5a315d").as_deref(), Some("aa5a315d"));
+        assert_eq!(gist_id("https://gist.github.com/"), None);
+    }
+
+    #[test]
+    fn test_payload_round_trip() {
+        let prompt = Prompt::new(
+            "Review".to_string(),
+            "Review {code}".to_string(),
+            vec!["code".into()],
+            HashSet::new(),
+            HashSet::from(["git".to_string()]),
+        );
+
+        let payload = gist_payload(&[prompt], "Review", false).unwrap();
+        assert_eq!(payload["public"], false);
+
+        let mut files = payload["files"].as_object().unwrap().clone();
+        assert!(files.contains_key(GIST_FILE_NAME));
+        files.insert("notes.md".to_string(), json!({ "content": "Summarize the notes" }));
+        files.insert("empty.txt".to_string(), json!({ "content": "  " }));
+
+        let imported = prompts_from_files(&files).unwrap();
+        let names: Vec<&str> = imported.prompts.iter().map(|prompt| prompt.name.as_str()).collect();
+        assert_eq!(names, vec!["notes", "Review"]);
+        assert_eq!(imported.prompts[1].content, "Review {code}");
+        assert!(imported.prompts[1].tags.contains("git"));
+    }
+}
@@ -0,0 +1,100 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::prompt;
+    use prompt_tool_lib::file_io::save_prompts;
+    use prompt_tool_lib::git::{commit_prompts, history, push, status, summarize_changes, GitSettings};
+    use prompt_tool_lib::prompt::PromptList;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_summarize_changes() {
+        let previous = PromptList { prompts: vec![prompt("Review", "v1"), prompt("Commit", "c1")] };
+        let current = PromptList { prompts: vec![prompt("Review", "v2"), prompt("Summary", "s1")] };
+
+        assert_eq!(summarize_changes(&previous, &current), "Добавлено: Summary; Изменено: Review; Удалено: Commit");
+        assert_eq!(summarize_changes(&previous, &previous), "Обновлен файл промптов");
+    }
+
+    #[test]
+    fn test_commit_and_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("prompts.toml");
+        let file_path = file.to_str().unwrap();
+        let settings = GitSettings { enabled: true, ..GitSettings::default() };
+
+        save_prompts(file_path, &PromptList { prompts: vec![prompt("Review", "v1"), prompt("Commit", "c1")] }).unwrap();
+        assert!(commit_prompts(&file, &settings).unwrap().is_some());
+        assert!(commit_prompts(&file, &settings).unwrap().is_none());
+
+        save_prompts(file_path, &PromptList { prompts: vec![prompt("Review", "v2"), prompt("Commit", "c1")] }).unwrap();
+        assert!(commit_prompts(&file, &settings).unwrap().is_some());
+
+        let review = history(&file, "Review", 10).unwrap();
+        assert_eq!(review.len(), 2);
+        assert_eq!(review[0].summary, "Изменено: Review");
+        assert_eq!(history(&file, "Commit", 10).unwrap().len(), 1);
+
+        let repo_status = status(&file).unwrap();
+        assert!(repo_status.files.is_empty());
+        assert!(repo_status.upstream.is_none());
+    }
+
+    #[test]
+    fn test_push_to_local_remote() {
+        let temp_dir = TempDir::new().unwrap();
+        let remote_dir = TempDir::new().unwrap();
+        git2::Repository::init_bare(remote_dir.path()).unwrap();
+
+        let file = temp_dir.path().join("prompts.toml");
+        let settings = GitSettings { enabled: true, ..GitSettings::default() };
+        save_prompts(file.to_str().unwrap(), &PromptList { prompts: vec![prompt("Review", "v1")] }).unwrap();
+        commit_prompts(&file, &settings).unwrap();
+
+        let repo = git2::Repository::open(temp_dir.path()).unwrap();
+        repo.remote("origin", remote_dir.path().to_str().unwrap()).unwrap();
+        push(&file, &settings).unwrap();
+
+        let remote = git2::Repository::open_bare(remote_dir.path()).unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        let pushed = remote.find_reference(&format!("refs/heads/{}", branch)).unwrap().target();
+        assert_eq!(pushed, repo.head().unwrap().target());
+    }
+
+    #[test]
+    fn test_commit_ignores_parent_repository_and_staged_files() {
+        let home = TempDir::new().unwrap();
+        let dotfiles = git2::Repository::init(home.path()).unwrap();
+
+        // Пользователь добавил в индекс свой файл, в папке промптов своего репозитория нет
+        std::fs::write(home.path().join(".bashrc"), "alias ll='ls -l'").unwrap();
+        let mut index = dotfiles.index().unwrap();
+        index.add_path(std::path::Path::new(".bashrc")).unwrap();
+        index.write().unwrap();
+
+        let prompts_dir = home.path().join("prompts");
+        std::fs::create_dir(&prompts_dir).unwrap();
+        let file = prompts_dir.join("prompts.toml");
+        std::fs::write(prompts_dir.join("notes.txt"), "staged").unwrap();
+        save_prompts(file.to_str().unwrap(), &PromptList { prompts: vec![prompt("Review", "v1")] }).unwrap();
+        let settings = GitSettings { enabled: true, ..GitSettings::default() };
+        commit_prompts(&file, &settings).unwrap();
+
+        // Репозиторий создан в папке промптов, родительский не тронут
+        assert!(dotfiles.head().is_err());
+        let repo = git2::Repository::open(&prompts_dir).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("notes.txt")).unwrap();
+        index.write().unwrap();
+
+        save_prompts(file.to_str().unwrap(), &PromptList { prompts: vec![prompt("Review", "v2")] }).unwrap();
+        commit_prompts(&file, &settings).unwrap();
+
+        // Добавленный в индекс файл не попадает в коммит
+        let tree = repo.head().unwrap().peel_to_tree().unwrap();
+        let names: Vec<String> = tree.iter().map(|entry| entry.name().unwrap().to_string()).collect();
+        assert_eq!(names, vec!["prompts.toml"]);
+    }
+}
+
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::history::SearchHistory;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_history_limit_and_dedup() {
+        let mut history = SearchHistory::new(2);
+
+        history.record("commit");
+        history.record("review");
+        history.record("commit");
+        history.record("translate");
+
+        let queries: Vec<String> = history.entries().into_iter().map(|e| e.query).collect();
+        assert_eq!(queries, vec!["translate", "commit"]);
+        assert_eq!(history.entries()[1].count, 2);
+
+        let stats = history.stats();
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.max_entries, 2);
+        assert!(stats.bytes >= "translate".len() + "commit".len());
+    }
+
+    #[test]
+    fn test_history_suggest_prefers_frequent_queries() {
+        let mut history = SearchHistory::new(10);
+
+        history.record("commit message");
+        history.record("commit message");
+        history.record("code review");
+        history.record("Commit body");
+
+        assert_eq!(history.suggest("comm", 10), vec!["commit message", "Commit body"]);
+        assert_eq!(history.suggest("c", 1), vec!["commit message"]);
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("history.json");
+
+        let mut history = SearchHistory::new(10);
+        history.record("summary");
+        history.save(&path).unwrap();
+
+        let loaded = SearchHistory::load(&path, 10);
+        assert_eq!(loaded.entries().len(), 1);
+        assert_eq!(loaded.entries()[0].query, "summary");
+    }
+}
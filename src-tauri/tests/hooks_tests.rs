@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::hooks::{hook_path, list_hooks, run_post_render, run_pre_render, PromptHooks};
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    fn write_script(dir: &std::path::Path, name: &str, body: &str) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn test_hook_names() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("disclaimer"), "").unwrap();
+        std::fs::write(temp_dir.path().join(".hidden"), "").unwrap();
+
+        assert_eq!(list_hooks(temp_dir.path()), vec!["disclaimer"]);
+        assert!(hook_path(temp_dir.path(), "disclaimer").is_ok());
+        assert!(hook_path(temp_dir.path(), "../disclaimer").is_err());
+        assert!(hook_path(temp_dir.path(), "missing").is_err());
+        assert!(list_hooks(&temp_dir.path().join("missing")).is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hooks() {
+        let temp_dir = TempDir::new().unwrap();
+        write_script(temp_dir.path(), "ticket", r#"cat > /dev/null; echo '{"ticket": "ABC-1"}'"#);
+        write_script(temp_dir.path(), "disclaimer", r#"cat; printf '\n-- %s' "$PROMPT_TOOL_PROMPT""#);
+        write_script(temp_dir.path(), "fail", "echo broken >&2; exit 3");
+
+        let hooks = PromptHooks {
+            pre_render: vec!["ticket".to_string()],
+            post_render: vec!["disclaimer".to_string()],
+        };
+
+        let values = run_pre_render(temp_dir.path(), &hooks, "Review", HashMap::new()).unwrap();
+        assert_eq!(values["ticket"], "ABC-1");
+
+        let text = run_post_render(temp_dir.path(), &hooks, "Review", "Text".to_string()).unwrap();
+        assert_eq!(text, "Text\n-- Review");
+
+        let failing = PromptHooks { post_render: vec!["fail".to_string()], ..PromptHooks::default() };
+        let error = run_post_render(temp_dir.path(), &failing, "Review", "Text".to_string()).unwrap_err();
+        assert!(error.to_string().contains("broken"));
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use prompt_tool_lib::import::{
+        import_awesome_csv, import_csv, import_directory, import_json, parse_timestamp, FolderMapping, FolderTarget,
+    };
+    use prompt_tool_lib::prompt::{Prompt, PromptList};
+    use std::collections::HashSet;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_timestamp_formats() {
+        let expected = Utc.with_ymd_and_hms(2024, 1, 31, 12, 0, 0).unwrap();
+
+        assert_eq!(parse_timestamp(&json!("2024-01-31T12:00:00Z")), Some(expected));
+        assert_eq!(parse_timestamp(&json!("2024-01-31T15:00:00+03:00")), Some(expected));
+        assert_eq!(parse_timestamp(&json!("2024-01-31 12:00:00")), Some(expected));
+        assert_eq!(parse_timestamp(&json!(1706702400)), Some(expected));
+        assert_eq!(parse_timestamp(&json!(1706702400000i64)), Some(expected));
+        assert_eq!(parse_timestamp(&json!("1706702400000")), Some(expected));
+        assert_eq!(parse_timestamp(&json!("not a date")), None);
+    }
+
+    #[test]
+    fn test_import_json_keeps_timestamps() {
+        let contents = r#"[
+            {
+                "title": "Summary",
+                "prompt": "Summarize {text}",
+                "tags": ["work"],
+                "createdAt": "2023-05-01T10:00:00Z",
+                "modified": 1706702400000
+            }
+        ]"#;
+
+        let list = import_json(contents).unwrap();
+        assert_eq!(list.prompts.len(), 1);
+
+        let prompt = &list.prompts[0];
+        assert_eq!(prompt.name, "Summary");
+        assert_eq!(prompt.content, "Summarize {text}");
+        assert!(prompt.tags.contains("work"));
+        assert_eq!(prompt.created_at, Utc.with_ymd_and_hms(2023, 5, 1, 10, 0, 0).unwrap());
+        assert_eq!(prompt.updated_at, Utc.with_ymd_and_hms(2024, 1, 31, 12, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_import_csv_without_updated_uses_created() {
+        let contents = "name,content,tags,created_at\n\
+                        Commit,Write a commit message,git;dev,2024-01-31\n";
+
+        let list = import_csv(contents).unwrap();
+        assert_eq!(list.prompts.len(), 1);
+
+        let prompt = &list.prompts[0];
+        let expected = Utc.with_ymd_and_hms(2024, 1, 31, 0, 0, 0).unwrap();
+        assert_eq!(prompt.tags.len(), 2);
+        assert_eq!(prompt.created_at, expected);
+        assert_eq!(prompt.updated_at, expected);
+    }
+
+    #[test]
+    fn test_import_directory_derives_tags_from_folders() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("Dev").join("git");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("commit.md"), "Write a commit message").unwrap();
+        fs::create_dir_all(temp_dir.path().join(".hidden")).unwrap();
+        fs::write(temp_dir.path().join(".hidden").join("skip.md"), "ignored").unwrap();
+
+        let mut mapping = FolderMapping { target: FolderTarget::Both, ..Default::default() };
+        mapping.aliases.insert("dev".to_string(), "development".to_string());
+
+        let list = import_directory(temp_dir.path().to_str().unwrap(), &mapping).unwrap();
+        assert_eq!(list.prompts.len(), 1);
+
+        let prompt = &list.prompts[0];
+        assert_eq!(prompt.name, "commit");
+        assert!(prompt.tags.contains("development"));
+        assert!(prompt.tags.contains("git"));
+        assert!(prompt.categories.contains("development"));
+    }
+
+    #[test]
+    fn test_import_awesome_csv() {
+        let csv = "act,prompt,for_devs\n\
+\"Linux Terminal\",\"I want you to act as a linux terminal.\",TRUE\n\
+Translator,\"I want you to act as a translator.\",FALSE\n\
+translator,Duplicate,FALSE\n\
+Existing,Skipped,FALSE\n";
+        let existing = PromptList {
+            prompts: vec![Prompt::new("existing".to_string(), "x".to_string(), Vec::new(), HashSet::new(), HashSet::new())],
+        };
+
+        let imported = import_awesome_csv(csv, Some("community"), &existing).unwrap();
+        let names: Vec<&str> = imported.prompts.iter().map(|prompt| prompt.name.as_str()).collect();
+        assert_eq!(names, vec!["Linux Terminal", "Translator"]);
+        assert_eq!(imported.prompts[0].content, "I want you to act as a linux terminal.");
+        assert!(imported.prompts[0].categories.contains("community"));
+        assert!(imported.prompts[0].tags.contains("dev"));
+        assert!(imported.prompts[1].tags.is_empty());
+        assert_eq!(imported.prompts[0].source_url, "https://github.com/f/awesome-chatgpt-prompts");
+        assert_eq!(imported.prompts[0].license, "CC0-1.0");
+
+        let uncategorized = import_awesome_csv("act,prompt\nA,B\n", Some(" "), &PromptList::new()).unwrap();
+        assert!(uncategorized.prompts[0].categories.is_empty());
+        assert!(import_awesome_csv("name,content\nA,B\n", None, &PromptList::new()).is_err());
+    }
+}
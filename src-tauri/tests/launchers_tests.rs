@@ -0,0 +1,63 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{prompt, tagged};
+    use prompt_tool_lib::launchers::{build_snippets, export_snippets, generate_keywords, LauncherFormat};
+    use prompt_tool_lib::snippet_import::{import_source, SourceKind};
+    use prompt_tool_lib::prompt::Prompt;
+    use std::fs::File;
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_generate_keywords() {
+        let prompts = [
+            tagged("Commit Message", "a", &["vcs", "git"]),
+            tagged("Commit Message!", "b", &["git"]),
+            tagged("Git Log", "c", &["git"]),
+            prompt("Summary", "d"),
+        ];
+        let refs: Vec<&Prompt> = prompts.iter().collect();
+
+        assert_eq!(
+            generate_keywords(&refs, ";"),
+            vec![";git-commit-message", ";git-commit-message2", ";git-log", ";summary"]
+        );
+    }
+
+    #[test]
+    fn test_raycast_export_round_trip() {
+        let review = tagged("Review", "Review {code} in {lang|default:rust}", &["dev"]);
+        let snippets = build_snippets(&[&review], LauncherFormat::Raycast, "!");
+        assert_eq!(snippets[0].text, "Review {argument name=\"code\"} in {argument name=\"lang\" default=\"rust\"}");
+        assert_eq!(snippets[0].keyword, "!dev-review");
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("raycast.json");
+        assert_eq!(export_snippets(&[&review], LauncherFormat::Raycast, "!", &path).unwrap(), 1);
+
+        let imported = import_source(SourceKind::Raycast, &path).unwrap();
+        assert_eq!(imported.prompts[0].name, "Review");
+    }
+
+    #[test]
+    fn test_alfred_bundle() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("prompts.alfredsnippets");
+        let review = prompt("Review/PR", "Review {code}");
+        export_snippets(&[&review], LauncherFormat::Alfred, ";", &path).unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(archive.len(), 1);
+        let mut file = archive.by_index(0).unwrap();
+        assert!(file.name().starts_with("Review_PR ["));
+
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).unwrap();
+        let item: serde_json::Value = serde_json::from_str(&contents).unwrap();
+        assert_eq!(item["alfredsnippet"]["snippet"], "Review {code}");
+        assert_eq!(item["alfredsnippet"]["keyword"], ";review-pr");
+        assert_eq!(item["alfredsnippet"]["uid"].as_str().unwrap().len(), 36);
+    }
+}
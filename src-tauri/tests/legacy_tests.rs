@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use prompt_tool_lib::file_io::load_prompts;
+    use prompt_tool_lib::legacy::{backup_path, migrate_library};
+    use std::fs;
+    use tempfile::TempDir;
+
+    const LEGACY: &str = r#"prompts = [
+    { name = "Example Prompt", content = "This is an example prompt", parameters = ["param1"] },
+    { name = "Dated", content = "text", created_at = "2024-01-01T00:00:00Z", tags = ["old"] }
+]"#;
+
+    #[test]
+    fn test_migrate_legacy_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("default.toml");
+        fs::write(&path, LEGACY).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+
+        let report = migrate_library(&path, now).unwrap();
+        assert_eq!(report.prompts, 2);
+        assert_eq!(report.filled_ids, 2);
+        assert_eq!(report.filled_timestamps, 2);
+        assert_eq!(report.filled_categories, 2);
+        assert_eq!(report.filled_tags, 1);
+        assert_eq!(report.filled_parameters, 1);
+
+        let backup = report.backup.unwrap();
+        assert_eq!(backup, backup_path(&path, now));
+        assert_eq!(fs::read_to_string(&backup).unwrap(), LEGACY);
+
+        let library = load_prompts(path.to_str().unwrap()).unwrap();
+        assert!(library.prompts.iter().all(|prompt| !prompt.id.is_empty()));
+        assert_ne!(library.prompts[0].id, library.prompts[1].id);
+        assert_eq!(library.prompts[0].created_at, now);
+        assert_eq!(library.prompts[1].updated_at, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        assert!(library.prompts[1].tags.contains("old"));
+
+        // Повторный запуск ничего не меняет и не создает копию
+        let again = migrate_library(&path, now).unwrap();
+        assert!(!again.changed());
+        assert_eq!(again.backup, None);
+    }
+
+    #[test]
+    fn test_invalid_library_is_untouched() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("broken.toml");
+        let contents = "prompts = [{ content = \"no name\" }]";
+        fs::write(&path, contents).unwrap();
+
+        assert!(migrate_library(&path, Utc::now()).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+}
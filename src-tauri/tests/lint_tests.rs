@@ -0,0 +1,37 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{parameters, prompt};
+    use prompt_tool_lib::lint::{has_errors, lint_library, Severity};
+    use prompt_tool_lib::prompt::{Prompt, PromptList};
+
+    #[test]
+    fn test_lint_library() {
+        let list = PromptList {
+            prompts: vec![
+                Prompt { parameters: parameters(&["code", "ticket"]), ..prompt("Review", "Review {code} about {topic}") },
+                prompt("review", "Duplicate"),
+                prompt("Empty", "  "),
+                Prompt {
+                    parameters: parameters(&["short", "lang"]),
+                    ..prompt("Tera", "{% if short %}Brief{% endif %} {{ lang | upper }} {selection}")
+                },
+            ],
+        };
+
+        let diagnostics = lint_library(&list);
+        let codes: Vec<(usize, &str)> = diagnostics.iter().map(|d| (d.index, d.code.as_str())).collect();
+        assert_eq!(codes, vec![
+            (0, "unused-parameter"),
+            (0, "undeclared-placeholder"),
+            (1, "duplicate-name"),
+            (2, "empty-content"),
+        ]);
+
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert!(diagnostics[0].message.contains("ticket"));
+        assert!(diagnostics[1].message.contains("{topic}"));
+        assert!(has_errors(&diagnostics));
+    }
+}
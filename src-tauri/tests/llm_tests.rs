@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::llm::{LineBuffer, LlmConfig, ProviderKind};
+    use serde_json::json;
+
+    #[test]
+    fn test_build_request_body() {
+        let config = LlmConfig { temperature: Some(0.5), ..LlmConfig::default() };
+        let body = ProviderKind::OpenAi.build_request_body(&config, "gpt-test", "Привет");
+
+        assert_eq!(body["model"], "gpt-test");
+        assert_eq!(body["messages"][0]["content"], "Привет");
+        assert_eq!(body["temperature"], 0.5);
+        assert!(body.get("max_tokens").is_none());
+
+        let body = ProviderKind::Ollama.build_request_body(&config, "llama3", "Привет");
+        assert_eq!(body["stream"], false);
+        assert_eq!(body["options"]["temperature"], 0.5);
+    }
+
+    #[test]
+    fn test_parse_completion() {
+        let response = json!({
+            "model": "gpt-test",
+            "choices": [{ "message": { "role": "assistant", "content": "Ответ" } }],
+            "usage": { "prompt_tokens": 3, "completion_tokens": 2, "total_tokens": 5 }
+        });
+
+        let completion = ProviderKind::OpenAi.parse_completion(&response).unwrap();
+        assert_eq!(completion.text, "Ответ");
+        assert_eq!(completion.usage.unwrap().total_tokens, 5);
+
+        let error = json!({ "error": { "message": "invalid api key" } });
+        assert!(ProviderKind::OpenAi.parse_completion(&error).is_err());
+    }
+
+    #[test]
+    fn test_parse_ollama_response() {
+        let response = json!({
+            "model": "llama3",
+            "message": { "role": "assistant", "content": "Ответ" },
+            "prompt_eval_count": 4,
+            "eval_count": 6
+        });
+
+        let completion = ProviderKind::Ollama.parse_completion(&response).unwrap();
+        assert_eq!(completion.text, "Ответ");
+        assert_eq!(completion.usage.unwrap().total_tokens, 10);
+
+        let models = json!({ "models": [{ "name": "mistral" }, { "name": "llama3" }] });
+        assert_eq!(ProviderKind::Ollama.parse_models(&models).unwrap(), vec!["llama3", "mistral"]);
+
+        let error = json!({ "error": "model not found" });
+        assert!(ProviderKind::Ollama.parse_completion(&error).is_err());
+    }
+
+    #[test]
+    fn test_stream_parsing() {
+        let mut buffer = LineBuffer::default();
+        assert!(buffer.push(b"data: {\"choices\":[{\"delta\":{\"content\":\"\xd0\x9f\xd1").is_empty());
+        let lines = buffer.push(b"\x80\"}}]}\n\ndata: [DONE]\n");
+        assert_eq!(lines.len(), 3);
+
+        let chunk = ProviderKind::OpenAi.parse_stream_line(&lines[0]).unwrap().unwrap();
+        assert_eq!(chunk.token, "Пр");
+        assert!(ProviderKind::OpenAi.parse_stream_line(&lines[1]).unwrap().is_none());
+        assert!(ProviderKind::OpenAi.parse_stream_line(&lines[2]).unwrap().unwrap().done);
+
+        let last = r#"{"model":"llama3","message":{"content":""},"done":true,"prompt_eval_count":2,"eval_count":3}"#;
+        let chunk = ProviderKind::Ollama.parse_stream_line(last).unwrap().unwrap();
+        assert!(chunk.done);
+        assert_eq!(chunk.usage.unwrap().total_tokens, 5);
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::logging::{recent_logs, LogLevels, LOG_FILE_PREFIX};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_log_levels_directives() {
+        let mut levels = LogLevels::default();
+        assert_eq!(levels.directives(), "info");
+
+        levels.modules.insert("prompt_tool_lib::git".to_string(), "debug".to_string());
+        levels.modules.insert("prompt_tool".to_string(), "warn".to_string());
+        assert!(levels.validate().is_ok());
+        assert_eq!(levels.directives(), "info,prompt_tool=warn,prompt_tool_lib::git=debug");
+    }
+
+    #[test]
+    fn test_log_levels_validation_lists_all_errors() {
+        let mut levels = LogLevels { default: "loud".to_string(), ..Default::default() };
+        levels.modules.insert("git=debug".to_string(), "debug".to_string());
+        levels.modules.insert("prompt_tool_lib::sync".to_string(), "chatty".to_string());
+
+        let message = levels.validate().unwrap_err().to_string();
+        assert!(message.contains("default"));
+        assert!(message.contains("git=debug"));
+        assert!(message.contains("prompt_tool_lib::sync"));
+    }
+
+    #[test]
+    fn test_recent_logs_spans_rotated_files() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(recent_logs(&temp_dir.path().join("missing"), 10).unwrap().is_empty());
+
+        fs::write(temp_dir.path().join(format!("{}.2026-01-01", LOG_FILE_PREFIX)), "a\nb\nc\n").unwrap();
+        fs::write(temp_dir.path().join(format!("{}.2026-01-02", LOG_FILE_PREFIX)), "d\ne\n").unwrap();
+        fs::write(temp_dir.path().join("other.txt"), "x\n").unwrap();
+
+        assert_eq!(recent_logs(temp_dir.path(), 3).unwrap(), vec!["c", "d", "e"]);
+        assert_eq!(recent_logs(temp_dir.path(), 100).unwrap(), vec!["a", "b", "c", "d", "e"]);
+        assert!(recent_logs(temp_dir.path(), 0).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,66 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{parameters, prompt, tagged};
+    use prompt_tool_lib::merge::{has_conflict_markers, merge_prompt, merge_text};
+    use prompt_tool_lib::prompt::Prompt;
+
+    #[test]
+    fn test_merge_text() {
+        let clean = merge_text("a\nb\nc\nd\ne", "a\nB\nc\nd\ne", "a\nb\nc\nD\ne");
+        assert_eq!(clean.text, "a\nB\nc\nD\ne");
+        assert_eq!(clean.conflicts, 0);
+
+        let same = merge_text("a\nb\n", "a\nc\n", "a\nc\n");
+        assert_eq!(same.text, "a\nc\n");
+        assert_eq!(same.conflicts, 0);
+
+        let conflict = merge_text("a\nb\nc", "a\nX\nc", "a\nY\nc");
+        assert_eq!(conflict.text, "a\n<<<<<<< ours\nX\n=======\nY\n>>>>>>> theirs\nc");
+        assert_eq!(conflict.conflicts, 1);
+        assert!(has_conflict_markers(&conflict.text));
+        assert!(!has_conflict_markers("a\n== not a marker ==\nc"));
+    }
+
+    #[test]
+    fn test_merge_prompt() {
+        let base = Prompt { parameters: parameters(&["code"]), ..tagged("Review", "Review {code}", &["review", "git"]) };
+        let ours = Prompt { parameters: parameters(&["code", "lang"]), ..tagged("Review", "Review {code}", &["review", "git", "go"]) };
+        let theirs = Prompt { parameters: parameters(&["code", "style"]), ..tagged("Review", "Review {code} carefully", &["review"]) };
+
+        let merged = merge_prompt(&base, &ours, &theirs);
+        assert_eq!(merged.prompt.content, "Review {code} carefully");
+        assert_eq!(merged.conflicts, 0);
+
+        let mut tags: Vec<String> = merged.prompt.tags.into_iter().collect();
+        tags.sort();
+        assert_eq!(tags, vec!["go", "review"]);
+
+        // Параметры изменены обеими сторонами: остается версия из приложения
+        assert_eq!(merged.conflicting_fields, vec!["parameters"]);
+        assert_eq!(merged.prompt.parameters, ours.parameters);
+    }
+
+    #[test]
+    fn test_merge_prompt_metadata() {
+        let base = prompt("Review", "Review");
+        let mut ours = base.clone();
+        ours.rating = Some(4);
+        ours.notes = "ours".to_string();
+        let mut theirs = base.clone();
+        theirs.aliases = vec!["rv".to_string()];
+        theirs.archived = true;
+        theirs.author = "Team".to_string();
+        theirs.notes = "theirs".to_string();
+
+        // Изменения внешней версии не теряются, а разные правки одного поля отмечаются
+        let merged = merge_prompt(&base, &ours, &theirs);
+        assert_eq!(merged.prompt.rating, Some(4));
+        assert_eq!(merged.prompt.aliases, vec!["rv"]);
+        assert!(merged.prompt.archived);
+        assert_eq!(merged.prompt.author, "Team");
+        assert_eq!(merged.prompt.notes, "ours");
+        assert_eq!(merged.conflicting_fields, vec!["notes"]);
+    }
+}
@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::error::PromptToolError;
+    use prompt_tool_lib::messages::{error_catalog, error_message, Language};
+
+    #[test]
+    fn test_every_error_code_has_messages() {
+        let errors = [
+            PromptToolError::Io(std::io::Error::other("disk")),
+            PromptToolError::Parse("p".to_string()),
+            PromptToolError::Config("c".to_string()),
+            PromptToolError::Validation("v".to_string()),
+            PromptToolError::Search("s".to_string()),
+            PromptToolError::Execution("e".to_string()),
+            PromptToolError::Locked("l".to_string()),
+            PromptToolError::ReadOnly("r".to_string()),
+            PromptToolError::State("s".to_string()),
+            PromptToolError::Conflict("c".to_string()),
+        ];
+
+        let ru = error_catalog(Language::Ru);
+        let en = error_catalog(Language::En);
+        for error in &errors {
+            assert!(ru.contains_key(error.code()), "{}", error.code());
+            assert!(en.contains_key(error.code()), "{}", error.code());
+            assert_ne!(ru[error.code()], en[error.code()]);
+        }
+        assert!(ru.contains_key("toml_parse") && ru.contains_key("git"));
+    }
+
+    #[test]
+    fn test_language_selection() {
+        assert_eq!(Language::from_code("en"), Language::En);
+        assert_eq!(Language::from_code("ru"), Language::Ru);
+        assert_eq!(Language::from_code("xx"), Language::Ru);
+
+        assert_eq!(error_message("read_only", Language::En), "The prompt file is read-only");
+        assert_eq!(error_message("unknown_code", Language::En), "Unexpected error");
+    }
+
+    #[test]
+    fn test_error_details_without_prefix() {
+        let error = PromptToolError::ReadOnly("/tmp/prompts.toml".to_string());
+        assert_eq!(error.code(), "read_only");
+        assert_eq!(error.details(), "/tmp/prompts.toml");
+
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value["code"], "read_only");
+        assert_eq!(value["details"], "/tmp/prompts.toml");
+        assert!(value["message"].is_string());
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use prompt_tool_lib::file_io::load_prompts;
+    use prompt_tool_lib::migrations::{migrate_file, migrate_table, schema_version, CURRENT_SCHEMA_VERSION};
+    use prompt_tool_lib::legacy::backup_path;
+    use std::fs;
+    use toml::{Table, Value};
+    use tempfile::TempDir;
+
+    const VERSION_0: &str = r#"prompts = [
+    { name = "Translate", content = "Translate {text} to {lang}", parameters = ["text", { name = "lang", kind = "string", required = true }] }
+]"#;
+
+    #[test]
+    fn test_migrate_table() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+        let mut library: Table = toml::from_str(VERSION_0).unwrap();
+        assert_eq!(schema_version(&library).unwrap(), 0);
+
+        let applied = migrate_table(&mut library, now).unwrap();
+        assert_eq!(applied.len(), 2);
+        assert_eq!(schema_version(&library).unwrap(), CURRENT_SCHEMA_VERSION);
+
+        // Параметр-строка стал описанием, готовое описание не тронуто
+        let parameters = library["prompts"][0]["parameters"].as_array().unwrap();
+        assert_eq!(parameters[0]["name"].as_str(), Some("text"));
+        assert_eq!(parameters[0]["required"].as_bool(), Some(false));
+        assert_eq!(parameters[1]["required"].as_bool(), Some(true));
+        assert!(library["prompts"][0].get("id").is_some());
+
+        // Обновленная библиотека больше не меняется
+        let migrated = library.clone();
+        assert!(migrate_table(&mut library, now).unwrap().is_empty());
+        assert_eq!(library, migrated);
+    }
+
+    #[test]
+    fn test_newer_version_rejected() {
+        let mut library: Table = toml::from_str("schema_version = 99\nprompts = []").unwrap();
+        assert!(migrate_table(&mut library, Utc::now()).is_err());
+
+        library.insert("schema_version".to_string(), Value::String("2".to_string()));
+        assert!(schema_version(&library).is_err());
+    }
+
+    #[test]
+    fn test_migrate_file_with_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("prompts.toml");
+        fs::write(&path, VERSION_0).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+
+        // Старый формат читается и без обновления на диске
+        assert_eq!(load_prompts(path.to_str().unwrap()).unwrap().prompts[0].parameters.len(), 2);
+
+        let migration = migrate_file(&path, now).unwrap().unwrap();
+        assert_eq!(migration.from_version, 0);
+        assert_eq!(migration.to_version, CURRENT_SCHEMA_VERSION);
+        assert_eq!(migration.backup, backup_path(&path, now));
+        assert_eq!(fs::read_to_string(&migration.backup).unwrap(), VERSION_0);
+
+        let saved: Table = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(schema_version(&saved).unwrap(), CURRENT_SCHEMA_VERSION);
+        assert!(migrate_file(&path, now).unwrap().is_none());
+
+        let library = load_prompts(path.to_str().unwrap()).unwrap();
+        assert_eq!(library.prompts[0].parameters[1].name, "lang");
+        assert!(library.prompts[0].parameters[1].required);
+    }
+}
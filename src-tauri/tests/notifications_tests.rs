@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::notifications::{DndSettings, NotificationKind, NotificationSettings};
+
+    #[test]
+    fn test_notification_toggles() {
+        let mut settings = NotificationSettings::default();
+        assert!(settings.is_enabled(NotificationKind::BackupFailed));
+
+        settings.set_enabled(NotificationKind::ImportCompleted, false);
+        assert!(!settings.is_enabled(NotificationKind::ImportCompleted));
+        assert!(settings.is_enabled(NotificationKind::SyncConflict));
+
+        // Отсутствующие в конфигурации типы включены
+        let settings: NotificationSettings = serde_json::from_str(r#"{"sync_conflict": false}"#).unwrap();
+        assert!(!settings.is_enabled(NotificationKind::SyncConflict));
+        assert!(settings.is_enabled(NotificationKind::ScheduledRunFinished));
+    }
+
+    #[test]
+    fn test_background_notification_defaults() {
+        let settings = NotificationSettings::default();
+        assert!(settings.is_enabled(NotificationKind::PromptCopied));
+        assert!(settings.is_enabled(NotificationKind::FileReloaded));
+        assert!(settings.is_enabled(NotificationKind::SyncFailed));
+        // Резервная копия создается при каждом сохранении, поэтому уведомление о ней выключено
+        assert!(!settings.is_enabled(NotificationKind::BackupCreated));
+
+        // В старых конфигурациях новых полей нет, значения берутся по умолчанию
+        let settings: NotificationSettings = serde_json::from_str(r#"{"backup_failed": false}"#).unwrap();
+        assert!(settings.is_enabled(NotificationKind::SyncFailed));
+        assert!(!settings.is_enabled(NotificationKind::BackupCreated));
+
+        let kind: NotificationKind = serde_json::from_str(r#""sync_failed""#).unwrap();
+        assert_eq!(kind, NotificationKind::SyncFailed);
+    }
+
+    #[test]
+    fn test_do_not_disturb() {
+        let settings = DndSettings::default();
+        assert!(!settings.is_active(None));
+        assert!(settings.is_active(Some(true)));
+
+        let settings = DndSettings { manual: false, follow_system: false };
+        assert!(!settings.is_active(Some(true)));
+
+        let settings = DndSettings { manual: true, follow_system: false };
+        assert!(settings.is_active(Some(false)));
+    }
+}
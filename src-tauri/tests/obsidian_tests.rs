@@ -0,0 +1,43 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{prompt, strings, tagged};
+    use prompt_tool_lib::obsidian::{export_vault, obsidian_tag, CATEGORIES_FOLDER};
+    use prompt_tool_lib::prompt::{Prompt, PromptList};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_obsidian_tag() {
+        assert_eq!(obsidian_tag("code review"), "code-review");
+        assert_eq!(obsidian_tag("c++"), "c__");
+        assert_eq!(obsidian_tag("dev/rust"), "dev/rust");
+        assert_eq!(obsidian_tag("2024"), "_2024");
+    }
+
+    #[test]
+    fn test_export_vault() {
+        let temp_dir = TempDir::new().unwrap();
+        let library = PromptList {
+            prompts: vec![
+                Prompt { categories: strings(&["dev"]), ..tagged("Review: PR", "Check ```code``` in {lang}", &["code review"]) },
+                Prompt { categories: strings(&["dev", "git"]), ..prompt("Commit", "Write a commit") },
+                prompt("Note", "# Heading"),
+            ],
+        };
+
+        let export = export_vault(&library, temp_dir.path()).unwrap();
+        assert_eq!((export.prompts, export.categories), (3, 2));
+
+        let review = fs::read_to_string(temp_dir.path().join("Review_ PR.md")).unwrap();
+        assert!(review.starts_with("---\n"));
+        assert!(review.contains("Категории: [[Категории/dev|dev]]"));
+        assert!(review.contains("Теги: #code-review"));
+        assert!(review.contains("````\nCheck ```code``` in {lang}\n````"));
+
+        let dev = fs::read_to_string(temp_dir.path().join(CATEGORIES_FOLDER).join("dev.md")).unwrap();
+        assert_eq!(dev, "# dev\n\n- [[Commit]]\n- [[Review_ PR]]\n");
+        assert!(temp_dir.path().join("Note.md").exists());
+    }
+}
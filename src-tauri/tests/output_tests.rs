@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone};
+    use prompt_tool_lib::output::{
+        file_entry, format_header, target_for, OutputTargetConfig, WebhookPayload, DEFAULT_FILE_HEADER,
+    };
+    use prompt_tool_lib::clipboard::PasteOptions;
+    use prompt_tool_lib::prompt::PromptList;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_file_target_appends() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("notes.md");
+        let target = target_for(
+            &OutputTargetConfig::File { path: path.to_string_lossy().to_string(), header: String::new() },
+            Default::default(),
+        );
+
+        let delivery = target.deliver("Journal", "first").unwrap();
+        assert!(delivery.handled);
+        target.deliver("Journal", "second\n").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first\n\nsecond\n");
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_clipboard_auto_paste() {
+        // Без нативного буфера текст кладет фронтенд, он же потом вызывает вставку
+        let delivery = target_for(&OutputTargetConfig::Clipboard, PasteOptions::default()).deliver("Commit", "text").unwrap();
+        assert!(!delivery.handled);
+        assert!(!delivery.paste);
+
+        let options = PasteOptions { auto_paste: true, ..PasteOptions::default() };
+        assert!(target_for(&OutputTargetConfig::Clipboard, options).deliver("Commit", "text").unwrap().paste);
+        assert!(target_for(&OutputTargetConfig::Paste, PasteOptions::default()).deliver("Commit", "text").unwrap().paste);
+    }
+
+    #[test]
+    fn test_file_entry_header() {
+        let time = Local.with_ymd_and_hms(2024, 5, 17, 9, 30, 0).unwrap();
+
+        let header = format_header(DEFAULT_FILE_HEADER, "Journal", time);
+        assert_eq!(header, "## 2024-05-17 09:30:00 Journal");
+        assert_eq!(format_header("# {date} / {time}", "Journal", time), "# 2024-05-17 / 09:30:00");
+
+        assert_eq!(file_entry(&header, "Today"), "## 2024-05-17 09:30:00 Journal\n\nToday\n");
+        assert_eq!(file_entry("", "Today\n"), "Today\n");
+    }
+
+    #[test]
+    fn test_prompt_output_in_toml() {
+        let list: PromptList = toml::from_str(r#"
+            [[prompts]]
+            name = "Journal"
+            content = "Today: {entry}"
+            parameters = ["entry"]
+            categories = []
+            tags = []
+            output = { type = "file", path = "notes.md" }
+
+            [[prompts]]
+            name = "Plain"
+            content = "text"
+            parameters = []
+            categories = []
+            tags = []
+        "#).unwrap();
+
+        assert_eq!(list.prompts[0].output, Some(OutputTargetConfig::File {
+            path: "notes.md".to_string(),
+            header: DEFAULT_FILE_HEADER.to_string(),
+        }));
+        assert_eq!(list.prompts[1].output, None);
+
+        let saved = toml::to_string_pretty(&list).unwrap();
+        let reloaded: PromptList = toml::from_str(&saved).unwrap();
+        assert_eq!(reloaded.prompts[0].output, list.prompts[0].output);
+        assert_eq!(reloaded.prompts[1].output, None);
+    }
+
+    #[test]
+    fn test_webhook_config_and_payload() {
+        let config: OutputTargetConfig = serde_json::from_str(
+            r#"{"type": "webhook", "url": "https://hooks.example.com/p", "headers": {"Authorization": "Bearer x"}}"#,
+        ).unwrap();
+        match &config {
+            OutputTargetConfig::Webhook { url, headers } => {
+                assert_eq!(url, "https://hooks.example.com/p");
+                assert_eq!(headers["Authorization"], "Bearer x");
+            }
+            other => panic!("unexpected target {:?}", other),
+        }
+
+        let legacy: OutputTargetConfig = serde_json::from_str(r#"{"type": "http", "url": "http://localhost"}"#).unwrap();
+        assert!(matches!(legacy, OutputTargetConfig::Webhook { headers, .. } if headers.is_empty()));
+
+        let payload = WebhookPayload {
+            prompt: "Journal",
+            text: "Today",
+            sent_at: chrono::Utc::now(),
+        };
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["prompt"], "Journal");
+        assert_eq!(json["text"], "Today");
+        assert!(json["sent_at"].is_string());
+    }
+}
@@ -0,0 +1,122 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{prompt, tagged};
+    use prompt_tool_lib::error::PromptToolError;
+    use prompt_tool_lib::paging::{page_of_prompts, PageRequest, PromptSummary, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE, SUMMARY_CONTENT_CHARS};
+    use prompt_tool_lib::prompt::Prompt;
+
+    fn library(count: usize) -> Vec<Prompt> {
+        (0..count)
+            .map(|i| {
+                let name = format!("P{:03}", i);
+                Prompt { id: name.to_lowercase(), ..prompt(&name, &format!("Content of {}", name)) }
+            })
+            .collect()
+    }
+
+    fn names(page: &[PromptSummary]) -> Vec<&str> {
+        page.iter().map(|summary| summary.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_default_page_size() {
+        let prompts = library(DEFAULT_PAGE_SIZE + 5);
+        let page = page_of_prompts(&prompts, &PageRequest::default()).unwrap();
+
+        assert_eq!(page.items.len(), DEFAULT_PAGE_SIZE);
+        assert_eq!(page.total, DEFAULT_PAGE_SIZE + 5);
+        assert_eq!(page.offset, 0);
+        assert_eq!(page.next_cursor.as_deref(), Some("100:p099"));
+    }
+
+    #[test]
+    fn test_cursor_continues_after_last_item() {
+        let mut prompts = library(5);
+        let request = PageRequest { limit: Some(2), ..Default::default() };
+        let first = page_of_prompts(&prompts, &request).unwrap();
+        assert_eq!(names(&first.items), vec!["P000", "P001"]);
+
+        // Промпт, добавленный перед курсором, не сдвигает следующую страницу
+        prompts.insert(0, prompt("New", "Content of New"));
+        let request = PageRequest { limit: Some(2), cursor: first.next_cursor, ..Default::default() };
+        let second = page_of_prompts(&prompts, &request).unwrap();
+        assert_eq!(names(&second.items), vec!["P002", "P003"]);
+        assert_eq!(second.offset, 3);
+        assert_eq!(second.total, 6);
+
+        let request = PageRequest { limit: Some(2), cursor: second.next_cursor, ..Default::default() };
+        let last = page_of_prompts(&prompts, &request).unwrap();
+        assert_eq!(names(&last.items), vec!["P004"]);
+        assert_eq!(last.next_cursor, None);
+    }
+
+    #[test]
+    fn test_cursor_with_duplicate_ids() {
+        // Одинаковые id не возвращают к первому промпту с этим id
+        let mut prompts = library(6);
+        for prompt in &mut prompts {
+            prompt.id = "same".to_string();
+        }
+
+        let request = PageRequest { limit: Some(2), ..Default::default() };
+        let first = page_of_prompts(&prompts, &request).unwrap();
+        let request = PageRequest { limit: Some(2), cursor: first.next_cursor, ..Default::default() };
+        let second = page_of_prompts(&prompts, &request).unwrap();
+        assert_eq!(names(&second.items), vec!["P002", "P003"]);
+
+        let request = PageRequest { limit: Some(2), cursor: second.next_cursor, ..Default::default() };
+        let last = page_of_prompts(&prompts, &request).unwrap();
+        assert_eq!(names(&last.items), vec!["P004", "P005"]);
+        assert_eq!(last.next_cursor, None);
+    }
+
+    #[test]
+    fn test_offset_and_prompts_without_id() {
+        let mut prompts = library(4);
+        for prompt in &mut prompts {
+            prompt.id.clear();
+        }
+
+        let page = page_of_prompts(&prompts, &PageRequest { offset: 1, limit: Some(2), cursor: None }).unwrap();
+        assert_eq!(names(&page.items), vec!["P001", "P002"]);
+        assert_eq!(page.next_cursor.as_deref(), Some("3:"));
+
+        let request = PageRequest { limit: Some(2), cursor: page.next_cursor, ..Default::default() };
+        assert_eq!(names(&page_of_prompts(&prompts, &request).unwrap().items), vec!["P003"]);
+
+        // Смещение за концом списка дает пустую страницу
+        let beyond = page_of_prompts(&prompts, &PageRequest { offset: 10, ..Default::default() }).unwrap();
+        assert!(beyond.items.is_empty());
+        assert_eq!(beyond.offset, 4);
+    }
+
+    #[test]
+    fn test_invalid_requests() {
+        let prompts = library(3);
+        for limit in [0, MAX_PAGE_SIZE + 1] {
+            let request = PageRequest { limit: Some(limit), ..Default::default() };
+            assert!(matches!(page_of_prompts(&prompts, &request), Err(PromptToolError::Validation(_))));
+        }
+
+        for cursor in ["p000", "x:p000", "1:deleted"] {
+            let request = PageRequest { cursor: Some(cursor.to_string()), ..Default::default() };
+            assert!(matches!(page_of_prompts(&prompts, &request), Err(PromptToolError::Validation(_))));
+        }
+    }
+
+    #[test]
+    fn test_summary_truncates_content() {
+        let long = tagged("Long", &"ж".repeat(SUMMARY_CONTENT_CHARS + 1), &["b", "a"]);
+
+        let summary = PromptSummary::from_prompt(&long);
+        assert!(summary.truncated);
+        assert_eq!(summary.content.chars().count(), SUMMARY_CONTENT_CHARS);
+        assert_eq!(summary.tags, vec!["a", "b"]);
+
+        let short = PromptSummary::from_prompt(&prompt("Short", "Content of Short"));
+        assert!(!short.truncated);
+        assert_eq!(short.content, "Content of Short");
+    }
+}
@@ -0,0 +1,35 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::prompt;
+    use prompt_tool_lib::palette::{LauncherItem, LauncherSelection};
+    use prompt_tool_lib::parameters::ParameterSpec;
+    use prompt_tool_lib::prompt::Prompt;
+
+    #[test]
+    fn test_item_preview_is_one_line() {
+        let item = LauncherItem::from_prompt(&prompt("Commit", "Write a commit\n\n  message"));
+        assert_eq!(item.name, "Commit");
+        assert_eq!(item.preview, "Write a commit message");
+
+        let item = LauncherItem::from_prompt(&prompt("Commit", &"слово ".repeat(40)));
+        assert_eq!(item.preview.chars().count(), 81);
+        assert!(item.preview.ends_with('…'));
+    }
+
+    #[test]
+    fn test_selection_asks_only_for_values_without_default() {
+        let parameters = vec![
+            ParameterSpec::from("changes"),
+            ParameterSpec { default: Some("en".to_string()), ..ParameterSpec::from("lang") },
+        ];
+        let selection = LauncherSelection::from_prompt(&Prompt { parameters, ..prompt("Commit", "{changes} {lang}") });
+
+        let inputs: Vec<&str> = selection.inputs.iter().map(|input| input.name.as_str()).collect();
+        assert_eq!(inputs, vec!["changes"]);
+
+        let selection = LauncherSelection::from_prompt(&prompt("Commit", "Review"));
+        assert!(selection.inputs.is_empty());
+    }
+}
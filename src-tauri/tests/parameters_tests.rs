@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::parameters::{
+        detect_parameters, sync_parameters, validate_values, ParameterConstraint, ParameterKind, ParameterSpec,
+    };
+    use prompt_tool_lib::prompt::Prompt;
+    use prompt_tool_lib::render::render_validated;
+    use std::collections::{HashMap, HashSet};
+
+    fn prompt_with_constraints() -> Prompt {
+        let mut prompt = Prompt::new(
+            "Ticket".to_string(),
+            "Ticket {ticket} in {language}".to_string(),
+            vec![ParameterSpec::from("ticket"), ParameterSpec::from("language")],
+            HashSet::new(),
+            HashSet::new(),
+        );
+        prompt.constraints.insert("ticket".to_string(), ParameterConstraint {
+            pattern: Some("[A-Z]+-\\d+".to_string()),
+            max_length: Some(10),
+            ..ParameterConstraint::default()
+        });
+        prompt.constraints.insert("language".to_string(), ParameterConstraint {
+            allowed: vec!["ru".to_string(), "en".to_string()],
+            ..ParameterConstraint::default()
+        });
+        prompt
+    }
+
+    #[test]
+    fn test_parameter_constraints() {
+        let prompt = prompt_with_constraints();
+
+        let values = HashMap::from([
+            ("ticket".to_string(), "ABC-123".to_string()),
+            ("language".to_string(), "ru".to_string()),
+        ]);
+        let output = render_validated(&prompt, &values);
+        assert!(output.errors.is_empty());
+        assert_eq!(output.text.unwrap(), "Ticket ABC-123 in ru");
+
+        let values = HashMap::from([
+            ("ticket".to_string(), "abc-123-too-long".to_string()),
+            ("language".to_string(), "de".to_string()),
+        ]);
+        let codes: Vec<(String, &str)> = validate_values(&prompt, &values)
+            .into_iter()
+            .map(|error| (error.parameter, error.code))
+            .collect();
+        assert_eq!(codes, vec![
+            ("language".to_string(), "not-allowed"),
+            ("ticket".to_string(), "too-long"),
+            ("ticket".to_string(), "pattern-mismatch"),
+        ]);
+        assert!(render_validated(&prompt, &values).text.is_none());
+    }
+
+    #[test]
+    fn test_typed_parameters() {
+        let list: prompt_tool_lib::prompt::PromptList = toml::from_str(r#"
+            [[prompts]]
+            name = "Summary"
+            content = "Summarize in {count} points, lang {lang}, short {short}, tone {tone}"
+            parameters = [
+                "tone",
+                { name = "count", kind = "number", required = true, description = "Число пунктов" },
+                { name = "short", kind = "bool" },
+                { name = "lang", kind = { enum = ["ru", "en"] } },
+            ]
+            categories = []
+            tags = []
+        "#).unwrap();
+        let prompt = &list.prompts[0];
+
+        assert_eq!(prompt.parameters[0], ParameterSpec::from("tone"));
+        assert_eq!(prompt.parameters[1].kind, ParameterKind::Number);
+        assert!(prompt.parameters[1].required);
+        assert_eq!(prompt.parameters[3].kind, ParameterKind::Enum(vec!["ru".to_string(), "en".to_string()]));
+
+        let values = HashMap::from([
+            ("short".to_string(), "maybe".to_string()),
+            ("lang".to_string(), "de".to_string()),
+        ]);
+        let codes: Vec<(String, &str)> = validate_values(prompt, &values)
+            .into_iter()
+            .map(|error| (error.parameter, error.code))
+            .collect();
+        assert_eq!(codes, vec![
+            ("count".to_string(), "required"),
+            ("short".to_string(), "not-a-bool"),
+            ("lang".to_string(), "not-allowed"),
+        ]);
+
+        let values = HashMap::from([
+            ("count".to_string(), "3".to_string()),
+            ("short".to_string(), "yes".to_string()),
+            ("lang".to_string(), "en".to_string()),
+        ]);
+        assert!(validate_values(prompt, &values).is_empty());
+
+        let saved = toml::to_string_pretty(&list).unwrap();
+        let reloaded: prompt_tool_lib::prompt::PromptList = toml::from_str(&saved).unwrap();
+        assert_eq!(reloaded.prompts[0].parameters, prompt.parameters);
+    }
+
+    #[test]
+    fn test_parameter_defaults() {
+        let mut prompt = Prompt::new(
+            "Translate".to_string(),
+            "Translate {text} to {lang}".to_string(),
+            vec![
+                ParameterSpec::from("text"),
+                ParameterSpec { default: Some("en".to_string()), ..ParameterSpec::from("lang") },
+            ],
+            HashSet::new(),
+            HashSet::new(),
+        );
+        prompt.parameters[0].required = true;
+
+        let values = HashMap::from([("text".to_string(), "привет".to_string())]);
+        assert_eq!(render_validated(&prompt, &values).text.unwrap(), "Translate привет to en");
+
+        let values = HashMap::from([
+            ("text".to_string(), "привет".to_string()),
+            ("lang".to_string(), "de".to_string()),
+        ]);
+        assert_eq!(render_validated(&prompt, &values).text.unwrap(), "Translate привет to de");
+
+        let saved = toml::to_string(&prompt).unwrap();
+        assert!(saved.contains("default = \"en\""));
+    }
+
+    #[test]
+    fn test_sync_parameters() {
+        let content = "Review {code|trim} in {language}, see {{ notes }} and {{include:Style}}. {code}";
+        assert_eq!(detect_parameters(content), vec!["code", "language"]);
+
+        let mut prompt = Prompt::new(
+            "Review".to_string(),
+            content.to_string(),
+            vec![ParameterSpec::from("language"), ParameterSpec::from("ticket")],
+            HashSet::new(),
+            HashSet::new(),
+        );
+
+        let sync = sync_parameters(&mut prompt);
+        assert_eq!(sync.added, vec!["code"]);
+        assert_eq!(sync.orphaned, vec!["ticket"]);
+
+        let names: Vec<&str> = prompt.parameters.iter().map(|spec| spec.name.as_str()).collect();
+        assert_eq!(names, vec!["language", "ticket", "code"]);
+
+        let sync = sync_parameters(&mut prompt);
+        assert!(sync.added.is_empty());
+        assert_eq!(sync.orphaned, vec!["ticket"]);
+    }
+}
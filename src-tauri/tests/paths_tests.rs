@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::paths::{claim_directory, migrate_legacy_prompt_file, parse_config_dir_arg, AppPaths};
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_config_dir_arg() {
+        assert_eq!(parse_config_dir_arg(["--config-dir", "/tmp/a"]), Some(PathBuf::from("/tmp/a")));
+        assert_eq!(parse_config_dir_arg(["--verbose", "--config-dir=/tmp/b"]), Some(PathBuf::from("/tmp/b")));
+        assert_eq!(parse_config_dir_arg(["--config-dir"]), None);
+        assert_eq!(parse_config_dir_arg(Vec::<String>::new()), None);
+    }
+
+    #[test]
+    fn test_claim_directory_rejects_other_user() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("index");
+
+        // Первое обращение закрепляет директорию, повторное проходит
+        claim_directory(&dir).unwrap();
+        claim_directory(&dir).unwrap();
+
+        fs::write(dir.join(".owner"), "someone-else").unwrap();
+        assert!(claim_directory(&dir).is_err());
+    }
+
+    #[test]
+    fn test_default_prompt_file_under_data_dir() {
+        let paths = AppPaths::from_override(PathBuf::from("/etc/prompt-tool"));
+        let default_file = paths.default_prompt_file();
+        assert!(default_file.is_absolute());
+        assert!(default_file.starts_with(&paths.data_dir));
+    }
+
+    #[test]
+    fn test_migrate_legacy_prompt_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let legacy = temp_dir.path().join("cwd").join("prompts").join("default.toml");
+        let target = temp_dir.path().join("data").join("prompts").join("default.toml");
+
+        // Старого файла нет - переносить нечего
+        assert!(!migrate_legacy_prompt_file(&legacy, &target).unwrap());
+        assert!(!target.exists());
+
+        fs::create_dir_all(legacy.parent().unwrap()).unwrap();
+        fs::write(&legacy, "prompts = []").unwrap();
+        assert!(migrate_legacy_prompt_file(&legacy, &target).unwrap());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "prompts = []");
+        assert!(legacy.exists());
+
+        // Файл на новом месте не перезаписывается
+        fs::write(&legacy, "changed").unwrap();
+        assert!(!migrate_legacy_prompt_file(&legacy, &target).unwrap());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "prompts = []");
+    }
+}
@@ -0,0 +1,19 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::pricing::{default_price_table, estimate_cost};
+
+    #[test]
+    fn test_estimate_cost() {
+        let table = default_price_table();
+
+        let estimate = estimate_cost(&table, "gpt-4o", 1_000_000, 500_000).unwrap();
+        assert_eq!(estimate.priced_as, "gpt-4o");
+        assert!((estimate.total_cost - 7.5).abs() < 1e-9);
+
+        // Версия модели считается по самому длинному подходящему префиксу
+        let estimate = estimate_cost(&table, "gpt-4o-mini-2024-07-18", 1_000_000, 0).unwrap();
+        assert_eq!(estimate.priced_as, "gpt-4o-mini");
+
+        assert!(estimate_cost(&table, "llama3", 10, 10).is_err());
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::paths::AppPaths;
+    use prompt_tool_lib::profiles::{profile_paths, validate_name, ProfileRegistry, DEFAULT_PROFILE};
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_registry_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("profiles.json");
+
+        // Без файла есть только профиль по умолчанию
+        let mut registry = ProfileRegistry::load(&path);
+        assert_eq!(registry, ProfileRegistry::default());
+
+        assert!(registry.activate("client-x").unwrap());
+        assert!(!registry.activate("client-x").unwrap());
+        registry.save(&path).unwrap();
+
+        let loaded = ProfileRegistry::load(&path);
+        assert_eq!(loaded.active, "client-x");
+        assert_eq!(loaded.profiles.iter().collect::<Vec<_>>(), vec!["client-x", DEFAULT_PROFILE]);
+    }
+
+    #[test]
+    fn test_registry_repairs_unknown_active() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("profiles.json");
+        fs::write(&path, r#"{ "active": "gone", "profiles": ["work"] }"#).unwrap();
+
+        let registry = ProfileRegistry::load(&path);
+        assert_eq!(registry.active, DEFAULT_PROFILE);
+        assert!(registry.profiles.contains("work"));
+        assert!(registry.profiles.contains(DEFAULT_PROFILE));
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("work").is_ok());
+        assert!(validate_name("клиент_1").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../work").is_err());
+        assert!(validate_name("a/b").is_err());
+        assert!(validate_name(&"x".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_profile_paths() {
+        let base = AppPaths::from_override(PathBuf::from("/cfg"));
+
+        let default = profile_paths(&base, DEFAULT_PROFILE);
+        assert_eq!(default.config_dir, PathBuf::from("/cfg"));
+        assert_eq!(default.data_dir, PathBuf::from("/cfg/data"));
+
+        let work = profile_paths(&base, "work");
+        assert_eq!(work.config_dir, PathBuf::from("/cfg/profiles/work"));
+        assert_eq!(work.data_dir, PathBuf::from("/cfg/data/profiles/work"));
+    }
+}
@@ -0,0 +1,184 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::prompt;
+    use prompt_tool_lib::prompt::{aliases_first, PromptList, SearchFilter, SortOrder};
+
+    fn query_filter(query: &str, exact: bool) -> SearchFilter {
+        SearchFilter {
+            query: Some(query.to_string()),
+            categories: None,
+            tags: None,
+            date_from: None,
+            date_to: None,
+            exact,
+            min_rating: None,
+            sort: None,
+            include_archived: false,
+        }
+    }
+
+    #[test]
+    fn test_quoted_phrase_search() {
+        let list = PromptList {
+            prompts: vec![
+                prompt("Git", "Write a commit message for these changes"),
+                prompt("Log", "Summarize the message of each commit"),
+            ],
+        };
+
+        // Фраза в кавычках совпадает только с соседними словами
+        let results = list.search(&query_filter("\"commit message\"", false));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "Git");
+
+        // Фраза не совпадает с частью слова
+        let results = list.search(&query_filter("\"commit messages\"", false));
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_exact_search_mode() {
+        let list = PromptList {
+            prompts: vec![
+                prompt("Review", "Review the\ncode   carefully"),
+                prompt("Reviewer", "Act as a code reviewer"),
+            ],
+        };
+
+        // Точный режим игнорирует переносы строк, но требует границы слов
+        let results = list.search(&query_filter("the code", true));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "Review");
+
+        let results = list.search(&query_filter("code review", true));
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_repair_ids() {
+        let mut list = PromptList {
+            prompts: vec![prompt("A", "a"), prompt("B", "b"), prompt("C", "c"), prompt("D", "d"), prompt("E", "e")],
+        };
+        list.prompts[0].id = "x".to_string();
+        list.prompts[1].id = "x".to_string();
+        list.prompts[2].id = "x-2".to_string();
+        list.prompts[3].id = "x".to_string();
+        list.prompts[4].id = String::new();
+
+        let repairs = list.repair_ids();
+        let new_ids: Vec<(&str, &str)> = repairs.iter().map(|r| (r.prompt_name.as_str(), r.new_id.as_str())).collect();
+        assert_eq!(new_ids, vec![("B", "x-3"), ("D", "x-4")]);
+        assert_eq!(list.prompts[2].id, "x-2");
+        assert!(list.prompts[4].id.is_empty());
+
+        assert!(list.repair_ids().is_empty());
+    }
+
+    #[test]
+    fn test_metadata_roundtrip_and_search() {
+        let mut annotated = prompt("Translator", "Translate {text}");
+        annotated.author = "Ada".to_string();
+        annotated.source_url = "https://example.com/prompts/translator".to_string();
+        annotated.license = "CC-BY-4.0".to_string();
+        annotated.models = vec!["gpt-4o".to_string(), "claude-3-5-sonnet".to_string()];
+        annotated.notes = "Works best with short paragraphs".to_string();
+
+        let list = PromptList { prompts: vec![annotated, prompt("Plain", "Review {code}")] };
+        let toml_string = toml::to_string_pretty(&list).unwrap();
+        assert!(toml_string.contains("license = \"CC-BY-4.0\""));
+
+        // Пустые метаданные в файл не попадают
+        let plain = toml::to_string_pretty(&PromptList { prompts: vec![prompt("Plain", "Review {code}")] }).unwrap();
+        assert!(!plain.contains("author") && !plain.contains("models"));
+
+        let loaded: PromptList = toml::from_str(&toml_string).unwrap();
+        assert_eq!(loaded.prompts[0].models, vec!["gpt-4o", "claude-3-5-sonnet"]);
+        assert_eq!(loaded.prompts[0].notes, "Works best with short paragraphs");
+
+        for query in ["ada", "\"short paragraphs\"", "cc-by"] {
+            let results = loaded.search(&query_filter(query, false));
+            assert_eq!(results.len(), 1, "{}", query);
+            assert_eq!(results[0].name, "Translator");
+        }
+    }
+
+    #[test]
+    fn test_fill_provenance() {
+        let mut imported = prompt("Imported", "text");
+        imported.author = "kept".to_string();
+        imported.fill_provenance(" octocat ", "https://gist.github.com/octocat/1");
+        assert_eq!(imported.author, "kept");
+        assert_eq!(imported.source_url, "https://gist.github.com/octocat/1");
+    }
+
+    #[test]
+    fn test_rating_filter_and_sort() {
+        let rated = |name: &str, rating: Option<u8>| {
+            let mut prompt = prompt(name, "text");
+            prompt.rating = rating;
+            prompt
+        };
+        let list = PromptList {
+            prompts: vec![rated("Draft", Some(2)), rated("Unrated", None), rated("Best", Some(5)), rated("Good", Some(4))],
+        };
+
+        let mut filter = query_filter("text", false);
+        filter.min_rating = Some(4);
+        let names: Vec<&str> = list.search(&filter).iter().map(|prompt| prompt.name.as_str()).collect();
+        assert_eq!(names, vec!["Best", "Good"]);
+
+        // Сортировка по оценке устойчива: неоцененные в конце, порядок остальных сохраняется
+        let mut found = list.prompts.clone();
+        SortOrder::Relevance.apply(&mut found);
+        assert_eq!(found[0].name, "Draft");
+        SortOrder::Rating.apply(&mut found);
+        let names: Vec<&str> = found.iter().map(|prompt| prompt.name.as_str()).collect();
+        assert_eq!(names, vec!["Best", "Good", "Draft", "Unrated"]);
+    }
+
+    #[test]
+    fn test_archived_excluded_by_default() {
+        let mut retired = prompt("Old review", "Review the code");
+        retired.archived = true;
+        let list = PromptList { prompts: vec![retired, prompt("Review", "Review the code")] };
+
+        let mut filter = query_filter("review", false);
+        let names: Vec<&str> = list.search(&filter).iter().map(|prompt| prompt.name.as_str()).collect();
+        assert_eq!(names, vec!["Review"]);
+
+        filter.include_archived = true;
+        assert_eq!(list.search(&filter).len(), 2);
+
+        // Признак сохраняется в файле только у архивных промптов
+        let toml_string = toml::to_string_pretty(&list).unwrap();
+        assert_eq!(toml_string.matches("archived = true").count(), 1);
+        assert!(!toml_string.contains("archived = false"));
+    }
+
+    #[test]
+    fn test_alias_matches_first() {
+        let mut commit = prompt("Conventional commit message", "Write a commit message for {changes}");
+        commit.aliases = vec!["cm".to_string()];
+        let list = PromptList {
+            prompts: vec![prompt("Describe cmake errors", "Explain cm output"), commit],
+        };
+
+        // Псевдоним совпадает целиком и без учета регистра, такой промпт идет первым
+        let names: Vec<&str> = list.search(&query_filter(" CM ", false)).iter().map(|prompt| prompt.name.as_str()).collect();
+        assert_eq!(names, vec!["Conventional commit message", "Describe cmake errors"]);
+
+        // Часть псевдонима не считается совпадением
+        assert!(!list.prompts[1].matches_alias("c"));
+
+        let mut found = list.prompts.clone();
+        aliases_first(&mut found, "cm");
+        assert_eq!(found[0].name, "Conventional commit message");
+        aliases_first(&mut found, "");
+        assert_eq!(found[0].name, "Conventional commit message");
+
+        let toml_string = toml::to_string_pretty(&list).unwrap();
+        assert_eq!(toml_string.matches("aliases").count(), 1);
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use prompt_tool_lib::error::PromptToolError;
+    use prompt_tool_lib::prompt::SearchFilter;
+    use prompt_tool_lib::query_macros::{apply_macros, default_macros, expand_query, validate_macro};
+    use std::collections::BTreeMap;
+
+    fn macros() -> BTreeMap<String, String> {
+        let mut macros = default_macros();
+        macros.insert("work".to_string(), "tag:work @new".to_string());
+        macros
+    }
+
+    fn filter(query: &str) -> SearchFilter {
+        SearchFilter {
+            query: Some(query.to_string()),
+            categories: None,
+            tags: None,
+            date_from: None,
+            date_to: None,
+            exact: false,
+            min_rating: None,
+            sort: None,
+            include_archived: false,
+        }
+    }
+
+    #[test]
+    fn test_expand_query() {
+        assert_eq!(expand_query("review @work", &macros()).unwrap(), "review tag:work updated:7d");
+        assert_eq!(expand_query("mail user@host @unknown", &macros()).unwrap(), "mail user@host @unknown");
+
+        let mut cyclic = macros();
+        cyclic.insert("a".to_string(), "@b".to_string());
+        cyclic.insert("b".to_string(), "@a".to_string());
+        assert!(expand_query("@a", &cyclic).is_err());
+        assert!(validate_macro("loop", "x @loop", &macros()).is_err());
+        assert!(validate_macro("bad name", "x", &macros()).is_err());
+        assert!(validate_macro("go", "tag:go", &macros()).is_ok());
+    }
+
+    #[test]
+    fn test_apply_macros() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 10, 12, 0, 0).unwrap();
+
+        let mut search = filter("review @work category:dev");
+        apply_macros(&mut search, &macros(), now).unwrap();
+        assert_eq!(search.query.as_deref(), Some("review"));
+        assert_eq!(search.tags, Some(vec!["work".to_string()]));
+        assert_eq!(search.categories, Some(vec!["dev".to_string()]));
+        assert_eq!(search.date_from, Some(now - Duration::days(7)));
+
+        let mut only_macro = filter("@new");
+        apply_macros(&mut only_macro, &macros(), now).unwrap();
+        assert!(only_macro.query.is_none());
+
+        assert!(apply_macros(&mut filter("updated:soon"), &macros(), now).is_err());
+    }
+
+    #[test]
+    fn test_out_of_range_period_is_rejected() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 10, 12, 0, 0).unwrap();
+
+        for query in ["updated:999999999d", "updated:9223372036854775807w", "updated:-3d"] {
+            let result = apply_macros(&mut filter(query), &macros(), now);
+            assert!(matches!(result, Err(PromptToolError::Validation(_))), "{}", query);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::prompt;
+    use prompt_tool_lib::platform::prompt_from_selection;
+    use prompt_tool_lib::parameters::ParameterSpec;
+    use prompt_tool_lib::prompt::PromptList;
+    use prompt_tool_lib::error::PromptToolError;
+    use prompt_tool_lib::render::{bind_secrets, debug_render, expand_includes, find_placeholders, mask_secrets, render_template, render_validated};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_render_template() {
+        let values = HashMap::from([
+            ("text".to_string(), "Hello".to_string()),
+            ("language".to_string(), "русский".to_string()),
+        ]);
+
+        assert_eq!(
+            render_template("Переведи {text} на { language }", &values).unwrap(),
+            "Переведи Hello на русский"
+        );
+
+        // Неизвестные плейсхолдеры и непарные скобки остаются как есть
+        assert_eq!(render_template("{missing} {text", &values).unwrap(), "{missing} {text");
+        assert_eq!(render_template("fn() { {text} }", &values).unwrap(), "fn() { Hello }");
+    }
+
+    #[test]
+    fn test_render_tera_syntax() {
+        let values = HashMap::from([
+            ("name".to_string(), "Ann".to_string()),
+            ("items".to_string(), "a,b".to_string()),
+            ("brace".to_string(), "{{ not a template }}".to_string()),
+        ]);
+
+        let template = "{% if name %}Hi {{ name | upper }}{% endif %}: {% for item in items | split(pat=\",\") %}[{{ item }}]{% endfor %} {brace}";
+        assert_eq!(render_template(template, &values).unwrap(), "Hi ANN: [a][b] {{ not a template }}");
+
+        let error = render_template("line one\n{% if name %}unclosed", &values).unwrap_err();
+        assert!(error.line.is_some());
+    }
+
+    #[test]
+    fn test_render_template_functions() {
+        let values = HashMap::from([
+            ("title".to_string(), "  Привет, Мир! 2024 ".to_string()),
+            ("quote".to_string(), "say \"hi\"\n".to_string()),
+        ]);
+
+        assert_eq!(render_template("{title|trim|upper}", &values).unwrap(), "ПРИВЕТ, МИР! 2024");
+        assert_eq!(render_template("{title|slugify}", &values).unwrap(), "привет-мир-2024");
+        assert_eq!(render_template("{title|trim|truncate:7}", &values).unwrap(), "Привет…");
+        assert_eq!(render_template("\"{quote|json-escape}\"", &values).unwrap(), "\"say \\\"hi\\\"\\n\"");
+        assert_eq!(render_template("{lang|default:русский|upper}", &values).unwrap(), "РУССКИЙ");
+
+        // Неизвестная функция - не плейсхолдер
+        assert_eq!(render_template("{title|unknown}", &values).unwrap(), "{title|unknown}");
+    }
+
+    #[test]
+    fn test_expand_includes() {
+        let library = PromptList {
+            prompts: vec![
+                prompt("preamble", "You are {role}."),
+                prompt("review", "{{include:preamble}}\n{{ include:footer }}Review {code}"),
+                prompt("footer", "Be brief. "),
+                prompt("loop-a", "{{include:loop-b}}"),
+                prompt("loop-b", "{{include:loop-a}}"),
+                prompt("broken", "{{include:missing}}"),
+            ],
+        };
+
+        let expanded = expand_includes(&library.prompts[1], &library).unwrap();
+        assert_eq!(expanded, "You are {role}.\nBe brief. Review {code}");
+
+        let error = expand_includes(&library.prompts[3], &library).unwrap_err().to_string();
+        assert!(error.contains("loop-a -> loop-b -> loop-a"));
+
+        assert!(expand_includes(&library.prompts[5], &library).is_err());
+    }
+
+    #[test]
+    fn test_unresolved_placeholder_warnings() {
+        let prompt = prompt("Translate", "Translate {text} to {lang|upper}, not {{ raw }} or {text}");
+        assert_eq!(find_placeholders(&prompt.content), vec!["text", "lang"]);
+
+        let values = HashMap::from([
+            ("text".to_string(), "hello".to_string()),
+            ("langauge".to_string(), "en".to_string()),
+            ("raw".to_string(), "x".to_string()),
+        ]);
+        let output = render_validated(&prompt, &values);
+
+        assert_eq!(output.text.as_deref(), Some("Translate hello to {lang|upper}, not x or hello"));
+        assert!(output.has_unresolved());
+        assert_eq!(output.warnings.len(), 1);
+        assert_eq!(output.warnings[0].placeholder, "lang");
+    }
+
+    #[test]
+    fn test_prompt_from_selection() {
+        let prompt = prompt_from_selection("\n  Review this code\nfn main() {}");
+        assert_eq!(prompt.name, "Review this code");
+        assert_eq!(prompt.content, "\n  Review this code\nfn main() {}");
+
+        let long = "a".repeat(100);
+        assert_eq!(prompt_from_selection(&long).name.chars().count(), 61);
+    }
+
+    #[test]
+    fn test_debug_render() {
+        let mut review = prompt("Review", "{{include:Intro}} Review {code|trim|upper} in {lang}.{% if short %} Brief.{% endif %}");
+        review.parameters = vec![ParameterSpec { default: Some("ru".to_string()), ..ParameterSpec::from("lang") }];
+        let library = PromptList { prompts: vec![review.clone(), prompt("Intro", "Hello.")] };
+
+        let values = HashMap::from([
+            ("code".to_string(), "  fn main  ".to_string()),
+            ("short".to_string(), "yes".to_string()),
+        ]);
+        let debug = debug_render(&review, &library, &values).unwrap();
+
+        let stages: Vec<&str> = debug.steps.iter().map(|step| step.stage).collect();
+        assert_eq!(stages, vec!["source", "include", "values", "placeholder", "placeholder", "template"]);
+
+        assert!(debug.steps[1].text.starts_with("Hello. Review"));
+        assert!(debug.steps[2].description.contains("lang = \"ru\" (по умолчанию)"));
+        assert!(debug.steps[3].description.contains("trim -> \"fn main\""));
+        assert!(debug.steps[3].description.contains("upper -> \"FN MAIN\""));
+        assert_eq!(debug.output.text.as_deref(), Some("Hello. Review FN MAIN in ru. Brief."));
+        assert_eq!(debug.steps.last().unwrap().text, "Hello. Review FN MAIN in ru. Brief.");
+    }
+
+    #[test]
+    fn test_bind_secrets() {
+        let template = "Key: {{secret:openai}}, again {{secret: openai }}; {topic} {{secret:github}}";
+        let mut requested = Vec::new();
+        let bindings = bind_secrets(template, |name| {
+            requested.push(name.to_string());
+            Ok(format!("{{{}-value}}", name))
+        }).unwrap();
+
+        // Каждый секрет запрашивается один раз и не попадает в текст шаблона
+        assert_eq!(requested, vec!["openai", "github"]);
+        assert!(!bindings.template.contains("secret:"));
+        assert!(!bindings.template.contains("-value"));
+
+        let mut values = bindings.values.clone();
+        values.insert("topic".to_string(), "Rust".to_string());
+        let text = render_template(&bindings.template, &values).unwrap();
+        assert_eq!(text, "Key: {openai-value}, again {openai-value}; Rust {github-value}");
+
+        let missing = bind_secrets(template, |name| Err(PromptToolError::Validation(format!("Секрет \"{}\" не найден", name))));
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn test_mask_secrets() {
+        let template = "Token {{secret:short}} and {{secret:long}}";
+        let bindings = bind_secrets(template, |name| Ok(match name {
+            "short" => "abc".to_string(),
+            _ => "abcdef".to_string(),
+        })).unwrap();
+        let text = render_template(&bindings.template, &bindings.values).unwrap();
+
+        assert_eq!(mask_secrets(&text, &bindings, "***"), "Token *** and ***");
+    }
+}
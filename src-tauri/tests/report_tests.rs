@@ -0,0 +1,38 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{prompt, tagged};
+    use chrono::{DateTime, TimeZone, Utc};
+    use prompt_tool_lib::prompt::{Prompt, PromptList};
+    use prompt_tool_lib::report::build_stats_report;
+    use prompt_tool_lib::usage::UsageStats;
+
+    fn month(month: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, month, 1, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_stats_report() {
+        let list = PromptList {
+            prompts: vec![
+                Prompt { created_at: month(1), ..tagged("Review", "text", &["code", "git"]) },
+                Prompt { created_at: month(1), ..tagged("Commit", "text", &["git"]) },
+                Prompt { created_at: month(3), ..prompt("Translate", "text") },
+            ],
+        };
+
+        let mut usage = UsageStats::default();
+        usage.record("Commit");
+        usage.record("Commit");
+        usage.record("Review");
+
+        let report = build_stats_report(&list, &usage, Utc::now());
+
+        assert!(report.contains("| Промптов | 3 |"));
+        assert!(report.contains("| git | 2 |"));
+        assert!(report.find("| Commit | 2 |").unwrap() < report.find("| Review | 1 |").unwrap());
+        assert!(report.contains("| 2024-01 | 2 | 2 |"));
+        assert!(report.contains("| 2024-03 | 1 | 3 |"));
+    }
+}
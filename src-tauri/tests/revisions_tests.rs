@@ -0,0 +1,26 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::prompt;
+    use prompt_tool_lib::revisions::RevisionStore;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_revision_store() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = RevisionStore::new(temp_dir.path().join("revisions.jsonl"));
+        assert!(store.list("Review").unwrap().is_empty());
+
+        // Первое изменение сохраняет и исходную версию
+        assert_eq!(store.record_change(&prompt("Review", "v1"), &prompt("Review", "v2")).unwrap(), 2);
+        assert_eq!(store.record_change(&prompt("Review", "v2"), &prompt("Review", "v3")).unwrap(), 3);
+        assert_eq!(store.record(&prompt("Commit", "c1")).unwrap(), 1);
+
+        let revisions = store.list("Review").unwrap();
+        assert_eq!(revisions.len(), 3);
+        assert_eq!(revisions[0].prompt.content, "v1");
+        assert_eq!(store.get("Review", 3).unwrap().prompt.content, "v3");
+        assert!(store.get("Review", 4).is_err());
+    }
+}
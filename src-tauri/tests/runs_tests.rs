@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use prompt_tool_lib::runs::{Run, RunStore};
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn run(prompt_name: &str, offset_secs: i64) -> Run {
+        let created_at = Utc::now() + Duration::seconds(offset_secs);
+        Run {
+            id: Run::new_id(created_at),
+            prompt_name: prompt_name.to_string(),
+            rendered_input: "input".to_string(),
+            parameters: HashMap::new(),
+            model: "gpt-test".to_string(),
+            response: Some("output".to_string()),
+            error: None,
+            latency_ms: 10,
+            usage: None,
+            created_at,
+        }
+    }
+
+    #[test]
+    fn test_run_store() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = RunStore::new(temp_dir.path().join("runs.jsonl"));
+        assert!(store.list(None).unwrap().is_empty());
+
+        let first = run("Review", 0);
+        let second = run("Review", 1);
+        store.append(&first).unwrap();
+        store.append(&run("Commit", 2)).unwrap();
+        store.append(&second).unwrap();
+
+        let runs = store.list(Some("Review")).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].id, second.id);
+
+        assert!(store.delete(&first.id).unwrap());
+        assert!(!store.delete(&first.id).unwrap());
+        assert_eq!(store.list(None).unwrap().len(), 2);
+    }
+}
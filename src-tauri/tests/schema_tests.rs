@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::schema::{library_schema, validate_file, validate_library};
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_library_schema_accepts_both_parameter_forms() {
+        let schema = library_schema();
+        assert!(schema["definitions"]["ParameterSpec"]["anyOf"].is_array());
+
+        let library = json!({
+            "prompts": [{
+                "name": "Translate",
+                "content": "Translate {text} to {lang}",
+                "parameters": ["text", { "name": "lang", "kind": { "enum": ["ru", "en"] }, "required": true }]
+            }]
+        });
+        assert!(validate_library(&library).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_validate_library_reports_paths() {
+        let library = json!({
+            "prompts": [
+                { "name": "Ok", "content": "text", "parameters": [] },
+                { "name": "Broken", "parameters": [{ "kind": "string" }], "tags": "one" }
+            ]
+        });
+
+        let errors = validate_library(&library).unwrap();
+        let paths: Vec<&str> = errors.iter().map(|error| error.path.as_str()).collect();
+        assert!(paths.contains(&"/prompts/1"));
+        assert!(paths.contains(&"/prompts/1/tags"));
+        assert!(paths.iter().any(|path| path.starts_with("/prompts/1/parameters/0")));
+        assert!(!paths.iter().any(|path| path.starts_with("/prompts/0")));
+    }
+
+    #[test]
+    fn test_validate_file() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let valid = temp_dir.path().join("valid.toml");
+        fs::write(&valid, "schema_version = 2\n\n[[prompts]]\nname = \"A\"\ncontent = \"a\"\nparameters = [\"x\"]\ncreated_at = 2024-01-01T00:00:00Z\n").unwrap();
+        assert!(validate_file(&valid).unwrap().is_empty());
+
+        // Синтаксическая ошибка указывает строку
+        let broken = temp_dir.path().join("broken.toml");
+        fs::write(&broken, "[[prompts]]\nname = \"A\"\ncontent = \n").unwrap();
+        let errors = validate_file(&broken).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("строка 3"), "{}", errors[0].message);
+
+        let newer = temp_dir.path().join("newer.toml");
+        fs::write(&newer, "schema_version = 99\nprompts = []\n").unwrap();
+        assert_eq!(validate_file(&newer).unwrap()[0].path, "/schema_version");
+
+        let json_file = temp_dir.path().join("library.json");
+        fs::write(&json_file, r#"{ "prompts": [{ "name": "A", "content": 1, "parameters": [] }] }"#).unwrap();
+        assert_eq!(validate_file(&json_file).unwrap()[0].path, "/prompts/0/content");
+
+        assert!(validate_file(&temp_dir.path().join("missing.toml")).is_err());
+    }
+}
@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::database::{build_schema, FieldBoosts};
+    use prompt_tool_lib::search_config::{describe_schema, SearchConfig, SEARCH_CONFIG_VERSION};
+    use tempfile::TempDir;
+
+    fn config() -> SearchConfig {
+        SearchConfig::new(
+            true,
+            vec!["english".to_string(), "ru".to_string()],
+            vec!["bug, error, defect".to_string()],
+            FieldBoosts { title: 4.0, tags: 2.0, text: 1.0 },
+        )
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("search.json");
+
+        let config = config();
+        config.save(&path).unwrap();
+
+        assert_eq!(SearchConfig::load(&path).unwrap(), config);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(config().validate().is_ok());
+
+        let mut unknown_language = config();
+        unknown_language.stemming_languages.push("klingon".to_string());
+        assert!(unknown_language.validate().is_err());
+
+        let mut newer = config();
+        newer.version = SEARCH_CONFIG_VERSION + 1;
+        assert!(newer.validate().is_err());
+
+        let mut negative = config();
+        negative.field_boosts.tags = -1.0;
+        assert!(negative.validate().is_err());
+    }
+
+    #[test]
+    fn test_describe_schema() {
+        let fields = describe_schema(&build_schema());
+
+        let title = fields.iter().find(|field| field.name == "title").unwrap();
+        assert_eq!(title.kind, "text");
+        assert_eq!(title.tokenizer.as_deref(), Some("multilang"));
+
+        let tags = fields.iter().find(|field| field.name == "tags").unwrap();
+        assert_eq!(tags.tokenizer.as_deref(), Some("raw"));
+    }
+}
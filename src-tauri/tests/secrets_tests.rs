@@ -0,0 +1,24 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::secrets::{reference, resolve, validate_name};
+
+    #[test]
+    fn test_secret_names() {
+        assert!(validate_name("openai").is_ok());
+        assert!(validate_name("github_token-2.work").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("with space").is_err());
+        assert!(validate_name("a}}b").is_err());
+    }
+
+    #[test]
+    fn test_secret_references() {
+        assert_eq!(reference("secret:openai"), Some("openai"));
+        assert_eq!(reference(" secret: openai "), Some("openai"));
+        assert_eq!(reference("sk-plain-key"), None);
+
+        // Обычные значения настроек возвращаются без обращения к связке ключей
+        assert_eq!(resolve("sk-plain-key").unwrap(), "sk-plain-key");
+        assert_eq!(resolve("").unwrap(), "");
+    }
+}
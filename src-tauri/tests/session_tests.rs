@@ -0,0 +1,32 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::session::SessionVariables;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_session_variables() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.json");
+
+        let mut session = SessionVariables::default();
+        session.set("ticket", "ABC-123".to_string(), false).unwrap();
+        session.set("project", "PromptTool".to_string(), true).unwrap();
+        assert!(session.set("  ", "x".to_string(), false).is_err());
+
+        let mut values = HashMap::from([("project".to_string(), "Other".to_string())]);
+        session.apply(&mut values);
+        assert_eq!(values["ticket"], "ABC-123");
+        assert_eq!(values["project"], "Other");
+
+        session.save(&path).unwrap();
+        let restored = SessionVariables::load(&path);
+        assert_eq!(restored.variables().len(), 1);
+        assert_eq!(restored.variables()["project"].value, "PromptTool");
+
+        session.clear_unpinned();
+        assert!(!session.variables().contains_key("ticket"));
+        assert!(session.remove("project"));
+        assert!(!session.remove("project"));
+    }
+}
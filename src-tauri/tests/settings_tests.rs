@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::prompt::SortOrder;
+    use prompt_tool_lib::settings::{Settings, SettingsUpdate};
+
+    #[test]
+    fn test_partial_update() {
+        let mut settings = Settings::default();
+        let update = SettingsUpdate {
+            result_limit: Some(20),
+            default_sort: Some(SortOrder::Rating),
+            language: Some("ru".to_string()),
+            ..Default::default()
+        };
+
+        // Язык не изменился и в списке изменений не появляется
+        assert_eq!(settings.apply(&update).unwrap(), vec!["result_limit", "default_sort"]);
+        assert_eq!(settings.result_limit, 20);
+        assert_eq!(settings.default_sort, SortOrder::Rating);
+        assert_eq!(settings.backup_count, Settings::default().backup_count);
+
+        assert!(settings.apply(&SettingsUpdate::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_update_changes_nothing() {
+        let mut settings = Settings::default();
+        let update = SettingsUpdate {
+            result_limit: Some(0),
+            language: Some("fr".to_string()),
+            fuzzy_threshold: Some(3),
+            backup_count: Some(5),
+            ..Default::default()
+        };
+
+        let error = settings.apply(&update).unwrap_err().to_string();
+        assert!(error.contains("result_limit"));
+        assert!(error.contains("language"));
+        assert!(error.contains("fuzzy_threshold"));
+        assert!(!error.contains("backup_count"));
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let settings: Settings = serde_json::from_str(r#"{"backup_count": 3}"#).unwrap();
+        assert_eq!(settings.backup_count, 3);
+        assert_eq!(settings.result_limit, Settings::default().result_limit);
+        assert!(settings.validate().is_ok());
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::shortcuts::{check_conflicts, find_binding, parse_shortcut, shortcuts_for, PromptHotkey};
+    use std::collections::BTreeMap;
+
+    fn bindings() -> BTreeMap<String, PromptHotkey> {
+        BTreeMap::from([
+            ("Ctrl+Alt+1".to_string(), PromptHotkey { prompt_id: "commit".to_string(), auto_paste: true }),
+            ("Ctrl+Alt+2".to_string(), PromptHotkey { prompt_id: "review".to_string(), auto_paste: false }),
+        ])
+    }
+
+    #[test]
+    fn test_parse_shortcut() {
+        assert!(parse_shortcut("Ctrl+Shift+K").is_ok());
+        assert!(parse_shortcut("Ctrl+Nope").is_err());
+        assert!(parse_shortcut("").is_err());
+    }
+
+    #[test]
+    fn test_find_binding_by_parsed_shortcut() {
+        let bindings = bindings();
+
+        // Регистр и порядок модификаторов не важны
+        let pressed = parse_shortcut("alt+ctrl+1").unwrap();
+        assert_eq!(find_binding(&bindings, &pressed).unwrap().prompt_id, "commit");
+        assert!(find_binding(&bindings, &parse_shortcut("Ctrl+Alt+3").unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_check_conflicts() {
+        let bindings = bindings();
+
+        assert!(check_conflicts(&bindings, "Ctrl+Space", "Ctrl+Alt+3", "commit").is_ok());
+        // Повторная привязка того же сочетания к тому же промпту допустима
+        assert!(check_conflicts(&bindings, "Ctrl+Space", "Ctrl+Alt+1", "commit").is_ok());
+        assert!(check_conflicts(&bindings, "Ctrl+Space", "Ctrl+Alt+2", "commit").is_err());
+        assert!(check_conflicts(&bindings, "Ctrl+Space", "ctrl+space", "commit").is_err());
+        assert!(check_conflicts(&bindings, "", "Ctrl+Alt+3", "commit").is_ok());
+    }
+
+    #[test]
+    fn test_shortcuts_for() {
+        assert_eq!(shortcuts_for(&bindings(), "review"), vec!["Ctrl+Alt+2"]);
+        assert!(shortcuts_for(&bindings(), "missing").is_empty());
+    }
+}
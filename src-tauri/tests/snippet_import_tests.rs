@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::snippet_import::{detect_sources, import_source, SourceKind};
+    use prompt_tool_lib::parameters::ParameterSpec;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_and_import_sources() {
+        let home = TempDir::new().unwrap();
+
+        let espanso = home.path().join(".config/espanso/match");
+        fs::create_dir_all(&espanso).unwrap();
+        fs::write(espanso.join("base.yml"), r#"
+matches:
+  - trigger: ":review"
+    replace: "Review this {{language}} code"
+  - trigger: ":logo"
+    image_path: "/tmp/logo.png"
+"#).unwrap();
+
+        let alfred = home.path().join("Library/Application Support/Alfred/Alfred.alfredpreferences/snippets/Work");
+        fs::create_dir_all(&alfred).unwrap();
+        fs::write(alfred.join("a.json"), r#"{"alfredsnippet": {"name": "Greeting", "snippet": "Hello!", "keyword": "hi"}}"#).unwrap();
+
+        let downloads = home.path().join("Downloads");
+        fs::create_dir_all(&downloads).unwrap();
+        fs::write(downloads.join("TextExpander Export.csv"), "sig,\"Best,\nMe\",Signature\n").unwrap();
+        fs::write(downloads.join("raycast-snippets.json"), r#"[{"name": "Date", "text": "{date}"}]"#).unwrap();
+        fs::write(downloads.join("unrelated.json"), "[]").unwrap();
+
+        let mut kinds: Vec<SourceKind> = detect_sources(home.path()).into_iter().map(|source| source.kind).collect();
+        kinds.sort_by_key(|kind| format!("{:?}", kind));
+        assert_eq!(kinds, vec![SourceKind::Alfred, SourceKind::Espanso, SourceKind::Raycast, SourceKind::TextExpander]);
+
+        let prompts = import_source(SourceKind::Espanso, &espanso).unwrap().prompts;
+        assert_eq!(prompts.len(), 1);
+        assert_eq!(prompts[0].content, "Review this {language} code");
+        assert_eq!(prompts[0].parameters, vec![ParameterSpec::from("language")]);
+
+        let prompts = import_source(SourceKind::TextExpander, &downloads.join("TextExpander Export.csv")).unwrap().prompts;
+        assert_eq!(prompts[0].name, "Signature");
+        assert_eq!(prompts[0].content, "Best,\nMe");
+
+        let prompts = import_source(SourceKind::Alfred, alfred.parent().unwrap()).unwrap().prompts;
+        assert!(prompts[0].categories.contains("Work"));
+    }
+}
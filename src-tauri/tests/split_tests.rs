@@ -0,0 +1,82 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{prompt, strings};
+    use prompt_tool_lib::file_io::load_prompts;
+    use prompt_tool_lib::import::{import_directory, FolderMapping};
+    use prompt_tool_lib::error::PromptToolError;
+    use prompt_tool_lib::prompt::{Prompt, PromptList};
+    use prompt_tool_lib::split::{split_by_category, SharedPromptStrategy};
+    use std::collections::HashSet;
+    use tempfile::TempDir;
+
+    fn library() -> PromptList {
+        PromptList {
+            prompts: vec![
+                Prompt { categories: strings(&["dev", "work"]), ..prompt("Review", "Review text") },
+                Prompt { categories: strings(&["dev"]), ..prompt("Commit", "Commit text") },
+                prompt("Note", "Note text"),
+                Prompt { categories: strings(&["a/b"]), ..prompt("Slash", "Slash text") },
+            ],
+        }
+    }
+
+    fn no_active_file() -> std::path::PathBuf {
+        std::path::PathBuf::from("missing-prompts.toml")
+    }
+
+    fn names(path: &std::path::Path) -> Vec<String> {
+        load_prompts(path.to_str().unwrap()).unwrap().prompts.into_iter().map(|prompt| prompt.name).collect()
+    }
+
+    #[test]
+    fn test_split_duplicate() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = split_by_category(&library(), temp_dir.path(), SharedPromptStrategy::Duplicate, false, &no_active_file()).unwrap();
+
+        let file_names: Vec<String> = files.iter()
+            .map(|file| file.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(file_names, vec!["uncategorized.toml", "a_b.toml", "dev.toml", "work.toml"]);
+
+        assert_eq!(names(&temp_dir.path().join("dev.toml")), vec!["Review", "Commit"]);
+        assert_eq!(names(&temp_dir.path().join("work.toml")), vec!["Review"]);
+    }
+
+    #[test]
+    fn test_split_reference() {
+        let temp_dir = TempDir::new().unwrap();
+        split_by_category(&library(), temp_dir.path(), SharedPromptStrategy::Reference, false, &no_active_file()).unwrap();
+
+        assert!(names(&temp_dir.path().join("work.toml")).is_empty());
+        let work = std::fs::read_to_string(temp_dir.path().join("work.toml")).unwrap();
+        assert!(work.starts_with("# См. также: \"Review\" в dev.toml\n"));
+
+        // Импорт директории возвращает каждый промпт ровно один раз и с теми же категориями
+        let imported = import_directory(temp_dir.path().to_str().unwrap(), &FolderMapping::default()).unwrap();
+        assert_eq!(imported.prompts.len(), 4);
+        let review = imported.prompts.iter().find(|prompt| prompt.name == "Review").unwrap();
+        assert_eq!(review.categories, HashSet::from(["dev".to_string(), "work".to_string()]));
+    }
+
+    #[test]
+    fn test_split_protects_existing_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let active_file = temp_dir.path().join("dev.toml");
+        std::fs::write(&active_file, "").unwrap();
+
+        // Непустая директория требует подтверждения
+        let result = split_by_category(&library(), temp_dir.path(), SharedPromptStrategy::Duplicate, false, &no_active_file());
+        assert!(matches!(result, Err(PromptToolError::Validation(_))));
+
+        // Текущий файл промптов не перезаписывается даже с подтверждением
+        let result = split_by_category(&library(), temp_dir.path(), SharedPromptStrategy::Duplicate, true, &active_file);
+        assert!(matches!(result, Err(PromptToolError::Validation(_))));
+        assert_eq!(std::fs::read_to_string(&active_file).unwrap(), "");
+
+        let files = split_by_category(&library(), temp_dir.path(), SharedPromptStrategy::Duplicate, true, &no_active_file()).unwrap();
+        assert_eq!(files.len(), 4);
+        assert_eq!(names(&active_file), vec!["Review", "Commit"]);
+    }
+}
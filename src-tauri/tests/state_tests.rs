@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::error::PromptToolError;
+    use prompt_tool_lib::state::StateCell;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_concurrent_readers() {
+        let cell = StateCell::new("Промпты", vec![1, 2, 3]);
+
+        let first = cell.read().unwrap();
+        let second = cell.read().unwrap();
+        assert_eq!(first.len() + second.len(), 6);
+        drop((first, second));
+
+        cell.write().unwrap().push(4);
+        assert_eq!(*cell.read().unwrap(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_poisoned_lock_returns_error() {
+        let cell = Arc::new(StateCell::new("Конфигурация", 0));
+
+        let writer = Arc::clone(&cell);
+        let result = std::thread::spawn(move || {
+            let _guard = writer.write().unwrap();
+            panic!("сбой во время записи");
+        })
+        .join();
+        assert!(result.is_err());
+
+        assert!(matches!(cell.read(), Err(PromptToolError::State(message)) if message.starts_with("Конфигурация")));
+        assert!(matches!(cell.write(), Err(PromptToolError::State(_))));
+    }
+}
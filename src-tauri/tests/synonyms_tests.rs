@@ -0,0 +1,22 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::synonyms::SynonymMap;
+
+    fn synonyms() -> SynonymMap {
+        SynonymMap::from_rules(&["js = javascript".to_string(), "bug, defect".to_string()])
+    }
+
+    #[test]
+    fn test_expand_query() {
+        assert_eq!(synonyms().expand_query("fix js bug"), "fix (js javascript) (bug defect)");
+        assert_eq!(synonyms().expand_query("title:js -bug"), "title:js -bug");
+        assert_eq!(SynonymMap::default().expand_query("fix js"), "fix js");
+    }
+
+    #[test]
+    fn test_expand_query_keeps_phrases() {
+        assert_eq!(synonyms().expand_query("\"fix js bug\""), "\"fix js bug\"");
+        assert_eq!(synonyms().expand_query("\"fix js\" bug"), "\"fix js\" (bug defect)");
+        assert_eq!(synonyms().expand_query("js \"bug\" js"), "(js javascript) \"bug\" (js javascript)");
+    }
+}
@@ -0,0 +1,13 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::tokens::count_tokens;
+
+    #[test]
+    fn test_count_tokens() {
+        assert_eq!(count_tokens("", "gpt-4o").unwrap(), 0);
+        assert_eq!(count_tokens("hello world", "gpt-4").unwrap(), 2);
+
+        // Для неизвестной модели используется приблизительная кодировка
+        assert!(count_tokens("Переведи этот текст на английский", "llama3").unwrap() > 0);
+    }
+}
@@ -0,0 +1,79 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{prompt, tagged};
+    use prompt_tool_lib::error::PromptToolError;
+    use prompt_tool_lib::output::OutputTargetConfig;
+    use prompt_tool_lib::parameters::ParameterSpec;
+    use prompt_tool_lib::prompt::PromptList;
+    use prompt_tool_lib::validation::{check_prompt, validate_new_prompts, validate_prompt, MAX_CONTENT_BYTES};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_check_prompt_fields() {
+        assert!(check_prompt(&tagged("Review", "text", &["code review", "git"])).is_empty());
+
+        let mut invalid = tagged("  ", "text", &["a,b", " padded"]);
+        invalid.content = "x".repeat(MAX_CONTENT_BYTES + 1);
+        invalid.parameters = vec![ParameterSpec::from("lang"), ParameterSpec::from("lang")];
+        invalid.rating = Some(6);
+
+        let fields: Vec<&str> = check_prompt(&invalid).iter().map(|error| error.field).collect();
+        assert_eq!(fields, vec!["name", "content", "tags", "tags", "rating", "parameters"]);
+    }
+
+    #[test]
+    fn test_webhook_headers_are_secret_references() {
+        let mut webhook = prompt("Journal", "text");
+        webhook.output = Some(OutputTargetConfig::Webhook {
+            url: "https://hooks.example.com/p".to_string(),
+            headers: HashMap::from([("Authorization".to_string(), "secret:n8n".to_string())]),
+        });
+        assert!(check_prompt(&webhook).is_empty());
+
+        webhook.output = Some(OutputTargetConfig::Webhook {
+            url: "https://hooks.example.com/p".to_string(),
+            headers: HashMap::from([("Authorization".to_string(), "Bearer x".to_string())]),
+        });
+        let fields: Vec<&str> = check_prompt(&webhook).iter().map(|error| error.field).collect();
+        assert_eq!(fields, vec!["output"]);
+    }
+
+    #[test]
+    fn test_unique_names() {
+        let library = PromptList { prompts: vec![prompt("Review", "text"), prompt("Commit", "text")] };
+
+        assert!(validate_prompt(&prompt("Summary", "text"), &library, None).is_ok());
+        assert!(validate_prompt(&prompt(" review ", "text"), &library, None).is_err());
+        assert!(validate_prompt(&tagged("Review", "text", &["go"]), &library, Some(0)).is_ok());
+
+        let batch = vec![prompt("Summary", "text"), prompt("summary", "text")];
+        match validate_new_prompts(&batch, &library) {
+            Err(PromptToolError::Validation(message)) => assert!(message.starts_with("summary: name:")),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unique_aliases() {
+        let mut commit = prompt("Commit", "text");
+        commit.aliases = vec!["cm".to_string()];
+        let library = PromptList { prompts: vec![commit.clone(), prompt("Review", "text")] };
+
+        let mut review = prompt("Review", "text");
+        review.aliases = vec!["CM".to_string()];
+        assert!(validate_prompt(&review, &library, Some(1)).is_err());
+        // Промпт не конфликтует со своими же псевдонимами
+        assert!(validate_prompt(&commit, &library, Some(0)).is_ok());
+
+        review.aliases = vec!["rv".to_string(), "rv".to_string(), "a,b".to_string()];
+        let fields: Vec<&str> = check_prompt(&review).iter().map(|error| error.field).collect();
+        assert_eq!(fields, vec!["aliases", "aliases"]);
+
+        let mut summary = prompt("Summary", "text");
+        summary.aliases = vec!["cm".to_string()];
+        assert!(validate_new_prompts(&[summary], &library).is_err());
+    }
+}
+
@@ -0,0 +1,41 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::prompt;
+    use prompt_tool_lib::parameters::ParameterSpec;
+    use prompt_tool_lib::vscode::{build_snippets, export_snippets, snippet_body};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_snippet_body_tab_stops() {
+        let mut review = prompt("Review", "Review {code} in {lang|default:rust}, then {code|upper} for {who}. Cost: $5 {{ raw }}");
+        review.parameters = vec![ParameterSpec::from("code"), ParameterSpec::from("lang"), ParameterSpec::from("who")];
+        review.parameters[2].default = Some("team}".to_string());
+
+        assert_eq!(
+            snippet_body(&review),
+            "Review $1 in ${2:rust}, then $1 for ${3:team\\}}. Cost: \\$5 {{ raw \\}\\}"
+        );
+    }
+
+    #[test]
+    fn test_build_and_export_snippets() {
+        let commit = prompt("Commit Message", "Line {summary}\r\nBody");
+        let snippets = build_snippets(&[&commit], Some(" rust , ,python"));
+
+        let snippet = &snippets["Commit Message"];
+        assert_eq!(snippet.prefix, "commit-message");
+        assert_eq!(snippet.body, vec!["Line $1".to_string(), "Body".to_string()]);
+        assert_eq!(snippet.scope.as_deref(), Some("rust,python"));
+        assert_eq!(build_snippets(&[&commit], Some(" "))["Commit Message"].scope, None);
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("prompts.code-snippets");
+        assert_eq!(export_snippets(&[&commit], None, &path).unwrap(), 1);
+
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["Commit Message"]["prefix"], "commit-message");
+        assert!(json["Commit Message"].get("scope").is_none());
+    }
+}
@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::webdav::{content_hash, plan, RemoteVersion, SyncAction, SyncState};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_plan() {
+        assert_eq!(plan(false, false), SyncAction::None);
+        assert_eq!(plan(true, false), SyncAction::Push);
+        assert_eq!(plan(false, true), SyncAction::Pull);
+        assert_eq!(plan(true, true), SyncAction::Conflict);
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(content_hash(""), "cbf29ce484222325");
+        assert_eq!(content_hash("a"), "af63dc4c8601ec8c");
+        assert_ne!(content_hash("prompts = []"), content_hash("prompts = [ ]"));
+    }
+
+    #[test]
+    fn test_sync_state_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("webdav_sync.json");
+        assert_eq!(SyncState::load(&path), SyncState::default());
+
+        let state = SyncState {
+            remote: Some(RemoteVersion { etag: Some("\"abc\"".to_string()), last_modified: None }),
+            local_hash: Some(content_hash("prompts = []")),
+            synced_at: None,
+        };
+        state.save(&path).unwrap();
+        assert_eq!(SyncState::load(&path), state);
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests {
+    use prompt_tool_lib::window_state::{MonitorArea, Rect, WindowState};
+    use tempfile::TempDir;
+
+    fn monitor(name: &str, x: i32) -> MonitorArea {
+        MonitorArea {
+            name: Some(name.to_string()),
+            area: Rect { x, y: 0, width: 1920, height: 1080 },
+        }
+    }
+
+    fn state(x: i32, monitor: Option<&str>) -> WindowState {
+        WindowState {
+            bounds: Rect { x, y: 100, width: 800, height: 600 },
+            monitor: monitor.map(str::to_string),
+            maximized: false,
+        }
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("window_state.json");
+        assert_eq!(WindowState::load(&path), None);
+
+        let saved = WindowState { maximized: true, ..state(2000, Some("DELL")) };
+        saved.save(&path).unwrap();
+        assert_eq!(WindowState::load(&path), Some(saved));
+    }
+
+    #[test]
+    fn test_position_on_second_monitor() {
+        let monitors = vec![monitor("Built-in", 0), monitor("DELL", 1920)];
+        assert!(state(2000, Some("DELL")).position_fits(&monitors));
+
+        // Второй монитор отключен - окно не должно открыться за пределами экрана
+        let monitors = vec![monitor("Built-in", 0)];
+        assert!(!state(2000, Some("DELL")).position_fits(&monitors));
+        assert!(!state(2000, None).position_fits(&monitors));
+    }
+
+    #[test]
+    fn test_window_must_stay_reachable() {
+        let monitors = vec![monitor("Built-in", 0)];
+        assert!(state(100, None).position_fits(&monitors));
+        // На экране остается меньше полосы, за которую окно можно перетащить
+        assert!(!state(1900, None).position_fits(&monitors));
+        assert!(!state(-790, None).position_fits(&monitors));
+    }
+}
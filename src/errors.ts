@@ -0,0 +1,23 @@
+/**
+ * Ошибки команд бэкенда: приходят объектом { code, message, details }.
+ */
+
+/** Ошибка команды */
+export interface AppError {
+    code: string;    // Стабильный код ошибки
+    message: string; // Текст на языке интерфейса из настроек
+    details: string; // Подробности: путь, имя поля и т.п.
+}
+
+/** Является ли значение ошибкой команды */
+export function isAppError(value: unknown): value is AppError {
+    return typeof value === "object" && value !== null && "code" in value && "message" in value;
+}
+
+/** Текст ошибки для пользователя */
+export function errorText(error: unknown): string {
+    if (isAppError(error)) {
+        return error.details ? `${error.message}: ${error.details}` : error.message;
+    }
+    return String(error);
+}
@@ -4,12 +4,43 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
+import { errorText } from "./errors";
+
+/** Тип значения параметра */
+type ParameterKind = "string" | "number" | "bool" | { enum: string[] };
+
+/** Интерфейс для описания параметра промпта */
+interface ParameterSpec {
+    name: string;         // Имя плейсхолдера
+    kind: ParameterKind;  // Тип значения, по нему выбирается поле ввода
+    required: boolean;    // Обязательно ли значение
+    description?: string; // Подсказка для пользователя
+    default?: string;     // Значение, если пользователь его не ввел
+}
 
 /** Интерфейс для структуры промпта */
 interface Prompt {
     name: string;        // Название промпта
     content: string;     // Содержимое промпта
-    parameters: string[]; // Параметры, которые нужно заполнить
+    parameters: ParameterSpec[]; // Параметры, которые нужно заполнить
+}
+
+/** Краткое описание промпта для списка */
+interface PromptSummary {
+    id: string;          // Идентификатор; пустой у промптов старого формата
+    name: string;        // Название промпта
+    tags: string[];      // Теги
+    content: string;     // Начало текста промпта
+    truncated: boolean;  // Текст обрезан, полный возвращает get_prompt_detail
+    archived: boolean;   // Промпт в архиве
+}
+
+/** Страница списка промптов */
+interface Page<T> {
+    items: T[];
+    total: number;               // Сколько всего промптов
+    offset: number;              // Позиция первого элемента страницы
+    next_cursor: string | null;  // Курсор следующей страницы
 }
 
 /** Интерфейс для настроек приложения */
@@ -23,8 +54,9 @@ interface Settings {
  */
 class PromptManager {
     private static instance: PromptManager;
-    private prompts: Prompt[] = [];
-    private filteredPrompts: Prompt[] = [];
+    private prompts: PromptSummary[] = [];
+    private filteredPrompts: PromptSummary[] = [];
+    private promptsGeneration = 0; // Номер загрузки списка: догрузка прежней загрузки отбрасывается
     private settings: Settings = {
         promptFilePath: "",
         hotkey: ""
@@ -111,7 +143,7 @@ class PromptManager {
                     this.elements.promptFilePathInput.value = selected;
                 }
             } catch (error) {
-                console.error("Ошибка при выборе файла:", error);
+                console.error("Ошибка при выборе файла:", errorText(error));
             }
         });
 
@@ -122,7 +154,7 @@ class PromptManager {
                 this.elements.settingsModal.classList.add("hidden");
                 await this.loadPrompts();
             } catch (error) {
-                console.error("Ошибка при сохранении настроек:", error);
+                console.error("Ошибка при сохранении настроек:", errorText(error));
             }
         });
 
@@ -143,7 +175,7 @@ class PromptManager {
             this.elements.hotkeyConfigInput.value = config.hotkey;
             await this.loadPrompts();
         } catch (error) {
-            console.error("Ошибка загрузки настроек:", error);
+            console.error("Ошибка загрузки настроек:", errorText(error));
         }
     }
 
@@ -158,20 +190,44 @@ class PromptManager {
             this.settings.promptFilePath = newPath;
             this.settings.hotkey = newHotkey;
         } catch (error) {
-            console.error("Ошибка сохранения настроек:", error);
+            console.error("Ошибка сохранения настроек:", errorText(error));
             throw error;
         }
     }
 
-    /** Загрузка промптов */
+    /** Загрузка промптов: первая страница сразу, остальные в фоне */
     private async loadPrompts(): Promise<void> {
+        this.prompts = [];
+        const generation = ++this.promptsGeneration;
         try {
-            this.prompts = await invoke<Prompt[]>("get_prompts", {
-                filePath: this.settings.promptFilePath
-            });
+            const first = await this.loadPromptPage(null, generation);
+            if (first) {
+                void this.loadRemainingPrompts(first, generation);
+            }
+        } catch (error) {
+            console.error("Ошибка загрузки промптов:", errorText(error));
+        }
+    }
+
+    /** Загружает страницу открытой библиотеки после курсора и добавляет ее в список; возвращает курсор следующей */
+    private async loadPromptPage(cursor: string | null, generation: number): Promise<string | null> {
+        const page = await invoke<Page<PromptSummary>>("get_prompts", { page: { cursor } });
+        if (generation !== this.promptsGeneration) {
+            return null;
+        }
+        this.prompts.push(...page.items);
+        return page.next_cursor;
+    }
+
+    /** Догружает оставшиеся страницы, не задерживая первую отрисовку */
+    private async loadRemainingPrompts(cursor: string, generation: number): Promise<void> {
+        let next: string | null = cursor;
+        try {
+            while (next) {
+                next = await this.loadPromptPage(next, generation);
+            }
         } catch (error) {
-            console.error("Ошибка загрузки промптов:", error);
-            this.prompts = [];
+            console.error("Ошибка загрузки промптов:", errorText(error));
         }
     }
 
@@ -200,7 +256,7 @@ class PromptManager {
             const li = document.createElement("li");
             li.textContent = prompt.name;
             li.addEventListener("click", () => {
-                navigator.clipboard.writeText(prompt.content).catch(console.error);
+                this.copyPrompt(prompt);
                 this.elements.searchBar.value = "";
                 this.elements.promptList.classList.add("hidden");
             });
@@ -208,6 +264,30 @@ class PromptManager {
         });
     }
 
+    /** Копирование промпта: в списке только начало текста, полный текст запрашивается отдельно */
+    private async copyPrompt(summary: PromptSummary): Promise<void> {
+        try {
+            const prompt = summary.truncated
+                ? await invoke<Prompt>("get_prompt_detail", { id: summary.id || summary.name })
+                : summary;
+            await this.copyToClipboard(prompt.content, prompt.name);
+        } catch (error) {
+            console.error(errorText(error));
+        }
+    }
+
+    /** Копирование текста промпта; если ОС не поддерживает нативную запись, текст кладет браузер */
+    private async copyToClipboard(text: string, promptName: string): Promise<void> {
+        try {
+            const result = await invoke<{ text: string; native: boolean }>("copy_to_clipboard", { text, promptName });
+            if (!result.native) {
+                await navigator.clipboard.writeText(result.text);
+            }
+        } catch (error) {
+            console.error(errorText(error));
+        }
+    }
+
     /** Работа с темами */
     private initializeTheme(): void {
         const savedTheme = localStorage.getItem("theme") || "dark";
@@ -1,5 +1,7 @@
 import { mount } from 'svelte';
+import { getCurrentWindow } from '@tauri-apps/api/window';
 import App from './App.svelte';
+import Launcher from './Launcher.svelte';
 
 console.log('Initializing Svelte app...');
 
@@ -12,7 +14,9 @@ if (!appElement) {
 
 console.log('Mounting Svelte app to element:', appElement);
 
-const app = mount(App, {target: appElement});
+// Окно быстрого запуска загружает тот же фронтенд и отличается только меткой
+const component = getCurrentWindow().label === 'launcher' ? Launcher : App;
+const app = mount(component, {target: appElement});
 
 console.log('Svelte app mounted successfully');
 